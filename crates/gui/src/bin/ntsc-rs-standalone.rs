@@ -2,23 +2,25 @@
 
 use std::{
     borrow::Cow,
-    collections::VecDeque,
+    collections::{hash_map::DefaultHasher, HashMap, HashSet, VecDeque},
     error::Error,
     ffi::OsStr,
     fs::File,
+    hash::{Hash, Hasher},
     io::Read,
     ops::RangeInclusive,
     path::{Path, PathBuf},
     pin::Pin,
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicU64, AtomicUsize, Ordering},
         Arc, Mutex, OnceLock,
     },
     task::{Context, Poll, Waker},
     thread,
+    time::Instant,
 };
 
-use eframe::egui::{self, pos2, util::undoer::Undoer, vec2, ColorImage, Rect, Response};
+use eframe::egui::{self, pos2, vec2, ColorImage, Rect, Response};
 use futures_lite::{Future, FutureExt};
 use glib::clone::Downgrade;
 use gstreamer::{
@@ -26,29 +28,53 @@ use gstreamer::{
     prelude::*,
     ClockTime,
 };
-use gstreamer_video::{VideoCapsBuilder, VideoFormat, VideoInterlaceMode};
+use gstreamer_video::{VideoCapsBuilder, VideoFieldOrder, VideoFormat, VideoInterlaceMode};
 
 use gui::{
     expression_parser::eval_expression_string,
     gst_utils::{
-        clock_format::{clock_time_format, clock_time_parser},
-        egui_sink::{EffectPreviewSetting, EguiCtx, EguiSink, SinkTexture},
+        camera::{is_camera, CAMERA_PATH},
+        clock_format::{
+            clock_time_format, clock_time_parser, smpte_timecode_format, smpte_timecode_parser,
+        },
+        debug_log::{DebugLogFile, DebugLogLevel},
+        egui_sink::{
+            EffectPreviewSetting, EguiCtx, EguiSink, NoisePin, PreviewChannel, SinkTexture,
+        },
         elements,
+        frame_grab::grab_frame,
         gstreamer_error::GstreamerError,
-        ntscrs_filter::NtscFilterSettings,
-        pipeline_utils::{create_pipeline, PipelineError},
-        scale_from_caps,
+        hdr_tonemap::{HdrTransfer, TonemapSettings},
+        image_sequence::{
+            is_image_sequence, sequence_pattern_for_frame, set_image_sequence_framerate,
+            IMAGE_SEQUENCE_SRC_NAME,
+        },
+        max_height_caps,
+        ntscrs_filter::{NtscFilterKeyframes, NtscFilterRegion, NtscFilterSettings},
+        pipeline_description::describe_pipeline,
+        pipeline_utils::{create_pipeline, create_source, is_url, link_source, PipelineError},
+        pixel_aspect_ratio,
+        process_gst_frame::EffectRegion,
+        scaled_caps,
+        thumbnails::ThumbnailGenerator,
     },
+    i18n,
+    keyframes::{Keyframe, KeyframeTracks},
+    shortcuts::{shortcut_modifiers, KeyboardShortcuts, ShortcutAction},
     splitscreen::SplitScreen,
     third_party_licenses::get_third_party_licenses,
     timeline::Timeline,
+    tr,
+    variation_grid::{compose_variation_grid, variation_values},
+    watch_folder::FolderWatcher,
 };
 
 use ntscrs::settings::{
-    NtscEffect, NtscEffectFullSettings, ParseSettingsError, SettingDescriptor, SettingID,
-    SettingKind, SettingsList, UseField,
+    ChromaDemodulationFilter, NtscEffect, NtscEffectFullSettings, ParseSettingsError, PhaseShift,
+    PresetMetadata, SettingDescriptor, SettingID, SettingKind, SettingsList, UseField,
 };
 use snafu::{prelude::*, ResultExt};
+use tinyjson::JsonValue;
 
 use log::debug;
 
@@ -71,6 +97,45 @@ enum ApplicationError {
 
     #[snafu(display("Error saving JSON: {source}"))]
     JSONSave { source: std::io::Error },
+
+    #[snafu(display("Error reading batch render folder: {source}"))]
+    BatchRead { source: std::io::Error },
+
+    #[snafu(display("Error watching folder: {source}"))]
+    WatchFolder { source: notify::Error },
+
+    #[snafu(display("Error creating debug log file: {source}"))]
+    DebugLogCreate { source: std::io::Error },
+
+    #[snafu(display("Error rendering variation grid: {source}"))]
+    VariationGridRender { source: gstreamer::FlowError },
+
+    #[snafu(display("Error saving variation grid: {source}"))]
+    VariationGridSave { source: image::ImageError },
+
+    #[snafu(display("Error creating output folder: {source}"))]
+    CreateOutputDirectory { source: std::io::Error },
+
+    #[snafu(display(
+        "Couldn't decode this file — the required codec{} may be missing",
+        codec.as_ref().map(|codec| format!(" ({codec})")).unwrap_or_default()
+    ))]
+    UnsupportedFile { codec: Option<String> },
+}
+
+/// Whether a preview pipeline error means the file couldn't be read at all, as opposed to something going wrong
+/// partway through.
+fn is_decode_error(err: &PipelineError) -> bool {
+    match err {
+        PipelineError::GlibError(err) => {
+            err.matches(gstreamer::CoreError::MissingPlugin)
+                || err.matches(gstreamer::StreamError::CodecNotFound)
+                || err.matches(gstreamer::StreamError::TypeNotFound)
+                || err.matches(gstreamer::StreamError::WrongType)
+                || err.matches(gstreamer::StreamError::Decode)
+        }
+        PipelineError::NoVideoError => false,
+    }
 }
 
 fn initialize_gstreamer() -> Result<(), GstreamerError> {
@@ -97,6 +162,13 @@ fn initialize_gstreamer() -> Result<(), GstreamerError> {
         elements::VideoPadFilter::static_type(),
     )?;
 
+    gstreamer::Element::register(
+        None,
+        "hdrtonemap",
+        gstreamer::Rank::None,
+        elements::HdrTonemapFilter::static_type(),
+    )?;
+
     // PulseAudio has a severe bug that will greatly delay initial playback to the point of unusability:
     // https://gitlab.freedesktop.org/pulseaudio/pulseaudio/-/issues/1383
     // A fix was merged a *year* ago, but the Pulse devs, in their infinite wisdom, won't give it to us until their
@@ -111,10 +183,135 @@ fn initialize_gstreamer() -> Result<(), GstreamerError> {
     Ok(())
 }
 
+/// List missing GStreamer elements along with the plugin packages that provide them, for telling users what to install.
+fn format_missing_elements(missing_elements: &[(&str, &str)]) -> String {
+    missing_elements
+        .iter()
+        .map(|(element, plugin)| format!("{element} (from {plugin})"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 fn format_percentage(n: f64, prec: RangeInclusive<usize>) -> String {
     format!("{:.*}%", prec.start().max(&2) - 2, n * 100.0)
 }
 
+/// Format a setting's default value the same way its widget displays it.
+fn format_default_value(kind: &SettingKind) -> String {
+    match kind {
+        SettingKind::Enumeration {
+            options,
+            default_value,
+        } => options
+            .iter()
+            .find(|item| item.index == *default_value)
+            .map_or_else(
+                || default_value.to_string(),
+                |item| tr!(item.label).to_string(),
+            ),
+        SettingKind::Percentage { default_value, .. } => {
+            format_percentage(*default_value as f64, 4..=4)
+        }
+        SettingKind::IntRange { default_value, .. } => default_value.to_string(),
+        SettingKind::FloatRange { default_value, .. } => format!("{default_value:.2}"),
+        SettingKind::Boolean { default_value } | SettingKind::Group { default_value, .. } => {
+            if *default_value { "on" } else { "off" }.to_string()
+        }
+    }
+}
+
+/// Collect every setting with a numeric value, including those inside groups, for picking one to vary.
+fn numeric_descriptors<'a>(
+    descriptors: &'a [SettingDescriptor],
+    dst: &mut Vec<&'a SettingDescriptor>,
+) {
+    for descriptor in descriptors {
+        match &descriptor.kind {
+            SettingKind::Percentage { .. }
+            | SettingKind::FloatRange { .. }
+            | SettingKind::IntRange { .. } => dst.push(descriptor),
+            SettingKind::Group { children, .. } => numeric_descriptors(children, dst),
+            SettingKind::Enumeration { .. } | SettingKind::Boolean { .. } => {}
+        }
+    }
+}
+
+/// If the mouse wheel is scrolled while `response` is hovered, consume the scroll so the settings panel doesn't scroll
+/// too, and return the direction it went: 1 for up, -1 for down, and 0 if it wasn't scrolled.
+fn slider_scroll_steps(ui: &egui::Ui, response: &Response) -> f32 {
+    if !response.hovered() {
+        return 0.0;
+    }
+    let scroll_delta = ui.ctx().input(|input| input.raw_scroll_delta.y);
+    if scroll_delta == 0.0 {
+        return 0.0;
+    }
+    ui.ctx().input_mut(|input| {
+        input.raw_scroll_delta = egui::Vec2::ZERO;
+        input.smooth_scroll_delta = egui::Vec2::ZERO;
+    });
+    scroll_delta.signum()
+}
+
+/// While Ctrl or Shift is held, dragging a slider moves it this much slower than the pointer.
+const FINE_DRAG_FACTOR: f64 = 0.1;
+
+/// If `response` is a slider being dragged with Ctrl or Shift held, return the value it should have instead of the one
+/// under the pointer: `old` moved by a fraction of how far the pointer moved, for precise adjustments on wide ranges.
+fn fine_drag_value(
+    ui: &egui::Ui,
+    response: &Response,
+    old: f64,
+    range: RangeInclusive<f64>,
+) -> Option<f64> {
+    if !response.dragged() {
+        return None;
+    }
+    let (fine, pointer_delta) = ui.ctx().input(|input| {
+        (
+            input.modifiers.command || input.modifiers.shift,
+            input.pointer.delta().x,
+        )
+    });
+    if !fine {
+        return None;
+    }
+    let span = range.end() - range.start();
+    let delta = pointer_delta as f64 / ui.spacing().slider_width as f64 * span * FINE_DRAG_FACTOR;
+    Some((old + delta).clamp(*range.start(), *range.end()))
+}
+
+/// Collect every setting, including those inside groups.
+fn all_descriptors<'a>(descriptors: &'a [SettingDescriptor], dst: &mut Vec<&'a SettingDescriptor>) {
+    for descriptor in descriptors {
+        dst.push(descriptor);
+        if let SettingKind::Group { children, .. } = &descriptor.kind {
+            all_descriptors(children, dst);
+        }
+    }
+}
+
+/// Nudge every continuous setting by a random amount of up to `amount` times its range in either direction. Integer
+/// settings are left alone, since most of them are seeds or counts where a small nudge isn't meaningful.
+fn jitter_settings(
+    settings: &mut NtscEffectFullSettings,
+    descriptors: &[&SettingDescriptor],
+    amount: f32,
+) {
+    for descriptor in descriptors {
+        let range = match &descriptor.kind {
+            SettingKind::Percentage { .. } => 0.0..=1.0,
+            SettingKind::FloatRange { range, .. } => range.clone(),
+            _ => continue,
+        };
+        if let Some(field) = descriptor.id.get_field_mut::<f32>(settings) {
+            let offset =
+                (rand::random::<f32>() * 2.0 - 1.0) * amount * (range.end() - range.start());
+            *field = (*field + offset).clamp(*range.start(), *range.end());
+        }
+    }
+}
+
 /// Parse a textbox input as either a decimal or percentage, depending on whether it's greater than a certain threshold.
 /// Returns a decimal.
 ///
@@ -134,10 +331,21 @@ static ICON: &[u8] = include_bytes!("../../../../assets/icon.png");
 
 fn main() -> Result<(), Box<dyn Error>> {
     env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
+
+    // Print a JSON Schema for the settings format, for validating presets, instead of starting the app
+    if std::env::args().any(|arg| arg == "--dump-schema") {
+        println!("{}", SettingsList::new().json_schema().format()?);
+        return Ok(());
+    }
+
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([1300.0, 720.0])
-            .with_icon(eframe::icon_data::from_png_bytes(ICON)?),
+            .with_icon(eframe::icon_data::from_png_bytes(ICON)?)
+            .with_drag_and_drop(true),
+        // eframe restores the window's size and position before it's created, shrinking it to fit the largest monitor
+        // and moving it back on-screen if it was left on a monitor that's no longer connected.
+        persist_window: true,
         ..Default::default()
     };
     Ok(eframe::run_native(
@@ -146,15 +354,27 @@ fn main() -> Result<(), Box<dyn Error>> {
         Box::new(|cc| {
             // GStreamer can be slow to initialize (on the order of minutes). Do it off-thread so we can display a
             // loading screen in the meantime. Thanks for being thread-safe, unlike GTK!
-            let gstreamer_initialized = Arc::new(AtomicBool::new(false));
+            let debug_log_level = cc
+                .storage
+                .and_then(|storage| storage.get_string("gstreamer_debug_level"))
+                .and_then(|level| DebugLogLevel::from_name(&level))
+                .unwrap_or_default();
+            let gstreamer_initialized = Arc::new(OnceLock::new());
             let gstreamer_initialized_for_thread = Arc::clone(&gstreamer_initialized);
+            let ctx_for_thread = cc.egui_ctx.clone();
             thread::spawn(move || {
-                initialize_gstreamer().unwrap();
-                gstreamer_initialized_for_thread.store(true, Ordering::Release);
+                let result = initialize_gstreamer();
+                // Leave the threshold from GST_DEBUG alone unless a level was chosen in the app
+                if result.is_ok() && debug_log_level != DebugLogLevel::None {
+                    debug_log_level.apply();
+                }
+                let _ = gstreamer_initialized_for_thread.set(result);
+                // If initialization failed, the spinner is no longer there to keep the UI repainting
+                ctx_for_thread.request_repaint();
             });
 
             let settings_list = SettingsList::new();
-            let (settings, theme) = if let Some(storage) = cc.storage {
+            let (settings, theme, accent_color) = if let Some(storage) = cc.storage {
                 // Load previous effect settings from storage
                 let settings = storage
                     .get_string("effect_settings")
@@ -166,21 +386,40 @@ fn main() -> Result<(), Box<dyn Error>> {
                     .and_then(|color_theme| ColorTheme::try_from(color_theme.as_str()).ok())
                     .unwrap_or_default();
 
-                (settings, theme)
+                let accent_color = storage
+                    .get_string("accent_color")
+                    .and_then(|accent_color| accent_color_from_hex(&accent_color));
+
+                (settings, theme, accent_color)
             } else {
-                (NtscEffectFullSettings::default(), ColorTheme::default())
+                (
+                    NtscEffectFullSettings::default(),
+                    ColorTheme::default(),
+                    None,
+                )
             };
 
             let ctx = cc.egui_ctx.clone();
-            ctx.set_visuals(theme.visuals(&cc.integration_info));
+            ctx.set_visuals(theme.visuals(&cc.integration_info, accent_color));
             ctx.style_mut(|style| style.interaction.tooltip_delay = 0.5);
-            Box::new(NtscApp::new(
+            let mut app = NtscApp::new(
                 ctx,
                 settings_list,
                 settings,
                 theme,
+                accent_color,
                 gstreamer_initialized,
-            ))
+            );
+            if let Some(storage) = cc.storage {
+                app.restore_session(storage);
+            }
+            cc.egui_ctx.set_zoom_factor(app.ui_scale);
+            app.debug_log_level = debug_log_level;
+            app.check_recovery_file();
+            // Off by default so that normal runs don't spam the terminal
+            app.progress_json = std::env::args().any(|arg| arg == "--progress-json")
+                || std::env::var_os("NTSCRS_PROGRESS_JSON").is_some();
+            Box::new(app)
         }),
     )?)
 }
@@ -201,23 +440,31 @@ struct PipelineInfo {
     preview: egui::TextureHandle,
     at_eos: Arc<Mutex<bool>>,
     metadata: Arc<Mutex<PipelineMetadata>>,
+    // named positions of interest, sorted by time
+    markers: Vec<(ClockTime, String)>,
+    // playing backwards
+    reverse: bool,
+    // index of the audio track being played, out of the metadata's audio tracks
+    audio_track: usize,
+    // where to seek to once the pipeline has loaded, to pick up where the user left off last time
+    resume_position: Option<ClockTime>,
 }
 
 impl PipelineInfo {
     fn toggle_playing(&self) -> Result<(), GstreamerError> {
         match self.pipeline.current_state() {
             gstreamer::State::Paused | gstreamer::State::Ready => {
-                // Restart from the beginning if "play" is pressed at the end of the video
+                // Restart from the beginning if "play" is pressed at the end of the video (or from the end if "play" is
+                // pressed at the beginning while playing backwards)
                 let (position, duration) = (
                     self.pipeline.query_position::<ClockTime>(),
                     self.pipeline.query_duration::<ClockTime>(),
                 );
                 if let (Some(position), Some(duration)) = (position, duration) {
-                    if position == duration {
-                        self.pipeline.seek_simple(
-                            gstreamer::SeekFlags::FLUSH | gstreamer::SeekFlags::ACCURATE,
-                            ClockTime::ZERO,
-                        )?;
+                    if self.reverse && position == ClockTime::ZERO {
+                        self.seek(duration)?;
+                    } else if !self.reverse && position == duration {
+                        self.seek(ClockTime::ZERO)?;
                     }
                 }
 
@@ -231,27 +478,359 @@ impl PipelineInfo {
 
         Ok(())
     }
+
+    fn is_seekable(&self) -> bool {
+        self.metadata.lock().unwrap().seekable != Some(false)
+    }
+
+    /// Seek to the given position, keeping the current playback direction. Does nothing if the source isn't seekable.
+    fn seek(&self, position: ClockTime) -> Result<(), GstreamerError> {
+        if !self.is_seekable() {
+            return Ok(());
+        }
+
+        // don't use KEY_UNIT here; it causes seeking to often be very inaccurate (almost a second of deviation)
+        let flags = gstreamer::SeekFlags::FLUSH | gstreamer::SeekFlags::ACCURATE;
+        if self.reverse {
+            self.pipeline.seek(
+                -1.0,
+                flags,
+                gstreamer::SeekType::Set,
+                ClockTime::ZERO,
+                gstreamer::SeekType::Set,
+                position,
+            )?;
+        } else {
+            self.pipeline.seek_simple(flags, position)?;
+        }
+
+        Ok(())
+    }
+
+    /// Switch between playing forwards and backwards, starting from the current position. Reverse playback depends on
+    /// the demuxer supporting negative-rate seeks, which many don't (or do very slowly, since every frame has to be
+    /// decoded starting from the previous keyframe). If the seek fails, this goes back to playing forwards.
+    fn set_reverse(&mut self, reverse: bool) -> Result<(), GstreamerError> {
+        let position = self
+            .pipeline
+            .query_position::<ClockTime>()
+            .unwrap_or(self.last_seek_pos);
+        self.reverse = reverse;
+        if let Err(err) = self.seek(position) {
+            self.reverse = false;
+            let _ = self.seek(position);
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    /// The framerate of the video going into the effect, once it's known.
+    fn framerate(&self) -> Option<gstreamer::Fraction> {
+        let caps = self
+            .pipeline
+            .by_name("video_queue")?
+            .static_pad("sink")?
+            .current_caps()?;
+        caps.structure(0)?
+            .get::<gstreamer::Fraction>("framerate")
+            .ok()
+    }
+
+    /// How long each frame is shown for, if the source has a fixed framerate.
+    fn frame_duration(&self) -> Option<ClockTime> {
+        let framerate = self.framerate()?;
+        if framerate.numer() <= 0 || framerate.denom() <= 0 {
+            return None;
+        }
+        ClockTime::SECOND.mul_div_floor(framerate.denom() as u64, framerate.numer() as u64)
+    }
+
+    /// The start of the video's last frame. The duration itself is where the last frame ends, and seeking there shows
+    /// nothing, so this steps back a frame from it like [`PipelineInfo::step_frame`] does.
+    fn last_frame_position(&self) -> Option<ClockTime> {
+        let duration = self.pipeline.query_duration::<ClockTime>()?;
+        Some(match self.frame_duration() {
+            Some(frame_duration) => duration.saturating_sub(frame_duration),
+            None => duration,
+        })
+    }
+
+    /// Pause and move one frame forwards or backwards. Does nothing for sources without a fixed framerate.
+    fn step_frame(&self, forward: bool) -> Result<(), GstreamerError> {
+        let Some(frame_duration) = self.frame_duration() else {
+            return Ok(());
+        };
+
+        if self.pipeline.current_state() == gstreamer::State::Playing {
+            self.pipeline.set_state(gstreamer::State::Paused)?;
+        }
+
+        let position = self
+            .pipeline
+            .query_position::<ClockTime>()
+            .unwrap_or(self.last_seek_pos);
+        let target = if forward {
+            let target = position + frame_duration;
+            match self.pipeline.query_duration::<ClockTime>() {
+                Some(duration) => target.min(duration),
+                None => target,
+            }
+        } else {
+            position.saturating_sub(frame_duration)
+        };
+        self.seek(target)
+    }
+}
+
+/// Details about the source's streams, for display. Codec and container names come from the tags that demuxers and
+/// decoders post, and the rest from the decoded streams' caps.
+#[derive(Debug, Default, Clone)]
+struct MediaInfo {
+    container_format: Option<String>,
+    video_codec: Option<String>,
+    pixel_format: Option<VideoFormat>,
+    audio_codec: Option<String>,
+    audio_sample_rate: Option<i32>,
+    audio_channels: Option<i32>,
+}
+
+impl MediaInfo {
+    fn update_from_tags(&mut self, tags: &gstreamer::TagListRef) {
+        if let Some(container_format) = tags.get::<gstreamer::tags::ContainerFormat>() {
+            self.container_format = Some(container_format.get().to_string());
+        }
+        if let Some(video_codec) = tags.get::<gstreamer::tags::VideoCodec>() {
+            self.video_codec = Some(video_codec.get().to_string());
+        }
+        if let Some(audio_codec) = tags.get::<gstreamer::tags::AudioCodec>() {
+            self.audio_codec = Some(audio_codec.get().to_string());
+        }
+    }
+
+    fn update_from_pipeline(&mut self, pipeline: &gstreamer::Pipeline) {
+        // Read the caps going into the queues, which are the decoders' output before any conversion
+        let queue_caps = |name: &str| pipeline.by_name(name)?.static_pad("sink")?.current_caps();
+
+        if let Some(caps) = queue_caps("video_queue") {
+            self.pixel_format = gstreamer_video::VideoInfo::from_caps(&caps)
+                .ok()
+                .map(|info| info.format());
+        }
+        if let Some(caps) = queue_caps("audio_queue") {
+            let structure = caps.structure(0);
+            self.audio_sample_rate = structure.and_then(|structure| structure.get("rate").ok());
+            self.audio_channels = structure.and_then(|structure| structure.get("channels").ok());
+        }
+    }
 }
 
 #[derive(Debug, Default)]
 struct PipelineMetadata {
     is_still_image: Option<bool>,
+    // numbered image files played back as a video, whose framerate can be changed like a still image's
+    is_image_sequence: bool,
+    // captured from a camera, so it has no duration and can't be paused or seeked
+    is_live: bool,
+    // whether the source can be seeked in, which network streams often can't be. unknown until the pipeline loads
+    seekable: Option<bool>,
     has_audio: Option<bool>,
     framerate: Option<gstreamer::Fraction>,
     interlace_mode: Option<VideoInterlaceMode>,
+    // which field of an interlaced source comes first, if its caps say so
+    field_order: Option<VideoFieldOrder>,
     resolution: Option<(usize, usize)>,
+    // the shape of the source's pixels, which isn't square for anamorphic video like DVDs
+    pixel_aspect_ratio: Option<gstreamer::Fraction>,
+    // description of the decoder that decodebin couldn't find, from its "missing-plugin" message
+    missing_codec: Option<String>,
+    // the source's HDR transfer function, if it has one
+    hdr_transfer: Option<HdrTransfer>,
+    // every audio track in the source, in the order decodebin found them
+    audio_tracks: Vec<AudioTrack>,
+    media_info: MediaInfo,
+}
+
+impl PipelineMetadata {
+    /// The interlaced field setting that matches the source's field order, if the source is interlaced and says
+    /// which field comes first.
+    fn source_use_field(&self) -> Option<UseField> {
+        if self.interlace_mode != Some(VideoInterlaceMode::Interleaved) {
+            return None;
+        }
+        match self.field_order? {
+            VideoFieldOrder::TopFieldFirst => Some(UseField::InterleavedUpper),
+            VideoFieldOrder::BottomFieldFirst => Some(UseField::InterleavedLower),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct AudioTrack {
+    language: Option<String>,
+    title: Option<String>,
+}
+
+impl AudioTrack {
+    fn label(&self, index: usize) -> String {
+        let mut label = tr!("Track {number}", number = index + 1);
+        if let Some(language) = &self.language {
+            label.push_str(&format!(" ({})", language));
+        }
+        if let Some(title) = &self.title {
+            label.push_str(&format!(": {}", title));
+        }
+        label
+    }
+}
+
+/// Measures how many frames per second the preview sink is rendering, averaged over about a second.
+#[derive(Debug, Default)]
+struct PreviewFpsCounter {
+    // when the current sample started, and the sink's rendered frame count at that time
+    sample_start: Option<(Instant, u64)>,
+    fps: Option<f64>,
+}
+
+impl PreviewFpsCounter {
+    const SAMPLE_DURATION: std::time::Duration = std::time::Duration::from_secs(1);
+
+    fn update(&mut self, frames_rendered: u64) {
+        let now = Instant::now();
+        match self.sample_start {
+            Some((start_time, start_frames)) => {
+                let elapsed = now - start_time;
+                if elapsed >= Self::SAMPLE_DURATION {
+                    self.fps = Some(
+                        frames_rendered.saturating_sub(start_frames) as f64 / elapsed.as_secs_f64(),
+                    );
+                    self.sample_start = Some((now, frames_rendered));
+                }
+            }
+            None => self.sample_start = Some((now, frames_rendered)),
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Which of the video's dimensions the preview is fit to the pane by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum ZoomFit {
+    #[default]
+    All,
+    Width,
+    Height,
+}
+
+impl ZoomFit {
+    const ALL: &'static [ZoomFit] = &[Self::All, Self::Width, Self::Height];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::All => "Fit all",
+            Self::Width => "Fit width",
+            Self::Height => "Fit height",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Width => "width",
+            Self::Height => "height",
+        }
+    }
 }
 
 #[derive(Debug)]
 struct VideoZoom {
     scale: f64,
     fit: bool,
+    fit_mode: ZoomFit,
+}
+
+impl Default for VideoZoom {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            fit: true,
+            fit_mode: ZoomFit::default(),
+        }
+    }
 }
 
 #[derive(Debug)]
 struct VideoScale {
     scale: usize,
     enabled: bool,
+    // stretch anamorphic video out to square pixels instead of keeping its pixel aspect ratio
+    square_pixels: bool,
+}
+
+impl Default for VideoScale {
+    fn default() -> Self {
+        Self {
+            scale: 480,
+            enabled: false,
+            square_pixels: true,
+        }
+    }
+}
+
+/// The resolution the effect is applied at, independently of the video's scale. The video is scaled down to it before
+/// the effect and back up afterwards.
+#[derive(Debug)]
+struct EffectResolution {
+    scanlines: usize,
+    enabled: bool,
+}
+
+impl Default for EffectResolution {
+    fn default() -> Self {
+        Self {
+            scanlines: 240,
+            enabled: false,
+        }
+    }
+}
+
+/// A rectangle of the video to confine the effect to, edited by dragging its edges on the preview.
+#[derive(Debug)]
+struct EffectRegionSettings {
+    // normalized to 0..1 on both axes, like the split-screen preview's rect
+    rect: Rect,
+    // as a fraction of the video's height
+    feather: f32,
+    invert: bool,
+    enabled: bool,
+}
+
+impl Default for EffectRegionSettings {
+    fn default() -> Self {
+        Self {
+            rect: Rect::from_min_max(pos2(0.25, 0.25), pos2(0.75, 0.75)),
+            feather: 0.02,
+            invert: false,
+            enabled: false,
+        }
+    }
+}
+
+impl EffectRegionSettings {
+    fn region(&self) -> Option<EffectRegion> {
+        self.enabled.then_some(EffectRegion {
+            left: self.rect.left(),
+            top: self.rect.top(),
+            right: self.rect.right(),
+            bottom: self.rect.bottom(),
+            feather: self.feather,
+            invert: self.invert,
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -271,12 +850,16 @@ enum EffectPreviewMode {
     Enabled,
     Disabled,
     SplitScreen,
+    Channel,
 }
 
 #[derive(Debug)]
 struct EffectPreviewSettings {
     mode: EffectPreviewMode,
     preview_rect: Rect,
+    // show the original image inside `preview_rect` and the effect outside it, instead of the other way around
+    invert_split: bool,
+    channel: PreviewChannel,
 }
 
 impl Default for EffectPreviewSettings {
@@ -284,6 +867,113 @@ impl Default for EffectPreviewSettings {
         Self {
             mode: Default::default(),
             preview_rect: Rect::from_min_max(pos2(0.0, 0.0), pos2(0.5, 1.0)),
+            invert_split: false,
+            channel: PreviewChannel::default(),
+        }
+    }
+}
+
+/// UI state for top-level setting groups. The solo/bypass overrides are used for debugging, and are applied on top of
+/// the effect settings without modifying them.
+#[derive(Debug, Default)]
+struct GroupUiState {
+    solo: Option<SettingID>,
+    bypassed: HashSet<SettingID>,
+    collapsed: HashSet<SettingID>,
+}
+
+/// The bandwidth scale setting's "auto" mode, as shown in the settings UI.
+struct AutoBandwidthScale<'a> {
+    enabled: &'a mut bool,
+    // the bandwidth scale derived from the video's height, if a video is loaded
+    value: Option<f32>,
+}
+
+/// Settings the user has starred, which are pinned to the top of the settings panel in the order they were starred.
+#[derive(Debug, Default)]
+struct FavoriteSettings(Vec<SettingID>);
+
+impl FavoriteSettings {
+    fn contains(&self, id: SettingID) -> bool {
+        self.0.contains(&id)
+    }
+
+    fn toggle(&mut self, id: SettingID) {
+        if self.contains(id) {
+            self.0.retain(|favorite| *favorite != id);
+        } else {
+            self.0.push(id);
+        }
+    }
+
+    fn to_storage(&self) -> String {
+        self.0
+            .iter()
+            .map(SettingID::name)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    fn from_storage(value: &str, settings_list: &SettingsList) -> Self {
+        let mut descriptors = Vec::new();
+        all_descriptors(&settings_list.settings, &mut descriptors);
+        Self(
+            value
+                .split(',')
+                .filter_map(|name| {
+                    descriptors
+                        .iter()
+                        .find(|descriptor| descriptor.id.name() == name)
+                        .map(|descriptor| descriptor.id)
+                })
+                .collect(),
+        )
+    }
+}
+
+/// How the user has customized the settings panel itself, as opposed to the settings in it.
+#[derive(Debug, Default)]
+struct SettingsPanelPrefs {
+    favorites: FavoriteSettings,
+    // sliders whose response curve has been switched between linear and logarithmic, overriding their descriptors.
+    // only affects how the slider responds, not the setting's value
+    logarithmic: HashMap<SettingID, bool>,
+}
+
+impl SettingsPanelPrefs {
+    fn is_logarithmic(&self, descriptor: &SettingDescriptor) -> Option<bool> {
+        let default = match &descriptor.kind {
+            SettingKind::Percentage { logarithmic, .. }
+            | SettingKind::FloatRange { logarithmic, .. } => *logarithmic,
+            _ => return None,
+        };
+        Some(
+            self.logarithmic
+                .get(&descriptor.id)
+                .copied()
+                .unwrap_or(default),
+        )
+    }
+}
+
+impl GroupUiState {
+    fn apply_overrides(
+        &self,
+        settings_list: &SettingsList,
+        effect_settings: &mut NtscEffectFullSettings,
+    ) {
+        for descriptor in settings_list.settings.iter() {
+            if !matches!(descriptor.kind, SettingKind::Group { .. }) {
+                continue;
+            }
+
+            let disabled = self.bypassed.contains(&descriptor.id)
+                || self.solo.is_some_and(|solo| solo != descriptor.id);
+            if disabled {
+                if let Some(enabled) = descriptor.id.get_field_mut::<bool>(effect_settings) {
+                    *enabled = false;
+                }
+            }
         }
     }
 }
@@ -309,6 +999,7 @@ enum RenderJobState {
 
 #[derive(Debug)]
 struct RenderJob {
+    src_path: PathBuf,
     settings: RenderPipelineSettings,
     pipeline: gstreamer::Pipeline,
     state: Arc<Mutex<RenderJobState>>,
@@ -317,10 +1008,82 @@ struct RenderJob {
     progress_samples: VecDeque<(f64, f64)>,
     start_time: Option<f64>,
     estimated_completion_time: Option<f64>,
+    /// Whether this job was started by the current batch render.
+    in_batch: bool,
+    /// Whether the job's final progress has been printed with `--progress-json`.
+    reported_finish: bool,
+    /// Whether a toast has been shown for the job finishing.
+    announced_finish: bool,
 }
 
 const NUM_PROGRESS_SAMPLES: usize = 5;
 const PROGRESS_SAMPLE_TIME_DELTA: f64 = 1.0;
+/// Minimum amount that progress must advance over the sample window before it's used to estimate the time remaining.
+/// Smaller deltas (e.g. while the muxer is flushing) produce wildly wrong estimates.
+const MIN_PROGRESS_SAMPLE_DELTA: f64 = 0.001;
+/// How much each new estimate of the completion time moves the displayed one, to keep it from jumping around.
+const COMPLETION_ESTIMATE_SMOOTHING: f64 = 0.5;
+
+/// File extensions that a batch render picks up from its input folder.
+const BATCH_VIDEO_EXTENSIONS: &[&str] = &[
+    "mp4", "m4v", "mkv", "mov", "avi", "webm", "mpg", "mpeg", "ts", "wmv", "flv",
+];
+
+/// The most render jobs that a batch render will have running at once. The encoders are already multithreaded, so
+/// starting every job in a large folder at once would only slow all of them down.
+const MAX_CONCURRENT_RENDERS: usize = 2;
+
+enum QueuedJobAction {
+    MoveUp,
+    MoveDown,
+    Remove,
+}
+
+/// A folder of videos being rendered with the same settings. Each video's job is created once there's room for it
+/// under `MAX_CONCURRENT_RENDERS`.
+struct BatchRender {
+    /// Source files whose jobs haven't been created yet, and the settings to render each one with.
+    pending: VecDeque<(PathBuf, RenderPipelineSettings)>,
+    /// Total number of videos in the batch.
+    total: usize,
+    /// Number of videos whose jobs have been created (or failed to be created).
+    started: usize,
+}
+
+impl RenderJob {
+    /// Print a line of JSON describing the job's progress to stdout, for scripts that run the app to keep track of.
+    fn print_progress_json(&self, state: &RenderJobState, progress: f64, eta: Option<f64>) {
+        let job = self
+            .settings
+            .output_path
+            .file_name()
+            .unwrap_or(self.settings.output_path.as_os_str())
+            .to_string_lossy()
+            .into_owned();
+        let state = match state {
+            RenderJobState::Waiting => "waiting",
+            RenderJobState::Rendering => "rendering",
+            RenderJobState::Paused => "paused",
+            RenderJobState::Complete { .. } => "complete",
+            RenderJobState::Error(_) => "error",
+        };
+        let Ok(job) = JsonValue::String(job).stringify() else {
+            return;
+        };
+        let eta = eta.map_or_else(|| String::from("null"), |eta| format!("{eta:.1}"));
+        println!(
+            "{{\"job\":{job},\"state\":\"{state}\",\"progress\":{progress:.4},\"eta\":{eta}}}"
+        );
+    }
+
+    /// Stop the job if it's still running, and clean up its partially-written output.
+    fn cancel(self) {
+        let _ = self.pipeline.set_state(gstreamer::State::Null);
+        if !matches!(*self.state.lock().unwrap(), RenderJobState::Complete { .. }) {
+            self.settings.discard_partial_output();
+        }
+    }
+}
 
 impl Drop for RenderJob {
     fn drop(&mut self) {
@@ -328,30 +1091,84 @@ impl Drop for RenderJob {
     }
 }
 
-#[derive(Debug, Clone)]
-struct H264Settings {
-    // Quality / constant rate factor (0-51)
-    crf: u8,
-    // 0-8 for libx264 presets veryslow-ultrafast
-    encode_speed: u8,
-    // Enable 10-bit color
-    ten_bit: bool,
-    // Subsample chroma to 4:2:0
-    chroma_subsampling: bool,
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum H264RateControl {
+    #[default]
+    Crf,
+    // target bitrates are in kbit/s
+    Vbr {
+        bitrate: u32,
+    },
+    TwoPass {
+        bitrate: u32,
+    },
 }
 
-impl Default for H264Settings {
-    fn default() -> Self {
-        Self {
-            crf: 23,
-            encode_speed: 5,
-            ten_bit: false,
-            chroma_subsampling: true,
-        }
+impl H264RateControl {
+    const DEFAULT_BITRATE: u32 = 8000;
+
+    /// Every mode, with the given bitrate for the ones that take one.
+    fn all(bitrate: u32) -> [H264RateControl; 3] {
+        [Self::Crf, Self::Vbr { bitrate }, Self::TwoPass { bitrate }]
     }
-}
 
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Crf => "Constant quality",
+            Self::Vbr { .. } => "Average bitrate",
+            Self::TwoPass { .. } => "Two-pass bitrate",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Crf => "crf",
+            Self::Vbr { .. } => "average_bitrate",
+            Self::TwoPass { .. } => "two_pass",
+        }
+    }
+
+    fn bitrate(&self) -> Option<u32> {
+        match self {
+            Self::Crf => None,
+            Self::Vbr { bitrate } | Self::TwoPass { bitrate } => Some(*bitrate),
+        }
+    }
+
+    fn bitrate_mut(&mut self) -> Option<&mut u32> {
+        match self {
+            Self::Crf => None,
+            Self::Vbr { bitrate } | Self::TwoPass { bitrate } => Some(bitrate),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct H264Settings {
+    rate_control: H264RateControl,
+    // Quality / constant rate factor (0-51)
+    crf: u8,
+    // 0-8 for libx264 presets veryslow-ultrafast
+    encode_speed: u8,
+    // Enable 10-bit color
+    ten_bit: bool,
+    // Subsample chroma to 4:2:0
+    chroma_subsampling: bool,
+}
+
+impl Default for H264Settings {
+    fn default() -> Self {
+        Self {
+            rate_control: H264RateControl::Crf,
+            crf: 23,
+            encode_speed: 5,
+            ten_bit: false,
+            chroma_subsampling: true,
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 enum Ffv1BitDepth {
     #[default]
     Bits8,
@@ -381,6 +1198,8 @@ enum OutputCodec {
     #[default]
     H264,
     Ffv1,
+    // one numbered image file per frame
+    ImageSequence,
 }
 
 impl OutputCodec {
@@ -388,2366 +1207,8743 @@ impl OutputCodec {
         match self {
             Self::H264 => "H.264",
             Self::Ffv1 => "FFV1 (Lossless)",
+            Self::ImageSequence => "Image sequence",
+        }
+    }
+
+    /// The audio codecs that this codec's container can hold. The first one is the default.
+    fn audio_codecs(&self) -> &'static [AudioCodec] {
+        match self {
+            Self::H264 => &[AudioCodec::Aac, AudioCodec::Opus, AudioCodec::Copy],
+            Self::Ffv1 => &[
+                AudioCodec::Flac,
+                AudioCodec::Aac,
+                AudioCodec::Opus,
+                AudioCodec::Copy,
+            ],
+            Self::ImageSequence => &[],
+        }
+    }
+
+    fn default_audio_codec(&self) -> AudioCodec {
+        self.audio_codecs().first().copied().unwrap_or_default()
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum ImageSequenceFormat {
+    #[default]
+    Png,
+    Tiff,
+}
+
+impl ImageSequenceFormat {
+    const ALL: &'static [ImageSequenceFormat] = &[Self::Png, Self::Tiff];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Png => "PNG",
+            Self::Tiff => "TIFF",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Tiff => "tiff",
         }
     }
 
     fn extension(&self) -> &'static str {
         match self {
-            Self::H264 => "mp4",
-            Self::Ffv1 => "mkv",
+            Self::Png => "png",
+            Self::Tiff => "tif",
         }
     }
 }
 
-#[derive(Debug, Clone)]
-enum RenderPipelineCodec {
-    H264(H264Settings),
-    Ffv1(Ffv1Settings),
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum StillImageFormat {
+    #[default]
     Png,
+    Jpeg,
+    Tiff,
+    WebP,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-enum RenderInterlaceMode {
-    Progressive,
-    TopFieldFirst,
-    BottomFieldFirst,
+impl StillImageFormat {
+    const ALL: &'static [StillImageFormat] = &[Self::Png, Self::Jpeg, Self::Tiff, Self::WebP];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Png => "PNG",
+            Self::Jpeg => "JPEG",
+            Self::Tiff => "TIFF",
+            Self::WebP => "WebP",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpeg",
+            Self::Tiff => "tiff",
+            Self::WebP => "webp",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+            Self::Tiff => "tif",
+            Self::WebP => "webp",
+        }
+    }
+
+    /// The encoder for this format, and the plugin package that provides it.
+    fn encoder(&self) -> (&'static str, &'static str) {
+        match self {
+            Self::Png => ("pngenc", "gst-plugins-good"),
+            Self::Jpeg => ("jpegenc", "gst-plugins-good"),
+            Self::Tiff => ("avenc_tiff", "gst-libav"),
+            Self::WebP => ("webpenc", "gst-plugins-bad"),
+        }
+    }
+
+    /// Whether the format is lossy, and so has a quality setting.
+    fn has_quality(&self) -> bool {
+        matches!(self, Self::Jpeg | Self::WebP)
+    }
+
+    /// Whether the format can optionally be encoded losslessly instead.
+    fn has_lossless(&self) -> bool {
+        matches!(self, Self::WebP)
+    }
+
+    fn is_available(&self) -> bool {
+        gstreamer::ElementFactory::find(self.encoder().0).is_some()
+    }
+
+    fn supports_alpha(&self) -> bool {
+        !matches!(self, Self::Jpeg)
+    }
 }
 
 #[derive(Debug, Clone)]
-struct RenderPipelineSettings {
-    codec_settings: RenderPipelineCodec,
-    output_path: PathBuf,
-    duration: ClockTime,
-    interlacing: RenderInterlaceMode,
-    effect_settings: NtscEffect,
+struct StillImageSettings {
+    format: StillImageFormat,
+    // 1-100, for the lossy formats
+    quality: u8,
+    // for formats that can be either lossy or lossless
+    lossless: bool,
 }
 
-#[derive(Default, Debug, Clone)]
-struct RenderSettings {
-    output_codec: OutputCodec,
-    // we want to keep these around even if the user changes their mind and selects ffv1, so they don't lose the
-    // settings if they change back
-    h264_settings: H264Settings,
-    ffv1_settings: Ffv1Settings,
-    output_path: PathBuf,
-    duration: ClockTime,
-    interlaced: bool,
+impl Default for StillImageSettings {
+    fn default() -> Self {
+        Self {
+            format: StillImageFormat::default(),
+            quality: 90,
+            lossless: false,
+        }
+    }
 }
 
-impl From<&RenderSettings> for RenderPipelineCodec {
-    fn from(value: &RenderSettings) -> Self {
-        match value.output_codec {
-            OutputCodec::H264 => RenderPipelineCodec::H264(value.h264_settings.clone()),
-            OutputCodec::Ffv1 => RenderPipelineCodec::Ffv1(value.ffv1_settings.clone()),
+impl StillImageSettings {
+    fn write_json(&self, map: &mut HashMap<String, JsonValue>) {
+        map.insert(
+            "still_format".to_string(),
+            JsonValue::String(self.format.name().to_string()),
+        );
+        map.insert(
+            "still_quality".to_string(),
+            JsonValue::Number(self.quality as f64),
+        );
+        map.insert(
+            "still_lossless".to_string(),
+            JsonValue::Boolean(self.lossless),
+        );
+    }
+
+    fn read_json(&mut self, map: &HashMap<String, JsonValue>) {
+        if let Some(format) = json_string(map, "still_format") {
+            self.format = StillImageFormat::ALL
+                .iter()
+                .copied()
+                .find(|mode| mode.name() == format)
+                .unwrap_or_default();
+        }
+        if let Some(quality) = json_number(map, "still_quality") {
+            self.quality = quality.clamp(1.0, 100.0) as u8;
+        }
+        if let Some(lossless) = json_bool(map, "still_lossless") {
+            self.lossless = lossless;
         }
     }
 }
 
-#[derive(Default, PartialEq, Eq)]
-enum LeftPanelState {
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputColorMatrix {
+    // standard-definition video, which is what NTSC content conventionally uses
     #[default]
-    EffectSettings,
-    RenderSettings,
+    Bt601,
+    // high-definition video
+    Bt709,
 }
 
-#[derive(Default, PartialEq, Eq)]
-enum ColorTheme {
-    Dark,
-    Light,
-    #[default]
-    System,
-}
+impl OutputColorMatrix {
+    const ALL: &'static [OutputColorMatrix] = &[Self::Bt601, Self::Bt709];
 
-impl ColorTheme {
-    fn visuals(&self, info: &eframe::IntegrationInfo) -> egui::Visuals {
-        match &self {
-            ColorTheme::Dark => egui::Visuals::dark(),
-            ColorTheme::Light => egui::Visuals::light(),
-            ColorTheme::System => match info.system_theme {
-                Some(eframe::Theme::Dark) => egui::Visuals::dark(),
-                Some(eframe::Theme::Light) => egui::Visuals::light(),
-                None => egui::Visuals::default(),
-            },
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Bt601 => "BT.601",
+            Self::Bt709 => "BT.709",
         }
     }
-}
 
-impl From<&ColorTheme> for &str {
-    fn from(value: &ColorTheme) -> Self {
-        match value {
-            ColorTheme::Dark => "Dark",
-            ColorTheme::Light => "Light",
-            ColorTheme::System => "System",
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Bt601 => "bt601",
+            Self::Bt709 => "bt709",
         }
     }
 }
 
-impl TryFrom<&str> for ColorTheme {
-    type Error = ();
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value {
-            "Dark" => Ok(ColorTheme::Dark),
-            "Light" => Ok(ColorTheme::Light),
-            "System" => Ok(ColorTheme::System),
-            _ => Err(()),
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputColorRange {
+    // 16-235 for luma, as broadcast video uses
+    #[default]
+    Limited,
+    // 0-255 for luma
+    Full,
+}
+
+impl OutputColorRange {
+    const ALL: &'static [OutputColorRange] = &[Self::Limited, Self::Full];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Limited => "Limited",
+            Self::Full => "Full",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Limited => "limited",
+            Self::Full => "full",
         }
     }
 }
 
-trait LayoutHelper {
-    fn ltr<R>(&mut self, add_contents: impl FnOnce(&mut Self) -> R) -> egui::InnerResponse<R>;
-    fn rtl<R>(&mut self, add_contents: impl FnOnce(&mut Self) -> R) -> egui::InnerResponse<R>;
+/// The colorimetry that YUV output is converted to and tagged with, so that players convert it back to RGB correctly.
+fn output_colorimetry(
+    matrix: OutputColorMatrix,
+    range: OutputColorRange,
+) -> gstreamer_video::VideoColorimetry {
+    let range = match range {
+        OutputColorRange::Limited => gstreamer_video::VideoColorRange::Range16235,
+        OutputColorRange::Full => gstreamer_video::VideoColorRange::Range0255,
+    };
+    match matrix {
+        OutputColorMatrix::Bt601 => gstreamer_video::VideoColorimetry::new(
+            range,
+            gstreamer_video::VideoColorMatrix::Bt601,
+            gstreamer_video::VideoTransferFunction::Bt601,
+            gstreamer_video::VideoColorPrimaries::Smpte170m,
+        ),
+        OutputColorMatrix::Bt709 => gstreamer_video::VideoColorimetry::new(
+            range,
+            gstreamer_video::VideoColorMatrix::Bt709,
+            gstreamer_video::VideoTransferFunction::Bt709,
+            gstreamer_video::VideoColorPrimaries::Bt709,
+        ),
+    }
 }
 
-fn ui_with_layout<'c, R>(
-    ui: &mut egui::Ui,
-    layout: egui::Layout,
-    add_contents: Box<dyn FnOnce(&mut egui::Ui) -> R + 'c>,
-) -> egui::InnerResponse<R> {
-    let initial_size = vec2(
-        ui.available_size_before_wrap().x,
-        ui.spacing().interact_size.y,
+/// Only pngenc has a "snapshot" mode that ends the stream after one frame. For the other still image encoders, let the
+/// first frame through and end the stream when the next one arrives.
+fn end_after_first_frame(video_enc: &gstreamer::Element) {
+    let mut seen_frame = false;
+    video_enc.static_pad("sink").unwrap().add_probe(
+        gstreamer::PadProbeType::BUFFER,
+        move |pad, _| {
+            if !seen_frame {
+                seen_frame = true;
+                return gstreamer::PadProbeReturn::Ok;
+            }
+            pad.send_event(gstreamer::event::Eos::new());
+            gstreamer::PadProbeReturn::Drop
+        },
     );
+}
 
-    ui.allocate_ui_with_layout(initial_size, layout, |ui| add_contents(ui))
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum AudioCodec {
+    #[default]
+    Aac,
+    Opus,
+    Flac,
+    // mux the source's audio stream into the output without re-encoding it
+    Copy,
 }
 
-impl LayoutHelper for egui::Ui {
-    fn ltr<R>(&mut self, add_contents: impl FnOnce(&mut egui::Ui) -> R) -> egui::InnerResponse<R> {
-        ui_with_layout(
-            self,
-            egui::Layout::left_to_right(egui::Align::Center),
-            Box::new(add_contents),
-        )
+impl AudioCodec {
+    const ALL: &'static [AudioCodec] = &[Self::Aac, Self::Opus, Self::Flac, Self::Copy];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Aac => "AAC",
+            Self::Opus => "Opus",
+            Self::Flac => "FLAC",
+            Self::Copy => "Copy source audio",
+        }
     }
 
-    fn rtl<R>(&mut self, add_contents: impl FnOnce(&mut egui::Ui) -> R) -> egui::InnerResponse<R> {
-        ui_with_layout(
-            self,
-            egui::Layout::right_to_left(egui::Align::Center),
-            Box::new(add_contents),
-        )
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Aac => "aac",
+            Self::Opus => "opus",
+            Self::Flac => "flac",
+            Self::Copy => "copy",
+        }
     }
 }
 
-type AppFn = Box<dyn FnOnce(&mut NtscApp) -> Result<(), ApplicationError> + Send>;
-
-struct AppExecutor {
-    waker: Waker,
-    ctx: egui::Context,
-    run_tasks: Vec<Pin<Box<dyn Future<Output = Option<AppFn>> + Send>>>,
-    queued: Vec<AppFn>,
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum OverlayCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
 }
 
-impl AppExecutor {
-    fn new(ctx: egui::Context) -> Self {
-        let waker_ctx = ctx.clone();
-        AppExecutor {
-            waker: waker_fn::waker_fn(move || waker_ctx.request_repaint()),
-            ctx,
-            run_tasks: Vec::new(),
-            queued: Vec::new(),
+impl OverlayCorner {
+    const ALL: &'static [OverlayCorner] = &[
+        Self::TopLeft,
+        Self::TopRight,
+        Self::BottomLeft,
+        Self::BottomRight,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::TopLeft => "Top left",
+            Self::TopRight => "Top right",
+            Self::BottomLeft => "Bottom left",
+            Self::BottomRight => "Bottom right",
         }
     }
 
-    #[must_use]
-    fn tick(&mut self) -> Vec<AppFn> {
-        let mut i = 0usize;
-        let mut queued = std::mem::take(&mut self.queued);
-        while i < self.run_tasks.len() {
-            let task = &mut self.run_tasks[i];
-            match task.poll(&mut Context::from_waker(&self.waker)) {
-                Poll::Pending => {
-                    i += 1;
-                }
-                Poll::Ready(f) => {
-                    if let Some(f) = f {
-                        queued.push(f);
-                    }
-                    let _ = self.run_tasks.swap_remove(i);
-                }
-            }
+    fn name(&self) -> &'static str {
+        match self {
+            Self::TopLeft => "top_left",
+            Self::TopRight => "top_right",
+            Self::BottomLeft => "bottom_left",
+            Self::BottomRight => "bottom_right",
         }
+    }
 
-        queued
+    /// The values of a text overlay element's "halignment" and "valignment" properties that place it in this corner.
+    fn text_alignment(&self) -> (&'static str, &'static str) {
+        match self {
+            Self::TopLeft => ("left", "top"),
+            Self::TopRight => ("right", "top"),
+            Self::BottomLeft => ("left", "bottom"),
+            Self::BottomRight => ("right", "bottom"),
+        }
     }
+}
 
-    fn spawn(
-        &mut self,
-        future: impl Future<Output = Option<AppFn>> + 'static + Send,
-        next_frame: bool,
-    ) {
-        let mut boxed = Box::pin(future);
-        if next_frame {
-            self.run_tasks.push(boxed);
-            self.ctx.request_repaint();
-        } else {
-            match boxed.poll(&mut Context::from_waker(&self.waker)) {
-                Poll::Ready(f) => {
-                    if let Some(f) = f {
-                        self.queued.push(f);
-                    }
-                }
-                Poll::Pending => {
-                    self.run_tasks.push(boxed);
-                }
-            }
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum TimecodeFormat {
+    // HH:MM:SS:FF, counted from the start of the render
+    #[default]
+    Smpte,
+    // HH:MM:SS.mmm, counted from the start of the render
+    Elapsed,
+}
+
+impl TimecodeFormat {
+    const ALL: &'static [TimecodeFormat] = &[Self::Smpte, Self::Elapsed];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Smpte => "SMPTE timecode",
+            Self::Elapsed => "Elapsed time",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Smpte => "smpte",
+            Self::Elapsed => "elapsed",
         }
     }
 }
 
-struct NtscApp {
-    gstreamer_initialized: Arc<AtomicBool>,
-    settings_list: SettingsList,
-    executor: Arc<Mutex<AppExecutor>>,
-    pipeline: Option<PipelineInfo>,
-    undoer: Undoer<NtscEffectFullSettings>,
-    video_zoom: VideoZoom,
-    video_scale: VideoScale,
-    audio_volume: AudioVolume,
-    effect_preview: EffectPreviewSettings,
-    left_panel_state: LeftPanelState,
-    effect_settings: NtscEffectFullSettings,
-    render_settings: RenderSettings,
-    render_jobs: Vec<RenderJob>,
-    settings_json_paste: String,
-    last_error: Option<String>,
-    color_theme: ColorTheme,
-    credits_dialog_open: bool,
-    licenses_dialog_open: bool,
+#[derive(Default, Debug, Clone)]
+struct TimecodeOverlaySettings {
+    format: TimecodeFormat,
+    corner: OverlayCorner,
 }
 
-impl NtscApp {
-    fn new(
-        ctx: egui::Context,
-        settings_list: SettingsList,
-        effect_settings: NtscEffectFullSettings,
-        color_theme: ColorTheme,
-        gstreamer_initialized: Arc<AtomicBool>,
-    ) -> Self {
+#[derive(Debug, Clone)]
+struct TextOverlaySettings {
+    text: String,
+    font_size: u32,
+    corner: OverlayCorner,
+    // 0-1
+    opacity: f32,
+}
+
+impl Default for TextOverlaySettings {
+    fn default() -> Self {
         Self {
-            gstreamer_initialized,
-            settings_list,
-            pipeline: None,
-            undoer: Undoer::default(),
-            executor: Arc::new(Mutex::new(AppExecutor::new(ctx.clone()))),
-            video_zoom: VideoZoom {
-                scale: 1.0,
-                fit: true,
-            },
-            video_scale: VideoScale {
-                scale: 480,
-                enabled: false,
-            },
-            audio_volume: AudioVolume::default(),
-            effect_preview: EffectPreviewSettings::default(),
-            left_panel_state: LeftPanelState::default(),
-            effect_settings,
-            render_settings: RenderSettings::default(),
-            render_jobs: Vec::new(),
-            settings_json_paste: String::new(),
-            last_error: None,
-            color_theme,
-            credits_dialog_open: false,
-            licenses_dialog_open: false,
+            text: String::new(),
+            font_size: 24,
+            corner: OverlayCorner::TopLeft,
+            opacity: 1.0,
         }
     }
+}
 
-    fn spawn(&mut self, future: impl Future<Output = Option<AppFn>> + 'static + Send) {
-        self.executor.lock().unwrap().spawn(future, false);
+impl TextOverlaySettings {
+    /// The text color in the ARGB format that textoverlay's "color" and "outline-color" properties take.
+    fn argb(&self, rgb: u32) -> u32 {
+        let alpha = (self.opacity.clamp(0.0, 1.0) * 255.0).round() as u32;
+        (alpha << 24) | (rgb & 0xFFFFFF)
     }
+}
 
-    fn execute_fn_next_frame<T: Future<Output = Option<AppFn>> + 'static + Send>(
-        &self,
-    ) -> impl Fn(T) + Send {
-        let weak_exec = self.executor.downgrade();
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFramerate {
+    #[default]
+    SameAsSource,
+    Fps23_976,
+    Fps24,
+    Fps25,
+    Fps29_97,
+    Fps30,
+    Fps50,
+    Fps59_94,
+    Fps60,
+}
 
-        move |future: T| {
-            if let Some(exec) = weak_exec.upgrade() {
-                exec.lock().unwrap().spawn(future, true);
-            }
+impl OutputFramerate {
+    const ALL: &'static [OutputFramerate] = &[
+        Self::SameAsSource,
+        Self::Fps23_976,
+        Self::Fps24,
+        Self::Fps25,
+        Self::Fps29_97,
+        Self::Fps30,
+        Self::Fps50,
+        Self::Fps59_94,
+        Self::Fps60,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::SameAsSource => "Same as source",
+            Self::Fps23_976 => "23.976",
+            Self::Fps24 => "24",
+            Self::Fps25 => "25",
+            Self::Fps29_97 => "29.97",
+            Self::Fps30 => "30",
+            Self::Fps50 => "50",
+            Self::Fps59_94 => "59.94",
+            Self::Fps60 => "60",
         }
     }
 
-    fn tick(&mut self) {
-        loop {
-            // Get the functions to be executed at the end of the completed futures.
-            let app_fns = {
-                let exec = Arc::clone(&self.executor);
-                let mut exec = exec.lock().unwrap();
-                exec.tick()
-            };
+    fn fraction(&self) -> Option<gstreamer::Fraction> {
+        Some(match self {
+            Self::SameAsSource => return None,
+            Self::Fps23_976 => gstreamer::Fraction::new(24000, 1001),
+            Self::Fps24 => gstreamer::Fraction::from(24),
+            Self::Fps25 => gstreamer::Fraction::from(25),
+            Self::Fps29_97 => gstreamer::Fraction::new(30000, 1001),
+            Self::Fps30 => gstreamer::Fraction::from(30),
+            Self::Fps50 => gstreamer::Fraction::from(50),
+            Self::Fps59_94 => gstreamer::Fraction::new(60000, 1001),
+            Self::Fps60 => gstreamer::Fraction::from(60),
+        })
+    }
+}
 
-            // If there are none, we're done. If there are, loop--executing them may spawn more futures.
-            if app_fns.is_empty() {
-                break;
-            }
+#[derive(Debug, Clone)]
+enum RenderPipelineCodec {
+    H264(H264Settings),
+    Ffv1(Ffv1Settings),
+    // a single snapshot of the current frame
+    Still(StillImageSettings),
+    // every frame, written into the output directory with a filename pattern containing the frame number
+    ImageSequence {
+        format: ImageSequenceFormat,
+        pattern: String,
+    },
+}
 
-            // Execute functions outside the executor--if they call `spawn`, we don't want to recursively lock the
-            // executor's mutex.
-            for f in app_fns {
-                self.handle_result_with(f);
-            }
+impl RenderPipelineCodec {
+    /// Bits per component of the encoded video.
+    fn bit_depth(&self) -> usize {
+        match self {
+            RenderPipelineCodec::H264(H264Settings { ten_bit: true, .. }) => 10,
+            RenderPipelineCodec::Ffv1(Ffv1Settings { bit_depth, .. }) => match bit_depth {
+                Ffv1BitDepth::Bits8 => 8,
+                Ffv1BitDepth::Bits10 => 10,
+                Ffv1BitDepth::Bits12 => 12,
+            },
+            _ => 8,
         }
     }
+}
 
-    fn load_video(&mut self, ctx: &egui::Context, path: PathBuf) -> Result<(), ApplicationError> {
-        self.remove_pipeline().context(LoadVideoSnafu)?;
-        self.pipeline = Some(
-            self.create_preview_pipeline(ctx, path)
-                .context(LoadVideoSnafu)?,
-        );
-
-        Ok(())
-    }
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RenderInterlaceMode {
+    Progressive,
+    TopFieldFirst,
+    BottomFieldFirst,
+}
 
-    fn rescale_video(
-        pipeline: &gstreamer::Pipeline,
-        seek_pos: ClockTime,
-        scanlines: Option<usize>,
-    ) -> Result<(), GstreamerError> {
-        let caps_filter = pipeline.by_name("caps_filter").unwrap();
+/// A new ID for a render job, unique within this run of the app.
+fn next_render_job_id() -> u64 {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
 
-        if let Some(scanlines) = scanlines {
-            let scale_caps = pipeline
-                .by_name("video_scale")
-                .and_then(|elem| elem.static_pad("sink"))
-                .and_then(|pad| pad.current_caps());
-            let scale_caps = match scale_caps {
-                Some(caps) => caps,
-                None => return Ok(()),
-            };
+#[derive(Debug, Clone)]
+struct RenderPipelineSettings {
+    codec_settings: RenderPipelineCodec,
+    output_path: PathBuf,
+    duration: ClockTime,
+    interlacing: RenderInterlaceMode,
+    // if unset, the output has no audio track
+    audio_codec: Option<AudioCodec>,
+    // which of the source's audio tracks to use
+    audio_track: usize,
+    // if set, burn a timecode into the video after applying the effect
+    timecode_overlay: Option<TimecodeOverlaySettings>,
+    // if set, draw this text over the video after applying the effect
+    text_overlay: Option<TextOverlaySettings>,
+    // if set, convert the video to this framerate before applying the effect
+    output_framerate: Option<gstreamer::Fraction>,
+    // if set, keep the source's alpha channel instead of rendering opaque frames. only image output can store it
+    preserve_alpha: bool,
+    // if set, the partially-written output of a failed or cancelled render is kept instead of deleted
+    keep_partial_output: bool,
+    // if set, dither the effect's high-precision output when reducing it to 8 bits, to avoid banding
+    dither: bool,
+    // if set, apply the effect to each frame in strips to reduce peak memory usage
+    low_memory: bool,
+    // how to tone-map HDR sources into SDR before applying the effect. SDR sources are unaffected
+    tonemap: TonemapSettings,
+    // if set, render at the source's resolution even if the preview is scaled down
+    full_resolution: bool,
+    // if set, apply the effect at no more than this many scanlines and scale the result back up afterwards
+    effect_scanlines: Option<usize>,
+    // if set, only apply the effect within this region of the frame
+    region: Option<EffectRegion>,
+    // the colorimetry to encode and tag YUV output with. RGB image output ignores these
+    color_matrix: OutputColorMatrix,
+    color_range: OutputColorRange,
+    // 0-based index of the current encoding pass, for multi-pass encodes
+    pass: usize,
+    // identifies the render job, which all of its passes share
+    job_id: u64,
+    effect_settings: NtscEffect,
+    // the random seed to render with, overriding the one in `effect_settings` so that renders of the same settings can
+    // have different noise
+    seed: i32,
+    // settings that change over the course of the video, overriding their values in `effect_settings`
+    keyframes: KeyframeTracks,
+}
 
-            if let Some((dst_width, dst_height)) = scale_from_caps(&scale_caps, scanlines) {
-                caps_filter.set_property(
-                    "caps",
-                    gstreamer_video::VideoCapsBuilder::default()
-                        .width(dst_width)
-                        .height(dst_height)
-                        .build(),
-                );
-            }
-        } else {
-            caps_filter.set_property("caps", gstreamer_video::VideoCapsBuilder::default().build());
+impl RenderPipelineSettings {
+    /// The effect settings to render with, with `seed` in place of their own random seed.
+    fn seeded_effect_settings(&self) -> NtscEffect {
+        NtscEffect {
+            random_seed: self.seed,
+            ..self.effect_settings.clone()
         }
+    }
 
-        pipeline.seek_simple(
-            gstreamer::SeekFlags::FLUSH | gstreamer::SeekFlags::ACCURATE,
-            pipeline.query_position::<ClockTime>().unwrap_or(seek_pos),
-        )?;
-
-        Ok(())
+    fn num_passes(&self) -> usize {
+        match &self.codec_settings {
+            RenderPipelineCodec::H264(H264Settings {
+                rate_control: H264RateControl::TwoPass { .. },
+                ..
+            }) => 2,
+            _ => 1,
+        }
     }
 
-    fn set_still_image_framerate(
-        pipeline: &gstreamer::Pipeline,
-        framerate: gstreamer::Fraction,
-    ) -> Result<Option<gstreamer::Fraction>, GstreamerError> {
-        let Some(caps_filter) = pipeline.by_name("framerate_caps_filter") else {
-            return Ok(None);
+    /// The GStreamer elements this render needs beyond the core ones the preview also uses, each paired with the plugin
+    /// package that provides it.
+    fn required_elements(&self) -> Vec<(&'static str, &'static str)> {
+        let mut elements = match &self.codec_settings {
+            RenderPipelineCodec::H264(_) => vec![
+                ("x264enc", "gst-plugins-ugly"),
+                ("mp4mux", "gst-plugins-good"),
+            ],
+            RenderPipelineCodec::Ffv1(_) => vec![
+                ("avenc_ffv1", "gst-libav"),
+                ("matroskamux", "gst-plugins-good"),
+            ],
+            RenderPipelineCodec::Still(still_settings) => vec![still_settings.format.encoder()],
+            RenderPipelineCodec::ImageSequence { format, .. } => match format {
+                ImageSequenceFormat::Png => vec![("pngenc", "gst-plugins-good")],
+                ImageSequenceFormat::Tiff => vec![("avenc_tiff", "gst-libav")],
+            },
         };
 
-        caps_filter.set_property(
-            "caps",
-            VideoCapsBuilder::default().framerate(framerate).build(),
-        );
-        // This seek is necessary to prevent caps negotiation from failing due to race conditions, for some reason.
-        // It seems like in some cases, there would be "tearing" in the caps between different elements, where some
-        // elements' caps would use the old framerate and some would use the new framerate. This would cause caps
-        // negotiation to fail, even though the caps filter sends a "reconfigure" event. This in turn woulc make the
-        // entire pipeline error out.
-        if let Some(seek_pos) = pipeline.query_position::<ClockTime>() {
-            pipeline.seek_simple(
-                gstreamer::SeekFlags::FLUSH | gstreamer::SeekFlags::ACCURATE,
-                seek_pos,
-            )?;
-            Ok(Some(framerate))
-        } else {
-            Ok(None)
+        match self.audio_codec {
+            Some(AudioCodec::Aac) => elements.push(("avenc_aac", "gst-libav")),
+            Some(AudioCodec::Opus) => elements.push(("opusenc", "gst-plugins-base")),
+            Some(AudioCodec::Flac) => elements.push(("flacenc", "gst-plugins-good")),
+            Some(AudioCodec::Copy) | None => {}
+        }
+        if self.interlacing != RenderInterlaceMode::Progressive {
+            elements.push(("interlace", "gst-plugins-bad"));
+        }
+        if let Some(timecode_overlay) = &self.timecode_overlay {
+            if timecode_overlay.format == TimecodeFormat::Smpte {
+                elements.push(("timecodestamper", "gst-plugins-bad"));
+            }
+            elements.push(("timeoverlay", "gst-plugins-base"));
+        }
+        if self.text_overlay.is_some() {
+            elements.push(("textoverlay", "gst-plugins-base"));
         }
-    }
 
-    fn set_volume(pipeline: &gstreamer::Pipeline, volume: f64, mute: bool) {
-        let Some(audio_volume) = pipeline.by_name("audio_volume") else {
-            return;
-        };
+        elements
+    }
 
-        audio_volume.set_property("volume", volume);
-        audio_volume.set_property("mute", mute);
+    /// The elements from [`Self::required_elements`] that aren't installed.
+    fn missing_elements(&self) -> Vec<(&'static str, &'static str)> {
+        self.required_elements()
+            .into_iter()
+            .filter(|(element, _)| gstreamer::ElementFactory::find(element).is_none())
+            .collect()
     }
 
-    fn sink_preview_mode(preview_settings: &EffectPreviewSettings) -> EffectPreviewSetting {
-        match preview_settings.mode {
-            EffectPreviewMode::Enabled => EffectPreviewSetting::Enabled,
-            EffectPreviewMode::Disabled => EffectPreviewSetting::Disabled,
-            EffectPreviewMode::SplitScreen => {
-                EffectPreviewSetting::SplitScreen(preview_settings.preview_rect)
-            }
-        }
+    /// The file that x264 uses to pass statistics between encoding passes. Jobs writing to files with the same name in
+    /// different folders, or to the same file, each get their own.
+    fn multipass_cache_file(&self) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+        self.output_path.hash(&mut hasher);
+        let path_hash = hasher.finish();
+        std::env::temp_dir().join(format!("ntsc-rs-{path_hash:016x}-{}-x264.log", self.job_id))
     }
 
-    fn create_preview_pipeline(
-        &mut self,
-        ctx: &egui::Context,
-        path: PathBuf,
-    ) -> Result<PipelineInfo, GstreamerError> {
-        let src = gstreamer::ElementFactory::make("filesrc")
-            .property("location", path.as_path())
-            .build()?;
+    /// Whether this pass only gathers statistics for the next one, rather than producing the output.
+    fn is_analysis_pass(&self) -> bool {
+        self.pass + 1 < self.num_passes()
+    }
 
-        let audio_sink = gstreamer::ElementFactory::make("autoaudiosink").build()?;
+    /// The file that the output is written to while rendering, which is moved to `output_path` once it's complete.
+    fn partial_output_path(&self) -> PathBuf {
+        let mut partial_path = self.output_path.clone().into_os_string();
+        partial_path.push(".part");
+        PathBuf::from(partial_path)
+    }
 
-        let tex = ctx.load_texture(
-            "preview",
-            egui::ColorImage::from_rgb([1, 1], &[0, 0, 0]),
-            egui::TextureOptions::LINEAR,
-        );
-        let tex_sink = SinkTexture(Some(tex.clone()));
-        let egui_ctx = EguiCtx(Some(ctx.clone()));
-        let video_sink = gstreamer::ElementFactory::make("eguisink")
-            .property("texture", tex_sink)
-            .property("ctx", egui_ctx)
-            .property(
-                "settings",
-                NtscFilterSettings((&self.effect_settings).into()),
-            )
-            .property(
-                "preview-mode",
-                Self::sink_preview_mode(&self.effect_preview),
-            )
-            .build()?;
+    /// Move the completed output from its temporary file to its real name.
+    fn finish_partial_output(&self) -> std::io::Result<()> {
+        // Image sequences are written straight to their final names
+        if matches!(
+            self.codec_settings,
+            RenderPipelineCodec::ImageSequence { .. }
+        ) {
+            return Ok(());
+        }
 
-        let pipeline_info_state = Arc::new(Mutex::new(PipelineInfoState::Loading));
-        let pipeline_info_state_for_handler = Arc::clone(&pipeline_info_state);
-        let pipeline_info_state_for_callback = Arc::clone(&pipeline_info_state);
-        let at_eos = Arc::new(Mutex::new(false));
-        let at_eos_for_handler = Arc::clone(&at_eos);
-        let ctx_for_handler = ctx.clone();
-        let ctx_for_callback = ctx.clone();
+        let partial_path = self.partial_output_path();
+        if std::fs::rename(&partial_path, &self.output_path).is_err() {
+            // Renaming doesn't work across filesystems
+            std::fs::copy(&partial_path, &self.output_path)?;
+            std::fs::remove_file(&partial_path)?;
+        }
+        Ok(())
+    }
 
-        let metadata = Arc::new(Mutex::new(PipelineMetadata::default()));
-        let metadata_for_audio_handler = metadata.clone();
-        let metadata_for_bus_handler = metadata.clone();
+    fn discard_partial_output(&self) {
+        if !self.keep_partial_output
+            && !matches!(
+                self.codec_settings,
+                RenderPipelineCodec::ImageSequence { .. }
+            )
+        {
+            let _ = std::fs::remove_file(self.partial_output_path());
+        }
+    }
 
-        let audio_sink_for_closure = audio_sink.clone();
-        let video_sink_for_closure = video_sink.clone();
+    /// Serialize a render of `src_path` with these settings, for saving in a render queue file. Paths inside
+    /// `base_directory` (the queue file's directory) are written relative to it, and all others are written as
+    /// absolute paths, so that a queue file kept alongside its videos can be moved to another machine.
+    fn to_json(
+        &self,
+        src_path: &Path,
+        base_directory: &Path,
+        settings_list: &SettingsList,
+    ) -> JsonValue {
+        let queue_path = |path: &Path| {
+            JsonValue::String(
+                path.strip_prefix(base_directory)
+                    .unwrap_or(path)
+                    .to_string_lossy()
+                    .into_owned(),
+            )
+        };
 
-        let pipeline = create_pipeline(
-            src.clone(),
-            move |pipeline| {
-                pipeline.add(&audio_sink_for_closure)?;
-                metadata_for_audio_handler.lock().unwrap().has_audio = Some(true);
-                Ok(Some(audio_sink_for_closure))
-            },
-            move |pipeline| {
-                pipeline.add(&video_sink_for_closure)?;
-                Ok(video_sink_for_closure)
-            },
-            move |bus, msg| {
-                debug!("{:?}", msg);
-                let at_eos = &at_eos_for_handler;
-                let ctx = &ctx_for_handler;
-                let pipeline_info_state = &pipeline_info_state_for_handler;
-                let metadata = &metadata_for_bus_handler;
-
-                let handle_msg = move |_bus, msg: &gstreamer::Message| -> Option<()> {
-                    // Make sure we're listening to a pipeline event
-                    let src = msg.src()?;
-
-                    if let gstreamer::MessageView::Error(err_msg) = msg.view() {
-                        debug!("handling error message: {:?}", msg);
-                        let mut pipeline_state = pipeline_info_state.lock().unwrap();
-                        if !matches!(&*pipeline_state, PipelineInfoState::Error(_)) {
-                            *pipeline_state = PipelineInfoState::Error(err_msg.error().into());
-                            ctx.request_repaint();
-                        }
-                    }
-
-                    if let Some(pipeline) = src.downcast_ref::<gstreamer::Pipeline>() {
-                        // We want to pause the pipeline at EOS, but setting an element's state inside the bus handler doesn't
-                        // work. Instead, wait for the next egui event loop then pause.
-                        if let gstreamer::MessageView::Eos(_) = msg.view() {
-                            *at_eos.lock().unwrap() = true;
-                            ctx.request_repaint();
-                        }
+        let mut map = HashMap::<String, JsonValue>::new();
+        map.insert("source_path".to_string(), queue_path(src_path));
+        map.insert("output_path".to_string(), queue_path(&self.output_path));
+        let codec = match &self.codec_settings {
+            RenderPipelineCodec::H264(h264_settings) => {
+                h264_settings.write_json(&mut map);
+                "h264"
+            }
+            RenderPipelineCodec::Ffv1(ffv1_settings) => {
+                ffv1_settings.write_json(&mut map);
+                "ffv1"
+            }
+            RenderPipelineCodec::Still(still_settings) => {
+                still_settings.write_json(&mut map);
+                "still"
+            }
+            RenderPipelineCodec::ImageSequence { format, pattern } => {
+                map.insert(
+                    "image_sequence_format".to_string(),
+                    JsonValue::String(format.name().to_string()),
+                );
+                map.insert(
+                    "image_sequence_pattern".to_string(),
+                    JsonValue::String(pattern.clone()),
+                );
+                "image_sequence"
+            }
+        };
+        map.insert("codec".to_string(), JsonValue::String(codec.to_string()));
+        map.insert(
+            "duration_ms".to_string(),
+            JsonValue::Number(self.duration.mseconds() as f64),
+        );
+        map.insert(
+            "interlacing".to_string(),
+            JsonValue::String(
+                match self.interlacing {
+                    RenderInterlaceMode::Progressive => "progressive",
+                    RenderInterlaceMode::TopFieldFirst => "top_field_first",
+                    RenderInterlaceMode::BottomFieldFirst => "bottom_field_first",
+                }
+                .to_string(),
+            ),
+        );
+        if let Some(audio_codec) = self.audio_codec {
+            map.insert(
+                "audio_codec".to_string(),
+                JsonValue::String(audio_codec.name().to_string()),
+            );
+        }
+        map.insert(
+            "audio_track".to_string(),
+            JsonValue::Number(self.audio_track as f64),
+        );
+        if let Some(timecode_overlay) = &self.timecode_overlay {
+            map.insert("burn_in_timecode".to_string(), JsonValue::Boolean(true));
+            timecode_overlay.write_json(&mut map);
+        }
+        if let Some(text_overlay) = &self.text_overlay {
+            map.insert("enable_text_overlay".to_string(), JsonValue::Boolean(true));
+            text_overlay.write_json(&mut map);
+        }
+        if let Some(output_framerate) = self.output_framerate {
+            map.insert(
+                "output_framerate".to_string(),
+                JsonValue::Array(vec![
+                    JsonValue::Number(output_framerate.numer() as f64),
+                    JsonValue::Number(output_framerate.denom() as f64),
+                ]),
+            );
+        }
+        map.insert(
+            "preserve_alpha".to_string(),
+            JsonValue::Boolean(self.preserve_alpha),
+        );
+        map.insert(
+            "keep_partial_output".to_string(),
+            JsonValue::Boolean(self.keep_partial_output),
+        );
+        map.insert("dither".to_string(), JsonValue::Boolean(self.dither));
+        map.insert(
+            "low_memory".to_string(),
+            JsonValue::Boolean(self.low_memory),
+        );
+        map.insert(
+            "tonemap_hdr".to_string(),
+            JsonValue::Boolean(self.tonemap.enabled),
+        );
+        map.insert(
+            "full_resolution".to_string(),
+            JsonValue::Boolean(self.full_resolution),
+        );
+        if let Some(effect_scanlines) = self.effect_scanlines {
+            map.insert(
+                "effect_scanlines".to_string(),
+                JsonValue::Number(effect_scanlines as f64),
+            );
+        }
+        if let Some(region) = &self.region {
+            map.insert(
+                "region".to_string(),
+                JsonValue::Array(
+                    [region.left, region.top, region.right, region.bottom]
+                        .into_iter()
+                        .map(|edge| JsonValue::Number(edge as f64))
+                        .collect(),
+                ),
+            );
+            map.insert(
+                "region_feather".to_string(),
+                JsonValue::Number(region.feather as f64),
+            );
+            map.insert(
+                "region_invert".to_string(),
+                JsonValue::Boolean(region.invert),
+            );
+        }
+        map.insert(
+            "tonemap_target_nits".to_string(),
+            JsonValue::Number(self.tonemap.target_nits),
+        );
+        map.insert(
+            "color_matrix".to_string(),
+            JsonValue::String(self.color_matrix.name().to_string()),
+        );
+        map.insert(
+            "color_range".to_string(),
+            JsonValue::String(self.color_range.name().to_string()),
+        );
+        map.insert(
+            "effect_settings".to_string(),
+            settings_list.to_json(&NtscEffectFullSettings::from(&self.effect_settings)),
+        );
+        map.insert("seed".to_string(), JsonValue::Number(self.seed as f64));
+        if !self.keyframes.is_empty() {
+            map.insert("keyframes".to_string(), self.keyframes.to_json());
+        }
 
-                        if let gstreamer::MessageView::StateChanged(state_changed) = msg.view() {
-                            if state_changed.old() == gstreamer::State::Ready
-                                && matches!(
-                                    state_changed.current(),
-                                    gstreamer::State::Paused | gstreamer::State::Playing
-                                )
-                            {
-                                // Changed from READY to PAUSED/PLAYING.
-                                *pipeline_info_state.lock().unwrap() = PipelineInfoState::Loaded;
+        JsonValue::Object(map)
+    }
 
-                                let mut metadata = metadata.lock().unwrap();
+    /// Restore a render saved with `to_json`, returning the source path along with the settings. Relative paths are
+    /// resolved against `base_directory`.
+    fn from_json(
+        map: &HashMap<String, JsonValue>,
+        base_directory: &Path,
+        settings_list: &SettingsList,
+    ) -> Result<(PathBuf, Self), ParseSettingsError> {
+        let queue_path = |key: &'static str| {
+            json_string(map, key)
+                .map(|path| base_directory.join(path))
+                .ok_or(ParseSettingsError::MissingField { field: key })
+        };
 
-                                let is_still_image =
-                                    pipeline.by_name("still_image_freeze").is_some();
-                                metadata.is_still_image = Some(is_still_image);
+        let src_path = queue_path("source_path")?;
+        let output_path = queue_path("output_path")?;
+        let codec_settings = match json_string(map, "codec").map(String::as_str) {
+            Some("h264") => {
+                let mut h264_settings = H264Settings::default();
+                h264_settings.read_json(map);
+                RenderPipelineCodec::H264(h264_settings)
+            }
+            Some("ffv1") => {
+                let mut ffv1_settings = Ffv1Settings::default();
+                ffv1_settings.read_json(map);
+                RenderPipelineCodec::Ffv1(ffv1_settings)
+            }
+            // Queued before other still image formats were added
+            Some("png") => RenderPipelineCodec::Still(StillImageSettings::default()),
+            Some("still") => {
+                let mut still_settings = StillImageSettings::default();
+                still_settings.read_json(map);
+                RenderPipelineCodec::Still(still_settings)
+            }
+            Some("image_sequence") => RenderPipelineCodec::ImageSequence {
+                format: json_string(map, "image_sequence_format")
+                    .and_then(|format| {
+                        ImageSequenceFormat::ALL
+                            .iter()
+                            .copied()
+                            .find(|mode| mode.name() == format)
+                    })
+                    .unwrap_or_default(),
+                pattern: json_string(map, "image_sequence_pattern").cloned().ok_or(
+                    ParseSettingsError::MissingField {
+                        field: "image_sequence_pattern",
+                    },
+                )?,
+            },
+            _ => return Err(ParseSettingsError::MissingField { field: "codec" }),
+        };
+        let interlacing = match json_string(map, "interlacing").map(String::as_str) {
+            Some("top_field_first") => RenderInterlaceMode::TopFieldFirst,
+            Some("bottom_field_first") => RenderInterlaceMode::BottomFieldFirst,
+            _ => RenderInterlaceMode::Progressive,
+        };
+        let audio_codec = json_string(map, "audio_codec").and_then(|audio_codec| {
+            AudioCodec::ALL
+                .iter()
+                .copied()
+                .find(|codec| codec.name() == audio_codec)
+        });
+        let timecode_overlay = json_bool(map, "burn_in_timecode")
+            .unwrap_or(false)
+            .then(|| {
+                let mut timecode_overlay = TimecodeOverlaySettings::default();
+                timecode_overlay.read_json(map);
+                timecode_overlay
+            });
+        let text_overlay = json_bool(map, "enable_text_overlay")
+            .unwrap_or(false)
+            .then(|| {
+                let mut text_overlay = TextOverlaySettings::default();
+                text_overlay.read_json(map);
+                text_overlay
+            });
+        let output_framerate = map
+            .get("output_framerate")
+            .and_then(|v| v.get::<Vec<JsonValue>>())
+            .and_then(|fraction| match fraction.as_slice() {
+                [numer, denom] => Some(gstreamer::Fraction::new(
+                    *numer.get::<f64>()? as i32,
+                    *denom.get::<f64>()? as i32,
+                )),
+                _ => None,
+            });
+        let region = map
+            .get("region")
+            .and_then(|v| v.get::<Vec<JsonValue>>())
+            .and_then(|edges| match edges.as_slice() {
+                [left, top, right, bottom] => Some(EffectRegion {
+                    left: *left.get::<f64>()? as f32,
+                    top: *top.get::<f64>()? as f32,
+                    right: *right.get::<f64>()? as f32,
+                    bottom: *bottom.get::<f64>()? as f32,
+                    feather: json_number(map, "region_feather").unwrap_or(0.0) as f32,
+                    invert: json_bool(map, "region_invert").unwrap_or(false),
+                }),
+                _ => None,
+            });
+        let color_matrix = json_string(map, "color_matrix")
+            .and_then(|matrix| {
+                OutputColorMatrix::ALL
+                    .iter()
+                    .copied()
+                    .find(|m| m.name() == matrix)
+            })
+            .unwrap_or_default();
+        let color_range = json_string(map, "color_range")
+            .and_then(|range| {
+                OutputColorRange::ALL
+                    .iter()
+                    .copied()
+                    .find(|r| r.name() == range)
+            })
+            .unwrap_or_default();
+        let effect_settings =
+            map.get("effect_settings")
+                .ok_or(ParseSettingsError::MissingField {
+                    field: "effect_settings",
+                })?;
+        let effect_settings: NtscEffect = settings_list
+            .from_json(&effect_settings.stringify().unwrap_or_default())?
+            .into();
+        let seed = json_number(map, "seed").map_or(effect_settings.random_seed, |seed| seed as i32);
+        let keyframes = map
+            .get("keyframes")
+            .map(|keyframes| KeyframeTracks::from_json(keyframes, settings_list))
+            .unwrap_or_default();
+
+        Ok((
+            src_path,
+            Self {
+                codec_settings,
+                output_path,
+                duration: ClockTime::from_mseconds(
+                    json_number(map, "duration_ms").unwrap_or(0.0).max(0.0) as u64,
+                ),
+                interlacing,
+                audio_codec,
+                audio_track: json_number(map, "audio_track").unwrap_or(0.0).max(0.0) as usize,
+                timecode_overlay,
+                text_overlay,
+                output_framerate,
+                preserve_alpha: json_bool(map, "preserve_alpha").unwrap_or(false),
+                keep_partial_output: json_bool(map, "keep_partial_output").unwrap_or(false),
+                dither: json_bool(map, "dither").unwrap_or(false),
+                low_memory: json_bool(map, "low_memory").unwrap_or(false),
+                tonemap: TonemapSettings {
+                    enabled: json_bool(map, "tonemap_hdr").unwrap_or(true),
+                    target_nits: json_number(map, "tonemap_target_nits")
+                        .unwrap_or(TonemapSettings::default().target_nits),
+                },
+                full_resolution: json_bool(map, "full_resolution").unwrap_or(false),
+                effect_scanlines: json_number(map, "effect_scanlines")
+                    .filter(|scanlines| *scanlines >= 1.0)
+                    .map(|scanlines| scanlines as usize),
+                region,
+                color_matrix,
+                color_range,
+                pass: 0,
+                job_id: next_render_job_id(),
+                effect_settings,
+                seed,
+                keyframes,
+            },
+        ))
+    }
+}
 
-                                let video_rate = pipeline.by_name("video_rate");
-                                let caps = video_rate.and_then(|video_rate| {
-                                    video_rate.static_pad("src").and_then(|pad| pad.caps())
-                                });
+#[derive(Debug, Clone)]
+struct RenderSettings {
+    output_codec: OutputCodec,
+    // we want to keep these around even if the user changes their mind and selects ffv1, so they don't lose the
+    // settings if they change back. they're all saved between sessions too (see `to_json`), not just the selected one's
+    h264_settings: H264Settings,
+    ffv1_settings: Ffv1Settings,
+    image_sequence_format: ImageSequenceFormat,
+    output_path: PathBuf,
+    // the directory that the last output file was saved to, possibly in a previous session
+    output_directory: Option<PathBuf>,
+    duration: ClockTime,
+    interlaced: bool,
+    output_framerate: OutputFramerate,
+    include_audio: bool,
+    audio_codec: AudioCodec,
+    burn_in_timecode: bool,
+    timecode_overlay: TimecodeOverlaySettings,
+    enable_text_overlay: bool,
+    text_overlay: TextOverlaySettings,
+    preserve_alpha: bool,
+    keep_partial_output: bool,
+    // give each render its own random seed instead of using the one in the effect settings
+    randomize_seed: bool,
+    dither: bool,
+    low_memory: bool,
+    tonemap: TonemapSettings,
+    color_matrix: OutputColorMatrix,
+    color_range: OutputColorRange,
+    // the framerate still images are loaded at, which decides how often the noise changes over a rendered still
+    still_image_framerate: f64,
+    // the format that "Save frame" writes
+    still_image: StillImageSettings,
+}
 
-                                if let Some(caps) = caps {
-                                    let structure = caps.structure(0);
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            output_codec: OutputCodec::default(),
+            h264_settings: H264Settings::default(),
+            ffv1_settings: Ffv1Settings::default(),
+            image_sequence_format: ImageSequenceFormat::default(),
+            output_path: PathBuf::default(),
+            output_directory: None,
+            duration: ClockTime::ZERO,
+            interlaced: false,
+            output_framerate: OutputFramerate::default(),
+            include_audio: true,
+            audio_codec: OutputCodec::default().default_audio_codec(),
+            burn_in_timecode: false,
+            timecode_overlay: TimecodeOverlaySettings::default(),
+            enable_text_overlay: false,
+            text_overlay: TextOverlaySettings::default(),
+            preserve_alpha: false,
+            keep_partial_output: false,
+            randomize_seed: false,
+            dither: false,
+            low_memory: false,
+            tonemap: TonemapSettings::default(),
+            color_matrix: OutputColorMatrix::default(),
+            color_range: OutputColorRange::default(),
+            still_image_framerate: 30.0,
+            still_image: StillImageSettings::default(),
+        }
+    }
+}
 
-                                    metadata.framerate = structure.and_then(|structure| {
-                                        structure.get::<gstreamer::Fraction>("framerate").ok()
-                                    });
+fn json_number(map: &HashMap<String, JsonValue>, key: &str) -> Option<f64> {
+    map.get(key).and_then(|v| v.get::<f64>()).copied()
+}
 
-                                    metadata.interlace_mode = structure.and_then(|structure| {
-                                        Some(VideoInterlaceMode::from_string(
-                                            structure.get("interlace-mode").ok()?,
-                                        ))
-                                    });
+fn json_bool(map: &HashMap<String, JsonValue>, key: &str) -> Option<bool> {
+    map.get(key).and_then(|v| v.get::<bool>()).copied()
+}
 
-                                    metadata.resolution = structure.and_then(|structure| {
-                                        Some((
-                                            structure.get::<i32>("width").ok()? as usize,
-                                            structure.get::<i32>("height").ok()? as usize,
-                                        ))
-                                    });
-                                } else {
-                                    metadata.framerate = None;
-                                    metadata.interlace_mode = None;
-                                    metadata.resolution = None;
-                                }
-                            }
-                        }
-                    }
+fn json_string<'a>(map: &'a HashMap<String, JsonValue>, key: &str) -> Option<&'a String> {
+    map.get(key).and_then(|v| v.get::<String>())
+}
 
-                    Some(())
-                };
+impl H264Settings {
+    fn write_json(&self, map: &mut HashMap<String, JsonValue>) {
+        map.insert(
+            "h264_rate_control".to_string(),
+            JsonValue::String(self.rate_control.name().to_string()),
+        );
+        if let Some(bitrate) = self.rate_control.bitrate() {
+            map.insert(
+                "h264_bitrate".to_string(),
+                JsonValue::Number(bitrate as f64),
+            );
+        }
+        map.insert("h264_crf".to_string(), JsonValue::Number(self.crf as f64));
+        map.insert(
+            "h264_encode_speed".to_string(),
+            JsonValue::Number(self.encode_speed as f64),
+        );
+        map.insert("h264_ten_bit".to_string(), JsonValue::Boolean(self.ten_bit));
+        map.insert(
+            "h264_chroma_subsampling".to_string(),
+            JsonValue::Boolean(self.chroma_subsampling),
+        );
+    }
 
-                handle_msg(bus, msg);
+    fn read_json(&mut self, map: &HashMap<String, JsonValue>) {
+        if let Some(rate_control) = json_string(map, "h264_rate_control") {
+            let bitrate = json_number(map, "h264_bitrate")
+                .map_or(H264RateControl::DEFAULT_BITRATE, |bitrate| {
+                    bitrate.clamp(100.0, 100000.0) as u32
+                });
+            self.rate_control = H264RateControl::all(bitrate)
+                .into_iter()
+                .find(|mode| mode.name() == rate_control)
+                .unwrap_or_default();
+        }
+        if let Some(crf) = json_number(map, "h264_crf") {
+            self.crf = crf.clamp(0.0, 50.0) as u8;
+        }
+        if let Some(encode_speed) = json_number(map, "h264_encode_speed") {
+            self.encode_speed = encode_speed.clamp(0.0, 8.0) as u8;
+        }
+        if let Some(ten_bit) = json_bool(map, "h264_ten_bit") {
+            self.ten_bit = ten_bit;
+        }
+        if let Some(chroma_subsampling) = json_bool(map, "h264_chroma_subsampling") {
+            self.chroma_subsampling = chroma_subsampling;
+        }
+    }
+}
 
-                gstreamer::BusSyncReply::Drop
-            },
-            None,
-            if self.video_scale.enabled {
-                Some(self.video_scale.scale)
-            } else {
-                None
-            },
-            gstreamer::Fraction::from(30),
-            Some(move |p: Result<gstreamer::Pipeline, PipelineError>| {
-                if let Err(e) = p {
-                    *pipeline_info_state_for_callback.lock().unwrap() = PipelineInfoState::Error(e);
-                    ctx_for_callback.request_repaint();
-                }
+impl Ffv1Settings {
+    fn write_json(&self, map: &mut HashMap<String, JsonValue>) {
+        map.insert(
+            "ffv1_bit_depth".to_string(),
+            JsonValue::Number(match self.bit_depth {
+                Ffv1BitDepth::Bits8 => 8.0,
+                Ffv1BitDepth::Bits10 => 10.0,
+                Ffv1BitDepth::Bits12 => 12.0,
             }),
-        )?;
+        );
+        map.insert(
+            "ffv1_chroma_subsampling".to_string(),
+            JsonValue::Boolean(self.chroma_subsampling),
+        );
+    }
 
-        pipeline.set_state(gstreamer::State::Paused)?;
+    fn read_json(&mut self, map: &HashMap<String, JsonValue>) {
+        match json_number(map, "ffv1_bit_depth") {
+            Some(bits) if bits == 10.0 => self.bit_depth = Ffv1BitDepth::Bits10,
+            Some(bits) if bits == 12.0 => self.bit_depth = Ffv1BitDepth::Bits12,
+            _ => {}
+        }
+        if let Some(chroma_subsampling) = json_bool(map, "ffv1_chroma_subsampling") {
+            self.chroma_subsampling = chroma_subsampling;
+        }
+    }
+}
 
-        Ok(PipelineInfo {
-            pipeline,
-            state: pipeline_info_state,
-            path,
-            egui_sink: video_sink,
-            at_eos,
-            last_seek_pos: ClockTime::ZERO,
-            preview: tex,
-            metadata,
-        })
+impl TimecodeOverlaySettings {
+    fn write_json(&self, map: &mut HashMap<String, JsonValue>) {
+        map.insert(
+            "timecode_format".to_string(),
+            JsonValue::String(self.format.name().to_string()),
+        );
+        map.insert(
+            "timecode_corner".to_string(),
+            JsonValue::String(self.corner.name().to_string()),
+        );
     }
 
-    fn pixel_formats_for(bit_depth: usize, chroma_subsampling: bool) -> &'static [VideoFormat] {
-        match (bit_depth, chroma_subsampling) {
-            (8, false) => &[
-                VideoFormat::Y444,
-                VideoFormat::V308,
-                VideoFormat::Iyu2,
-                VideoFormat::Nv24,
-            ],
-            (8, true) => &[
-                VideoFormat::I420,
-                VideoFormat::Yv12,
-                VideoFormat::Nv12,
-                VideoFormat::Nv21,
-            ],
-            (10, false) => &[VideoFormat::Y44410be, VideoFormat::Y44410le],
-            (10, true) => &[VideoFormat::I42010be, VideoFormat::I42010le],
-            (12, false) => &[VideoFormat::Y44412be, VideoFormat::Y44412le],
-            (12, true) => &[VideoFormat::I42012be, VideoFormat::I42012le],
-            _ => panic!("No pixel format for bit depth {bit_depth}"),
+    fn read_json(&mut self, map: &HashMap<String, JsonValue>) {
+        if let Some(format) = json_string(map, "timecode_format") {
+            self.format = TimecodeFormat::ALL
+                .iter()
+                .copied()
+                .find(|mode| mode.name() == format)
+                .unwrap_or_default();
+        }
+        if let Some(corner) = json_string(map, "timecode_corner") {
+            self.corner = OverlayCorner::ALL
+                .iter()
+                .copied()
+                .find(|mode| mode.name() == corner)
+                .unwrap_or_default();
         }
     }
+}
 
-    fn interlaced_output_allowed(&self) -> bool {
-        matches!(
-            self.effect_settings.use_field,
-            UseField::InterleavedUpper | UseField::InterleavedLower
-        )
+impl TextOverlaySettings {
+    fn write_json(&self, map: &mut HashMap<String, JsonValue>) {
+        map.insert(
+            "text_overlay_text".to_string(),
+            JsonValue::String(self.text.clone()),
+        );
+        map.insert(
+            "text_overlay_font_size".to_string(),
+            JsonValue::Number(self.font_size as f64),
+        );
+        map.insert(
+            "text_overlay_corner".to_string(),
+            JsonValue::String(self.corner.name().to_string()),
+        );
+        map.insert(
+            "text_overlay_opacity".to_string(),
+            JsonValue::Number(self.opacity as f64),
+        );
     }
 
-    fn create_render_job(
-        &mut self,
-        ctx: &egui::Context,
-        src_path: &Path,
-        settings: RenderPipelineSettings,
-    ) -> Result<RenderJob, GstreamerError> {
-        let src = gstreamer::ElementFactory::make("filesrc")
-            .property("location", src_path)
-            .build()?;
+    fn read_json(&mut self, map: &HashMap<String, JsonValue>) {
+        if let Some(text) = json_string(map, "text_overlay_text") {
+            self.text = text.clone();
+        }
+        if let Some(font_size) = json_number(map, "text_overlay_font_size") {
+            self.font_size = font_size.clamp(1.0, 500.0) as u32;
+        }
+        if let Some(corner) = json_string(map, "text_overlay_corner") {
+            self.corner = OverlayCorner::ALL
+                .iter()
+                .copied()
+                .find(|mode| mode.name() == corner)
+                .unwrap_or(OverlayCorner::TopLeft);
+        }
+        if let Some(opacity) = json_number(map, "text_overlay_opacity") {
+            self.opacity = opacity.clamp(0.0, 1.0) as f32;
+        }
+    }
+}
 
-        let settings = Arc::new(settings);
-        let settings_audio_closure = Arc::clone(&settings);
-        let settings_video_closure = Arc::clone(&settings);
+impl RenderSettings {
+    fn still_image_framerate(&self) -> gstreamer::Fraction {
+        gstreamer::Fraction::approximate_f64(self.still_image_framerate)
+            .unwrap_or(gstreamer::Fraction::from(30))
+    }
 
-        let output_elems_cell = Arc::new(OnceLock::new());
-        let output_elems_cell_video = Arc::clone(&output_elems_cell);
-        let closure_settings = settings.clone();
-        let create_output_elems = move |pipeline: &gstreamer::Pipeline| -> Result<
-            (Option<gstreamer::Element>, gstreamer::Element),
-            GstreamerError,
-        > {
-            let video_mux = match &closure_settings.codec_settings {
-                RenderPipelineCodec::H264(_) => Some(
-                    gstreamer::ElementFactory::make("mp4mux")
-                        .name("output_muxer")
-                        .build()?,
-                ),
-                RenderPipelineCodec::Ffv1(_) => Some(
-                    gstreamer::ElementFactory::make("matroskamux")
-                        .name("output_muxer")
-                        .build()?,
-                ),
-                RenderPipelineCodec::Png => None,
-            };
-
-            let file_sink = gstreamer::ElementFactory::make("filesink")
-                .property("location", closure_settings.output_path.as_path())
-                .build()?;
+    /// Serialize the settings that should persist between sessions. The duration and output filename depend on the
+    /// current source, so they are not saved.
+    fn to_json(&self) -> JsonValue {
+        let mut map = HashMap::<String, JsonValue>::new();
+        map.insert(
+            "output_codec".to_string(),
+            JsonValue::String(
+                match self.output_codec {
+                    OutputCodec::H264 => "h264",
+                    OutputCodec::Ffv1 => "ffv1",
+                    OutputCodec::ImageSequence => "image_sequence",
+                }
+                .to_string(),
+            ),
+        );
+        map.insert(
+            "image_sequence_format".to_string(),
+            JsonValue::String(self.image_sequence_format.name().to_string()),
+        );
+        self.h264_settings.write_json(&mut map);
+        self.ffv1_settings.write_json(&mut map);
+        self.still_image.write_json(&mut map);
+        map.insert(
+            "interlaced".to_string(),
+            JsonValue::Boolean(self.interlaced),
+        );
+        map.insert(
+            "output_framerate".to_string(),
+            JsonValue::String(self.output_framerate.label().to_string()),
+        );
+        map.insert(
+            "include_audio".to_string(),
+            JsonValue::Boolean(self.include_audio),
+        );
+        map.insert(
+            "audio_codec".to_string(),
+            JsonValue::String(self.audio_codec.name().to_string()),
+        );
+        map.insert(
+            "burn_in_timecode".to_string(),
+            JsonValue::Boolean(self.burn_in_timecode),
+        );
+        self.timecode_overlay.write_json(&mut map);
+        map.insert(
+            "enable_text_overlay".to_string(),
+            JsonValue::Boolean(self.enable_text_overlay),
+        );
+        self.text_overlay.write_json(&mut map);
+        map.insert(
+            "preserve_alpha".to_string(),
+            JsonValue::Boolean(self.preserve_alpha),
+        );
+        map.insert(
+            "keep_partial_output".to_string(),
+            JsonValue::Boolean(self.keep_partial_output),
+        );
+        map.insert(
+            "randomize_seed".to_string(),
+            JsonValue::Boolean(self.randomize_seed),
+        );
+        map.insert("dither".to_string(), JsonValue::Boolean(self.dither));
+        map.insert(
+            "low_memory".to_string(),
+            JsonValue::Boolean(self.low_memory),
+        );
+        map.insert(
+            "tonemap_hdr".to_string(),
+            JsonValue::Boolean(self.tonemap.enabled),
+        );
+        map.insert(
+            "tonemap_target_nits".to_string(),
+            JsonValue::Number(self.tonemap.target_nits),
+        );
+        map.insert(
+            "color_matrix".to_string(),
+            JsonValue::String(self.color_matrix.name().to_string()),
+        );
+        map.insert(
+            "color_range".to_string(),
+            JsonValue::String(self.color_range.name().to_string()),
+        );
+        map.insert(
+            "still_image_framerate".to_string(),
+            JsonValue::Number(self.still_image_framerate),
+        );
 
-            pipeline.add(&file_sink)?;
-            file_sink.sync_state_with_parent()?;
+        let output_directory = self
+            .output_path
+            .parent()
+            .filter(|parent| parent.components().next().is_some())
+            .or(self.output_directory.as_deref());
+        if let Some(output_directory) = output_directory {
+            map.insert(
+                "output_directory".to_string(),
+                JsonValue::String(output_directory.to_string_lossy().into_owned()),
+            );
+        }
 
-            if let Some(video_mux) = video_mux {
-                pipeline.add(&video_mux)?;
-                video_mux.link(&file_sink)?;
-                video_mux.sync_state_with_parent()?;
+        JsonValue::Object(map)
+    }
 
-                Ok((Some(video_mux.clone()), video_mux))
-            } else {
-                Ok((None, file_sink))
+    /// Restore settings saved with `to_json`. Missing or invalid values are left at their defaults.
+    fn from_json(json: &str) -> Option<Self> {
+        let json = json.parse::<JsonValue>().ok()?;
+        let map = json.get::<HashMap<_, _>>()?;
+        let boolean = |key: &str| json_bool(map, key);
+
+        let mut settings = Self::default();
+        match map.get("output_codec").and_then(|v| v.get::<String>()) {
+            Some(codec) if codec == "h264" => settings.output_codec = OutputCodec::H264,
+            Some(codec) if codec == "ffv1" => settings.output_codec = OutputCodec::Ffv1,
+            Some(codec) if codec == "image_sequence" => {
+                settings.output_codec = OutputCodec::ImageSequence
             }
-        };
+            _ => {}
+        }
+        if let Some(format) = map
+            .get("image_sequence_format")
+            .and_then(|v| v.get::<String>())
+        {
+            settings.image_sequence_format = ImageSequenceFormat::ALL
+                .iter()
+                .copied()
+                .find(|mode| mode.name() == format)
+                .unwrap_or_default();
+        }
+        settings.h264_settings.read_json(map);
+        settings.ffv1_settings.read_json(map);
+        settings.still_image.read_json(map);
+        if let Some(interlaced) = boolean("interlaced") {
+            settings.interlaced = interlaced;
+        }
+        if let Some(output_framerate) = map.get("output_framerate").and_then(|v| v.get::<String>())
+        {
+            settings.output_framerate = OutputFramerate::ALL
+                .iter()
+                .copied()
+                .find(|framerate| framerate.label() == output_framerate)
+                .unwrap_or_default();
+        }
+        if let Some(include_audio) = boolean("include_audio") {
+            settings.include_audio = include_audio;
+        }
+        let audio_codec = map.get("audio_codec").and_then(|v| v.get::<String>());
+        settings.audio_codec = settings
+            .output_codec
+            .audio_codecs()
+            .iter()
+            .copied()
+            .find(|codec| Some(codec.name()) == audio_codec.map(String::as_str))
+            .unwrap_or_else(|| settings.output_codec.default_audio_codec());
+        if let Some(burn_in_timecode) = boolean("burn_in_timecode") {
+            settings.burn_in_timecode = burn_in_timecode;
+        }
+        settings.timecode_overlay.read_json(map);
+        if let Some(enable_text_overlay) = boolean("enable_text_overlay") {
+            settings.enable_text_overlay = enable_text_overlay;
+        }
+        settings.text_overlay.read_json(map);
+        if let Some(preserve_alpha) = boolean("preserve_alpha") {
+            settings.preserve_alpha = preserve_alpha;
+        }
+        if let Some(keep_partial_output) = boolean("keep_partial_output") {
+            settings.keep_partial_output = keep_partial_output;
+        }
+        if let Some(randomize_seed) = boolean("randomize_seed") {
+            settings.randomize_seed = randomize_seed;
+        }
+        if let Some(dither) = boolean("dither") {
+            settings.dither = dither;
+        }
+        if let Some(low_memory) = boolean("low_memory") {
+            settings.low_memory = low_memory;
+        }
+        if let Some(tonemap_hdr) = boolean("tonemap_hdr") {
+            settings.tonemap.enabled = tonemap_hdr;
+        }
+        if let Some(target_nits) = json_number(map, "tonemap_target_nits") {
+            settings.tonemap.target_nits = target_nits.clamp(10.0, 1000.0);
+        }
+        if let Some(still_image_framerate) = json_number(map, "still_image_framerate") {
+            settings.still_image_framerate = still_image_framerate.clamp(1.0, 240.0);
+        }
+        if let Some(color_matrix) = map.get("color_matrix").and_then(|v| v.get::<String>()) {
+            settings.color_matrix = OutputColorMatrix::ALL
+                .iter()
+                .copied()
+                .find(|matrix| matrix.name() == color_matrix)
+                .unwrap_or_default();
+        }
+        if let Some(color_range) = map.get("color_range").and_then(|v| v.get::<String>()) {
+            settings.color_range = OutputColorRange::ALL
+                .iter()
+                .copied()
+                .find(|range| range.name() == color_range)
+                .unwrap_or_default();
+        }
+        settings.output_directory = map
+            .get("output_directory")
+            .and_then(|v| v.get::<String>())
+            .map(PathBuf::from);
 
-        let create_output_elems_audio = create_output_elems.clone();
-        let create_output_elems_video = create_output_elems.clone();
+        Some(settings)
+    }
 
-        let job_state = Arc::new(Mutex::new(RenderJobState::Waiting));
-        let job_state_for_handler = Arc::clone(&job_state);
-        let exec = self.execute_fn_next_frame();
-        let exec2 = self.execute_fn_next_frame();
-        let ctx_for_handler = ctx.clone();
+    fn output_extension(&self) -> &'static str {
+        match self.output_codec {
+            OutputCodec::H264 => "mp4",
+            OutputCodec::Ffv1 => "mkv",
+            OutputCodec::ImageSequence => self.image_sequence_format.extension(),
+        }
+    }
 
-        //let still_image_duration = settings.duration;
-        let current_time = self
-            .pipeline
-            .as_ref()
-            .and_then(|info| info.pipeline.query_position::<ClockTime>())
-            .unwrap_or(ClockTime::ZERO);
-        let is_png = matches!(settings.codec_settings, RenderPipelineCodec::Png);
+    /// Where the render job should write to. Image sequences are written into the output file's directory instead,
+    /// with the frame number added to the file name.
+    fn pipeline_output_path(&self) -> PathBuf {
+        match self.output_codec {
+            OutputCodec::ImageSequence => self
+                .output_path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default(),
+            _ => self.output_path.clone(),
+        }
+    }
 
-        let pipeline = create_pipeline(
-            src,
-            move |pipeline| {
-                let (audio_out, _) = output_elems_cell
-                    .get_or_init(|| create_output_elems_audio(pipeline))
-                    .as_ref()
-                    .map_err(|err| err.clone())?;
-                if let Some(audio_out) = audio_out {
-                    let audio_enc = match settings_audio_closure.codec_settings {
-                        RenderPipelineCodec::H264(_) => {
-                            gstreamer::ElementFactory::make("avenc_aac").build()?
-                        }
-                        RenderPipelineCodec::Ffv1(_) => {
-                            gstreamer::ElementFactory::make("flacenc").build()?
-                        }
-                        RenderPipelineCodec::Png => return Ok(None),
-                    };
+    /// The folder the output path is in, if it doesn't exist yet. The sink would only fail on it once the render starts.
+    fn missing_output_directory(&self) -> Option<&Path> {
+        self.output_path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty() && !parent.is_dir())
+    }
 
-                    pipeline.add(&audio_enc)?;
-                    audio_enc.link(audio_out)?;
-                    audio_enc.sync_state_with_parent()?;
-                    Ok(Some(audio_enc))
-                } else {
-                    Ok(None)
-                }
-            },
-            move |pipeline| {
-                let (_, video_out) = output_elems_cell_video
-                    .get_or_init(|| create_output_elems_video(pipeline))
-                    .as_ref()
-                    .map_err(|err| err.clone())?;
+    /// The multifilesink location pattern for an image sequence. The frame number is appended to the chosen file name,
+    /// unless it already contains a placeholder for it.
+    fn image_sequence_pattern(&self) -> String {
+        let file_name = self
+            .output_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if file_name.contains('%') {
+            return file_name;
+        }
 
-                let (video_enc, pixel_formats) = match &settings_video_closure.codec_settings {
-                    RenderPipelineCodec::H264(h264_settings) => {
-                        // Load the x264enc plugin so the enum classes exist. Nothing seems to work except actually instantiating an Element.
-                        let _ = gstreamer::ElementFactory::make("x264enc").build().unwrap();
-                        #[allow(non_snake_case)]
-                        let GstX264EncPass = gstreamer::glib::EnumClass::with_type(
-                            gstreamer::glib::Type::from_name("GstX264EncPass").unwrap(),
-                        )
-                        .unwrap();
-                        #[allow(non_snake_case)]
-                        let GstX264EncPreset = gstreamer::glib::EnumClass::with_type(
-                            gstreamer::glib::Type::from_name("GstX264EncPreset").unwrap(),
-                        )
-                        .unwrap();
+        let file_stem = self
+            .output_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        format!(
+            "{}_%05d.{}",
+            file_stem,
+            self.image_sequence_format.extension()
+        )
+    }
 
-                        let video_enc = gstreamer::ElementFactory::make("x264enc")
-                            // CRF mode
-                            .property("pass", GstX264EncPass.to_value_by_nick("quant").unwrap())
-                            // invert CRF (so that low numbers = low quality)
-                            .property("quantizer", 50 - h264_settings.crf as u32)
-                            .property(
-                                "speed-preset",
-                                GstX264EncPreset
-                                    .to_value(9 - h264_settings.encode_speed as i32)
-                                    .unwrap(),
-                            )
-                            .build()?;
+    /// Suggest an output path for the given source file, in the last-used output directory.
+    fn suggest_output_path(&mut self, src_path: &Path) {
+        let output_directory = self
+            .output_path
+            .parent()
+            .filter(|parent| parent.components().next().is_some())
+            .map(Path::to_path_buf)
+            .or_else(|| self.output_directory.clone());
+        let (Some(output_directory), Some(file_stem)) = (output_directory, src_path.file_stem())
+        else {
+            return;
+        };
 
-                        let pixel_formats = Self::pixel_formats_for(
-                            if h264_settings.ten_bit { 10 } else { 8 },
-                            h264_settings.chroma_subsampling,
-                        );
+        self.output_path = output_directory.join(format!(
+            "{}_ntsc.{}",
+            file_stem.to_string_lossy(),
+            self.output_extension()
+        ));
+        self.output_directory = Some(output_directory);
+    }
+}
 
-                        (video_enc, pixel_formats)
-                    }
-                    RenderPipelineCodec::Ffv1(ffv1_settings) => {
-                        let video_enc = gstreamer::ElementFactory::make("avenc_ffv1").build()?;
+impl From<&RenderSettings> for RenderPipelineCodec {
+    fn from(value: &RenderSettings) -> Self {
+        match value.output_codec {
+            OutputCodec::H264 => RenderPipelineCodec::H264(value.h264_settings.clone()),
+            OutputCodec::Ffv1 => RenderPipelineCodec::Ffv1(value.ffv1_settings.clone()),
+            OutputCodec::ImageSequence => RenderPipelineCodec::ImageSequence {
+                format: value.image_sequence_format,
+                pattern: value.image_sequence_pattern(),
+            },
+        }
+    }
+}
 
-                        let pixel_formats = Self::pixel_formats_for(
-                            match ffv1_settings.bit_depth {
-                                Ffv1BitDepth::Bits8 => 8,
-                                Ffv1BitDepth::Bits10 => 10,
-                                Ffv1BitDepth::Bits12 => 12,
-                            },
-                            ffv1_settings.chroma_subsampling,
-                        );
+#[derive(Default, PartialEq, Eq)]
+enum LeftPanelState {
+    #[default]
+    EffectSettings,
+    RenderSettings,
+    History,
+}
 
-                        (video_enc, pixel_formats)
-                    }
-                    RenderPipelineCodec::Png => {
-                        let video_enc = gstreamer::ElementFactory::make("pngenc")
-                            .property("snapshot", true)
-                            .build()?;
+struct UndoHistoryEntry {
+    description: String,
+    settings: NtscEffectFullSettings,
+}
 
-                        let pixel_formats: &[VideoFormat] = &[VideoFormat::Rgb];
+/// The effect settings' undo history: a list of past states that undo and redo step through, and that can be displayed
+/// and jumped between.
+struct UndoHistory {
+    entries: VecDeque<UndoHistoryEntry>,
+    position: usize,
+    max_undos: usize,
+}
 
-                        (video_enc, pixel_formats)
-                    }
-                };
+impl UndoHistory {
+    const DEFAULT_MAX_UNDOS: usize = 100;
 
-                let mut elems = Vec::<gstreamer::Element>::new();
+    fn new(settings: NtscEffectFullSettings) -> Self {
+        Self {
+            entries: VecDeque::from([UndoHistoryEntry {
+                description: tr!("Initial state").to_string(),
+                settings,
+            }]),
+            position: 0,
+            max_undos: Self::DEFAULT_MAX_UNDOS,
+        }
+    }
 
-                let video_ntsc = gstreamer::ElementFactory::make("ntscfilter")
-                    .property(
-                        "settings",
-                        NtscFilterSettings(settings_video_closure.effect_settings.clone()),
-                    )
-                    .build()?;
-                elems.push(video_ntsc.clone());
+    fn set_max_undos(&mut self, max_undos: usize) {
+        self.max_undos = max_undos;
+        self.trim();
+    }
 
-                // libx264 can't encode 4:2:0 subsampled videos with odd dimensions. Pad them out to even dimensions.
-                if let RenderPipelineCodec::H264(H264Settings {
-                    chroma_subsampling: true,
-                    ..
-                }) = &settings_video_closure.codec_settings
-                {
-                    let video_padding =
-                        gstreamer::ElementFactory::make("videopadfilter").build()?;
-                    elems.push(video_padding);
-                }
+    fn trim(&mut self) {
+        // There's one more entry than there are undo steps
+        while self.entries.len() > self.max_undos + 1 && self.position > 0 {
+            self.entries.pop_front();
+            self.position -= 1;
+        }
+    }
 
-                let ntsc_caps_filter = gstreamer::ElementFactory::make("capsfilter")
-                    .property(
-                        "caps",
-                        gstreamer_video::VideoCapsBuilder::new()
-                            .format(gstreamer_video::VideoFormat::Argb64)
-                            .build(),
-                    )
-                    .build()?;
-                elems.push(ntsc_caps_filter);
+    /// Record the settings as a new entry if they've changed since the current one. Like `egui`'s `Undoer`, any entries
+    /// after the current one are dropped, even if the new settings happen to match one of them.
+    fn feed_state(&mut self, settings_list: &SettingsList, settings: &NtscEffectFullSettings) {
+        if self.entries[self.position].settings == *settings {
+            return;
+        }
 
-                let video_convert = gstreamer::ElementFactory::make("videoconvert").build()?;
-                elems.push(video_convert);
+        let changed =
+            settings_list.changed_settings(&self.entries[self.position].settings, settings);
+        let description = match changed.as_slice() {
+            [] => tr!("Changed settings").to_string(),
+            [descriptor] => tr!(descriptor.label).to_string(),
+            changed => tr!("Changed {count} settings", count = changed.len()),
+        };
 
-                if settings_video_closure.interlacing != RenderInterlaceMode::Progressive {
-                    // Load the interlace plugin so the enum class exists. Nothing seems to work except actually instantiating an Element.
-                    let _ = gstreamer::ElementFactory::make("interlace")
-                        .build()
-                        .unwrap();
-                    #[allow(non_snake_case)]
-                    let GstInterlacePattern = gstreamer::glib::EnumClass::with_type(
-                        gstreamer::glib::Type::from_name("GstInterlacePattern").unwrap(),
-                    )
-                    .unwrap();
+        self.entries.truncate(self.position + 1);
+        self.entries.push_back(UndoHistoryEntry {
+            description,
+            settings: settings.clone(),
+        });
+        self.position = self.entries.len() - 1;
+        self.trim();
+    }
 
-                    let interlace = gstreamer::ElementFactory::make("interlace")
-                        .property(
-                            "field-pattern",
-                            GstInterlacePattern.to_value_by_nick("2:2").unwrap(),
-                        )
-                        .property(
-                            "top-field-first",
-                            settings_video_closure.interlacing
-                                == RenderInterlaceMode::TopFieldFirst,
-                        )
-                        .build()?;
-                    elems.push(interlace);
-                }
+    /// Move to the entry at `index`, returning its settings.
+    fn jump_to(&mut self, index: usize) -> &NtscEffectFullSettings {
+        self.position = index.min(self.entries.len() - 1);
+        &self.entries[self.position].settings
+    }
 
-                let video_caps = gstreamer_video::VideoCapsBuilder::new()
-                    .format_list(pixel_formats.iter().copied())
-                    .build();
-                let caps_filter = gstreamer::ElementFactory::make("capsfilter")
-                    .property("caps", &video_caps)
-                    .build()?;
-                elems.push(caps_filter);
+    /// The state to go back to. If the settings have changed since the current entry was recorded (e.g. in the middle
+    /// of dragging a slider), that's the current entry itself.
+    fn undo(&mut self, settings: &NtscEffectFullSettings) -> Option<&NtscEffectFullSettings> {
+        if self.entries[self.position].settings != *settings {
+            return Some(&self.entries[self.position].settings);
+        }
+        let position = self.position.checked_sub(1)?;
+        Some(self.jump_to(position))
+    }
 
-                elems.push(video_enc.clone());
+    fn redo(&mut self, settings: &NtscEffectFullSettings) -> Option<&NtscEffectFullSettings> {
+        if !self.has_redo(settings) {
+            return None;
+        }
+        Some(self.jump_to(self.position + 1))
+    }
 
-                pipeline.add_many(elems.iter())?;
-                gstreamer::Element::link_many(elems.iter())?;
+    fn has_undo(&self, settings: &NtscEffectFullSettings) -> bool {
+        self.position > 0 || self.entries[self.position].settings != *settings
+    }
 
-                video_enc.link(video_out)?;
+    /// Redoing isn't possible once the settings have changed, since the change will replace the entries after this one.
+    fn has_redo(&self, settings: &NtscEffectFullSettings) -> bool {
+        self.num_redos() > 0 && self.entries[self.position].settings == *settings
+    }
 
-                for elem in elems.iter() {
-                    elem.sync_state_with_parent()?;
-                }
-                video_enc.sync_state_with_parent()?;
+    fn num_undos(&self) -> usize {
+        self.position
+    }
 
-                Ok(video_ntsc)
-            },
-            move |bus, msg| {
-                let job_state = &job_state_for_handler;
-                let exec = &exec;
-                let ctx = &ctx_for_handler;
+    fn num_redos(&self) -> usize {
+        self.entries.len() - 1 - self.position
+    }
+}
 
-                let handle_msg = move |_bus, msg: &gstreamer::Message| -> Option<()> {
-                    debug!("{:?}", msg);
-                    let src = msg.src()?;
+/// Options for rendering the whole video several times, each with different noise.
+#[derive(Debug, Clone)]
+struct RenderVariationOptions {
+    count: usize,
+    // how far to randomly vary the continuous settings in each render, as a fraction of their range. 0 only changes
+    // the seed
+    jitter: f32,
+}
 
-                    if let gstreamer::MessageView::Error(err) = msg.view() {
-                        let mut job_state = job_state.lock().unwrap();
-                        if !matches!(*job_state, RenderJobState::Error(_)) {
-                            *job_state = RenderJobState::Error(err.error().into());
-                            ctx.request_repaint();
-                        }
-                    }
+impl Default for RenderVariationOptions {
+    fn default() -> Self {
+        Self {
+            count: 4,
+            jitter: 0.0,
+        }
+    }
+}
 
-                    // Make sure we're listening to a pipeline event
-                    if let Some(pipeline) = src.downcast_ref::<gstreamer::Pipeline>() {
-                        let pipeline_for_handler = pipeline.clone();
-                        if let gstreamer::MessageView::Eos(_) = msg.view() {
-                            let job_state_inner = Arc::clone(job_state);
-                            let end_time = ctx.input(|input| input.time);
-                            exec(async move {
-                                let _ = pipeline_for_handler.set_state(gstreamer::State::Null);
-                                *job_state_inner.lock().unwrap() =
-                                    RenderJobState::Complete { end_time };
-                                None
-                            })
-                        }
+/// Options for rendering the current frame once for each of a range of values of one setting.
+#[derive(Debug, Clone)]
+struct VariationGridOptions {
+    setting: SettingID,
+    start: f32,
+    end: f32,
+    count: usize,
+}
 
-                        if let gstreamer::MessageView::StateChanged(state_changed) = msg.view() {
-                            if state_changed.pending() == gstreamer::State::Null {
-                                let end_time = ctx.input(|input| input.time);
-                                *job_state.lock().unwrap() = RenderJobState::Complete { end_time };
-                            } else {
-                                *job_state.lock().unwrap() = match state_changed.current() {
-                                    gstreamer::State::Paused => RenderJobState::Paused,
-                                    gstreamer::State::Playing => RenderJobState::Rendering,
-                                    gstreamer::State::Ready => RenderJobState::Waiting,
-                                    gstreamer::State::Null => {
-                                        let end_time = ctx.input(|input| input.time);
-                                        RenderJobState::Complete { end_time }
-                                    }
-                                    gstreamer::State::VoidPending => {
-                                        unreachable!("current state should never be VOID_PENDING")
-                                    }
-                                };
-                            }
-                            ctx.request_repaint();
-                        }
-                    }
+impl VariationGridOptions {
+    /// Vary the given setting across its whole range.
+    fn for_descriptor(descriptor: &SettingDescriptor, count: usize) -> Self {
+        let (start, end) = match &descriptor.kind {
+            SettingKind::FloatRange { range, .. } => (*range.start(), *range.end()),
+            SettingKind::IntRange { range, .. } => (*range.start() as f32, *range.end() as f32),
+            _ => (0.0, 1.0),
+        };
+        Self {
+            setting: descriptor.id,
+            start,
+            end,
+            count,
+        }
+    }
+}
 
-                    Some(())
-                };
+/// A named set of effect settings with its own undo history, so that several looks can be compared side by side. The
+/// active slot's settings and history live in `NtscApp` itself, and are swapped in and out when switching slots.
+struct SettingsSlot {
+    name: String,
+    settings: NtscEffectFullSettings,
+    undo_history: UndoHistory,
+}
 
-                handle_msg(bus, msg);
+impl SettingsSlot {
+    fn new(name: String, settings: NtscEffectFullSettings, max_undos: usize) -> Self {
+        let mut undo_history = UndoHistory::new(settings.clone());
+        undo_history.set_max_undos(max_undos);
+        Self {
+            name,
+            settings,
+            undo_history,
+        }
+    }
+}
 
-                gstreamer::BusSyncReply::Drop
-            },
-            if is_png {
-                None
-            } else {
-                Some(settings.duration)
+/// Something the user did to the settings slot tabs, applied once they're done being drawn.
+enum SettingsSlotAction {
+    Switch(usize),
+    Add,
+    Duplicate(usize),
+    Remove(usize),
+}
+
+#[derive(Default, PartialEq, Eq)]
+enum ColorTheme {
+    Dark,
+    Light,
+    #[default]
+    System,
+}
+
+impl ColorTheme {
+    /// The theme's visuals, with the accent color (if any) used for selections, links, and hovered/active widgets.
+    fn visuals(
+        &self,
+        info: &eframe::IntegrationInfo,
+        accent_color: Option<egui::Color32>,
+    ) -> egui::Visuals {
+        let mut visuals = match &self {
+            ColorTheme::Dark => egui::Visuals::dark(),
+            ColorTheme::Light => egui::Visuals::light(),
+            ColorTheme::System => match info.system_theme {
+                Some(eframe::Theme::Dark) => egui::Visuals::dark(),
+                Some(eframe::Theme::Light) => egui::Visuals::light(),
+                None => egui::Visuals::default(),
             },
-            if self.video_scale.enabled {
-                Some(self.video_scale.scale)
+        };
+
+        if let Some(accent) = accent_color {
+            // Selected text is drawn over the accent color, so pick whichever of black or white is readable on it
+            let [r, g, b, _] = accent.to_array();
+            let luma = 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32;
+            let selected_text = if luma > 140.0 {
+                egui::Color32::BLACK
             } else {
-                None
-            },
-            self.pipeline
-                .as_ref()
-                .map(|info| info.metadata.lock().unwrap())
-                .and_then(|metadata| metadata.framerate)
-                .unwrap_or(gstreamer::Fraction::from(30)),
-            Some(move |p: Result<gstreamer::Pipeline, _>| {
-                exec2(async move {
-                    Some(
-                        Box::new(move |_: &mut NtscApp| -> Result<(), ApplicationError> {
-                            let pipeline = p.context(CreatePipelineSnafu)?;
-                            if is_png {
-                                pipeline
-                                    .seek_simple(
-                                        gstreamer::SeekFlags::FLUSH
-                                            | gstreamer::SeekFlags::ACCURATE,
-                                        current_time,
-                                    )
-                                    .map_err(|e| e.into())
-                                    .context(CreateRenderJobSnafu)?;
-                            }
+                egui::Color32::WHITE
+            };
 
-                            pipeline
-                                .set_state(gstreamer::State::Playing)
-                                .map_err(|e| e.into())
-                                .context(CreateRenderJobSnafu)?;
-                            Ok(())
-                        }) as _,
-                    )
-                });
-            }),
-        )?;
+            visuals.selection.bg_fill = accent;
+            visuals.selection.stroke.color = selected_text;
+            visuals.hyperlink_color = accent;
+            visuals.widgets.hovered.bg_stroke.color = accent;
+            visuals.widgets.active.bg_stroke.color = accent;
+        }
 
-        pipeline.set_state(gstreamer::State::Paused)?;
+        visuals
+    }
+}
 
-        Ok(RenderJob {
-            settings: settings.as_ref().clone(),
-            pipeline,
-            state: job_state,
-            last_progress: 0.0,
-            progress_samples: VecDeque::new(),
-            start_time: None,
-            estimated_completion_time: None,
-        })
+fn accent_color_to_hex(color: egui::Color32) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r(), color.g(), color.b())
+}
+
+fn accent_color_from_hex(hex: &str) -> Option<egui::Color32> {
+    let hex = hex.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
     }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some(egui::Color32::from_rgb(
+        channel(0)?,
+        channel(2)?,
+        channel(4)?,
+    ))
+}
 
-    fn remove_pipeline(&mut self) -> Result<(), GstreamerError> {
-        if let Some(PipelineInfo { pipeline, .. }) = &mut self.pipeline {
-            pipeline.set_state(gstreamer::State::Null)?;
-            self.pipeline = None;
+impl From<&ColorTheme> for &str {
+    fn from(value: &ColorTheme) -> Self {
+        match value {
+            ColorTheme::Dark => "Dark",
+            ColorTheme::Light => "Light",
+            ColorTheme::System => "System",
         }
-
-        Ok(())
     }
+}
 
-    fn update_effect(&self) {
-        if let Some(PipelineInfo { egui_sink, .. }) = &self.pipeline {
-            egui_sink.set_property(
-                "settings",
-                NtscFilterSettings((&self.effect_settings).into()),
-            );
+impl TryFrom<&str> for ColorTheme {
+    type Error = ();
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "Dark" => Ok(ColorTheme::Dark),
+            "Light" => Ok(ColorTheme::Light),
+            "System" => Ok(ColorTheme::System),
+            _ => Err(()),
         }
     }
+}
 
-    fn handle_error(&mut self, err: &dyn Error) {
-        self.last_error = Some(format!("{}", err));
+/// What to draw behind the video in the preview pane, to make any transparency in it visible.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum VideoBackground {
+    #[default]
+    None,
+    Checkerboard,
+    Solid(egui::Color32),
+}
+
+/// Composition guides drawn over the preview. They're only drawn in the UI, and never end up in the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct GuideOverlays {
+    thirds: bool,
+    center: bool,
+    safe_4_3: bool,
+    safe_16_9: bool,
+}
+
+impl GuideOverlays {
+    fn to_storage(self) -> String {
+        [
+            (self.thirds, "thirds"),
+            (self.center, "center"),
+            (self.safe_4_3, "safe_4_3"),
+            (self.safe_16_9, "safe_16_9"),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, name)| enabled.then_some(name))
+        .collect::<Vec<_>>()
+        .join(",")
     }
 
-    fn handle_result<T, E: Error>(&mut self, result: Result<T, E>) {
-        if let Err(err) = result {
-            self.handle_error(&err);
+    fn from_storage(value: &str) -> Self {
+        let names = value.split(',').collect::<Vec<_>>();
+        Self {
+            thirds: names.contains(&"thirds"),
+            center: names.contains(&"center"),
+            safe_4_3: names.contains(&"safe_4_3"),
+            safe_16_9: names.contains(&"safe_16_9"),
         }
     }
 
-    fn handle_result_with<T, E: Error, F: FnOnce(&mut Self) -> Result<T, E>>(&mut self, cb: F) {
-        let result = cb(self);
-        self.handle_result(result);
-    }
+    /// Draw the enabled guides over the video, which is displayed in `rect`.
+    fn paint(&self, painter: &egui::Painter, rect: Rect) {
+        let stroke = egui::Stroke::new(1.0, egui::Color32::from_white_alpha(160));
+        let faint_stroke = egui::Stroke::new(1.0, egui::Color32::from_white_alpha(96));
+
+        if self.thirds {
+            for i in 1..3 {
+                let t = i as f32 / 3.0;
+                let x = egui::lerp(rect.x_range(), t);
+                let y = egui::lerp(rect.y_range(), t);
+                painter.vline(x, rect.y_range(), stroke);
+                painter.hline(rect.x_range(), y, stroke);
+            }
+        }
 
-    fn undo(&mut self) {
-        if let Some(new_state) = self.undoer.undo(&self.effect_settings) {
-            self.effect_settings = new_state.clone();
-            self.update_effect();
+        if self.center {
+            let size = rect.size().min_elem() * 0.05;
+            let center = rect.center();
+            painter.hline((center.x - size)..=(center.x + size), center.y, stroke);
+            painter.vline(center.x, (center.y - size)..=(center.y + size), stroke);
+        }
+
+        // Action safe areas are drawn more prominently than title safe ones. The 4:3 percentages are the traditional
+        // ones; the 16:9 ones are from SMPTE ST 2046-1.
+        let mut safe_areas = Vec::new();
+        if self.safe_4_3 {
+            safe_areas.push((4.0 / 3.0, 0.9, 0.8));
+        }
+        if self.safe_16_9 {
+            safe_areas.push((16.0 / 9.0, 0.93, 0.9));
+        }
+        for (aspect, action, title) in safe_areas {
+            // The largest area with this aspect ratio that fits in the video
+            let size = if rect.aspect_ratio() > aspect {
+                vec2(rect.height() * aspect, rect.height())
+            } else {
+                vec2(rect.width(), rect.width() / aspect)
+            };
+            let area = Rect::from_center_size(rect.center(), size);
+            painter.rect_stroke(
+                Rect::from_center_size(area.center(), area.size() * action),
+                egui::Rounding::ZERO,
+                stroke,
+            );
+            painter.rect_stroke(
+                Rect::from_center_size(area.center(), area.size() * title),
+                egui::Rounding::ZERO,
+                faint_stroke,
+            );
         }
     }
+}
 
-    fn redo(&mut self) {
-        if let Some(new_state) = self.undoer.redo(&self.effect_settings) {
-            self.effect_settings = new_state.clone();
-            self.update_effect();
+/// An error shown in the error log, along with when it happened.
+struct ErrorLogEntry {
+    message: String,
+    // the message along with every error that caused it, for bug reports
+    details: String,
+    time: Instant,
+}
+
+impl ErrorLogEntry {
+    /// How long ago the error happened, rounded to a human-friendly unit.
+    fn age(&self) -> String {
+        let seconds = self.time.elapsed().as_secs();
+        match seconds {
+            0..=4 => tr!("just now").to_string(),
+            5..=59 => tr!("{seconds}s ago", seconds = seconds),
+            60..=3599 => tr!("{minutes} min ago", minutes = seconds / 60),
+            _ => tr!("{hours} h ago", hours = seconds / 3600),
         }
     }
 }
 
-fn parse_expression_string(input: &str) -> Option<f64> {
-    eval_expression_string(input).ok()
+/// Settings that were loaded or pasted, waiting for the user to look over what they'd change before applying them.
+struct PendingPreset {
+    settings: NtscEffectFullSettings,
+    metadata: PresetMetadata,
+    // the toast to show once the preset is applied
+    applied_message: &'static str,
 }
 
-impl NtscApp {
-    fn setting_from_descriptor(
-        ui: &mut egui::Ui,
-        effect_settings: &mut NtscEffectFullSettings,
-        descriptor: &SettingDescriptor,
-        interlace_mode: VideoInterlaceMode,
-    ) -> (Response, bool) {
-        let mut changed = false;
-        let resp = match &descriptor {
-            SettingDescriptor {
-                id: SettingID::RANDOM_SEED,
-                ..
-            } => {
-                ui.horizontal(|ui| {
-                    let rand_btn_width = ui.spacing().interact_size.y + 4.0;
-                    let resp = ui.add_sized(
-                        egui::vec2(
-                            ui.spacing().slider_width + ui.spacing().interact_size.x
-                                - rand_btn_width,
-                            ui.spacing().interact_size.y,
-                        ),
-                        egui::DragValue::new(&mut effect_settings.random_seed)
-                            .clamp_range(i32::MIN..=i32::MAX),
-                    );
+/// A short-lived message confirming that something happened, shown in the corner of the window.
+struct Toast {
+    message: String,
+    time: Instant,
+}
 
-                    if ui
-                        .add_sized(
-                            egui::vec2(rand_btn_width, ui.spacing().interact_size.y),
-                            egui::Button::new("🎲"),
-                        )
-                        .on_hover_text("Randomize seed")
-                        .clicked()
-                    {
-                        effect_settings.random_seed = rand::random::<i32>();
-                        changed = true;
-                    }
+/// How long each toast is shown for before it disappears.
+const TOAST_DURATION: std::time::Duration = std::time::Duration::from_millis(2500);
 
-                    let label = ui.add(egui::Label::new(descriptor.label).truncate(true));
-                    if let Some(description) = descriptor.description {
-                        label.on_hover_text(description);
-                    }
+/// The number of toasts shown at once. Older ones are dismissed early to make room.
+const MAX_TOASTS: usize = 4;
 
-                    // Return the DragValue response because that's what we want to add the tooltip to
-                    resp
-                })
-                .response
-            }
-            SettingDescriptor {
-                kind: SettingKind::Enumeration { options, .. },
-                ..
-            } => {
-                let selected_index = descriptor.id.get_field_enum(effect_settings).unwrap();
-                let selected_item = options
-                    .iter()
-                    .find(|option| option.index == selected_index)
-                    .unwrap();
-                egui::ComboBox::new(descriptor.id, descriptor.label)
-                    .selected_text(selected_item.label)
-                    .show_ui(ui, |ui| {
-                        for item in options {
-                            let mut label =
-                                ui.selectable_label(selected_index == item.index, item.label);
+/// A frame that was saved or copied during this session, kept around so frames rendered with different settings can be
+/// compared.
+struct SavedFrame {
+    texture: egui::TextureHandle,
+    // the settings the frame was rendered with, so they can be restored
+    settings: NtscEffectFullSettings,
+    settings_hash: String,
+    position: ClockTime,
+}
 
-                            if let Some(desc) = item.description {
-                                label = label.on_hover_text(desc);
-                            }
+const MAX_SAVED_FRAMES: usize = 32;
 
-                            if label.clicked() {
-                                let _ = descriptor.id.set_field_enum(effect_settings, item.index);
-                                // a selectable_label being clicked doesn't set response.changed
-                                changed = true;
-                            };
-                        }
-                    })
-                    .response
-            }
-            SettingDescriptor {
-                kind: SettingKind::Percentage { logarithmic, .. },
-                ..
-            } => ui.add(
-                egui::Slider::new(
-                    descriptor.id.get_field_mut::<f32>(effect_settings).unwrap(),
-                    0.0..=1.0,
-                )
-                .text(descriptor.label)
-                .custom_parser(parse_expression_string)
-                .custom_formatter(format_percentage)
-                .logarithmic(*logarithmic),
-            ),
-            SettingDescriptor {
-                kind: SettingKind::IntRange { range, .. },
-                ..
-            } => {
-                let mut value = 0i32;
-                if let Some(v) = descriptor.id.get_field_mut::<i32>(effect_settings) {
-                    value = *v;
-                } else if let Some(v) = descriptor.id.get_field_mut::<u32>(effect_settings) {
-                    value = *v as i32;
-                }
+/// How many files to remember the playback position of. The least recently closed ones are forgotten first.
+const MAX_PLAYBACK_POSITIONS: usize = 100;
 
-                let slider = ui.add(
-                    egui::Slider::new(&mut value, range.clone())
-                        .text(descriptor.label)
-                        .custom_parser(parse_expression_string),
-                );
+/// How long to wait after the effect settings stop changing before writing them to the recovery file.
+const AUTOSAVE_DELAY: std::time::Duration = std::time::Duration::from_secs(3);
 
-                if slider.changed() {
-                    if let Some(v) = descriptor.id.get_field_mut::<i32>(effect_settings) {
-                        *v = value;
-                    } else if let Some(v) = descriptor.id.get_field_mut::<u32>(effect_settings) {
-                        *v = value as u32;
-                    }
-                }
+/// Where the effect settings are autosaved while the app runs. It's deleted on a clean exit, so if it's still around on
+/// startup, the app must have crashed.
+fn recovery_file_path() -> Option<PathBuf> {
+    Some(eframe::storage_dir("ntsc-rs")?.join("recovery.json"))
+}
 
-                slider
-            }
-            SettingDescriptor {
-                kind:
-                    SettingKind::FloatRange {
-                        range, logarithmic, ..
-                    },
-                ..
-            } => ui.add(
-                egui::Slider::new(
-                    descriptor.id.get_field_mut::<f32>(effect_settings).unwrap(),
-                    range.clone(),
-                )
-                .text(descriptor.label)
-                .custom_parser(parse_expression_string)
-                .logarithmic(*logarithmic),
-            ),
-            SettingDescriptor {
-                kind: SettingKind::Boolean { .. },
-                ..
-            } => {
-                let checkbox = ui.checkbox(
-                    descriptor
-                        .id
-                        .get_field_mut::<bool>(effect_settings)
-                        .unwrap(),
-                    descriptor.label,
-                );
+/// Whether the recovery file was written after eframe last saved its own storage, meaning it has changes that the
+/// normal storage doesn't.
+fn recovery_file_is_newer(recovery_path: &Path) -> bool {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|metadata| metadata.modified());
+    let Ok(recovery_modified) = modified(recovery_path) else {
+        return false;
+    };
+    match recovery_path
+        .parent()
+        .map(|storage_dir| modified(&storage_dir.join("app.ron")))
+    {
+        Some(Ok(storage_modified)) => recovery_modified > storage_modified,
+        _ => true,
+    }
+}
 
-                checkbox
-            }
-            SettingDescriptor {
-                kind: SettingKind::Group { children, .. },
-                ..
-            } => {
-                ui.add_space(2.0);
-                let resp = ui
-                    .group(|ui| {
-                        ui.set_width(ui.max_rect().width());
-                        let checkbox = ui.checkbox(
-                            descriptor
-                                .id
-                                .get_field_mut::<bool>(effect_settings)
-                                .unwrap(),
-                            descriptor.label,
-                        );
+/// The number of errors kept in the error log. Older ones are dropped.
+const MAX_ERROR_LOG_ENTRIES: usize = 100;
 
-                        ui.set_enabled(
-                            *descriptor
-                                .id
-                                .get_field_mut::<bool>(effect_settings)
-                                .unwrap(),
-                        );
+const UI_SCALE_RANGE: RangeInclusive<f32> = 0.75..=2.0;
 
-                        changed |= Self::settings_from_descriptors(
-                            effect_settings,
-                            ui,
-                            children,
-                            interlace_mode,
-                        );
+trait LayoutHelper {
+    fn ltr<R>(&mut self, add_contents: impl FnOnce(&mut Self) -> R) -> egui::InnerResponse<R>;
+    fn rtl<R>(&mut self, add_contents: impl FnOnce(&mut Self) -> R) -> egui::InnerResponse<R>;
+}
 
-                        checkbox
-                    })
-                    .inner;
-                ui.add_space(2.0);
-                resp
-            }
-        };
+fn ui_with_layout<'c, R>(
+    ui: &mut egui::Ui,
+    layout: egui::Layout,
+    add_contents: Box<dyn FnOnce(&mut egui::Ui) -> R + 'c>,
+) -> egui::InnerResponse<R> {
+    let initial_size = vec2(
+        ui.available_size_before_wrap().x,
+        ui.spacing().interact_size.y,
+    );
 
-        (resp, changed)
+    ui.allocate_ui_with_layout(initial_size, layout, |ui| add_contents(ui))
+}
+
+impl LayoutHelper for egui::Ui {
+    fn ltr<R>(&mut self, add_contents: impl FnOnce(&mut egui::Ui) -> R) -> egui::InnerResponse<R> {
+        ui_with_layout(
+            self,
+            egui::Layout::left_to_right(egui::Align::Center),
+            Box::new(add_contents),
+        )
     }
 
-    fn settings_from_descriptors(
-        effect_settings: &mut NtscEffectFullSettings,
-        ui: &mut egui::Ui,
-        descriptors: &[SettingDescriptor],
-        interlace_mode: VideoInterlaceMode,
-    ) -> bool {
-        let mut changed = false;
-        for descriptor in descriptors {
-            // The "Use field" setting has no effect on interlaced video.
-            let (response, setting_changed) = if descriptor.id == SettingID::USE_FIELD
-                && interlace_mode != VideoInterlaceMode::Progressive
-            {
-                let resp = ui.add_enabled_ui(false, |ui| {
-                    Self::setting_from_descriptor(
-                        ui,
-                        effect_settings,
-                        descriptor,
-                        VideoInterlaceMode::Progressive,
-                    )
-                });
+    fn rtl<R>(&mut self, add_contents: impl FnOnce(&mut egui::Ui) -> R) -> egui::InnerResponse<R> {
+        ui_with_layout(
+            self,
+            egui::Layout::right_to_left(egui::Align::Center),
+            Box::new(add_contents),
+        )
+    }
+}
 
-                resp.inner
-            } else {
-                Self::setting_from_descriptor(ui, effect_settings, descriptor, interlace_mode)
-            };
+type AppFn = Box<dyn FnOnce(&mut NtscApp) -> Result<(), ApplicationError> + Send>;
 
-            changed |= response.changed() || setting_changed;
+struct AppExecutor {
+    waker: Waker,
+    ctx: egui::Context,
+    run_tasks: Vec<Pin<Box<dyn Future<Output = Option<AppFn>> + Send>>>,
+    queued: Vec<AppFn>,
+}
 
-            if let Some(desc) = descriptor.description {
-                response.on_hover_text(desc);
-            }
+impl AppExecutor {
+    fn new(ctx: egui::Context) -> Self {
+        let waker_ctx = ctx.clone();
+        AppExecutor {
+            waker: waker_fn::waker_fn(move || waker_ctx.request_repaint()),
+            ctx,
+            run_tasks: Vec::new(),
+            queued: Vec::new(),
         }
-
-        changed
     }
 
-    fn show_effect_settings(&mut self, ui: &mut egui::Ui) {
-        egui::TopBottomPanel::bottom("effect_load_save")
-            .exact_height(ui.spacing().interact_size.y * 2.0)
-            .show_inside(ui, |ui| {
-                ui.horizontal_centered(|ui| {
-                    if ui.button("Save").clicked() {
-                        let json = self.settings_list.to_json(&self.effect_settings);
-                        let handle = rfd::AsyncFileDialog::new()
-                            .set_file_name("settings.json")
-                            .save_file();
-                        self.spawn(async move {
-                            let handle = handle.await;
-                            let handle = match handle {
-                                Some(h) => h,
-                                None => return None,
-                            };
-
-                            Some(Box::new(move |_: &mut NtscApp| {
-                                let mut file =
-                                    File::create(handle.path()).context(JSONSaveSnafu)?;
-                                json.write_to(&mut file).context(JSONSaveSnafu)?;
-                                Ok(())
-                            }) as _)
-                        });
+    #[must_use]
+    fn tick(&mut self) -> Vec<AppFn> {
+        let mut i = 0usize;
+        let mut queued = std::mem::take(&mut self.queued);
+        while i < self.run_tasks.len() {
+            let task = &mut self.run_tasks[i];
+            match task.poll(&mut Context::from_waker(&self.waker)) {
+                Poll::Pending => {
+                    i += 1;
+                }
+                Poll::Ready(f) => {
+                    if let Some(f) = f {
+                        queued.push(f);
                     }
+                    let _ = self.run_tasks.swap_remove(i);
+                }
+            }
+        }
 
-                    if ui.button("Load").clicked() {
-                        let handle = rfd::AsyncFileDialog::new()
-                            .add_filter("JSON", &["json"])
-                            .pick_file();
-                        self.spawn(async move {
-                            let handle = handle.await;
+        queued
+    }
 
-                            Some(Box::new(
-                                move |app: &mut NtscApp| -> Result<(), ApplicationError> {
-                                    let handle = match handle {
-                                        Some(h) => h,
-                                        // user cancelled the operation
-                                        None => return Ok(()),
-                                    };
+    fn spawn(
+        &mut self,
+        future: impl Future<Output = Option<AppFn>> + 'static + Send,
+        next_frame: bool,
+    ) {
+        let mut boxed = Box::pin(future);
+        if next_frame {
+            self.run_tasks.push(boxed);
+            self.ctx.request_repaint();
+        } else {
+            match boxed.poll(&mut Context::from_waker(&self.waker)) {
+                Poll::Ready(f) => {
+                    if let Some(f) = f {
+                        self.queued.push(f);
+                    }
+                }
+                Poll::Pending => {
+                    self.run_tasks.push(boxed);
+                }
+            }
+        }
+    }
+}
 
-                                    let mut file =
-                                        File::open(handle.path()).context(JSONReadSnafu)?;
+struct NtscApp {
+    // set once GStreamer has finished initializing on its background thread, successfully or not
+    gstreamer_initialized: Arc<OnceLock<Result<(), GstreamerError>>>,
+    settings_list: SettingsList,
+    executor: Arc<Mutex<AppExecutor>>,
+    pipeline: Option<PipelineInfo>,
+    undo_history: UndoHistory,
+    // every settings slot, including the active one. see `SettingsSlot` for how the active one is stored
+    settings_slots: Vec<SettingsSlot>,
+    active_slot: usize,
+    // effect settings saved with the "Snapshot" button, to compare the current ones against
+    settings_snapshot: Option<NtscEffectFullSettings>,
+    // whether the "Compare" button is being held down this frame
+    compare_button_held: bool,
+    // whether the preview is currently showing the snapshot instead of the current settings
+    comparing_snapshot: bool,
+    video_zoom: VideoZoom,
+    video_scale: VideoScale,
+    effect_resolution: EffectResolution,
+    effect_region: EffectRegionSettings,
+    audio_volume: AudioVolume,
+    effect_preview: EffectPreviewSettings,
+    left_panel_state: LeftPanelState,
+    effect_settings: NtscEffectFullSettings,
+    effect_intensity: f32,
+    group_state: GroupUiState,
+    settings_panel_prefs: SettingsPanelPrefs,
+    render_settings: RenderSettings,
+    render_jobs: Vec<RenderJob>,
+    batch_render: Option<BatchRender>,
+    // whether the "clear all" button is waiting for the user to confirm
+    confirm_clear_render_jobs: bool,
+    // whether to ask before resetting the effect settings to their defaults
+    confirm_reset_settings: bool,
+    // the folder being watched for new videos, and the folder to render them into
+    watch_folder: Option<(FolderWatcher, PathBuf)>,
+    watch_folder_log: Vec<String>,
+    settings_json_paste: String,
+    // the name, author, and description of the last preset that was loaded or saved
+    preset_metadata: PresetMetadata,
+    // the settings as they were when a preset was last loaded, to revert back to
+    loaded_preset: Option<NtscEffectFullSettings>,
+    save_preset_open: bool,
+    pending_preset: Option<PendingPreset>,
+    // the most recent errors, oldest first
+    error_log: VecDeque<ErrorLogEntry>,
+    saved_frames: VecDeque<SavedFrame>,
+    saved_frames_open: bool,
+    // how many errors have come in since the user last dismissed the error banner
+    unseen_errors: usize,
+    // confirmations that are currently being shown, oldest first
+    toasts: VecDeque<Toast>,
+    error_log_open: bool,
+    keyboard_shortcuts: KeyboardShortcuts,
+    preview_fps: PreviewFpsCounter,
+    show_performance_overlay: bool,
+    // whether the performance overlay breaks down how long each stage of the effect takes
+    profile_effect_stages: bool,
+    guides: GuideOverlays,
+    keyboard_shortcuts_open: bool,
+    // the action whose shortcut is being recorded, and which of its shortcuts to replace (or None to add one)
+    rebinding_shortcut: Option<(ShortcutAction, Option<usize>)>,
+    // set by the "save frame" shortcut, and picked up by the video pane which has the button that does the same
+    save_frame_requested: bool,
+    color_theme: ColorTheme,
+    accent_color: Option<egui::Color32>,
+    credits_dialog_open: bool,
+    licenses_dialog_open: bool,
+    // the URL being typed into the "Open URL" dialog, if it's open
+    open_url: Option<String>,
+    left_panel_width: f32,
+    // show the transport bar's timecode as HH:MM:SS:FF instead of HH:MM:SS.mm
+    smpte_timecode: bool,
+    show_thumbnails: bool,
+    thumbnails: Option<ThumbnailGenerator>,
+    // lower the preview resolution while scrubbing or playing
+    fast_scrubbing: bool,
+    // derive the bandwidth scale from the video's height instead of using the setting's value
+    auto_bandwidth_scale: bool,
+    // the automatic bandwidth scale that the preview was last updated with
+    preview_auto_bandwidth_scale: Option<f32>,
+    // whether the preview resolution is currently lowered for fast scrubbing
+    preview_reduced: bool,
+    // run the effect's filters on the GPU. Only ever true if a usable GPU was found
+    use_gpu: bool,
+    // whether the timeline is being dragged
+    scrubbing: bool,
+    pause_when_unfocused: bool,
+    // whether to pick up where playback left off when a file is reopened
+    remember_positions: bool,
+    // copy and save frames at the source's full resolution instead of the preview's
+    full_resolution_frames: bool,
+    // the position each recently-closed file was left at, least recently closed first
+    playback_positions: VecDeque<(PathBuf, ClockTime)>,
+    // zoom factor for the whole UI, on top of the OS's display scaling
+    ui_scale: f32,
+    // whether the preview was playing when the window lost focus, and should resume once it regains focus
+    paused_for_focus: bool,
+    video_background: VideoBackground,
+    checkerboard_texture: Option<egui::TextureHandle>,
+    // print each render job's progress to stdout as JSON lines, for wrapper scripts
+    progress_json: bool,
+    // the effect settings as of their most recent change, and when that change happened if they haven't been
+    // autosaved since
+    autosave_settings: NtscEffectFullSettings,
+    autosave_changed_at: Option<Instant>,
+    // settings from a recovery file left behind by a crash, which the user is being asked whether to restore
+    recovered_settings: Option<NtscEffectFullSettings>,
+    // the options for the "Variation grid" dialog, if it's open
+    variation_grid: Option<VariationGridOptions>,
+    render_variations: RenderVariationOptions,
+    // the title last set on the window, so it's only changed when the render progress in it does
+    window_title: String,
+    keyframes: KeyframeTracks,
+    // the setting that "Add keyframe" adds a keyframe for
+    keyframe_setting: Option<SettingID>,
+    debug_log_level: DebugLogLevel,
+    // the file GStreamer's debug log is being copied into, if any
+    debug_log_file: Option<DebugLogFile>,
+}
 
-                                    let mut buf = String::new();
-                                    file.read_to_string(&mut buf).context(JSONReadSnafu)?;
+impl NtscApp {
+    fn new(
+        ctx: egui::Context,
+        settings_list: SettingsList,
+        effect_settings: NtscEffectFullSettings,
+        color_theme: ColorTheme,
+        accent_color: Option<egui::Color32>,
+        gstreamer_initialized: Arc<OnceLock<Result<(), GstreamerError>>>,
+    ) -> Self {
+        Self {
+            gstreamer_initialized,
+            settings_list,
+            pipeline: None,
+            undo_history: UndoHistory::new(effect_settings.clone()),
+            settings_slots: vec![SettingsSlot::new(
+                String::from("A"),
+                NtscEffectFullSettings::default(),
+                UndoHistory::DEFAULT_MAX_UNDOS,
+            )],
+            active_slot: 0,
+            settings_snapshot: None,
+            compare_button_held: false,
+            comparing_snapshot: false,
+            executor: Arc::new(Mutex::new(AppExecutor::new(ctx.clone()))),
+            video_zoom: VideoZoom::default(),
+            video_scale: VideoScale::default(),
+            effect_resolution: EffectResolution::default(),
+            effect_region: EffectRegionSettings::default(),
+            audio_volume: AudioVolume::default(),
+            effect_preview: EffectPreviewSettings::default(),
+            left_panel_state: LeftPanelState::default(),
+            effect_settings: effect_settings.clone(),
+            effect_intensity: 1.0,
+            group_state: GroupUiState::default(),
+            settings_panel_prefs: SettingsPanelPrefs::default(),
+            render_settings: RenderSettings::default(),
+            render_jobs: Vec::new(),
+            batch_render: None,
+            confirm_clear_render_jobs: false,
+            confirm_reset_settings: false,
+            watch_folder: None,
+            watch_folder_log: Vec::new(),
+            settings_json_paste: String::new(),
+            preset_metadata: PresetMetadata::default(),
+            loaded_preset: None,
+            save_preset_open: false,
+            pending_preset: None,
+            error_log: VecDeque::new(),
+            saved_frames: VecDeque::new(),
+            saved_frames_open: false,
+            unseen_errors: 0,
+            toasts: VecDeque::new(),
+            error_log_open: false,
+            keyboard_shortcuts: KeyboardShortcuts::default(),
+            preview_fps: PreviewFpsCounter::default(),
+            show_performance_overlay: false,
+            profile_effect_stages: false,
+            guides: GuideOverlays::default(),
+            keyboard_shortcuts_open: false,
+            rebinding_shortcut: None,
+            save_frame_requested: false,
+            color_theme,
+            accent_color,
+            credits_dialog_open: false,
+            licenses_dialog_open: false,
+            open_url: None,
+            left_panel_width: 425.0,
+            smpte_timecode: false,
+            show_thumbnails: false,
+            thumbnails: None,
+            fast_scrubbing: false,
+            auto_bandwidth_scale: false,
+            preview_auto_bandwidth_scale: None,
+            preview_reduced: false,
+            use_gpu: false,
+            scrubbing: false,
+            pause_when_unfocused: true,
+            remember_positions: false,
+            full_resolution_frames: false,
+            playback_positions: VecDeque::new(),
+            ui_scale: 1.0,
+            paused_for_focus: false,
+            video_background: VideoBackground::default(),
+            checkerboard_texture: None,
+            progress_json: false,
+            autosave_settings: effect_settings,
+            autosave_changed_at: None,
+            recovered_settings: None,
+            variation_grid: None,
+            render_variations: RenderVariationOptions::default(),
+            window_title: String::from("ntsc-rs"),
+            keyframes: KeyframeTracks::default(),
+            keyframe_setting: None,
+            debug_log_level: DebugLogLevel::default(),
+            debug_log_file: None,
+        }
+    }
 
-                                    let settings = app
-                                        .settings_list
-                                        .from_json(&buf)
-                                        .context(JSONParseSnafu)?;
+    /// Set the maximum number of undo steps that are kept around. This clears the undo history.
+    fn set_max_undos(&mut self, max_undos: usize) {
+        self.undo_history = UndoHistory::new(self.effect_settings.clone());
+        self.undo_history.set_max_undos(max_undos);
+        for slot in &mut self.settings_slots {
+            let name = std::mem::take(&mut slot.name);
+            *slot = SettingsSlot::new(name, slot.settings.clone(), max_undos);
+        }
+    }
 
-                                    app.effect_settings = settings;
-                                    app.update_effect();
+    /// Exchange the active slot's settings and undo history with the ones stored in `settings_slots`. Called once to
+    /// stash the active slot away, and again after changing `active_slot` to bring the new one in.
+    fn swap_active_slot(&mut self) {
+        let slot = &mut self.settings_slots[self.active_slot];
+        std::mem::swap(&mut self.effect_settings, &mut slot.settings);
+        std::mem::swap(&mut self.undo_history, &mut slot.undo_history);
+    }
 
-                                    Ok(())
+    fn switch_settings_slot(&mut self, index: usize) {
+        if index == self.active_slot || index >= self.settings_slots.len() {
+            return;
+        }
+        self.swap_active_slot();
+        self.active_slot = index;
+        self.swap_active_slot();
+        self.update_effect();
+    }
+
+    /// The settings of every slot, including the active one.
+    fn settings_slot_settings(&self, index: usize) -> &NtscEffectFullSettings {
+        if index == self.active_slot {
+            &self.effect_settings
+        } else {
+            &self.settings_slots[index].settings
+        }
+    }
+
+    /// A name for a new slot that isn't taken yet: the next letter of the alphabet, or a number once those run out.
+    fn new_settings_slot_name(&self) -> String {
+        ('A'..='Z')
+            .map(String::from)
+            .chain((1..).map(|n| n.to_string()))
+            .find(|name| self.settings_slots.iter().all(|slot| slot.name != *name))
+            .unwrap()
+    }
+
+    fn apply_settings_slot_action(&mut self, action: SettingsSlotAction) {
+        match action {
+            SettingsSlotAction::Switch(index) => self.switch_settings_slot(index),
+            SettingsSlotAction::Add | SettingsSlotAction::Duplicate(_) => {
+                let settings = match action {
+                    SettingsSlotAction::Duplicate(index) => {
+                        self.settings_slot_settings(index).clone()
+                    }
+                    _ => NtscEffectFullSettings::default(),
+                };
+                let slot = SettingsSlot::new(
+                    self.new_settings_slot_name(),
+                    settings,
+                    self.undo_history.max_undos,
+                );
+                self.settings_slots.push(slot);
+                self.switch_settings_slot(self.settings_slots.len() - 1);
+            }
+            SettingsSlotAction::Remove(index) => {
+                if self.settings_slots.len() <= 1 {
+                    return;
+                }
+                if index == self.active_slot {
+                    // Move to a neighboring slot first so the removed one isn't holding the active settings
+                    let neighbor = if index == 0 { 1 } else { index - 1 };
+                    self.switch_settings_slot(neighbor);
+                }
+                self.settings_slots.remove(index);
+                if self.active_slot > index {
+                    self.active_slot -= 1;
+                }
+            }
+        }
+    }
+
+    fn settings_slots_to_json(&self) -> JsonValue {
+        JsonValue::Array(
+            (0..self.settings_slots.len())
+                .map(|index| {
+                    JsonValue::Object(HashMap::from([
+                        (
+                            String::from("name"),
+                            JsonValue::String(self.settings_slots[index].name.clone()),
+                        ),
+                        (
+                            String::from("settings"),
+                            self.settings_list
+                                .to_json(self.settings_slot_settings(index)),
+                        ),
+                    ]))
+                })
+                .collect(),
+        )
+    }
+
+    /// Replace the settings slots with ones saved by `settings_slots_to_json`. Slots whose settings can't be parsed are
+    /// skipped.
+    fn restore_settings_slots(&mut self, json: &str, active_slot: usize) {
+        let Ok(JsonValue::Array(slots)) = json.parse::<JsonValue>() else {
+            return;
+        };
+        let max_undos = self.undo_history.max_undos;
+        let slots = slots
+            .iter()
+            .filter_map(|slot| {
+                let slot = slot.get::<HashMap<String, JsonValue>>()?;
+                let name = slot.get("name")?.get::<String>()?.clone();
+                let settings = self
+                    .settings_list
+                    .from_json(&slot.get("settings")?.stringify().ok()?)
+                    .ok()?;
+                Some(SettingsSlot::new(name, settings, max_undos))
+            })
+            .collect::<Vec<_>>();
+        if slots.is_empty() {
+            return;
+        }
+
+        self.settings_slots = slots;
+        self.active_slot = active_slot.min(self.settings_slots.len() - 1);
+        self.effect_settings = NtscEffectFullSettings::default();
+        self.undo_history = UndoHistory::new(NtscEffectFullSettings::default());
+        self.undo_history.set_max_undos(max_undos);
+        self.swap_active_slot();
+    }
+
+    /// Restore the window geometry, panel layout, and render settings from the last session.
+    fn restore_session(&mut self, storage: &dyn eframe::Storage) {
+        if let Some(max_undos) = storage
+            .get_string("max_undos")
+            .and_then(|max_undos| max_undos.parse::<usize>().ok())
+        {
+            self.set_max_undos(max_undos.max(1));
+        }
+
+        if let Some(settings_slots) = storage.get_string("settings_slots") {
+            let active_slot = storage
+                .get_string("active_slot")
+                .and_then(|active_slot| active_slot.parse::<usize>().ok())
+                .unwrap_or_default();
+            self.restore_settings_slots(&settings_slots, active_slot);
+        }
+
+        if let Some(render_settings) = storage
+            .get_string("render_settings")
+            .and_then(|render_settings| RenderSettings::from_json(&render_settings))
+        {
+            self.render_settings = render_settings;
+        }
+
+        if let Some(width) = storage
+            .get_string("left_panel_width")
+            .and_then(|width| width.parse::<f32>().ok())
+        {
+            self.left_panel_width = width;
+        }
+
+        if let Some(smpte_timecode) = storage
+            .get_string("smpte_timecode")
+            .and_then(|smpte_timecode| smpte_timecode.parse::<bool>().ok())
+        {
+            self.smpte_timecode = smpte_timecode;
+        }
+
+        if let Some(keyboard_shortcuts) = storage
+            .get_string("keyboard_shortcuts")
+            .and_then(|keyboard_shortcuts| KeyboardShortcuts::from_json(&keyboard_shortcuts))
+        {
+            self.keyboard_shortcuts = keyboard_shortcuts;
+        }
+
+        if let Some(show_performance_overlay) = storage
+            .get_string("show_performance_overlay")
+            .and_then(|show_performance_overlay| show_performance_overlay.parse::<bool>().ok())
+        {
+            self.show_performance_overlay = show_performance_overlay;
+        }
+
+        if let Some(profile_effect_stages) = storage
+            .get_string("profile_effect_stages")
+            .and_then(|profile_effect_stages| profile_effect_stages.parse::<bool>().ok())
+        {
+            self.profile_effect_stages = profile_effect_stages;
+        }
+
+        if let Some(guides) = storage.get_string("guides") {
+            self.guides = GuideOverlays::from_storage(&guides);
+        }
+
+        if let Some(favorite_settings) = storage.get_string("favorite_settings") {
+            self.settings_panel_prefs.favorites =
+                FavoriteSettings::from_storage(&favorite_settings, &self.settings_list);
+        }
+
+        if let Some(language) = storage.get_string("language") {
+            i18n::set_language(&language);
+        }
+
+        if storage
+            .get_string("use_gpu")
+            .and_then(|use_gpu| use_gpu.parse::<bool>().ok())
+            .unwrap_or(false)
+        {
+            self.set_use_gpu(true);
+        }
+
+        if let Some(show_thumbnails) = storage
+            .get_string("show_thumbnails")
+            .and_then(|show_thumbnails| show_thumbnails.parse::<bool>().ok())
+        {
+            self.show_thumbnails = show_thumbnails;
+        }
+
+        if let Some(square_pixels) = storage
+            .get_string("square_pixels")
+            .and_then(|square_pixels| square_pixels.parse::<bool>().ok())
+        {
+            self.video_scale.square_pixels = square_pixels;
+        }
+
+        if let Some(auto_bandwidth_scale) = storage
+            .get_string("auto_bandwidth_scale")
+            .and_then(|auto_bandwidth_scale| auto_bandwidth_scale.parse::<bool>().ok())
+        {
+            self.auto_bandwidth_scale = auto_bandwidth_scale;
+        }
+
+        if let Some(fast_scrubbing) = storage
+            .get_string("fast_scrubbing")
+            .and_then(|fast_scrubbing| fast_scrubbing.parse::<bool>().ok())
+        {
+            self.fast_scrubbing = fast_scrubbing;
+        }
+
+        if let Some(full_resolution_frames) = storage
+            .get_string("full_resolution_frames")
+            .and_then(|full_resolution_frames| full_resolution_frames.parse::<bool>().ok())
+        {
+            self.full_resolution_frames = full_resolution_frames;
+        }
+
+        if let Some(fit_mode) = storage.get_string("zoom_fit_mode").and_then(|fit_mode| {
+            ZoomFit::ALL
+                .iter()
+                .copied()
+                .find(|mode| mode.name() == fit_mode)
+        }) {
+            self.video_zoom.fit_mode = fit_mode;
+        }
+
+        if let Some(pause_when_unfocused) = storage
+            .get_string("pause_when_unfocused")
+            .and_then(|pause_when_unfocused| pause_when_unfocused.parse::<bool>().ok())
+        {
+            self.pause_when_unfocused = pause_when_unfocused;
+        }
+
+        if let Some(remember_positions) = storage
+            .get_string("remember_positions")
+            .and_then(|remember_positions| remember_positions.parse::<bool>().ok())
+        {
+            self.remember_positions = remember_positions;
+        }
+
+        if let Some(playback_positions) = storage
+            .get_string("playback_positions")
+            .and_then(|json| json.parse::<JsonValue>().ok())
+        {
+            if let Some(entries) = playback_positions.get::<Vec<JsonValue>>() {
+                self.playback_positions = entries
+                    .iter()
+                    .filter_map(|entry| {
+                        let entry = entry.get::<HashMap<String, JsonValue>>()?;
+                        let path = json_string(entry, "path")?;
+                        let position = json_number(entry, "position_ms")?;
+                        Some((
+                            PathBuf::from(path),
+                            ClockTime::from_mseconds(position.max(0.0) as u64),
+                        ))
+                    })
+                    .collect();
+            }
+        }
+
+        if let Some(ui_scale) = storage
+            .get_string("ui_scale")
+            .and_then(|ui_scale| ui_scale.parse::<f32>().ok())
+        {
+            self.ui_scale = ui_scale.clamp(*UI_SCALE_RANGE.start(), *UI_SCALE_RANGE.end());
+        }
+    }
+
+    fn spawn(&mut self, future: impl Future<Output = Option<AppFn>> + 'static + Send) {
+        self.executor.lock().unwrap().spawn(future, false);
+    }
+
+    fn execute_fn_next_frame<T: Future<Output = Option<AppFn>> + 'static + Send>(
+        &self,
+    ) -> impl Fn(T) + Send {
+        let weak_exec = self.executor.downgrade();
+
+        move |future: T| {
+            if let Some(exec) = weak_exec.upgrade() {
+                exec.lock().unwrap().spawn(future, true);
+            }
+        }
+    }
+
+    fn tick(&mut self) {
+        loop {
+            // Get the functions to be executed at the end of the completed futures.
+            let app_fns = {
+                let exec = Arc::clone(&self.executor);
+                let mut exec = exec.lock().unwrap();
+                exec.tick()
+            };
+
+            // If there are none, we're done. If there are, loop--executing them may spawn more futures.
+            if app_fns.is_empty() {
+                break;
+            }
+
+            // Execute functions outside the executor--if they call `spawn`, we don't want to recursively lock the
+            // executor's mutex.
+            for f in app_fns {
+                self.handle_result_with(f);
+            }
+        }
+    }
+
+    fn load_video(&mut self, ctx: &egui::Context, path: PathBuf) -> Result<(), ApplicationError> {
+        self.remove_pipeline().context(LoadVideoSnafu)?;
+        self.preview_reduced = false;
+        self.paused_for_focus = false;
+        self.render_settings.suggest_output_path(&path);
+        let mut info = self
+            .create_preview_pipeline(ctx, path)
+            .context(LoadVideoSnafu)?;
+        if self.remember_positions {
+            info.resume_position = self
+                .playback_positions
+                .iter()
+                .find(|(p, _)| *p == info.path)
+                .map(|(_, position)| *position);
+        }
+        self.pipeline = Some(info);
+        self.update_thumbnails(ctx);
+
+        Ok(())
+    }
+
+    fn load_camera(&mut self, ctx: &egui::Context) -> Result<(), ApplicationError> {
+        self.remove_pipeline().context(LoadVideoSnafu)?;
+        self.preview_reduced = false;
+        self.paused_for_focus = false;
+        self.render_settings
+            .suggest_output_path(Path::new("camera"));
+        let info = self
+            .create_preview_pipeline(ctx, PathBuf::from(CAMERA_PATH))
+            .context(LoadVideoSnafu)?;
+        // Live sources don't preroll, so nothing would be shown until the preview is played
+        info.pipeline
+            .set_state(gstreamer::State::Playing)
+            .map_err(GstreamerError::from)
+            .context(LoadVideoSnafu)?;
+        self.pipeline = Some(info);
+        self.thumbnails = None;
+
+        Ok(())
+    }
+
+    /// Start generating timeline thumbnails for the current video if they're enabled, or stop if they're not.
+    fn update_thumbnails(&mut self, ctx: &egui::Context) {
+        const NUM_THUMBNAILS: usize = 100;
+
+        self.thumbnails = match &self.pipeline {
+            // Thumbnails would mean capturing from the camera or downloading the stream a second time
+            Some(PipelineInfo { path, .. })
+                if self.show_thumbnails && !is_camera(path) && !is_url(path) =>
+            {
+                Some(ThumbnailGenerator::new(ctx, path.clone(), NUM_THUMBNAILS))
+            }
+            _ => None,
+        };
+    }
+
+    /// The number of scanlines to scale the preview to, if any. While fast scrubbing is active, this is capped to a
+    /// low resolution so the effect can keep up.
+    fn preview_scanlines(&self) -> Option<usize> {
+        const FAST_SCRUBBING_SCANLINES: usize = 360;
+
+        let scanlines = self.video_scale.enabled.then_some(self.video_scale.scale);
+        if !self.preview_reduced {
+            return scanlines;
+        }
+
+        let source_height = self
+            .pipeline
+            .as_ref()
+            .and_then(|info| info.metadata.lock().unwrap().resolution)
+            .map(|(_, height)| height);
+        match scanlines.or(source_height) {
+            Some(height) if height > FAST_SCRUBBING_SCANLINES => Some(FAST_SCRUBBING_SCANLINES),
+            _ => scanlines,
+        }
+    }
+
+    /// A 2x2 checkerboard texture which repeats when drawn with UV coordinates outside 0-1.
+    fn checkerboard_texture(&mut self, ctx: &egui::Context) -> egui::TextureHandle {
+        self.checkerboard_texture
+            .get_or_insert_with(|| {
+                let light = egui::Color32::from_gray(204);
+                let dark = egui::Color32::from_gray(153);
+                ctx.load_texture(
+                    "checkerboard",
+                    ColorImage {
+                        size: [2, 2],
+                        pixels: vec![light, dark, dark, light],
+                    },
+                    egui::TextureOptions {
+                        wrap_mode: egui::TextureWrapMode::Repeat,
+                        ..egui::TextureOptions::NEAREST
+                    },
+                )
+            })
+            .clone()
+    }
+
+    /// Pause the preview while the window is in the background, and resume it when the window is focused again. Only
+    /// a preview that was playing is resumed.
+    fn update_focus_pause(&mut self, ctx: &egui::Context) {
+        let Some(info) = &self.pipeline else {
+            self.paused_for_focus = false;
+            return;
+        };
+
+        let focused = ctx.input(|input| input.focused);
+        if focused {
+            if self.paused_for_focus {
+                self.paused_for_focus = false;
+                let res = info.pipeline.set_state(gstreamer::State::Playing);
+                self.handle_result(res);
+            }
+        } else if self.pause_when_unfocused
+            && !self.paused_for_focus
+            && info.pipeline.current_state() == gstreamer::State::Playing
+        {
+            self.paused_for_focus = true;
+            let res = info.pipeline.set_state(gstreamer::State::Paused);
+            self.handle_result(res);
+        }
+    }
+
+    /// Lower the preview resolution when the user starts scrubbing or playing the video, and restore it once they stop.
+    fn update_fast_scrubbing(&mut self) {
+        let Some(info) = &self.pipeline else {
+            return;
+        };
+        let reduce = self.fast_scrubbing
+            && (self.scrubbing || info.pipeline.current_state() == gstreamer::State::Playing);
+        if reduce == self.preview_reduced {
+            return;
+        }
+
+        self.preview_reduced = reduce;
+        let res = Self::rescale_video(
+            &info.pipeline,
+            info.last_seek_pos,
+            self.preview_scanlines(),
+            self.video_scale.square_pixels,
+        );
+        self.handle_result(res);
+    }
+
+    fn rescale_video(
+        pipeline: &gstreamer::Pipeline,
+        seek_pos: ClockTime,
+        scanlines: Option<usize>,
+        square_pixels: bool,
+    ) -> Result<(), GstreamerError> {
+        let caps_filter = pipeline.by_name("caps_filter").unwrap();
+
+        let scale_caps = pipeline
+            .by_name("video_scale")
+            .and_then(|elem| elem.static_pad("sink"))
+            .and_then(|pad| pad.current_caps());
+        let caps = match scale_caps {
+            Some(scale_caps) => scaled_caps(&scale_caps, scanlines, square_pixels),
+            None if scanlines.is_none() && !square_pixels => {
+                gstreamer_video::VideoCapsBuilder::default().build()
+            }
+            None => return Ok(()),
+        };
+        caps_filter.set_property("caps", caps);
+
+        pipeline.seek_simple(
+            gstreamer::SeekFlags::FLUSH | gstreamer::SeekFlags::ACCURATE,
+            pipeline.query_position::<ClockTime>().unwrap_or(seek_pos),
+        )?;
+
+        Ok(())
+    }
+
+    /// Change the resolution the preview's effect is applied at, and re-render the current frame.
+    fn set_effect_resolution(
+        pipeline: &gstreamer::Pipeline,
+        seek_pos: ClockTime,
+        scanlines: Option<usize>,
+    ) -> Result<(), GstreamerError> {
+        let Some(caps_filter) = pipeline.by_name("effect_caps_filter") else {
+            return Ok(());
+        };
+        caps_filter.set_property("caps", max_height_caps(scanlines));
+
+        pipeline.seek_simple(
+            gstreamer::SeekFlags::FLUSH | gstreamer::SeekFlags::ACCURATE,
+            pipeline.query_position::<ClockTime>().unwrap_or(seek_pos),
+        )?;
+
+        Ok(())
+    }
+
+    fn set_still_image_framerate(
+        pipeline: &gstreamer::Pipeline,
+        framerate: gstreamer::Fraction,
+    ) -> Result<Option<gstreamer::Fraction>, GstreamerError> {
+        // Image sequences get their framerate from the source; still images from the caps filter after imagefreeze
+        if !set_image_sequence_framerate(pipeline, framerate) {
+            let Some(caps_filter) = pipeline.by_name("framerate_caps_filter") else {
+                return Ok(None);
+            };
+
+            caps_filter.set_property(
+                "caps",
+                VideoCapsBuilder::default().framerate(framerate).build(),
+            );
+        }
+        // This seek is necessary to prevent caps negotiation from failing due to race conditions, for some reason.
+        // It seems like in some cases, there would be "tearing" in the caps between different elements, where some
+        // elements' caps would use the old framerate and some would use the new framerate. This would cause caps
+        // negotiation to fail, even though the caps filter sends a "reconfigure" event. This in turn woulc make the
+        // entire pipeline error out.
+        if let Some(seek_pos) = pipeline.query_position::<ClockTime>() {
+            pipeline.seek_simple(
+                gstreamer::SeekFlags::FLUSH | gstreamer::SeekFlags::ACCURATE,
+                seek_pos,
+            )?;
+            Ok(Some(framerate))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Switch the preview to another of the source's audio tracks. Renders started afterwards use it too.
+    fn set_audio_track(&mut self, track: usize) {
+        let Some(info) = &mut self.pipeline else {
+            return;
+        };
+        let Some(selector) = info.pipeline.by_name("audio_selector") else {
+            return;
+        };
+        let Some(pad) = selector.static_pad(&format!("sink_{}", track)) else {
+            return;
+        };
+        selector.set_property("active-pad", &pad);
+        info.audio_track = track;
+    }
+
+    fn update_volume(&self) {
+        if let Some(pipeline_info) = &self.pipeline {
+            NtscApp::set_volume(
+                &pipeline_info.pipeline,
+                // Unlogarithmify volume (at least to my ears, this gives more control at the low end of the slider)
+                10f64.powf(self.audio_volume.gain - 1.0).max(0.0),
+                // Audio played backwards isn't very useful, and neither are the snippets played while scrubbing
+                self.audio_volume.mute || pipeline_info.reverse || self.scrubbing,
+            );
+        }
+    }
+
+    fn set_reverse_playback(&mut self, reverse: bool) {
+        let Some(info) = &mut self.pipeline else {
+            return;
+        };
+        let res = info.set_reverse(reverse).and_then(|_| {
+            if reverse {
+                info.pipeline.set_state(gstreamer::State::Playing)?;
+            }
+            Ok(())
+        });
+        self.update_volume();
+        if let Err(err) = res {
+            self.push_error(tr!("This video can't be played backwards: {error}", error = err));
+        }
+    }
+
+    fn set_volume(pipeline: &gstreamer::Pipeline, volume: f64, mute: bool) {
+        let Some(audio_volume) = pipeline.by_name("audio_volume") else {
+            return;
+        };
+
+        audio_volume.set_property("volume", volume);
+        audio_volume.set_property("mute", mute);
+    }
+
+    fn sink_preview_mode(preview_settings: &EffectPreviewSettings) -> EffectPreviewSetting {
+        match preview_settings.mode {
+            EffectPreviewMode::Enabled => EffectPreviewSetting::Enabled,
+            EffectPreviewMode::Disabled => EffectPreviewSetting::Disabled,
+            EffectPreviewMode::SplitScreen => EffectPreviewSetting::SplitScreen {
+                rect: preview_settings.preview_rect,
+                invert: preview_settings.invert_split,
+            },
+            EffectPreviewMode::Channel => EffectPreviewSetting::Channel(preview_settings.channel),
+        }
+    }
+
+    fn create_preview_pipeline(
+        &mut self,
+        ctx: &egui::Context,
+        path: PathBuf,
+    ) -> Result<PipelineInfo, GstreamerError> {
+        let still_image_framerate = self.render_settings.still_image_framerate();
+        let src = create_source(&path, still_image_framerate)?;
+
+        let audio_sink = gstreamer::ElementFactory::make("autoaudiosink").build()?;
+
+        let tex = ctx.load_texture(
+            "preview",
+            egui::ColorImage::from_rgb([1, 1], &[0, 0, 0]),
+            egui::TextureOptions::LINEAR,
+        );
+        let tex_sink = SinkTexture(Some(tex.clone()));
+        let egui_ctx = EguiCtx(Some(ctx.clone()));
+        let video_sink = gstreamer::ElementFactory::make("eguisink")
+            .property("texture", tex_sink)
+            .property("ctx", egui_ctx)
+            .property(
+                "settings",
+                NtscFilterSettings(self.preview_effect_settings()),
+            )
+            .property(
+                "preview-mode",
+                Self::sink_preview_mode(&self.effect_preview),
+            )
+            .property("preserve-alpha", self.render_settings.preserve_alpha)
+            .property("region", NtscFilterRegion(self.effect_region.region()))
+            .property("profile-stages", self.profiling_stages())
+            .build()?;
+
+        let pipeline_info_state = Arc::new(Mutex::new(PipelineInfoState::Loading));
+        let pipeline_info_state_for_handler = Arc::clone(&pipeline_info_state);
+        let pipeline_info_state_for_callback = Arc::clone(&pipeline_info_state);
+        let at_eos = Arc::new(Mutex::new(false));
+        let at_eos_for_handler = Arc::clone(&at_eos);
+        let ctx_for_handler = ctx.clone();
+        let ctx_for_callback = ctx.clone();
+
+        let metadata = Arc::new(Mutex::new(PipelineMetadata {
+            is_live: is_camera(&path),
+            ..Default::default()
+        }));
+        let metadata_for_audio_handler = metadata.clone();
+        let metadata_for_bus_handler = metadata.clone();
+
+        let audio_sink_for_closure = audio_sink.clone();
+        let video_sink_for_closure = video_sink.clone();
+        let effect_scanlines = self
+            .effect_resolution
+            .enabled
+            .then_some(self.effect_resolution.scanlines);
+
+        let pipeline = create_pipeline(
+            src.clone(),
+            move |pipeline| {
+                pipeline.add(&audio_sink_for_closure)?;
+                metadata_for_audio_handler.lock().unwrap().has_audio = Some(true);
+                Ok(Some(audio_sink_for_closure))
+            },
+            move |pipeline| {
+                // The preview is scaled back up when it's drawn, so it only needs to be scaled down before the effect
+                let effect_scale = gstreamer::ElementFactory::make("videoscale")
+                    .name("effect_scale")
+                    .build()?;
+                let effect_caps_filter = gstreamer::ElementFactory::make("capsfilter")
+                    .name("effect_caps_filter")
+                    .property("caps", max_height_caps(effect_scanlines))
+                    .build()?;
+                let elems = [&effect_scale, &effect_caps_filter, &video_sink_for_closure];
+                pipeline.add_many(elems)?;
+                gstreamer::Element::link_many(elems)?;
+                effect_caps_filter.sync_state_with_parent()?;
+                video_sink_for_closure.sync_state_with_parent()?;
+                Ok(effect_scale)
+            },
+            move |bus, msg| {
+                debug!("{:?}", msg);
+                let at_eos = &at_eos_for_handler;
+                let ctx = &ctx_for_handler;
+                let pipeline_info_state = &pipeline_info_state_for_handler;
+                let metadata = &metadata_for_bus_handler;
+
+                let handle_msg = move |_bus, msg: &gstreamer::Message| -> Option<()> {
+                    // Make sure we're listening to a pipeline event
+                    let src = msg.src()?;
+
+                    if let gstreamer::MessageView::Tag(tag_msg) = msg.view() {
+                        metadata
+                            .lock()
+                            .unwrap()
+                            .media_info
+                            .update_from_tags(&tag_msg.tags());
+                    }
+
+                    if let gstreamer::MessageView::Element(element_msg) = msg.view() {
+                        if let Some(structure) = element_msg
+                            .structure()
+                            .filter(|structure| structure.name() == "missing-plugin")
+                        {
+                            metadata.lock().unwrap().missing_codec =
+                                structure.get::<String>("name").ok();
+                        }
+                        if let Some(structure) = element_msg
+                            .structure()
+                            .filter(|structure| structure.name() == "hdr-input")
+                        {
+                            metadata.lock().unwrap().hdr_transfer =
+                                structure.get::<HdrTransfer>("transfer").ok();
+                        }
+                        if let Some(structure) = element_msg
+                            .structure()
+                            .filter(|structure| structure.name() == "audio-track")
+                        {
+                            let index = structure.get::<u32>("index").ok()? as usize;
+                            let audio_tracks = &mut metadata.lock().unwrap().audio_tracks;
+                            if audio_tracks.len() <= index {
+                                audio_tracks.resize(index + 1, AudioTrack::default());
+                            }
+                            audio_tracks[index] = AudioTrack {
+                                language: structure.get("language").ok(),
+                                title: structure.get("title").ok(),
+                            };
+                            ctx.request_repaint();
+                        }
+                    }
+
+                    if let gstreamer::MessageView::Error(err_msg) = msg.view() {
+                        debug!("handling error message: {:?}", msg);
+                        let mut pipeline_state = pipeline_info_state.lock().unwrap();
+                        if !matches!(&*pipeline_state, PipelineInfoState::Error(_)) {
+                            *pipeline_state = PipelineInfoState::Error(err_msg.error().into());
+                            ctx.request_repaint();
+                        }
+                    }
+
+                    if let Some(pipeline) = src.downcast_ref::<gstreamer::Pipeline>() {
+                        // We want to pause the pipeline at EOS, but setting an element's state inside the bus handler doesn't
+                        // work. Instead, wait for the next egui event loop then pause.
+                        if let gstreamer::MessageView::Eos(_) = msg.view() {
+                            *at_eos.lock().unwrap() = true;
+                            ctx.request_repaint();
+                        }
+
+                        if let gstreamer::MessageView::StateChanged(state_changed) = msg.view() {
+                            if state_changed.old() == gstreamer::State::Ready
+                                && matches!(
+                                    state_changed.current(),
+                                    gstreamer::State::Paused | gstreamer::State::Playing
+                                )
+                            {
+                                // Changed from READY to PAUSED/PLAYING.
+                                *pipeline_info_state.lock().unwrap() = PipelineInfoState::Loaded;
+
+                                let mut metadata = metadata.lock().unwrap();
+
+                                let is_still_image =
+                                    pipeline.by_name("still_image_freeze").is_some();
+                                metadata.is_still_image = Some(is_still_image);
+                                metadata.is_image_sequence =
+                                    pipeline.by_name(IMAGE_SEQUENCE_SRC_NAME).is_some();
+
+                                let mut seeking =
+                                    gstreamer::query::Seeking::new(gstreamer::Format::Time);
+                                metadata.seekable =
+                                    pipeline.query(&mut seeking).then(|| seeking.result().0);
+
+                                metadata.media_info.update_from_pipeline(pipeline);
+
+                                let video_rate = pipeline.by_name("video_rate");
+                                let caps = video_rate.and_then(|video_rate| {
+                                    video_rate.static_pad("src").and_then(|pad| pad.caps())
+                                });
+
+                                if let Some(caps) = caps {
+                                    let structure = caps.structure(0);
+
+                                    metadata.framerate = structure.and_then(|structure| {
+                                        structure.get::<gstreamer::Fraction>("framerate").ok()
+                                    });
+
+                                    metadata.interlace_mode = structure.and_then(|structure| {
+                                        Some(VideoInterlaceMode::from_string(
+                                            structure.get("interlace-mode").ok()?,
+                                        ))
+                                    });
+
+                                    metadata.field_order = structure.and_then(|structure| {
+                                        Some(VideoFieldOrder::from_string(
+                                            structure.get("field-order").ok()?,
+                                        ))
+                                    });
+
+                                    metadata.resolution = structure.and_then(|structure| {
+                                        Some((
+                                            structure.get::<i32>("width").ok()? as usize,
+                                            structure.get::<i32>("height").ok()? as usize,
+                                        ))
+                                    });
+
+                                    metadata.pixel_aspect_ratio = Some(pixel_aspect_ratio(&caps));
+                                } else {
+                                    metadata.framerate = None;
+                                    metadata.interlace_mode = None;
+                                    metadata.field_order = None;
+                                    metadata.resolution = None;
+                                    metadata.pixel_aspect_ratio = None;
+                                }
+                            }
+                        }
+                    }
+
+                    Some(())
+                };
+
+                handle_msg(bus, msg);
+
+                gstreamer::BusSyncReply::Drop
+            },
+            None,
+            if self.video_scale.enabled {
+                Some(self.video_scale.scale)
+            } else {
+                None
+            },
+            self.video_scale.square_pixels,
+            still_image_framerate,
+            self.render_settings.tonemap,
+            0,
+            Some(move |p: Result<gstreamer::Pipeline, PipelineError>| {
+                if let Err(e) = p {
+                    *pipeline_info_state_for_callback.lock().unwrap() = PipelineInfoState::Error(e);
+                    ctx_for_callback.request_repaint();
+                }
+            }),
+        )?;
+
+        pipeline.set_state(gstreamer::State::Paused)?;
+
+        Ok(PipelineInfo {
+            pipeline,
+            state: pipeline_info_state,
+            path,
+            egui_sink: video_sink,
+            at_eos,
+            last_seek_pos: ClockTime::ZERO,
+            preview: tex,
+            metadata,
+            markers: Vec::new(),
+            reverse: false,
+            audio_track: 0,
+            resume_position: None,
+        })
+    }
+
+    fn pixel_formats_for(bit_depth: usize, chroma_subsampling: bool) -> &'static [VideoFormat] {
+        match (bit_depth, chroma_subsampling) {
+            (8, false) => &[
+                VideoFormat::Y444,
+                VideoFormat::V308,
+                VideoFormat::Iyu2,
+                VideoFormat::Nv24,
+            ],
+            (8, true) => &[
+                VideoFormat::I420,
+                VideoFormat::Yv12,
+                VideoFormat::Nv12,
+                VideoFormat::Nv21,
+            ],
+            (10, false) => &[VideoFormat::Y44410be, VideoFormat::Y44410le],
+            (10, true) => &[VideoFormat::I42010be, VideoFormat::I42010le],
+            (12, false) => &[VideoFormat::Y44412be, VideoFormat::Y44412le],
+            (12, true) => &[VideoFormat::I42012be, VideoFormat::I42012le],
+            _ => panic!("No pixel format for bit depth {bit_depth}"),
+        }
+    }
+
+    /// Whether video in any of these pixel formats has to have even dimensions. Formats whose chroma planes are
+    /// subsampled (like 4:2:0) store one chroma sample per 2 pixels, and encoders reject frames that don't divide evenly
+    /// into them. Formats without subsampling can have any dimensions.
+    fn needs_even_dimensions(pixel_formats: &[VideoFormat]) -> bool {
+        pixel_formats.iter().any(|format| {
+            let format_info = gstreamer_video::VideoFormatInfo::from_format(*format);
+            format_info
+                .w_sub()
+                .iter()
+                .chain(format_info.h_sub())
+                .any(|&sub| sub > 0)
+        })
+    }
+
+    fn interlaced_output_allowed(&self) -> bool {
+        matches!(
+            self.effect_settings.use_field,
+            UseField::InterleavedUpper
+                | UseField::InterleavedLower
+                | UseField::Alternating
+                | UseField::Both
+        )
+    }
+
+    fn render_interlace_mode(&self) -> RenderInterlaceMode {
+        if !(self.interlaced_output_allowed() && self.render_settings.interlaced) {
+            return RenderInterlaceMode::Progressive;
+        }
+
+        match self.effect_settings.use_field {
+            UseField::InterleavedUpper => RenderInterlaceMode::TopFieldFirst,
+            UseField::InterleavedLower => RenderInterlaceMode::BottomFieldFirst,
+            // Even frames use the lower field and odd frames use the upper field, so weaving each pair of frames
+            // together bottom-field-first keeps only the fields that were actually processed.
+            UseField::Alternating => RenderInterlaceMode::BottomFieldFirst,
+            UseField::Both => RenderInterlaceMode::TopFieldFirst,
+            UseField::Upper | UseField::Lower => RenderInterlaceMode::Progressive,
+        }
+    }
+
+    /// Demux the source file a second time and mux its first audio stream into the output as-is. Streams that aren't
+    /// copied are discarded.
+    fn add_audio_passthrough(
+        pipeline: &gstreamer::Pipeline,
+        src_path: &Path,
+        muxer: &gstreamer::Element,
+        audio_track: usize,
+    ) -> Result<(), GstreamerError> {
+        let audio_src = create_source(src_path, gstreamer::Fraction::from(30))?;
+        let audio_parse = gstreamer::ElementFactory::make("parsebin").build()?;
+        pipeline.add_many([&audio_src, &audio_parse])?;
+        link_source(&audio_src, &audio_parse)?;
+
+        let pipeline_weak = pipeline.downgrade();
+        let muxer = muxer.clone();
+        // Count the audio streams as they're found, so the selected one is copied and the rest are discarded
+        let num_audio_tracks = AtomicUsize::new(0);
+        audio_parse.connect_pad_added(move |audio_parse, pad| {
+            let Some(pipeline) = pipeline_weak.upgrade() else {
+                return;
+            };
+            let is_audio = pad
+                .current_caps()
+                .and_then(|caps| {
+                    caps.structure(0)
+                        .map(|structure| structure.name().starts_with("audio/"))
+                })
+                .unwrap_or(false);
+
+            let res = (|| -> Result<(), GstreamerError> {
+                if is_audio && num_audio_tracks.fetch_add(1, Ordering::SeqCst) == audio_track {
+                    let queue = gstreamer::ElementFactory::make("queue").build()?;
+                    pipeline.add(&queue)?;
+                    pad.link(&queue.static_pad("sink").unwrap())?;
+                    queue.link(&muxer)?;
+                    queue.sync_state_with_parent()?;
+                } else {
+                    let sink = gstreamer::ElementFactory::make("fakesink")
+                        .property("async", false)
+                        .build()?;
+                    pipeline.add(&sink)?;
+                    pad.link(&sink.static_pad("sink").unwrap())?;
+                    sink.sync_state_with_parent()?;
+                }
+                Ok(())
+            })();
+
+            if let Err(err) = res {
+                gstreamer::element_error!(
+                    audio_parse,
+                    gstreamer::StreamError::Mux,
+                    ("Could not copy the source audio into the output: {}", err)
+                );
+            }
+        });
+
+        audio_src.sync_state_with_parent()?;
+        audio_parse.sync_state_with_parent()?;
+
+        Ok(())
+    }
+
+    fn create_render_job(
+        &mut self,
+        ctx: &egui::Context,
+        src_path: &Path,
+        settings: RenderPipelineSettings,
+    ) -> Result<RenderJob, GstreamerError> {
+        // Fail up front rather than with a confusing error partway through setting up the pipeline
+        let missing_elements = settings.missing_elements();
+        if !missing_elements.is_empty() {
+            return Err(glib::bool_error!(
+                "Missing GStreamer elements needed for this output format: {}",
+                format_missing_elements(&missing_elements)
+            )
+            .into());
+        }
+
+        // Still images and image sequences don't have a framerate of their own, so use the one set in the preview
+        let source_framerate = self
+            .pipeline
+            .as_ref()
+            .map(|info| info.metadata.lock().unwrap())
+            .and_then(|metadata| metadata.framerate)
+            .unwrap_or(self.render_settings.still_image_framerate());
+        // Most platforms only let one pipeline use a camera at a time, so the preview has to let go of it
+        let is_live = is_camera(src_path);
+        if is_live {
+            self.remove_pipeline()?;
+        }
+        let src = create_source(src_path, source_framerate)?;
+
+        let settings_hash = self
+            .settings_list
+            .settings_hash(&NtscEffectFullSettings::from(
+                &settings.seeded_effect_settings(),
+            ));
+        let settings = Arc::new(settings);
+        let settings_audio_closure = Arc::clone(&settings);
+        let settings_video_closure = Arc::clone(&settings);
+
+        let output_elems_cell = Arc::new(OnceLock::new());
+        let output_elems_cell_video = Arc::clone(&output_elems_cell);
+        let closure_settings = settings.clone();
+        let create_output_elems = move |pipeline: &gstreamer::Pipeline| -> Result<
+            (Option<gstreamer::Element>, gstreamer::Element),
+            GstreamerError,
+        > {
+            // The first pass of a two-pass encode only writes the encoder's statistics, so there's nothing to mux
+            if closure_settings.is_analysis_pass() {
+                let fake_sink = gstreamer::ElementFactory::make("fakesink").build()?;
+                pipeline.add(&fake_sink)?;
+                fake_sink.sync_state_with_parent()?;
+                return Ok((None, fake_sink));
+            }
+
+            let video_mux = match &closure_settings.codec_settings {
+                RenderPipelineCodec::H264(_) => Some(
+                    gstreamer::ElementFactory::make("mp4mux")
+                        .name("output_muxer")
+                        .build()?,
+                ),
+                RenderPipelineCodec::Ffv1(_) => Some(
+                    gstreamer::ElementFactory::make("matroskamux")
+                        .name("output_muxer")
+                        .build()?,
+                ),
+                RenderPipelineCodec::Still(_) | RenderPipelineCodec::ImageSequence { .. } => None,
+            };
+
+            let file_sink = match &closure_settings.codec_settings {
+                RenderPipelineCodec::ImageSequence { pattern, .. } => {
+                    gstreamer::ElementFactory::make("multifilesink")
+                        .property("location", closure_settings.output_path.join(pattern))
+                        .build()?
+                }
+                // Write to a temporary file until the render finishes, so a failed render doesn't leave a broken file
+                // under the real name
+                _ => gstreamer::ElementFactory::make("filesink")
+                    .property("location", closure_settings.partial_output_path())
+                    .build()?,
+            };
+
+            pipeline.add(&file_sink)?;
+            file_sink.sync_state_with_parent()?;
+
+            if let Some(video_mux) = video_mux {
+                // Record which settings the video was rendered with, so renders can be compared later
+                if let Some(tag_setter) = video_mux.dynamic_cast_ref::<gstreamer::TagSetter>() {
+                    tag_setter.add_tag::<gstreamer::tags::Comment>(
+                        &format!("ntsc-rs settings hash: {settings_hash}").as_str(),
+                        gstreamer::TagMergeMode::Replace,
+                    );
+                }
+                pipeline.add(&video_mux)?;
+                video_mux.link(&file_sink)?;
+                video_mux.sync_state_with_parent()?;
+
+                Ok((Some(video_mux.clone()), video_mux))
+            } else {
+                Ok((None, file_sink))
+            }
+        };
+
+        let create_output_elems_audio = create_output_elems.clone();
+        let create_output_elems_video = create_output_elems.clone();
+
+        let job_state = Arc::new(Mutex::new(RenderJobState::Waiting));
+        let job_state_for_handler = Arc::clone(&job_state);
+        let exec = self.execute_fn_next_frame();
+        let exec2 = self.execute_fn_next_frame();
+        let ctx_for_handler = ctx.clone();
+        let src_path_for_handler = src_path.to_path_buf();
+        let src_path_for_video = src_path.to_path_buf();
+        let settings_for_handler = Arc::clone(&settings);
+        let is_final_pass = !settings.is_analysis_pass();
+
+        //let still_image_duration = settings.duration;
+        let current_time = self
+            .pipeline
+            .as_ref()
+            .and_then(|info| info.pipeline.query_position::<ClockTime>())
+            .unwrap_or(ClockTime::ZERO);
+        let is_still = matches!(settings.codec_settings, RenderPipelineCodec::Still(_));
+
+        let pipeline = create_pipeline(
+            src,
+            move |pipeline| {
+                let (audio_out, _) = output_elems_cell
+                    .get_or_init(|| create_output_elems_audio(pipeline))
+                    .as_ref()
+                    .map_err(|err| err.clone())?;
+                if let Some(audio_out) = audio_out {
+                    if matches!(
+                        settings_audio_closure.codec_settings,
+                        RenderPipelineCodec::Still(_)
+                    ) {
+                        return Ok(None);
+                    }
+                    let audio_enc = match settings_audio_closure.audio_codec {
+                        // Leaving the audio branch out entirely gives a video-only output
+                        None => return Ok(None),
+                        Some(AudioCodec::Aac) => {
+                            gstreamer::ElementFactory::make("avenc_aac").build()?
+                        }
+                        Some(AudioCodec::Opus) => {
+                            gstreamer::ElementFactory::make("opusenc").build()?
+                        }
+                        Some(AudioCodec::Flac) => {
+                            gstreamer::ElementFactory::make("flacenc").build()?
+                        }
+                        // Copied audio comes from a separate branch set up alongside the video encoder
+                        Some(AudioCodec::Copy) => return Ok(None),
+                    };
+
+                    pipeline.add(&audio_enc)?;
+                    audio_enc.link(audio_out)?;
+                    audio_enc.sync_state_with_parent()?;
+                    Ok(Some(audio_enc))
+                } else {
+                    Ok(None)
+                }
+            },
+            move |pipeline| {
+                let (audio_out, video_out) = output_elems_cell_video
+                    .get_or_init(|| create_output_elems_video(pipeline))
+                    .as_ref()
+                    .map_err(|err| err.clone())?;
+
+                let (video_enc, pixel_formats) = match &settings_video_closure.codec_settings {
+                    RenderPipelineCodec::H264(h264_settings) => {
+                        // Load the x264enc plugin so the enum classes exist. Nothing seems to work except actually instantiating an Element.
+                        let _ = gstreamer::ElementFactory::make("x264enc").build().unwrap();
+                        #[allow(non_snake_case)]
+                        let GstX264EncPass = gstreamer::glib::EnumClass::with_type(
+                            gstreamer::glib::Type::from_name("GstX264EncPass").unwrap(),
+                        )
+                        .unwrap();
+                        #[allow(non_snake_case)]
+                        let GstX264EncPreset = gstreamer::glib::EnumClass::with_type(
+                            gstreamer::glib::Type::from_name("GstX264EncPreset").unwrap(),
+                        )
+                        .unwrap();
+
+                        let video_enc = gstreamer::ElementFactory::make("x264enc").property(
+                            "speed-preset",
+                            GstX264EncPreset
+                                .to_value(9 - h264_settings.encode_speed as i32)
+                                .unwrap(),
+                        );
+                        let video_enc = match h264_settings.rate_control {
+                            H264RateControl::Crf => video_enc
+                                // CRF mode
+                                .property("pass", GstX264EncPass.to_value_by_nick("quant").unwrap())
+                                // invert CRF (so that low numbers = low quality)
+                                .property("quantizer", 50 - h264_settings.crf as u32),
+                            H264RateControl::Vbr { bitrate } => video_enc
+                                .property("pass", GstX264EncPass.to_value_by_nick("cbr").unwrap())
+                                .property("bitrate", bitrate),
+                            H264RateControl::TwoPass { bitrate } => video_enc
+                                .property(
+                                    "pass",
+                                    GstX264EncPass
+                                        .to_value_by_nick(if settings_video_closure.pass == 0 {
+                                            "pass1"
+                                        } else {
+                                            "pass2"
+                                        })
+                                        .unwrap(),
+                                )
+                                .property("bitrate", bitrate)
+                                .property(
+                                    "multipass-cache-file",
+                                    settings_video_closure
+                                        .multipass_cache_file()
+                                        .to_string_lossy()
+                                        .as_ref(),
+                                ),
+                        }
+                        .build()?;
+
+                        let pixel_formats = Self::pixel_formats_for(
+                            if h264_settings.ten_bit { 10 } else { 8 },
+                            h264_settings.chroma_subsampling,
+                        );
+
+                        (video_enc, pixel_formats)
+                    }
+                    RenderPipelineCodec::Ffv1(ffv1_settings) => {
+                        let video_enc = gstreamer::ElementFactory::make("avenc_ffv1").build()?;
+
+                        let pixel_formats = Self::pixel_formats_for(
+                            match ffv1_settings.bit_depth {
+                                Ffv1BitDepth::Bits8 => 8,
+                                Ffv1BitDepth::Bits10 => 10,
+                                Ffv1BitDepth::Bits12 => 12,
+                            },
+                            ffv1_settings.chroma_subsampling,
+                        );
+
+                        (video_enc, pixel_formats)
+                    }
+                    RenderPipelineCodec::Still(still_settings) => {
+                        let quality = still_settings.quality.clamp(1, 100);
+                        let video_enc = match still_settings.format {
+                            StillImageFormat::Png => gstreamer::ElementFactory::make("pngenc")
+                                .property("snapshot", true)
+                                .build()?,
+                            StillImageFormat::Jpeg => gstreamer::ElementFactory::make("jpegenc")
+                                .property("quality", quality as i32)
+                                .build()?,
+                            StillImageFormat::Tiff => {
+                                gstreamer::ElementFactory::make("avenc_tiff").build()?
+                            }
+                            StillImageFormat::WebP => gstreamer::ElementFactory::make("webpenc")
+                                .property("quality", quality as f32)
+                                .property("lossless", still_settings.lossless)
+                                .build()?,
+                        };
+                        if still_settings.format != StillImageFormat::Png {
+                            end_after_first_frame(&video_enc);
+                        }
+
+                        let pixel_formats: &[VideoFormat] = if settings_video_closure.preserve_alpha
+                            && still_settings.format.supports_alpha()
+                        {
+                            &[VideoFormat::Rgba]
+                        } else {
+                            &[VideoFormat::Rgb]
+                        };
+
+                        (video_enc, pixel_formats)
+                    }
+                    RenderPipelineCodec::ImageSequence { format, .. } => {
+                        let video_enc = match format {
+                            ImageSequenceFormat::Png => {
+                                gstreamer::ElementFactory::make("pngenc").build()?
+                            }
+                            ImageSequenceFormat::Tiff => {
+                                gstreamer::ElementFactory::make("avenc_tiff").build()?
+                            }
+                        };
+
+                        let pixel_formats: &[VideoFormat] = if settings_video_closure.preserve_alpha
+                        {
+                            &[VideoFormat::Rgba]
+                        } else {
+                            &[VideoFormat::Rgb]
+                        };
+
+                        (video_enc, pixel_formats)
+                    }
+                };
+
+                let mut elems = Vec::<gstreamer::Element>::new();
+
+                if let Some(output_framerate) = settings_video_closure.output_framerate {
+                    let video_rate = gstreamer::ElementFactory::make("videorate").build()?;
+                    elems.push(video_rate);
+
+                    let framerate_caps_filter = gstreamer::ElementFactory::make("capsfilter")
+                        .property(
+                            "caps",
+                            VideoCapsBuilder::new().framerate(output_framerate).build(),
+                        )
+                        .build()?;
+                    elems.push(framerate_caps_filter);
+                }
+
+                // Scale the video down to the effect's resolution. It's scaled back up to the size it had before that
+                // once the effect is applied, which isn't known until the video's caps arrive.
+                let effect_upscale = settings_video_closure
+                    .effect_scanlines
+                    .map(|effect_scanlines| -> Result<_, GstreamerError> {
+                        let effect_scale = gstreamer::ElementFactory::make("videoscale").build()?;
+                        let effect_caps_filter = gstreamer::ElementFactory::make("capsfilter")
+                            .property("caps", max_height_caps(Some(effect_scanlines)))
+                            .build()?;
+                        let upscale = gstreamer::ElementFactory::make("videoscale").build()?;
+                        let upscale_caps_filter =
+                            gstreamer::ElementFactory::make("capsfilter").build()?;
+
+                        let upscale_caps_filter_for_probe = upscale_caps_filter.clone();
+                        effect_scale.static_pad("sink").unwrap().add_probe(
+                            gstreamer::PadProbeType::EVENT_DOWNSTREAM,
+                            move |_, info| {
+                                let Some(gstreamer::PadProbeData::Event(event)) = &info.data else {
+                                    return gstreamer::PadProbeReturn::Ok;
+                                };
+                                if let gstreamer::EventView::Caps(caps) = event.view() {
+                                    let size = caps.caps().structure(0).and_then(|structure| {
+                                        Some((
+                                            structure.get::<i32>("width").ok()?,
+                                            structure.get::<i32>("height").ok()?,
+                                        ))
+                                    });
+                                    if let Some((width, height)) = size {
+                                        upscale_caps_filter_for_probe.set_property(
+                                            "caps",
+                                            VideoCapsBuilder::default()
+                                                .width(width)
+                                                .height(height)
+                                                .build(),
+                                        );
+                                    }
+                                }
+                                gstreamer::PadProbeReturn::Ok
+                            },
+                        );
+
+                        elems.push(effect_scale);
+                        elems.push(effect_caps_filter);
+                        Ok([upscale, upscale_caps_filter])
+                    })
+                    .transpose()?;
+
+                let video_ntsc = gstreamer::ElementFactory::make("ntscfilter")
+                    .property(
+                        "settings",
+                        NtscFilterSettings(settings_video_closure.seeded_effect_settings()),
+                    )
+                    .property("preserve-alpha", settings_video_closure.preserve_alpha)
+                    .property("region", NtscFilterRegion(settings_video_closure.region))
+                    .property("low-memory", settings_video_closure.low_memory)
+                    .property(
+                        "keyframes",
+                        NtscFilterKeyframes(settings_video_closure.keyframes.clone()),
+                    )
+                    .build()?;
+                elems.push(video_ntsc);
+                elems.extend(effect_upscale.into_iter().flatten());
+
+                // Encoders can't encode chroma-subsampled videos with odd dimensions. Pad them out to even dimensions.
+                if Self::needs_even_dimensions(pixel_formats) {
+                    let video_padding =
+                        gstreamer::ElementFactory::make("videopadfilter").build()?;
+                    elems.push(video_padding);
+                }
+
+                let ntsc_caps_filter = gstreamer::ElementFactory::make("capsfilter")
+                    .property(
+                        "caps",
+                        gstreamer_video::VideoCapsBuilder::new()
+                            .format(gstreamer_video::VideoFormat::Argb64)
+                            .build(),
+                    )
+                    .build()?;
+                elems.push(ntsc_caps_filter);
+
+                // The effect's output is 16 bits per channel, so this is where it gets reduced to the output's bit
+                // depth. Dithering only makes a visible difference when reducing it all the way to 8 bits.
+                let dither = if settings_video_closure.dither
+                    && settings_video_closure.codec_settings.bit_depth() == 8
+                {
+                    "floyd-steinberg"
+                } else {
+                    "bayer"
+                };
+                let video_convert = gstreamer::ElementFactory::make("videoconvert")
+                    .property_from_str("dither", dither)
+                    .build()?;
+                elems.push(video_convert);
+
+                if let Some(timecode_overlay) = &settings_video_closure.timecode_overlay {
+                    let time_mode = match timecode_overlay.format {
+                        TimecodeFormat::Smpte => {
+                            // timeoverlay reads SMPTE timecodes from buffer metadata, so something needs to attach it
+                            let timecode_stamper =
+                                gstreamer::ElementFactory::make("timecodestamper").build()?;
+                            elems.push(timecode_stamper);
+                            "time-code"
+                        }
+                        TimecodeFormat::Elapsed => "running-time",
+                    };
+
+                    let (halignment, valignment) = timecode_overlay.corner.text_alignment();
+                    let time_overlay = gstreamer::ElementFactory::make("timeoverlay")
+                        .property_from_str("time-mode", time_mode)
+                        .property_from_str("halignment", halignment)
+                        .property_from_str("valignment", valignment)
+                        .build()?;
+                    elems.push(time_overlay);
+                }
+
+                if let Some(text_overlay) = &settings_video_closure.text_overlay {
+                    let (halignment, valignment) = text_overlay.corner.text_alignment();
+                    let text_overlay_elem = gstreamer::ElementFactory::make("textoverlay")
+                        .property("text", text_overlay.text.as_str())
+                        .property("font-desc", format!("Sans {}", text_overlay.font_size))
+                        .property("color", text_overlay.argb(0xFFFFFF))
+                        .property("outline-color", text_overlay.argb(0x000000))
+                        .property_from_str("halignment", halignment)
+                        .property_from_str("valignment", valignment)
+                        .build()?;
+                    elems.push(text_overlay_elem);
+                }
+
+                if settings_video_closure.timecode_overlay.is_some()
+                    || settings_video_closure.text_overlay.is_some()
+                {
+                    // The overlays don't support every format the encoders do
+                    let overlay_convert = gstreamer::ElementFactory::make("videoconvert")
+                        .property_from_str("dither", dither)
+                        .build()?;
+                    elems.push(overlay_convert);
+                }
+
+                if settings_video_closure.interlacing != RenderInterlaceMode::Progressive {
+                    // Load the interlace plugin so the enum class exists. Nothing seems to work except actually instantiating an Element.
+                    let _ = gstreamer::ElementFactory::make("interlace")
+                        .build()
+                        .unwrap();
+                    #[allow(non_snake_case)]
+                    let GstInterlacePattern = gstreamer::glib::EnumClass::with_type(
+                        gstreamer::glib::Type::from_name("GstInterlacePattern").unwrap(),
+                    )
+                    .unwrap();
+
+                    let interlace = gstreamer::ElementFactory::make("interlace")
+                        .property(
+                            "field-pattern",
+                            GstInterlacePattern.to_value_by_nick("2:2").unwrap(),
+                        )
+                        .property(
+                            "top-field-first",
+                            settings_video_closure.interlacing
+                                == RenderInterlaceMode::TopFieldFirst,
+                        )
+                        .build()?;
+                    elems.push(interlace);
+                }
+
+                let video_caps = gstreamer_video::VideoCapsBuilder::new()
+                    .format_list(pixel_formats.iter().copied());
+                // Setting the colorimetry here makes videoconvert convert to it, and the encoder picks it up from the
+                // caps and writes it into the stream. The image encoders take RGB, which has no matrix or range.
+                let video_caps = if matches!(
+                    settings_video_closure.codec_settings,
+                    RenderPipelineCodec::H264(_) | RenderPipelineCodec::Ffv1(_)
+                ) {
+                    video_caps.field(
+                        "colorimetry",
+                        output_colorimetry(
+                            settings_video_closure.color_matrix,
+                            settings_video_closure.color_range,
+                        )
+                        .to_string(),
+                    )
+                } else {
+                    video_caps
+                }
+                .build();
+                let caps_filter = gstreamer::ElementFactory::make("capsfilter")
+                    .property("caps", &video_caps)
+                    .build()?;
+                elems.push(caps_filter);
+
+                elems.push(video_enc.clone());
+
+                pipeline.add_many(elems.iter())?;
+                gstreamer::Element::link_many(elems.iter())?;
+
+                video_enc.link(video_out)?;
+
+                for elem in elems.iter() {
+                    elem.sync_state_with_parent()?;
+                }
+                video_enc.sync_state_with_parent()?;
+
+                // Image sequences have no audio to copy
+                if let (Some(AudioCodec::Copy), Some(muxer), false) = (
+                    settings_video_closure.audio_codec,
+                    audio_out,
+                    is_image_sequence(&src_path_for_video),
+                ) {
+                    Self::add_audio_passthrough(
+                        pipeline,
+                        &src_path_for_video,
+                        muxer,
+                        settings_video_closure.audio_track,
+                    )?;
+                }
+
+                Ok(elems[0].clone())
+            },
+            move |bus, msg| {
+                let job_state = &job_state_for_handler;
+                let exec = &exec;
+                let ctx = &ctx_for_handler;
+                let src_path = &src_path_for_handler;
+                let settings = &settings_for_handler;
+
+                let handle_msg = move |_bus, msg: &gstreamer::Message| -> Option<()> {
+                    debug!("{:?}", msg);
+                    let src = msg.src()?;
+
+                    if let gstreamer::MessageView::Error(err) = msg.view() {
+                        let mut job_state = job_state.lock().unwrap();
+                        if !matches!(*job_state, RenderJobState::Error(_)) {
+                            *job_state = RenderJobState::Error(err.error().into());
+                            settings.discard_partial_output();
+                            ctx.request_repaint();
+                        }
+                    }
+
+                    // Make sure we're listening to a pipeline event
+                    if let Some(pipeline) = src.downcast_ref::<gstreamer::Pipeline>() {
+                        let pipeline_for_handler = pipeline.clone();
+                        if let gstreamer::MessageView::Eos(_) = msg.view() {
+                            let job_state_inner = Arc::clone(job_state);
+                            let end_time = ctx.input(|input| input.time);
+                            let ctx = ctx.clone();
+                            let src_path = src_path.clone();
+                            let mut next_pass_settings = settings.as_ref().clone();
+                            next_pass_settings.pass += 1;
+                            exec(async move {
+                                let _ = pipeline_for_handler.set_state(gstreamer::State::Null);
+                                if is_final_pass {
+                                    if next_pass_settings.num_passes() > 1 {
+                                        let cache_file = next_pass_settings.multipass_cache_file();
+                                        let _ = std::fs::remove_file(&cache_file);
+                                        let mut mbtree_file = cache_file.into_os_string();
+                                        mbtree_file.push(".mbtree");
+                                        let _ = std::fs::remove_file(mbtree_file);
+                                    }
+                                    *job_state_inner.lock().unwrap() =
+                                        match next_pass_settings.finish_partial_output() {
+                                            Ok(_) => RenderJobState::Complete { end_time },
+                                            Err(err) => RenderJobState::Error(
+                                                glib::Error::new(
+                                                    gstreamer::ResourceError::Write,
+                                                    &format!(
+                                                        "Couldn't move the finished render into place: {err}"
+                                                    ),
+                                                )
+                                                .into(),
+                                            ),
+                                        };
+                                    None
+                                } else {
+                                    Some(Box::new(move |app: &mut NtscApp| {
+                                        app.start_next_render_pass(
+                                            &ctx,
+                                            &src_path,
+                                            &pipeline_for_handler,
+                                            next_pass_settings,
+                                        )
+                                    }) as _)
+                                }
+                            })
+                        }
+
+                        if let gstreamer::MessageView::StateChanged(state_changed) = msg.view() {
+                            // Intermediate passes are only stopped to start the next pass, so they shouldn't mark the
+                            // job as complete.
+                            if !is_final_pass
+                                && (state_changed.pending() == gstreamer::State::Null
+                                    || state_changed.current() == gstreamer::State::Null)
+                            {
+                                return Some(());
+                            }
+
+                            if state_changed.pending() == gstreamer::State::Null {
+                                let end_time = ctx.input(|input| input.time);
+                                *job_state.lock().unwrap() = RenderJobState::Complete { end_time };
+                            } else {
+                                *job_state.lock().unwrap() = match state_changed.current() {
+                                    gstreamer::State::Paused => RenderJobState::Paused,
+                                    gstreamer::State::Playing => RenderJobState::Rendering,
+                                    gstreamer::State::Ready => RenderJobState::Waiting,
+                                    gstreamer::State::Null => {
+                                        let end_time = ctx.input(|input| input.time);
+                                        RenderJobState::Complete { end_time }
+                                    }
+                                    gstreamer::State::VoidPending => {
+                                        unreachable!("current state should never be VOID_PENDING")
+                                    }
+                                };
+                            }
+                            ctx.request_repaint();
+                        }
+                    }
+
+                    Some(())
+                };
+
+                handle_msg(bus, msg);
+
+                gstreamer::BusSyncReply::Drop
+            },
+            if is_still {
+                None
+            } else {
+                Some(settings.duration)
+            },
+            if self.video_scale.enabled && !settings.full_resolution {
+                Some(self.video_scale.scale)
+            } else {
+                None
+            },
+            self.video_scale.square_pixels,
+            source_framerate,
+            settings.tonemap,
+            settings.audio_track,
+            Some(move |p: Result<gstreamer::Pipeline, _>| {
+                exec2(async move {
+                    Some(
+                        Box::new(move |_: &mut NtscApp| -> Result<(), ApplicationError> {
+                            let pipeline = p.context(CreatePipelineSnafu)?;
+                            if is_still {
+                                pipeline
+                                    .seek_simple(
+                                        gstreamer::SeekFlags::FLUSH
+                                            | gstreamer::SeekFlags::ACCURATE,
+                                        current_time,
+                                    )
+                                    .map_err(|e| e.into())
+                                    .context(CreateRenderJobSnafu)?;
+                            }
+
+                            pipeline
+                                .set_state(gstreamer::State::Playing)
+                                .map_err(|e| e.into())
+                                .context(CreateRenderJobSnafu)?;
+                            Ok(())
+                        }) as _,
+                    )
+                });
+            }),
+        )?;
+
+        if is_live {
+            // Live sources don't preroll, so the callback that would start the render once it's ready never gets
+            // called. Start recording right away, and end the recording once the chosen duration is up.
+            pipeline.set_state(gstreamer::State::Playing)?;
+            let pipeline_weak = pipeline.downgrade();
+            let duration = std::time::Duration::from_nanos(settings.duration.nseconds());
+            thread::spawn(move || {
+                thread::sleep(duration);
+                if let Some(pipeline) = pipeline_weak.upgrade() {
+                    pipeline.send_event(gstreamer::event::Eos::new());
+                }
+            });
+        } else {
+            pipeline.set_state(gstreamer::State::Paused)?;
+        }
+
+        Ok(RenderJob {
+            src_path: src_path.to_path_buf(),
+            settings: settings.as_ref().clone(),
+            pipeline,
+            state: job_state,
+            last_progress: 0.0,
+            progress_samples: VecDeque::new(),
+            start_time: None,
+            estimated_completion_time: None,
+            in_batch: false,
+            reported_finish: false,
+            announced_finish: false,
+        })
+    }
+
+    /// The pipeline settings to render a video with, using the current effect settings and the given render settings.
+    fn render_pipeline_settings(&self, render_settings: &RenderSettings) -> RenderPipelineSettings {
+        let effect_settings = self.applied_effect_settings();
+        let seed = if render_settings.randomize_seed {
+            rand::random::<i32>()
+        } else {
+            effect_settings.random_seed
+        };
+        RenderPipelineSettings {
+            codec_settings: render_settings.into(),
+            output_path: render_settings.pipeline_output_path(),
+            duration: render_settings.duration,
+            interlacing: self.render_interlace_mode(),
+            audio_codec: (render_settings.include_audio
+                && !render_settings.output_codec.audio_codecs().is_empty())
+            .then_some(render_settings.audio_codec),
+            audio_track: self.pipeline.as_ref().map_or(0, |info| info.audio_track),
+            timecode_overlay: render_settings
+                .burn_in_timecode
+                .then(|| render_settings.timecode_overlay.clone()),
+            text_overlay: (render_settings.enable_text_overlay
+                && !render_settings.text_overlay.text.is_empty())
+            .then(|| render_settings.text_overlay.clone()),
+            output_framerate: render_settings.output_framerate.fraction(),
+            // None of the video codecs can store an alpha channel
+            preserve_alpha: render_settings.preserve_alpha
+                && render_settings.output_codec == OutputCodec::ImageSequence,
+            keep_partial_output: render_settings.keep_partial_output,
+            dither: render_settings.dither,
+            low_memory: render_settings.low_memory,
+            tonemap: render_settings.tonemap,
+            full_resolution: false,
+            effect_scanlines: self
+                .effect_resolution
+                .enabled
+                .then_some(self.effect_resolution.scanlines),
+            region: self.effect_region.region(),
+            color_matrix: render_settings.color_matrix,
+            color_range: render_settings.color_range,
+            pass: 0,
+            job_id: next_render_job_id(),
+            effect_settings,
+            seed,
+            keyframes: self.keyframes.clone(),
+        }
+    }
+
+    /// Queue up every video in `input_directory` to be rendered into `output_directory` with the current settings.
+    /// This replaces any batch render that's still in progress, although its running jobs are left alone.
+    fn start_batch_render(
+        &mut self,
+        input_directory: &Path,
+        output_directory: &Path,
+    ) -> Result<(), ApplicationError> {
+        let mut src_paths = std::fs::read_dir(input_directory)
+            .context(BatchReadSnafu)?
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| {
+                path.is_file()
+                    && path.extension().is_some_and(|extension| {
+                        BATCH_VIDEO_EXTENSIONS
+                            .iter()
+                            .any(|video_ext| extension.eq_ignore_ascii_case(video_ext))
+                    })
+            })
+            .collect::<Vec<_>>();
+        src_paths.sort();
+
+        if src_paths.is_empty() {
+            self.push_error(tr!(
+                "No videos found in {folder}",
+                folder = input_directory.to_string_lossy()
+            ));
+            return Ok(());
+        }
+
+        for job in &mut self.render_jobs {
+            job.in_batch = false;
+        }
+        self.batch_render = None;
+        self.queue_batch_render(src_paths, output_directory);
+
+        Ok(())
+    }
+
+    /// Add videos to the current batch render, starting a new one if there isn't one already. They'll be rendered into
+    /// `output_directory` with the current settings.
+    fn queue_batch_render(&mut self, src_paths: Vec<PathBuf>, output_directory: &Path) {
+        let pending = src_paths
+            .into_iter()
+            .map(|src_path| {
+                let mut render_settings = self.render_settings.clone();
+                render_settings.output_path = output_directory.join(format!(
+                    "{}_ntsc.{}",
+                    src_path.file_stem().unwrap_or_default().to_string_lossy(),
+                    render_settings.output_extension()
+                ));
+                let settings = self.render_pipeline_settings(&render_settings);
+                (src_path, settings)
+            })
+            .collect::<Vec<_>>();
+        self.enqueue_batch_jobs(pending);
+    }
+
+    /// Queue up several renders of `src_path`, each with its own random seed and a numbered output file, and with the
+    /// continuous settings randomly varied if the options ask for it.
+    fn queue_render_variations(&mut self, src_path: &Path) {
+        let options = self.render_variations.clone();
+        let mut descriptors = Vec::new();
+        numeric_descriptors(&self.settings_list.settings, &mut descriptors);
+        let output_path = self.render_settings.output_path.clone();
+        let stem = output_path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy();
+
+        let jobs = (1..=options.count)
+            .map(|i| {
+                let mut render_settings = self.render_settings.clone();
+                render_settings.output_path = output_path
+                    .with_file_name(format!("{stem}_{i}.{}", render_settings.output_extension()));
+                let mut settings = self.render_pipeline_settings(&render_settings);
+                settings.seed = rand::random::<i32>();
+                if options.jitter > 0.0 {
+                    let mut full_settings = NtscEffectFullSettings::from(&settings.effect_settings);
+                    jitter_settings(&mut full_settings, &descriptors, options.jitter);
+                    settings.effect_settings = full_settings.into();
+                }
+                (src_path.to_path_buf(), settings)
+            })
+            .collect::<Vec<_>>();
+        self.enqueue_batch_jobs(jobs);
+    }
+
+    fn enqueue_batch_jobs(&mut self, jobs: Vec<(PathBuf, RenderPipelineSettings)>) {
+        let batch = self.batch_render.get_or_insert_with(|| BatchRender {
+            pending: VecDeque::new(),
+            total: 0,
+            started: 0,
+        });
+        batch.total += jobs.len();
+        batch.pending.extend(jobs);
+    }
+
+    /// Serialize every render job, including ones in the batch queue that haven't started yet, so that they can be
+    /// re-run later or on another machine.
+    fn render_queue_to_json(&self, base_directory: &Path) -> JsonValue {
+        let started_jobs = self.render_jobs.iter().map(|job| {
+            // Multi-pass jobs need to be restarted from the first pass
+            let mut settings = job.settings.clone();
+            settings.pass = 0;
+            settings.to_json(&job.src_path, base_directory, &self.settings_list)
+        });
+        let pending_jobs = self
+            .batch_render
+            .iter()
+            .flat_map(|batch| batch.pending.iter())
+            .map(|(src_path, settings)| {
+                settings.to_json(src_path, base_directory, &self.settings_list)
+            });
+
+        let mut map = HashMap::<String, JsonValue>::new();
+        map.insert("version".to_string(), JsonValue::Number(1.0));
+        map.insert(
+            "jobs".to_string(),
+            JsonValue::Array(started_jobs.chain(pending_jobs).collect()),
+        );
+        JsonValue::Object(map)
+    }
+
+    /// Load a render queue saved with `render_queue_to_json`. Its jobs are added to the batch queue, so they'll wait
+    /// their turn rather than all starting at once.
+    fn load_render_queue(
+        &mut self,
+        json: &str,
+        base_directory: &Path,
+    ) -> Result<(), ParseSettingsError> {
+        let json = json.parse::<JsonValue>()?;
+        let map =
+            json.get::<HashMap<_, _>>()
+                .ok_or_else(|| ParseSettingsError::InvalidSettingType {
+                    key: "<root>".to_string(),
+                    expected: "object",
+                })?;
+        let version = json_number(map, "version")
+            .ok_or(ParseSettingsError::MissingField { field: "version" })?;
+        if version != 1.0 {
+            return Err(ParseSettingsError::UnsupportedVersion { version });
+        }
+        let jobs = map
+            .get("jobs")
+            .and_then(|jobs| jobs.get::<Vec<_>>())
+            .ok_or_else(|| ParseSettingsError::InvalidSettingType {
+                key: "jobs".to_string(),
+                expected: "array",
+            })?;
+
+        let jobs = jobs
+            .iter()
+            .map(|job| {
+                let job = job.get::<HashMap<_, _>>().ok_or_else(|| {
+                    ParseSettingsError::InvalidSettingType {
+                        key: "jobs".to_string(),
+                        expected: "array of objects",
+                    }
+                })?;
+                RenderPipelineSettings::from_json(job, base_directory, &self.settings_list)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.enqueue_batch_jobs(jobs);
+
+        Ok(())
+    }
+
+    fn start_watch_folder(
+        &mut self,
+        ctx: &egui::Context,
+        input_directory: PathBuf,
+        output_directory: PathBuf,
+    ) -> Result<(), ApplicationError> {
+        // The rendered videos would be picked up and rendered again, forever
+        if input_directory.canonicalize().ok() == output_directory.canonicalize().ok() {
+            self.push_error(
+                tr!("The output folder must be different from the watched folder").to_string(),
+            );
+            return Ok(());
+        }
+
+        let watcher = FolderWatcher::new(ctx, input_directory, BATCH_VIDEO_EXTENSIONS)
+            .context(WatchFolderSnafu)?;
+        self.watch_folder_log.push(tr!(
+            "Watching {folder} for new videos",
+            folder = watcher.directory().to_string_lossy()
+        ));
+        self.watch_folder = Some((watcher, output_directory));
+
+        Ok(())
+    }
+
+    /// Queue renders for any videos that have finished arriving in the watched folder.
+    fn update_watch_folder(&mut self) {
+        let Some((watcher, output_directory)) = &mut self.watch_folder else {
+            return;
+        };
+        let new_files = watcher.take_settled_files();
+        if new_files.is_empty() {
+            return;
+        }
+
+        let output_directory = output_directory.clone();
+        for file in &new_files {
+            self.watch_folder_log.push(tr!(
+                "Queued {file_name}",
+                file_name = file.file_name().unwrap_or_default().to_string_lossy()
+            ));
+        }
+        self.queue_batch_render(new_files, &output_directory);
+    }
+
+    /// Start the batch render's next jobs, if there's room for them.
+    fn update_batch_render(&mut self, ctx: &egui::Context) {
+        let Some(batch) = &self.batch_render else {
+            return;
+        };
+        if batch.pending.is_empty() {
+            return;
+        }
+
+        let mut num_running = self
+            .render_jobs
+            .iter()
+            .filter(|job| {
+                matches!(
+                    *job.state.lock().unwrap(),
+                    RenderJobState::Waiting | RenderJobState::Rendering | RenderJobState::Paused
+                )
+            })
+            .count();
+
+        while num_running < MAX_CONCURRENT_RENDERS {
+            let Some((src_path, settings)) = self
+                .batch_render
+                .as_mut()
+                .and_then(|batch| batch.pending.pop_front())
+            else {
+                break;
+            };
+            if let Some(batch) = &mut self.batch_render {
+                batch.started += 1;
+            }
+
+            match self.create_render_job(ctx, &src_path, settings) {
+                Ok(mut render_job) => {
+                    render_job.in_batch = true;
+                    self.render_jobs.push(render_job);
+                    num_running += 1;
+                }
+                Err(err) => {
+                    self.handle_error(&err);
+                }
+            }
+        }
+    }
+
+    fn start_next_render_pass(
+        &mut self,
+        ctx: &egui::Context,
+        src_path: &Path,
+        prev_pipeline: &gstreamer::Pipeline,
+        settings: RenderPipelineSettings,
+    ) -> Result<(), ApplicationError> {
+        // The job may have been removed in the meantime
+        if !self
+            .render_jobs
+            .iter()
+            .any(|job| &job.pipeline == prev_pipeline)
+        {
+            return Ok(());
+        }
+
+        let mut next_pass = self
+            .create_render_job(ctx, src_path, settings)
+            .context(CreateRenderJobSnafu)?;
+        if let Some(job) = self
+            .render_jobs
+            .iter_mut()
+            .find(|job| &job.pipeline == prev_pipeline)
+        {
+            // The previous pass's pipeline is dropped along with `next_pass`
+            std::mem::swap(&mut job.settings, &mut next_pass.settings);
+            std::mem::swap(&mut job.pipeline, &mut next_pass.pipeline);
+            std::mem::swap(&mut job.state, &mut next_pass.state);
+        }
+
+        Ok(())
+    }
+
+    /// Note down where the current video is at, so it can be picked up from there when it's reopened.
+    fn remember_position(&mut self) {
+        let Some(info) = &self.pipeline else {
+            return;
+        };
+        if !self.remember_positions || !info.is_seekable() {
+            return;
+        }
+        {
+            let metadata = info.metadata.lock().unwrap();
+            if metadata.is_live || metadata.is_still_image != Some(false) {
+                return;
+            }
+        }
+
+        let path = info.path.clone();
+        let position = info.last_seek_pos;
+        self.playback_positions.retain(|(p, _)| *p != path);
+        if position > ClockTime::ZERO {
+            if self.playback_positions.len() >= MAX_PLAYBACK_POSITIONS {
+                self.playback_positions.pop_front();
+            }
+            self.playback_positions.push_back((path, position));
+        }
+    }
+
+    fn remove_pipeline(&mut self) -> Result<(), GstreamerError> {
+        self.remember_position();
+        if let Some(PipelineInfo { pipeline, .. }) = &mut self.pipeline {
+            pipeline.set_state(gstreamer::State::Null)?;
+            self.pipeline = None;
+        }
+        self.thumbnails = None;
+
+        Ok(())
+    }
+
+    /// Give up on a file that's still being opened. Everything the pipeline's callbacks touch is reference-counted, so
+    /// any of them that are still in flight just update state that nothing reads anymore.
+    fn cancel_loading(&mut self) {
+        if !self
+            .pipeline
+            .as_ref()
+            .is_some_and(|info| matches!(*info.state.lock().unwrap(), PipelineInfoState::Loading))
+        {
+            return;
+        }
+        let Some(PipelineInfo { pipeline, .. }) = self.pipeline.take() else {
+            return;
+        };
+        self.thumbnails = None;
+
+        // A demuxer or decoder that's stuck on the file can block the state change for a long time, so don't wait on
+        // it here
+        thread::spawn(move || {
+            if let Err(e) = pipeline.set_state(gstreamer::State::Null) {
+                debug!("error tearing down canceled pipeline: {:?}", e);
+            }
+        });
+    }
+
+    /// The effect settings that are actually applied to renders, with the master intensity factored in. The saved
+    /// settings are left untouched.
+    fn applied_effect_settings(&self) -> NtscEffect {
+        let mut effect = scale_effect_intensity(&self.effect_settings, self.effect_intensity);
+        if let Some(bandwidth_scale) = self.auto_bandwidth_scale_override() {
+            effect.bandwidth_scale = bandwidth_scale;
+        }
+        effect.use_gpu = self.use_gpu;
+        effect
+    }
+
+    /// The effect settings applied to the preview. These additionally have the solo/bypass overrides, which are only
+    /// meant for tuning and shouldn't end up in rendered output.
+    fn preview_effect_settings(&self) -> NtscEffect {
+        let mut effect_settings = match &self.settings_snapshot {
+            Some(snapshot) if self.comparing_snapshot => snapshot.clone(),
+            _ => self.effect_settings.clone(),
+        };
+        self.group_state
+            .apply_overrides(&self.settings_list, &mut effect_settings);
+        let mut effect = scale_effect_intensity(&effect_settings, self.effect_intensity);
+        if let Some(bandwidth_scale) = self.auto_bandwidth_scale_override() {
+            effect.bandwidth_scale = bandwidth_scale;
+        }
+        effect.use_gpu = self.use_gpu;
+        effect
+    }
+
+    /// The bandwidth scale for the height of the loaded video, after scaling: 1.0 at 480 scanlines, and proportionally
+    /// larger or smaller from there, so that higher-resolution video gets a comparable look.
+    fn height_bandwidth_scale(&self) -> Option<f32> {
+        const REFERENCE_SCANLINES: f32 = 480.0;
+
+        let (_, source_height) = self
+            .pipeline
+            .as_ref()?
+            .metadata
+            .lock()
+            .unwrap()
+            .resolution?;
+        let height = if self.video_scale.enabled {
+            self.video_scale.scale
+        } else {
+            source_height
+        };
+        Some((height as f32 / REFERENCE_SCANLINES).clamp(0.125, 8.0))
+    }
+
+    /// The bandwidth scale to use instead of the setting's value, if the "auto" mode is on.
+    fn auto_bandwidth_scale_override(&self) -> Option<f32> {
+        if !self.auto_bandwidth_scale {
+            return None;
+        }
+        self.height_bandwidth_scale()
+    }
+
+    /// Update the preview when the automatic bandwidth scale changes, which happens once a video's resolution is known
+    /// and whenever the preview's scale is changed.
+    fn update_auto_bandwidth_scale(&mut self) {
+        let bandwidth_scale = self.auto_bandwidth_scale_override();
+        if bandwidth_scale != self.preview_auto_bandwidth_scale {
+            self.preview_auto_bandwidth_scale = bandwidth_scale;
+            self.update_effect();
+        }
+    }
+
+    /// Show the snapshot in the preview while the "Compare" button or the C key is held down, and go back to the current
+    /// settings once it's released. `effect_settings` is left alone the whole time.
+    fn update_snapshot_compare(&mut self, ctx: &egui::Context) {
+        let key_held = !ctx.wants_keyboard_input()
+            && ctx.input(|input| input.modifiers.is_none() && input.key_down(egui::Key::C));
+        let comparing = self.settings_snapshot.is_some() && (self.compare_button_held || key_held);
+        if comparing != self.comparing_snapshot {
+            self.comparing_snapshot = comparing;
+            self.update_effect();
+        }
+    }
+
+    fn update_effect(&self) {
+        if let Some(PipelineInfo { egui_sink, .. }) = &self.pipeline {
+            egui_sink.set_property(
+                "settings",
+                NtscFilterSettings(self.preview_effect_settings()),
+            );
+        }
+    }
+
+    /// Put the preview's zoom, scale, volume, and effect preview mode back to their defaults. The effect settings and
+    /// the loaded video are left alone.
+    fn reset_view(&mut self) {
+        let was_scaled = self.video_scale.enabled || !self.video_scale.square_pixels;
+        self.video_zoom = VideoZoom::default();
+        self.video_scale = VideoScale::default();
+        self.audio_volume = AudioVolume::default();
+        self.effect_preview = EffectPreviewSettings::default();
+
+        if let (Some(info), true) = (&self.pipeline, was_scaled) {
+            let res = Self::rescale_video(
+                &info.pipeline,
+                info.last_seek_pos,
+                self.preview_scanlines(),
+                self.video_scale.square_pixels,
+            );
+            self.handle_result(res);
+        }
+        self.update_volume();
+        self.update_effect_preview();
+    }
+
+    fn update_effect_preview(&self) {
+        if let Some(PipelineInfo { egui_sink, .. }) = &self.pipeline {
+            // Use the generated setter rather than naming the property, so a misspelled name can't slip through
+            egui_sink
+                .downcast_ref::<elements::EguiSink>()
+                .unwrap()
+                .set_preview_mode(Self::sink_preview_mode(&self.effect_preview));
+        }
+    }
+
+    /// Turn GPU acceleration on or off for the preview and renders. It stays off if there's no usable GPU.
+    fn set_use_gpu(&mut self, use_gpu: bool) {
+        #[cfg(feature = "gpu")]
+        {
+            self.use_gpu = use_gpu && ntscrs::gpu::is_available();
+        }
+        #[cfg(not(feature = "gpu"))]
+        {
+            let _ = use_gpu;
+            self.use_gpu = false;
+        }
+        // Re-render the preview, since the GPU's output differs very slightly from the CPU's
+        self.update_effect();
+    }
+
+    fn update_tonemap(&self) {
+        let Some(tonemap) = self
+            .pipeline
+            .as_ref()
+            .and_then(|info| info.pipeline.by_name("hdr_tonemap"))
+        else {
+            return;
+        };
+        let settings = self.render_settings.tonemap;
+        tonemap.set_property("enabled", settings.enabled);
+        tonemap.set_property("target-nits", settings.target_nits);
+    }
+
+    fn update_preserve_alpha(&self) {
+        if let Some(PipelineInfo { egui_sink, .. }) = &self.pipeline {
+            egui_sink.set_property("preserve-alpha", self.render_settings.preserve_alpha);
+        }
+    }
+
+    /// Whether the preview should measure how long each stage of the effect takes. Measuring has a small cost, so it's
+    /// only done while the results are being shown.
+    fn profiling_stages(&self) -> bool {
+        self.show_performance_overlay && self.profile_effect_stages
+    }
+
+    fn update_profile_stages(&self) {
+        if let Some(PipelineInfo { egui_sink, .. }) = &self.pipeline {
+            egui_sink.set_property("profile-stages", self.profiling_stages());
+        }
+    }
+
+    fn update_effect_region(&self) {
+        if let Some(PipelineInfo { egui_sink, .. }) = &self.pipeline {
+            egui_sink.set_property("region", NtscFilterRegion(self.effect_region.region()));
+        }
+    }
+
+    /// Write the effect settings to the recovery file once they've stopped changing for a bit.
+    fn tick_autosave(&mut self, ctx: &egui::Context) {
+        if self.effect_settings != self.autosave_settings {
+            self.autosave_settings = self.effect_settings.clone();
+            self.autosave_changed_at = Some(Instant::now());
+        }
+
+        let Some(changed_at) = self.autosave_changed_at else {
+            return;
+        };
+        let elapsed = changed_at.elapsed();
+        if elapsed < AUTOSAVE_DELAY {
+            ctx.request_repaint_after(AUTOSAVE_DELAY - elapsed);
+            return;
+        }
+
+        self.autosave_changed_at = None;
+        let Some(recovery_path) = recovery_file_path() else {
+            return;
+        };
+        if let Ok(settings_json) = self
+            .settings_list
+            .to_json(&self.autosave_settings)
+            .stringify()
+        {
+            let result = std::fs::create_dir_all(recovery_path.parent().unwrap())
+                .and_then(|_| std::fs::write(&recovery_path, settings_json));
+            if let Err(err) = result {
+                debug!("Couldn't write recovery file: {err}");
+            }
+        }
+    }
+
+    /// Offer to restore the settings from a recovery file, if the app crashed with changes that weren't saved.
+    fn check_recovery_file(&mut self) {
+        let Some(recovery_path) = recovery_file_path() else {
+            return;
+        };
+        if !recovery_file_is_newer(&recovery_path) {
+            return;
+        }
+
+        let recovered_settings = std::fs::read_to_string(&recovery_path)
+            .ok()
+            .and_then(|settings_json| self.settings_list.from_json(&settings_json).ok());
+        match recovered_settings {
+            Some(settings) if settings != self.effect_settings => {
+                self.recovered_settings = Some(settings);
+            }
+            _ => {
+                let _ = std::fs::remove_file(&recovery_path);
+            }
+        }
+    }
+
+    /// Put every effect setting back to its default. This is a single undo step, since the undo history picks up the
+    /// new settings as one change on the next frame.
+    fn reset_effect_settings(&mut self) {
+        self.effect_settings = NtscEffectFullSettings::default();
+        self.preset_metadata = PresetMetadata::default();
+        self.update_effect();
+    }
+
+    /// Put every effect setting back to how it was when a preset was last loaded. Like resetting, this is a single undo
+    /// step.
+    fn revert_to_loaded_preset(&mut self) {
+        if let Some(settings) = &self.loaded_preset {
+            self.effect_settings = settings.clone();
+            self.update_effect();
+        }
+    }
+
+    fn show_reset_settings_dialog(&mut self, ctx: &egui::Context) {
+        let mut reset = None;
+        egui::Window::new(tr!("Reset settings?"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(
+                    tr!("Every effect setting will go back to its default value. You can still undo this afterwards."),
+                );
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("Reset")).clicked() {
+                        reset = Some(true);
+                    }
+                    if ui.button(tr!("Cancel")).clicked() {
+                        reset = Some(false);
+                    }
+                });
+            });
+
+        let Some(reset) = reset else {
+            return;
+        };
+        self.confirm_reset_settings = false;
+        if reset {
+            self.reset_effect_settings();
+        }
+    }
+
+    fn show_recovery_dialog(&mut self, ctx: &egui::Context) {
+        let mut restore = None;
+        egui::Window::new(tr!("Restore settings?"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(
+                    tr!("The app didn't shut down properly last time, and some effect settings changes weren't saved. Do \
+                    you want to restore them?"),
+                );
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("Restore")).clicked() {
+                        restore = Some(true);
+                    }
+                    if ui.button(tr!("Discard")).clicked() {
+                        restore = Some(false);
+                    }
+                });
+            });
+
+        let Some(restore) = restore else {
+            return;
+        };
+        let recovered_settings = self.recovered_settings.take();
+        if restore {
+            if let Some(settings) = recovered_settings {
+                self.effect_settings = settings;
+                self.update_effect();
+            }
+        } else if let Some(recovery_path) = recovery_file_path() {
+            let _ = std::fs::remove_file(recovery_path);
+        }
+    }
+
+    fn push_error(&mut self, message: String) {
+        let details = message.clone();
+        self.push_error_with_details(message, details);
+    }
+
+    fn push_error_with_details(&mut self, message: String, details: String) {
+        if self.error_log.len() >= MAX_ERROR_LOG_ENTRIES {
+            self.error_log.pop_front();
+        }
+        self.error_log.push_back(ErrorLogEntry {
+            message,
+            details,
+            time: Instant::now(),
+        });
+        self.unseen_errors += 1;
+    }
+
+    /// Show a message that disappears on its own after a couple of seconds.
+    fn show_toast(&mut self, message: impl Into<String>) {
+        if self.toasts.len() >= MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+        self.toasts.push_back(Toast {
+            message: message.into(),
+            time: Instant::now(),
+        });
+    }
+
+    fn handle_error(&mut self, err: &dyn Error) {
+        // Most of our errors include their source in their message, but the whole chain (and the debug representation,
+        // which has things like GStreamer's error domains) is more useful in bug reports
+        let mut details = format!("{}", err);
+        let mut source = err.source();
+        while let Some(err) = source {
+            details.push_str(&format!("\nCaused by: {}", err));
+            source = err.source();
+        }
+        details.push_str(&format!("\n\n{:?}", err));
+        self.push_error_with_details(format!("{}", err), details);
+    }
+
+    fn handle_result<T, E: Error>(&mut self, result: Result<T, E>) {
+        if let Err(err) = result {
+            self.handle_error(&err);
+        }
+    }
+
+    fn handle_result_with<T, E: Error, F: FnOnce(&mut Self) -> Result<T, E>>(&mut self, cb: F) {
+        let result = cb(self);
+        self.handle_result(result);
+    }
+
+    fn undo(&mut self) {
+        if let Some(new_state) = self.undo_history.undo(&self.effect_settings) {
+            self.effect_settings = new_state.clone();
+            self.update_effect();
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(new_state) = self.undo_history.redo(&self.effect_settings) {
+            self.effect_settings = new_state.clone();
+            self.update_effect();
+        }
+    }
+}
+
+/// Scale all the "amplitude" settings (noise intensities, shifts, etc.) towards their "clean" value of 0, leaving
+/// everything else as-is.
+fn scale_effect_intensity(settings: &NtscEffectFullSettings, intensity: f32) -> NtscEffect {
+    let mut settings = settings.clone();
+    if intensity >= 1.0 {
+        return settings.into();
+    }
+
+    settings.luma_smear *= intensity;
+    settings.luma_overshoot *= intensity;
+    settings.luma_undershoot *= intensity;
+    settings.composite_noise_intensity *= intensity;
+    settings.snow_intensity *= intensity;
+    settings.chroma_phase_noise_intensity *= intensity;
+    settings.chroma_phase_error *= intensity;
+    settings.head_switching.settings.horiz_shift *= intensity;
+    settings.tbc_jitter *= intensity;
+    let tracking_noise = &mut settings.tracking_noise.settings;
+    tracking_noise.wave_intensity *= intensity;
+    tracking_noise.snow_intensity *= intensity;
+    tracking_noise.noise_intensity *= intensity;
+    settings.hum_bars.settings.intensity *= intensity;
+    settings.dropout.settings.density *= intensity;
+    settings.ghosting.settings.intensity *= intensity;
+    settings.ringing.settings.intensity *= intensity;
+    settings.chroma_noise.settings.intensity *= intensity;
+    let vhs_settings = &mut settings.vhs_settings.settings;
+    vhs_settings.chroma_loss *= intensity;
+    vhs_settings.sharpen.settings.intensity *= intensity;
+    vhs_settings.edge_wave.settings.intensity *= intensity;
+    settings.vignette.settings.intensity *= intensity;
+    settings.scanlines.settings.darkness *= intensity;
+    settings.film_grain.settings.intensity *= intensity;
+    settings.bloom.settings.intensity *= intensity;
+
+    settings.into()
+}
+
+fn parse_expression_string(input: &str) -> Option<f64> {
+    eval_expression_string(input).ok()
+}
+
+impl NtscApp {
+    fn setting_from_descriptor(
+        ui: &mut egui::Ui,
+        effect_settings: &mut NtscEffectFullSettings,
+        descriptor: &SettingDescriptor,
+        interlace_mode: VideoInterlaceMode,
+        group_state: Option<&mut GroupUiState>,
+        prefs: &mut SettingsPanelPrefs,
+    ) -> (Response, bool) {
+        let mut changed = false;
+        let resp = match &descriptor {
+            SettingDescriptor {
+                id:
+                    SettingID::RANDOM_SEED
+                    | SettingID::COMPOSITE_NOISE_SEED
+                    | SettingID::SNOW_SEED
+                    | SettingID::CHROMA_NOISE_SEED
+                    | SettingID::TRACKING_NOISE_SEED,
+                ..
+            } => {
+                ui.horizontal(|ui| {
+                    let seed = descriptor.id.get_field_mut::<i32>(effect_settings).unwrap();
+                    let rand_btn_width = ui.spacing().interact_size.y + 4.0;
+                    let resp = ui.add_sized(
+                        egui::vec2(
+                            ui.spacing().slider_width + ui.spacing().interact_size.x
+                                - rand_btn_width,
+                            ui.spacing().interact_size.y,
+                        ),
+                        egui::DragValue::new(seed).clamp_range(i32::MIN..=i32::MAX),
+                    );
+
+                    if ui
+                        .add_sized(
+                            egui::vec2(rand_btn_width, ui.spacing().interact_size.y),
+                            egui::Button::new("🎲"),
+                        )
+                        .on_hover_text(tr!("Randomize seed"))
+                        .clicked()
+                    {
+                        *seed = rand::random::<i32>();
+                        changed = true;
+                    }
+
+                    let label = ui.add(egui::Label::new(tr!(descriptor.label)).truncate(true));
+                    if let Some(description) = descriptor.description {
+                        label.on_hover_text(tr!(description));
+                    }
+
+                    // Return the DragValue response because that's what we want to add the tooltip to
+                    resp
+                })
+                .response
+            }
+            SettingDescriptor {
+                kind: SettingKind::Enumeration { options, .. },
+                ..
+            } => {
+                let selected_index = descriptor.id.get_field_enum(effect_settings).unwrap();
+                let selected_item = options
+                    .iter()
+                    .find(|option| option.index == selected_index)
+                    .unwrap();
+                egui::ComboBox::new(descriptor.id, tr!(descriptor.label))
+                    .selected_text(tr!(selected_item.label))
+                    .show_ui(ui, |ui| {
+                        for item in options {
+                            let mut label =
+                                ui.selectable_label(selected_index == item.index, tr!(item.label));
+
+                            if let Some(desc) = item.description {
+                                label = label.on_hover_text(tr!(desc));
+                            }
+
+                            if label.clicked() {
+                                let _ = descriptor.id.set_field_enum(effect_settings, item.index);
+                                // a selectable_label being clicked doesn't set response.changed
+                                changed = true;
+                            };
+                        }
+                    })
+                    .response
+            }
+            SettingDescriptor {
+                kind: SettingKind::Percentage { .. },
+                ..
+            } => {
+                let logarithmic = prefs.is_logarithmic(descriptor).unwrap_or_default();
+                let value = descriptor.id.get_field_mut::<f32>(effect_settings).unwrap();
+                let old_value = *value;
+                let mut slider = ui.add(
+                    egui::Slider::new(value, 0.0..=1.0)
+                        .text(tr!(descriptor.label))
+                        .custom_parser(parse_expression_string)
+                        .custom_formatter(format_percentage)
+                        .logarithmic(logarithmic),
+                );
+
+                if let Some(fine_value) = fine_drag_value(ui, &slider, old_value as f64, 0.0..=1.0)
+                {
+                    *value = fine_value as f32;
+                }
+
+                let steps = slider_scroll_steps(ui, &slider);
+                if steps != 0.0 {
+                    *value = (*value + steps * 0.01).clamp(0.0, 1.0);
+                    slider.mark_changed();
+                }
+
+                slider
+            }
+            SettingDescriptor {
+                kind: SettingKind::IntRange { range, .. },
+                ..
+            } => {
+                let mut value = 0i32;
+                if let Some(v) = descriptor.id.get_field_mut::<i32>(effect_settings) {
+                    value = *v;
+                } else if let Some(v) = descriptor.id.get_field_mut::<u32>(effect_settings) {
+                    value = *v as i32;
+                }
+
+                let old_value = value;
+                let mut slider = ui.add(
+                    egui::Slider::new(&mut value, range.clone())
+                        .text(tr!(descriptor.label))
+                        .custom_parser(parse_expression_string),
+                );
+
+                // Integer settings can't hold the fractional part of a fine drag, so it's kept between frames
+                let fine_value_id = slider.id.with("fine_drag_value");
+                let fine_range = *range.start() as f64..=*range.end() as f64;
+                let fine_old_value = ui
+                    .ctx()
+                    .data(|data| data.get_temp::<f64>(fine_value_id))
+                    .filter(|fine_value| fine_value.round() as i32 == old_value)
+                    .unwrap_or(old_value as f64);
+                if let Some(fine_value) = fine_drag_value(ui, &slider, fine_old_value, fine_range) {
+                    value = fine_value.round() as i32;
+                    ui.ctx()
+                        .data_mut(|data| data.insert_temp(fine_value_id, fine_value));
+                }
+
+                let steps = slider_scroll_steps(ui, &slider);
+                if steps != 0.0 {
+                    value = (value + steps as i32).clamp(*range.start(), *range.end());
+                    slider.mark_changed();
+                }
+
+                if slider.changed() {
+                    if let Some(v) = descriptor.id.get_field_mut::<i32>(effect_settings) {
+                        *v = value;
+                    } else if let Some(v) = descriptor.id.get_field_mut::<u32>(effect_settings) {
+                        *v = value as u32;
+                    }
+                }
+
+                slider
+            }
+            SettingDescriptor {
+                kind: SettingKind::FloatRange { range, .. },
+                ..
+            } => {
+                let logarithmic = prefs.is_logarithmic(descriptor).unwrap_or_default();
+                let value = descriptor.id.get_field_mut::<f32>(effect_settings).unwrap();
+                let old_value = *value;
+                let mut slider = ui.add(
+                    egui::Slider::new(value, range.clone())
+                        .text(tr!(descriptor.label))
+                        .custom_parser(parse_expression_string)
+                        .logarithmic(logarithmic),
+                );
+
+                let fine_range = *range.start() as f64..=*range.end() as f64;
+                if let Some(fine_value) = fine_drag_value(ui, &slider, old_value as f64, fine_range)
+                {
+                    *value = fine_value as f32;
+                }
+
+                // Each notch of the wheel moves the slider by a hundredth of its range
+                let steps = slider_scroll_steps(ui, &slider);
+                if steps != 0.0 {
+                    let step = (range.end() - range.start()) * 0.01;
+                    *value = (*value + steps * step).clamp(*range.start(), *range.end());
+                    slider.mark_changed();
+                }
+
+                slider
+            }
+            SettingDescriptor {
+                kind: SettingKind::Boolean { .. },
+                ..
+            } => {
+                let checkbox = ui.checkbox(
+                    descriptor
+                        .id
+                        .get_field_mut::<bool>(effect_settings)
+                        .unwrap(),
+                    tr!(descriptor.label),
+                );
+
+                checkbox
+            }
+            SettingDescriptor {
+                kind: SettingKind::Group { children, .. },
+                ..
+            } => {
+                ui.add_space(2.0);
+                let resp = ui
+                    .group(|ui| {
+                        ui.set_width(ui.max_rect().width());
+                        let mut group_state = group_state;
+                        let checkbox = ui
+                            .horizontal(|ui| {
+                                if let Some(group_state) = group_state.as_deref_mut() {
+                                    let collapsed = group_state.collapsed.contains(&descriptor.id);
+                                    if ui
+                                        .add(
+                                            egui::Button::new(if collapsed {
+                                                "⏵"
+                                            } else {
+                                                "⏷"
+                                            })
+                                            .frame(false),
+                                        )
+                                        .on_hover_text(if collapsed {
+                                            "Expand"
+                                        } else {
+                                            "Collapse"
+                                        })
+                                        .clicked()
+                                    {
+                                        if collapsed {
+                                            group_state.collapsed.remove(&descriptor.id);
+                                        } else {
+                                            group_state.collapsed.insert(descriptor.id);
+                                        }
+                                    }
+                                }
+
+                                let checkbox = ui.checkbox(
+                                    descriptor
+                                        .id
+                                        .get_field_mut::<bool>(effect_settings)
+                                        .unwrap(),
+                                    tr!(descriptor.label),
+                                );
+
+                                if let Some(group_state) = group_state.as_deref_mut() {
+                                    ui.with_layout(
+                                        egui::Layout::right_to_left(egui::Align::Center),
+                                        |ui| {
+                                            Self::group_state_buttons(
+                                                ui,
+                                                descriptor.id,
+                                                group_state,
+                                            );
+                                        },
+                                    );
+                                }
+
+                                checkbox
+                            })
+                            .inner;
+
+                        let collapsed = group_state.is_some_and(|group_state| {
+                            group_state.collapsed.contains(&descriptor.id)
+                        });
+                        if collapsed {
+                            return checkbox;
+                        }
+
+                        ui.set_enabled(
+                            *descriptor
+                                .id
+                                .get_field_mut::<bool>(effect_settings)
+                                .unwrap(),
+                        );
+
+                        changed |= Self::settings_from_descriptors(
+                            effect_settings,
+                            ui,
+                            children,
+                            interlace_mode,
+                            None,
+                            prefs,
+                            None,
+                        );
+
+                        checkbox
+                    })
+                    .inner;
+                ui.add_space(2.0);
+                resp
+            }
+        };
+
+        (resp, changed)
+    }
+
+    /// Show the solo and bypass toggles for a group. These are view state rather than settings, so they aren't reported
+    /// as changes; the settings panel updates the preview when they're toggled.
+    fn group_state_buttons(ui: &mut egui::Ui, id: SettingID, group_state: &mut GroupUiState) {
+        let bypassed = group_state.bypassed.contains(&id);
+        if ui
+            .selectable_label(bypassed, tr!("B"))
+            .on_hover_text(tr!("Bypass: temporarily disable this group"))
+            .clicked()
+        {
+            if bypassed {
+                group_state.bypassed.remove(&id);
+            } else {
+                group_state.bypassed.insert(id);
+            }
+        }
+
+        let soloed = group_state.solo == Some(id);
+        if ui
+            .selectable_label(soloed, tr!("S"))
+            .on_hover_text(tr!("Solo: temporarily disable all other groups"))
+            .clicked()
+        {
+            group_state.solo = if soloed { None } else { Some(id) };
+        }
+    }
+
+    fn settings_from_descriptors(
+        effect_settings: &mut NtscEffectFullSettings,
+        ui: &mut egui::Ui,
+        descriptors: &[SettingDescriptor],
+        interlace_mode: VideoInterlaceMode,
+        mut group_state: Option<&mut GroupUiState>,
+        prefs: &mut SettingsPanelPrefs,
+        mut auto_bandwidth_scale: Option<AutoBandwidthScale>,
+    ) -> bool {
+        let mut changed = false;
+        for descriptor in descriptors {
+            // The "Use field" setting has no effect on interlaced video.
+            let (response, setting_changed) = if descriptor.id == SettingID::USE_FIELD
+                && interlace_mode != VideoInterlaceMode::Progressive
+            {
+                let resp = ui.add_enabled_ui(false, |ui| {
+                    Self::setting_from_descriptor(
+                        ui,
+                        effect_settings,
+                        descriptor,
+                        VideoInterlaceMode::Progressive,
+                        group_state.as_deref_mut(),
+                        prefs,
+                    )
+                });
+
+                resp.inner
+            } else if let (SettingID::BANDWIDTH_SCALE, Some(auto)) =
+                (descriptor.id, auto_bandwidth_scale.as_mut())
+            {
+                Self::bandwidth_scale_setting(
+                    ui,
+                    effect_settings,
+                    descriptor,
+                    interlace_mode,
+                    prefs,
+                    auto,
+                )
+            } else {
+                Self::setting_from_descriptor(
+                    ui,
+                    effect_settings,
+                    descriptor,
+                    interlace_mode,
+                    group_state.as_deref_mut(),
+                    prefs,
+                )
+            };
+
+            changed |= response.changed() || setting_changed;
+
+            // Mark settings that have been changed from their defaults, and allow resetting them individually.
+            let is_default = descriptor.is_default(effect_settings);
+            if !is_default {
+                ui.painter().circle_filled(
+                    pos2(response.rect.left() - 3.0, response.rect.center().y),
+                    2.0,
+                    ui.visuals().selection.bg_fill,
+                );
+            }
+
+            let is_favorite = prefs.favorites.contains(descriptor.id);
+            let mut reset = false;
+            let mut toggle_favorite = false;
+            let mut logarithmic = prefs.is_logarithmic(descriptor);
+            let _ = response.clone().context_menu(|ui| {
+                let favorite_label = if is_favorite {
+                    "★ Remove from favorites"
+                } else {
+                    "☆ Add to favorites"
+                };
+                if ui.button(favorite_label).clicked() {
+                    toggle_favorite = true;
+                    ui.close_menu();
+                }
+                if !is_default && ui.button(tr!("Reset to default")).clicked() {
+                    reset = true;
+                    ui.close_menu();
+                }
+                if let Some(logarithmic) = &mut logarithmic {
+                    if ui
+                        .checkbox(logarithmic, tr!("Logarithmic slider"))
+                        .on_hover_text(tr!("Give the slider finer control over low values. This only changes how the slider moves, not the setting."))
+                        .changed()
+                    {
+                        ui.close_menu();
+                    }
+                }
+            });
+            if toggle_favorite {
+                prefs.favorites.toggle(descriptor.id);
+            }
+            if let Some(logarithmic) = logarithmic {
+                if prefs.is_logarithmic(descriptor) != Some(logarithmic) {
+                    prefs.logarithmic.insert(descriptor.id, logarithmic);
+                }
+            }
+            if reset {
+                descriptor.reset_to_default(effect_settings);
+                changed = true;
+            }
+
+            let default_text = tr!("(default: {value})", value = format_default_value(&descriptor.kind));
+            match descriptor.description {
+                Some(desc) => response.on_hover_text(format!("{}\n\n{default_text}", tr!(desc))),
+                None => response.on_hover_text(default_text),
+            };
+
+            // The comb filters cancel out the chrominance by relying on its phase flipping from one scanline to the
+            // next. At any other phase shift, they leave it smeared into the luminance.
+            if descriptor.id == SettingID::CHROMA_DEMODULATION
+                && matches!(
+                    effect_settings.chroma_demodulation,
+                    ChromaDemodulationFilter::OneLineComb
+                        | ChromaDemodulationFilter::TwoLineComb
+                        | ChromaDemodulationFilter::ThreeLineComb
+                )
+                && effect_settings.video_scanline_phase_shift != PhaseShift::Degrees180
+            {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    tr!("⚠ Comb filters only work if the scanline phase shift is 180 degrees."),
+                );
+            }
+        }
+
+        changed
+    }
+
+    /// Show the bandwidth scale slider with an "Auto" checkbox next to it. While the automatic bandwidth scale is in use,
+    /// the slider shows it but can't be changed.
+    fn bandwidth_scale_setting(
+        ui: &mut egui::Ui,
+        effect_settings: &mut NtscEffectFullSettings,
+        descriptor: &SettingDescriptor,
+        interlace_mode: VideoInterlaceMode,
+        prefs: &mut SettingsPanelPrefs,
+        auto: &mut AutoBandwidthScale,
+    ) -> (Response, bool) {
+        const AUTO_CHECKBOX_WIDTH: f32 = 56.0;
+
+        ui.horizontal(|ui| {
+            let auto_changed = ui
+                .checkbox(auto.enabled, tr!("Auto"))
+                .on_hover_text(tr!("Set the bandwidth scale from the video's height, so higher-resolution video gets a comparable look. 480p video gets a bandwidth scale of 1.0."))
+                .changed();
+            ui.spacing_mut().slider_width -= AUTO_CHECKBOX_WIDTH;
+
+            let (response, changed) = match auto.value.filter(|_| *auto.enabled) {
+                Some(value) => {
+                    let mut shown_settings = effect_settings.clone();
+                    shown_settings.bandwidth_scale = value;
+                    ui.add_enabled_ui(false, |ui| {
+                        Self::setting_from_descriptor(
+                            ui,
+                            &mut shown_settings,
+                            descriptor,
+                            interlace_mode,
+                            None,
+                            prefs,
+                        )
+                    })
+                    .inner
+                }
+                None => Self::setting_from_descriptor(
+                    ui,
+                    effect_settings,
+                    descriptor,
+                    interlace_mode,
+                    None,
+                    prefs,
+                ),
+            };
+
+            (response, changed || auto_changed)
+        })
+        .inner
+    }
+
+    fn show_settings_slots(&mut self, ui: &mut egui::Ui) {
+        let mut action = None;
+        let can_remove = self.settings_slots.len() > 1;
+        ui.horizontal_wrapped(|ui| {
+            for (index, slot) in self.settings_slots.iter_mut().enumerate() {
+                let response = ui
+                    .selectable_label(index == self.active_slot, &slot.name)
+                    .on_hover_text(tr!("Right-click to rename, duplicate, or remove"));
+                if response.clicked() {
+                    action = Some(SettingsSlotAction::Switch(index));
+                }
+                response.context_menu(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("Name"));
+                        ui.text_edit_singleline(&mut slot.name);
+                    });
+                    if ui.button(tr!("Duplicate")).clicked() {
+                        action = Some(SettingsSlotAction::Duplicate(index));
+                        ui.close_menu();
+                    }
+                    if ui
+                        .add_enabled(can_remove, egui::Button::new(tr!("Remove")))
+                        .clicked()
+                    {
+                        action = Some(SettingsSlotAction::Remove(index));
+                        ui.close_menu();
+                    }
+                });
+            }
+
+            if ui
+                .button("+")
+                .on_hover_text(tr!("Add a settings slot, to compare different looks"))
+                .clicked()
+            {
+                action = Some(SettingsSlotAction::Add);
+            }
+        });
+
+        if let Some(action) = action {
+            self.apply_settings_slot_action(action);
+        }
+    }
+
+    fn show_effect_settings(&mut self, ui: &mut egui::Ui) {
+        egui::TopBottomPanel::top("settings_slots").show_inside(ui, |ui| {
+            self.show_settings_slots(ui);
+        });
+        egui::TopBottomPanel::bottom("effect_load_save")
+            .exact_height(ui.spacing().interact_size.y * 2.0)
+            .show_inside(ui, |ui| {
+                ui.horizontal_centered(|ui| {
+                    if ui.button(tr!("Save")).clicked() {
+                        self.save_preset_open = true;
+                    }
+
+                    if ui.button(tr!("Load")).clicked() {
+                        let handle = rfd::AsyncFileDialog::new()
+                            .add_filter("JSON", &["json"])
+                            .pick_file();
+                        self.spawn(async move {
+                            let handle = handle.await;
+
+                            Some(Box::new(
+                                move |app: &mut NtscApp| -> Result<(), ApplicationError> {
+                                    match handle {
+                                        Some(handle) => app.load_settings_file(handle.path()),
+                                        // user cancelled the operation
+                                        None => Ok(()),
+                                    }
+                                },
+                            ) as _)
+                        });
+                    }
+
+                    if ui.button(tr!("📋 Copy")).clicked() {
+                        ui.output_mut(|output| {
+                            output.copied_text = self
+                                .settings_list
+                                .to_json_with_metadata(&self.effect_settings, &self.preset_metadata)
+                                .stringify()
+                                .unwrap()
+                        });
+                        self.show_toast(tr!("Settings copied"));
+                    }
+
+                    let btn = ui.button(tr!("📄 Paste"));
+
+                    let paste_popup_id = ui.make_persistent_id("paste_popup_open");
+
+                    if btn.clicked() {
+                        ui.ctx().data_mut(|map| {
+                            let old_value =
+                                map.get_temp_mut_or_insert_with(paste_popup_id, || false);
+                            *old_value = !*old_value;
+                        });
+                    }
+
+                    if ui
+                        .ctx()
+                        .data(|map| map.get_temp(paste_popup_id).unwrap_or(false))
+                    {
+                        let mut is_open = true;
+                        egui::Window::new(tr!("Paste JSON"))
+                            .default_pos(btn.rect.center_top())
+                            .open(&mut is_open)
+                            .show(ui.ctx(), |ui| {
+                                ui.with_layout(egui::Layout::bottom_up(egui::Align::Min), |ui| {
+                                    if ui.button(tr!("Load")).clicked() {
+                                        let json = self.settings_json_paste.clone();
+                                        match self.apply_settings_json(&json, "Preset applied") {
+                                            Ok(()) => {
+                                                // Close the popup if the JSON was successfully loaded
+                                                ui.ctx().data_mut(|map| {
+                                                    map.insert_temp(paste_popup_id, false)
+                                                });
+                                            }
+                                            Err(e) => {
+                                                self.handle_error(&e);
+                                            }
+                                        }
+                                    }
+                                    ui.with_layout(
+                                        egui::Layout::top_down(egui::Align::Min),
+                                        |ui| {
+                                            egui::ScrollArea::new([false, true])
+                                                .auto_shrink([true, false])
+                                                .show(ui, |ui| {
+                                                    ui.add_sized(
+                                                        ui.available_size(),
+                                                        egui::TextEdit::multiline(
+                                                            &mut self.settings_json_paste,
+                                                        ),
+                                                    );
+                                                });
+                                        },
+                                    );
+                                });
+                            });
+
+                        if !is_open {
+                            ui.ctx()
+                                .data_mut(|map| map.insert_temp(paste_popup_id, false));
+                        }
+                    }
+
+                    if ui.button(tr!("Reset")).clicked() {
+                        let is_default = self
+                            .settings_list
+                            .changed_settings(&self.effect_settings, &NtscEffectFullSettings::default())
+                            .is_empty();
+                        if is_default {
+                            self.reset_effect_settings();
+                        } else {
+                            self.confirm_reset_settings = true;
+                        }
+                    }
+
+                    let can_revert = self
+                        .loaded_preset
+                        .as_ref()
+                        .is_some_and(|loaded_preset| *loaded_preset != self.effect_settings);
+                    if ui
+                        .add_enabled(can_revert, egui::Button::new(tr!("Revert to loaded preset")))
+                        .on_hover_text(tr!("Put the settings back to how they were when a preset was last loaded"))
+                        .clicked()
+                    {
+                        self.revert_to_loaded_preset();
+                    }
+
+                    if ui
+                        .button(tr!("No signal"))
+                        .on_hover_text(tr!("Load a preset that fills the frame with static, like a TV tuned to a dead channel"))
+                        .clicked()
+                    {
+                        self.offer_preset(Self::no_signal_preset());
+                    }
+
+                    if ui
+                        .button(tr!("Snapshot"))
+                        .on_hover_text(tr!("Remember the current settings, to compare later edits against"))
+                        .clicked()
+                    {
+                        self.settings_snapshot = Some(self.effect_settings.clone());
+                    }
+
+                    self.compare_button_held = ui
+                        .add_enabled(
+                            self.settings_snapshot.is_some(),
+                            egui::Button::new(tr!("Compare")).selected(self.comparing_snapshot),
+                        )
+                        .on_hover_text(tr!("Hold to preview the snapshot instead of the current settings (or hold C)"))
+                        .on_disabled_hover_text(tr!("Take a snapshot first"))
+                        .is_pointer_button_down_on();
+
+                    let settings_hash = self.settings_list.settings_hash(&self.effect_settings);
+                    if ui
+                        .add(
+                            egui::Label::new(
+                                egui::RichText::new(format!("#{}", &settings_hash[..8])).monospace(),
+                            )
+                            .sense(egui::Sense::click()),
+                        )
+                        .on_hover_text(tr!(
+                            "Settings hash: {settings_hash}\nRenders with the same hash used the same settings. \
+                            Click to copy.",
+                            settings_hash = settings_hash
+                        ))
+                        .clicked()
+                    {
+                        ui.output_mut(|output| output.copied_text = settings_hash);
+                    }
+
+                    if let Some(name) = &self.preset_metadata.name {
+                        let mut details = Vec::new();
+                        if let Some(author) = &self.preset_metadata.author {
+                            details.push(tr!("By {author}", author = author));
+                        }
+                        if let Some(description) = &self.preset_metadata.description {
+                            details.push(description.clone());
+                        }
+                        let label = ui.add(egui::Label::new(name.as_str()).truncate(true));
+                        if !details.is_empty() {
+                            label.on_hover_text(details.join("\n\n"));
+                        }
+                    }
+                });
+            });
+        egui::CentralPanel::default().show_inside(ui, |ui| {
+            ui.visuals_mut().clip_rect_margin = 4.0;
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, true])
+                .show(ui, |ui| {
+                    Self::setup_control_rows(ui);
+
+                    let intensity_changed = ui
+                        .add(
+                            egui::Slider::new(&mut self.effect_intensity, 0.0..=1.0)
+                                .text(tr!("Effect intensity")),
+                        )
+                        .on_hover_text(
+                            tr!("Scales all noise and distortion settings at once, without changing the settings themselves."),
+                        )
+                        .changed();
+                    if intensity_changed {
+                        self.update_effect();
+                    }
+
+                    ui.horizontal(|ui| {
+                        if ui.button(tr!("Collapse all")).clicked() {
+                            self.group_state.collapsed = self
+                                .settings_list
+                                .settings
+                                .iter()
+                                .filter(|descriptor| {
+                                    matches!(descriptor.kind, SettingKind::Group { .. })
+                                })
+                                .map(|descriptor| descriptor.id)
+                                .collect();
+                        }
+                        if ui.button(tr!("Expand all")).clicked() {
+                            self.group_state.collapsed.clear();
+                        }
+                    });
+                    ui.separator();
+
+                    let height_bandwidth_scale = self.height_bandwidth_scale();
+                    let Self {
+                        settings_list,
+                        effect_settings,
+                        pipeline,
+                        group_state,
+                        settings_panel_prefs,
+                        auto_bandwidth_scale,
+                        ..
+                    } = self;
+                    let interlace_mode = pipeline
+                        .as_ref()
+                        .and_then(|pipeline| pipeline.metadata.lock().unwrap().interlace_mode)
+                        .unwrap_or(VideoInterlaceMode::Progressive);
+
+                    let overrides_before = (group_state.solo, group_state.bypassed.clone());
+                    let mut settings_changed = false;
+                    if !settings_panel_prefs.favorites.0.is_empty() {
+                        let mut descriptors = Vec::new();
+                        all_descriptors(&settings_list.settings, &mut descriptors);
+                        let favorite_descriptors = settings_panel_prefs
+                            .favorites
+                            .0
+                            .iter()
+                            .filter_map(|id| {
+                                descriptors.iter().find(|descriptor| descriptor.id == *id)
+                            })
+                            .collect::<Vec<_>>();
+                        // The favorites are also shown in the full list below, so keep their widget IDs apart
+                        egui::CollapsingHeader::new(tr!("★ Favorites"))
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                for descriptor in favorite_descriptors {
+                                    ui.push_id(("favorite", descriptor.id.name()), |ui| {
+                                        settings_changed |= Self::settings_from_descriptors(
+                                            effect_settings,
+                                            ui,
+                                            std::slice::from_ref(*descriptor),
+                                            interlace_mode,
+                                            None,
+                                            settings_panel_prefs,
+                                            Some(AutoBandwidthScale {
+                                                enabled: auto_bandwidth_scale,
+                                                value: height_bandwidth_scale,
+                                            }),
+                                        );
+                                    });
+                                }
+                            });
+                        ui.separator();
+                    }
+
+                    settings_changed |= Self::settings_from_descriptors(
+                        effect_settings,
+                        ui,
+                        &settings_list.settings,
+                        interlace_mode,
+                        Some(&mut *group_state),
+                        settings_panel_prefs,
+                        Some(AutoBandwidthScale {
+                            enabled: auto_bandwidth_scale,
+                            value: height_bandwidth_scale,
+                        }),
+                    );
+                    // Soloing or bypassing a group doesn't change the settings, but the preview still needs updating
+                    let overrides_changed =
+                        overrides_before != (group_state.solo, group_state.bypassed.clone());
+                    if settings_changed || overrides_changed {
+                        self.update_effect();
+                    }
+                });
+        });
+    }
+
+    fn setup_control_rows(ui: &mut egui::Ui) {
+        const LABEL_WIDTH: f32 = 180.0;
+
+        let remaining_width = ui.max_rect().width() - LABEL_WIDTH;
+
+        let spacing = ui.spacing_mut();
+        spacing.slider_width = remaining_width - 48.0;
+        spacing.interact_size.x = 48.0;
+        spacing.combo_width =
+            spacing.slider_width + spacing.interact_size.x + spacing.item_spacing.x;
+    }
+
+    /// Show a batch job that hasn't been started yet, with controls for moving it around in the queue.
+    fn show_queued_render_job(
+        ui: &mut egui::Ui,
+        settings: &RenderPipelineSettings,
+        can_move_up: bool,
+        can_move_down: bool,
+    ) -> Option<QueuedJobAction> {
+        let mut action = None;
+        ui.with_layout(egui::Layout::top_down_justified(egui::Align::Min), |ui| {
+            let fill = ui.style().visuals.faint_bg_color;
+            egui::Frame::none()
+                .fill(fill)
+                .stroke(ui.style().visuals.window_stroke)
+                .rounding(ui.style().noninteractive().rounding)
+                .inner_margin(ui.style().spacing.window_margin)
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.button("🗙").clicked() {
+                                action = Some(QueuedJobAction::Remove);
+                            }
+                            if ui
+                                .add_enabled(can_move_down, egui::Button::new("⏷"))
+                                .on_hover_text(tr!("Render this later"))
+                                .clicked()
+                            {
+                                action = Some(QueuedJobAction::MoveDown);
+                            }
+                            if ui
+                                .add_enabled(can_move_up, egui::Button::new("⏶"))
+                                .on_hover_text(tr!("Render this sooner"))
+                                .clicked()
+                            {
+                                action = Some(QueuedJobAction::MoveUp);
+                            }
+                            ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
+                                ui.add(
+                                    egui::Label::new(settings.output_path.to_string_lossy())
+                                        .truncate(true),
+                                );
+                            })
+                        });
+                    });
+
+                    ui.separator();
+
+                    ui.label(tr!("Queued"));
+                });
+        });
+
+        action
+    }
+
+    fn show_render_job(ui: &mut egui::Ui, job: &mut RenderJob, progress_json: bool) -> bool {
+        let mut remove_job = false;
+        ui.with_layout(egui::Layout::top_down_justified(egui::Align::Min), |ui| {
+            let fill = ui.style().visuals.faint_bg_color;
+            egui::Frame::none()
+                .fill(fill)
+                .stroke(ui.style().visuals.window_stroke)
+                .rounding(ui.style().noninteractive().rounding)
+                .inner_margin(ui.style().spacing.window_margin)
+                .show(ui, |ui| {
+                    let job_state = &*job.state.lock().unwrap();
+
+                    let (pass_progress, job_position, job_duration) = match job_state {
+                        RenderJobState::Waiting => (0.0, None, None),
+                        RenderJobState::Paused
+                        | RenderJobState::Rendering
+                        | RenderJobState::Error(_) => {
+                            let job_position = job.pipeline.query_position::<ClockTime>();
+                            // Recordings from a camera last as long as they were set to
+                            let job_duration =
+                                job.pipeline.query_duration::<ClockTime>().or_else(|| {
+                                    is_camera(&job.src_path).then_some(job.settings.duration)
+                                });
+
+                            (
+                                if let (Some(job_position), Some(job_duration)) =
+                                    (job_position, job_duration)
+                                {
+                                    job_position.nseconds() as f64 / job_duration.nseconds() as f64
+                                } else {
+                                    job.last_progress
                                 },
-                            ) as _)
+                                job_position,
+                                job_duration,
+                            )
+                        }
+                        RenderJobState::Complete { .. } => (1.0, None, None),
+                    };
+                    // For multi-pass encodes, each pass makes up an equal part of the total progress
+                    let num_passes = job.settings.num_passes();
+                    let progress = if matches!(job_state, RenderJobState::Complete { .. }) {
+                        1.0
+                    } else {
+                        (job.settings.pass as f64 + pass_progress) / num_passes as f64
+                    };
+
+                    if matches!(
+                        job_state,
+                        RenderJobState::Rendering | RenderJobState::Waiting
+                    ) {
+                        let current_time = ui.ctx().input(|input| input.time);
+                        let most_recent_sample = job.progress_samples.back().copied();
+                        let should_update_estimate =
+                            if let Some((_, sample_time)) = most_recent_sample {
+                                current_time - sample_time > PROGRESS_SAMPLE_TIME_DELTA
+                            } else {
+                                true
+                            };
+                        if should_update_estimate {
+                            if job.start_time.is_none() {
+                                job.start_time = Some(current_time);
+                            }
+                            let new_sample = (progress, current_time);
+                            let oldest_sample =
+                                if job.progress_samples.len() >= NUM_PROGRESS_SAMPLES {
+                                    job.progress_samples.pop_front()
+                                } else {
+                                    job.progress_samples.front().copied()
+                                };
+                            job.progress_samples.push_back(new_sample);
+                            if let Some((old_progress, old_sample_time)) = oldest_sample {
+                                let progress_delta = progress - old_progress;
+                                if progress_delta >= MIN_PROGRESS_SAMPLE_DELTA {
+                                    let time_estimate = (current_time - old_sample_time)
+                                        / progress_delta
+                                        + job.start_time.unwrap();
+                                    job.estimated_completion_time =
+                                        Some(match job.estimated_completion_time {
+                                            Some(old_estimate) => {
+                                                old_estimate
+                                                    + (time_estimate - old_estimate)
+                                                        * COMPLETION_ESTIMATE_SMOOTHING
+                                            }
+                                            None => time_estimate,
+                                        });
+                                }
+                            }
+
+                            if progress_json {
+                                let eta = job.estimated_completion_time.map(
+                                    |estimated_completion_time| {
+                                        (estimated_completion_time - current_time).max(0.0)
+                                    },
+                                );
+                                job.print_progress_json(job_state, progress, eta);
+                            }
+                        }
+                    }
+
+                    if progress_json
+                        && !job.reported_finish
+                        && matches!(
+                            job_state,
+                            RenderJobState::Complete { .. } | RenderJobState::Error(_)
+                        )
+                    {
+                        job.reported_finish = true;
+                        job.print_progress_json(job_state, progress, Some(0.0));
+                    }
+
+                    ui.horizontal(|ui| {
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            remove_job = ui.button("🗙").clicked();
+                            ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
+                                ui.add(
+                                    egui::Label::new(job.settings.output_path.to_string_lossy())
+                                        .truncate(true)
+                                        .sense(egui::Sense::click()),
+                                )
+                                .context_menu(|ui| {
+                                    if ui.button(tr!("Copy pipeline description")).clicked() {
+                                        let description = describe_pipeline(&job.pipeline);
+                                        ui.output_mut(|output| output.copied_text = description);
+                                        ui.close_menu();
+                                    }
+                                });
+                            })
+                        });
+                    });
+
+                    ui.separator();
+
+                    ui.add(egui::ProgressBar::new(progress as f32).show_percentage());
+                    if let RenderJobState::Rendering = job_state {
+                        ui.ctx().request_repaint();
+                    }
+
+                    ui.label(match job_state {
+                        RenderJobState::Waiting => Cow::Borrowed(tr!("Waiting...")),
+                        RenderJobState::Rendering => {
+                            let pass = if num_passes > 1 {
+                                tr!(
+                                    " (pass {pass} of {num_passes})",
+                                    pass = job.settings.pass + 1,
+                                    num_passes = num_passes
+                                )
+                            } else {
+                                String::new()
+                            };
+                            if let (Some(position), Some(duration)) = (job_position, job_duration) {
+                                Cow::Owned(tr!(
+                                    "Rendering{pass}... ({position} / {duration})",
+                                    pass = pass,
+                                    position = format!("{:.2}", position),
+                                    duration = format!("{:.2}", duration)
+                                ))
+                            } else {
+                                Cow::Owned(tr!("Rendering{pass}...", pass = pass))
+                            }
+                        }
+                        RenderJobState::Paused => Cow::Borrowed(tr!("Paused")),
+                        // if the job's start_time is missing, it's probably because it never got a chance to update--in that case, just say it took 0 seconds
+                        RenderJobState::Complete { end_time } => Cow::Owned(tr!(
+                            "Completed in {time}",
+                            time = format!(
+                                "{:.2}",
+                                ClockTime::from_mseconds(
+                                    ((*end_time - job.start_time.unwrap_or(*end_time)) * 1000.0) as u64
+                                )
+                            )
+                        )),
+                        RenderJobState::Error(err) => Cow::Owned(tr!("Error: {error}", error = err)),
+                    });
+
+                    if matches!(
+                        job_state,
+                        RenderJobState::Rendering | RenderJobState::Paused
+                    ) {
+                        if let Some(estimated_completion_time) = job.estimated_completion_time {
+                            let current_time = ui.ctx().input(|input| input.time);
+                            let time_remaining =
+                                (estimated_completion_time - current_time).max(0.0).ceil();
+                            ui.label(tr!("Time remaining: {seconds} seconds", seconds = format!("{time_remaining:.0}")));
+                        }
+                    }
+
+                    job.last_progress = progress;
+                });
+        });
+
+        remove_job
+    }
+
+    fn show_undo_history(&mut self, ui: &mut egui::Ui) {
+        egui::Frame::central_panel(ui.style()).show(ui, |ui| {
+            ui.label(tr!(
+                "{undos} undo steps, {redos} redo steps available",
+                undos = self.undo_history.num_undos(),
+                redos = self.undo_history.num_redos()
+            ));
+            ui.separator();
+
+            let mut jump_to = None;
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, true])
+                .show(ui, |ui| {
+                    for (index, entry) in self.undo_history.entries.iter().enumerate() {
+                        let mut text = egui::RichText::new(&entry.description);
+                        // Entries after the current one will be discarded if a new change is made.
+                        if index > self.undo_history.position {
+                            text = text.weak();
+                        }
+                        if ui
+                            .selectable_label(index == self.undo_history.position, text)
+                            .clicked()
+                        {
+                            jump_to = Some(index);
+                        }
+                    }
+                });
+
+            if let Some(index) = jump_to {
+                self.effect_settings = self.undo_history.jump_to(index).clone();
+                self.update_effect();
+            }
+        });
+    }
+
+    fn show_keyframes(&mut self, ui: &mut egui::Ui) {
+        // Only settings with f32 values can be keyframed for now
+        let mut descriptors = Vec::new();
+        numeric_descriptors(&self.settings_list.settings, &mut descriptors);
+        descriptors.retain(|descriptor| !matches!(descriptor.kind, SettingKind::IntRange { .. }));
+        let label_for = |setting: SettingID| {
+            descriptors
+                .iter()
+                .find(|descriptor| descriptor.id == setting)
+                .map_or("", |descriptor| tr!(descriptor.label))
+        };
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("keyframe_setting")
+                .selected_text(self.keyframe_setting.map_or(tr!("Setting"), label_for))
+                .show_ui(ui, |ui| {
+                    for descriptor in &descriptors {
+                        ui.selectable_value(
+                            &mut self.keyframe_setting,
+                            Some(descriptor.id),
+                            tr!(descriptor.label),
+                        );
+                    }
+                });
+
+            let position = self
+                .pipeline
+                .as_ref()
+                .and_then(|info| info.pipeline.query_position::<ClockTime>());
+            if ui
+                .add_enabled(
+                    self.keyframe_setting.is_some() && position.is_some(),
+                    egui::Button::new(tr!("Add keyframe")),
+                )
+                .on_hover_text(
+                    tr!("Add a keyframe at the current position, with the setting's current value"),
+                )
+                .clicked()
+            {
+                if let (Some(setting), Some(time)) = (self.keyframe_setting, position) {
+                    if let Some(value) = setting.get_field_ref::<f32>(&self.effect_settings) {
+                        self.keyframes.track_mut(setting).insert(Keyframe {
+                            time,
+                            value: *value,
+                        });
+                    }
+                }
+            }
+        });
+
+        let mut remove_track = None;
+        for (track_index, track) in self.keyframes.0.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.strong(label_for(track.setting));
+                if ui
+                    .small_button("🗙")
+                    .on_hover_text(tr!("Remove all keyframes for this setting"))
+                    .clicked()
+                {
+                    remove_track = Some(track_index);
+                }
+            });
+
+            let mut remove_keyframe = None;
+            ui.indent(track_index, |ui| {
+                for index in 0..track.keyframes().len() {
+                    let keyframe = track.keyframes()[index];
+                    ui.horizontal(|ui| {
+                        ui.label(clock_time_format(keyframe.time.nseconds()));
+                        let mut value = keyframe.value;
+                        if ui
+                            .add(egui::DragValue::new(&mut value).speed(0.01))
+                            .changed()
+                        {
+                            track.set_value(index, value);
+                        }
+                        if ui.small_button("🗙").clicked() {
+                            remove_keyframe = Some(index);
+                        }
+                    });
+                }
+            });
+            if let Some(index) = remove_keyframe {
+                track.remove(index);
+            }
+        }
+        if let Some(index) = remove_track {
+            self.keyframes.0.remove(index);
+        }
+    }
+
+    fn show_render_settings(&mut self, ui: &mut egui::Ui) {
+        egui::Frame::central_panel(ui.style()).show(ui, |ui| {
+            Self::setup_control_rows(ui);
+            let mut codec_changed = false;
+            egui::ComboBox::from_label(tr!("Codec"))
+                .selected_text(tr!(self.render_settings.output_codec.label()))
+                .show_ui(ui, |ui| {
+                    codec_changed |= ui.selectable_value(
+                        &mut self.render_settings.output_codec,
+                        OutputCodec::H264,
+                        tr!(OutputCodec::H264.label()),
+                    ).changed();
+                    codec_changed |= ui.selectable_value(
+                        &mut self.render_settings.output_codec,
+                        OutputCodec::Ffv1,
+                        tr!(OutputCodec::Ffv1.label()),
+                    ).changed();
+                    codec_changed |= ui.selectable_value(
+                        &mut self.render_settings.output_codec,
+                        OutputCodec::ImageSequence,
+                        tr!(OutputCodec::ImageSequence.label()),
+                    ).changed();
+                });
+
+            if let OutputCodec::ImageSequence = self.render_settings.output_codec {
+                egui::ComboBox::from_label(tr!("Image format"))
+                    .selected_text(tr!(self.render_settings.image_sequence_format.label()))
+                    .show_ui(ui, |ui| {
+                        for format in ImageSequenceFormat::ALL {
+                            codec_changed |= ui.selectable_value(
+                                &mut self.render_settings.image_sequence_format,
+                                *format,
+                                tr!(format.label()),
+                            ).changed();
+                        }
+                    })
+                    .response
+                    .on_hover_text(tr!("Each frame is saved as a separate numbered file next to the destination file, e.g. \"video_00000.png\". Put a placeholder like %05d in the file name to number the frames yourself."));
+            }
+
+            if codec_changed {
+                self.render_settings.output_path.set_extension(self.render_settings.output_extension());
+                self.render_settings.audio_codec = self.render_settings.output_codec.default_audio_codec();
+            }
+
+            match self.render_settings.output_codec {
+                OutputCodec::H264 => {
+                    let rate_control = &mut self.render_settings.h264_settings.rate_control;
+                    egui::ComboBox::from_label(tr!("Rate control"))
+                        .selected_text(tr!(rate_control.label()))
+                        .show_ui(ui, |ui| {
+                            // Keep the bitrate when switching between the bitrate modes
+                            let bitrate = rate_control.bitrate().unwrap_or(H264RateControl::DEFAULT_BITRATE);
+                            for mode in H264RateControl::all(bitrate) {
+                                if ui
+                                    .selectable_label(rate_control.name() == mode.name(), tr!(mode.label()))
+                                    .clicked()
+                                {
+                                    *rate_control = mode;
+                                }
+                            }
+                        })
+                        .response
+                        .on_hover_text(tr!("Constant quality gives the best quality for a given file size. The bitrate modes let you target a specific file size, with two-pass encoding giving better quality at the cost of taking twice as long."));
+                    if let Some(bitrate) = self.render_settings.h264_settings.rate_control.bitrate_mut() {
+                        ui.add(
+                            egui::Slider::new(bitrate, 100..=100000)
+                                .logarithmic(true)
+                                .text(tr!("Bitrate (kbps)")),
+                        ).on_hover_text(tr!("Target video bitrate, in kilobits per second."));
+                    } else {
+                        ui.add(
+                            egui::Slider::new(&mut self.render_settings.h264_settings.crf, 0..=50)
+                                .text(tr!("Quality")),
+                        ).on_hover_text(tr!("Video quality factor, where 0 is the worst quality and 50 is the best. Higher quality videos take up more space."));
+                    }
+                    ui.add(
+                        egui::Slider::new(
+                            &mut self.render_settings.h264_settings.encode_speed,
+                            0..=8,
+                        )
+                        .text(tr!("Encoding speed")),
+                    ).on_hover_text(tr!("Encoding speed preset. Higher encoding speeds provide a worse compression ratio, resulting in larger videos at a given quality."));
+                    // Disabled for now until I can find a way to query for 10-bit support
+                    /*ui.checkbox(
+                        &mut self.render_settings.h264_settings.ten_bit,
+                        tr!("10-bit color"),
+                    );*/
+                    ui.checkbox(
+                        &mut self.render_settings.h264_settings.chroma_subsampling,
+                        tr!("4:2:0 chroma subsampling"),
+                    ).on_hover_text(tr!("Subsample the chrominance planes to half the resolution of the luminance plane. Increases playback compatibility."));
+                }
+
+                OutputCodec::Ffv1 => {
+                    egui::ComboBox::from_label(tr!("Bit depth"))
+                        .selected_text(tr!(self.render_settings.ffv1_settings.bit_depth.label()))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(
+                                &mut self.render_settings.ffv1_settings.bit_depth,
+                                Ffv1BitDepth::Bits8,
+                                tr!(Ffv1BitDepth::Bits8.label()),
+                            );
+                            ui.selectable_value(
+                                &mut self.render_settings.ffv1_settings.bit_depth,
+                                Ffv1BitDepth::Bits10,
+                                tr!(Ffv1BitDepth::Bits10.label()),
+                            );
+                            ui.selectable_value(
+                                &mut self.render_settings.ffv1_settings.bit_depth,
+                                Ffv1BitDepth::Bits12,
+                                tr!(Ffv1BitDepth::Bits12.label()),
+                            );
+                        });
+
+                    ui.checkbox(
+                        &mut self.render_settings.ffv1_settings.chroma_subsampling,
+                        tr!("4:2:0 chroma subsampling"),
+                    ).on_hover_text(tr!("Subsample the chrominance planes to half the resolution of the luminance plane. Results in smaller files."));
+                }
+                OutputCodec::ImageSequence => {}
+            }
+
+            if self.render_settings.output_codec != OutputCodec::ImageSequence {
+                egui::ComboBox::from_label(tr!("Color matrix"))
+                    .selected_text(tr!(self.render_settings.color_matrix.label()))
+                    .show_ui(ui, |ui| {
+                        for matrix in OutputColorMatrix::ALL {
+                            ui.selectable_value(
+                                &mut self.render_settings.color_matrix,
+                                *matrix,
+                                tr!(matrix.label()),
+                            );
+                        }
+                    })
+                    .response
+                    .on_hover_text(tr!("The color space the video is encoded in and tagged as. NTSC video is conventionally BT.601; choose BT.709 if the output is going to be treated as HD video."));
+                egui::ComboBox::from_label(tr!("Color range"))
+                    .selected_text(tr!(self.render_settings.color_range.label()))
+                    .show_ui(ui, |ui| {
+                        for range in OutputColorRange::ALL {
+                            ui.selectable_value(
+                                &mut self.render_settings.color_range,
+                                *range,
+                                tr!(range.label()),
+                            );
+                        }
+                    })
+                    .response
+                    .on_hover_text(tr!("Limited range is the standard for video and is what most players expect. Only use full range if whatever you're importing the video into asks for it."));
+            }
+
+            egui::ComboBox::from_label(tr!("Frame rate"))
+                .selected_text(tr!(self.render_settings.output_framerate.label()))
+                .show_ui(ui, |ui| {
+                    for framerate in OutputFramerate::ALL {
+                        ui.selectable_value(
+                            &mut self.render_settings.output_framerate,
+                            *framerate,
+                            tr!(framerate.label()),
+                        );
+                    }
+                })
+                .response
+                .on_hover_text(tr!("Convert the video to this frame rate, dropping or duplicating frames as necessary."));
+
+            let has_audio = !self.render_settings.output_codec.audio_codecs().is_empty();
+            ui.add_enabled(has_audio, egui::Checkbox::new(&mut self.render_settings.include_audio, tr!("Include audio")))
+                .on_hover_text(tr!("Include the source's audio in the rendered video. Turn this off to render a video with no audio track."))
+                .on_disabled_hover_text(tr!("Image sequences can't contain audio."));
+
+            ui.add_enabled_ui(has_audio && self.render_settings.include_audio, |ui| {
+                egui::ComboBox::from_label(tr!("Audio codec"))
+                    .selected_text(tr!(self.render_settings.audio_codec.label()))
+                    .show_ui(ui, |ui| {
+                        for audio_codec in self.render_settings.output_codec.audio_codecs() {
+                            ui.selectable_value(
+                                &mut self.render_settings.audio_codec,
+                                *audio_codec,
+                                tr!(audio_codec.label()),
+                            );
+                        }
+                    })
+                    .response
+                    .on_hover_text(tr!("Audio codec to encode the source's audio with. \"Copy source audio\" puts the original audio into the output without re-encoding it, if the container supports it."));
+            });
+
+            ui.checkbox(&mut self.render_settings.burn_in_timecode, tr!("Burn-in timecode"))
+                .on_hover_text(tr!("Draw a timecode over the rendered video. This does not affect the preview."));
+
+            ui.add_enabled_ui(self.render_settings.burn_in_timecode, |ui| {
+                egui::ComboBox::from_label(tr!("Timecode format"))
+                    .selected_text(tr!(self.render_settings.timecode_overlay.format.label()))
+                    .show_ui(ui, |ui| {
+                        for format in TimecodeFormat::ALL {
+                            ui.selectable_value(
+                                &mut self.render_settings.timecode_overlay.format,
+                                *format,
+                                tr!(format.label()),
+                            );
+                        }
+                    });
+                egui::ComboBox::from_label(tr!("Timecode position"))
+                    .selected_text(tr!(self.render_settings.timecode_overlay.corner.label()))
+                    .show_ui(ui, |ui| {
+                        for corner in OverlayCorner::ALL {
+                            ui.selectable_value(
+                                &mut self.render_settings.timecode_overlay.corner,
+                                *corner,
+                                tr!(corner.label()),
+                            );
+                        }
+                    });
+            });
+
+            ui.checkbox(&mut self.render_settings.enable_text_overlay, tr!("Text overlay"))
+                .on_hover_text(tr!("Draw some text, such as a label or watermark, over the rendered video. This does not affect the preview."));
+
+            ui.add_enabled_ui(self.render_settings.enable_text_overlay, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(tr!("Text:"));
+                    ui.text_edit_singleline(&mut self.render_settings.text_overlay.text);
+                });
+                ui.add(
+                    egui::Slider::new(&mut self.render_settings.text_overlay.font_size, 8..=200)
+                        .text(tr!("Font size")),
+                );
+                ui.add(
+                    egui::Slider::new(&mut self.render_settings.text_overlay.opacity, 0.0..=1.0)
+                        .text(tr!("Opacity")),
+                );
+                egui::ComboBox::from_label(tr!("Text position"))
+                    .selected_text(tr!(self.render_settings.text_overlay.corner.label()))
+                    .show_ui(ui, |ui| {
+                        for corner in OverlayCorner::ALL {
+                            ui.selectable_value(
+                                &mut self.render_settings.text_overlay.corner,
+                                *corner,
+                                tr!(corner.label()),
+                            );
+                        }
+                    });
+            });
+
+            ui.separator();
+
+            ui.rtl(|ui| {
+                let save_file = ui.button("📁").on_hover_text(tr!("Browse for a path")).clicked();
+
+                ui.ltr(|ui| {
+                    ui.label(tr!("Destination file:"));
+                    let mut path = self.render_settings.output_path.to_string_lossy();
+                    if ui.add_sized(ui.available_size(), egui::TextEdit::singleline(&mut path)).changed() {
+                        self.render_settings.output_path = PathBuf::from(OsStr::new(path.as_ref()));
+                    }
+                });
+
+                if save_file {
+                    let mut dialog_path = &self.render_settings.output_path;
+                    if dialog_path.components().next().is_none() {
+                        if let Some(PipelineInfo { path, .. }) = &self.pipeline {
+                            dialog_path = path;
+                        }
+                    }
+                    let mut file_dialog = rfd::AsyncFileDialog::new();
+
+                    if dialog_path.components().next().is_some() {
+                        if let Some(parent) = dialog_path.parent() {
+                            file_dialog = file_dialog.set_directory(parent);
+                        }
+                        if let Some(file_name) = dialog_path.file_stem() {
+                            file_dialog = file_dialog.set_file_name(format!(
+                                "{}_ntsc.{}",
+                                file_name.to_string_lossy(),
+                                self.render_settings.output_extension()
+                            ));
+                        }
+                    }
+
+                    let file_dialog = file_dialog.save_file();
+                    self.spawn(async move {
+                        let handle = file_dialog.await;
+                        Some(Box::new(|app: &mut NtscApp| {
+                            if let Some(handle) = handle {
+                                let mut output_path: PathBuf = handle.into();
+                                if output_path.extension().is_none() {
+                                    output_path.set_extension(app.render_settings.output_extension());
+                                }
+                                app.render_settings.output_path = output_path;
+                            }
+
+                            Ok(())
+                        }) as _)
+                    });
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(tr!("Still image framerate:"));
+                let changed = ui
+                    .add(
+                        egui::DragValue::new(&mut self.render_settings.still_image_framerate)
+                            .clamp_range(1.0..=240.0)
+                            .suffix(tr!(" fps")),
+                    )
+                    .on_hover_text(tr!("The framerate still images are loaded and rendered at. The noise changes once per frame, so this decides how fast it animates over a rendered still."))
+                    .changed();
+                if changed {
+                    // Apply it to the still image that's already loaded too
+                    let framerate = self.render_settings.still_image_framerate();
+                    let res = self
+                        .pipeline
+                        .as_ref()
+                        .filter(|info| info.metadata.lock().unwrap().is_still_image == Some(true))
+                        .map(|info| {
+                            let res = Self::set_still_image_framerate(&info.pipeline, framerate);
+                            if let Ok(Some(new_framerate)) = res {
+                                info.metadata.lock().unwrap().framerate = Some(new_framerate);
+                            }
+                            res
                         });
+                    if let Some(res) = res {
+                        self.handle_result(res);
                     }
+                }
+            });
 
-                    if ui.button("📋 Copy").clicked() {
-                        ui.output_mut(|output| {
-                            output.copied_text = self
-                                .settings_list
-                                .to_json(&self.effect_settings)
-                                .stringify()
-                                .unwrap()
+            let src_path = self.pipeline.as_ref().map(|info| &info.path);
+
+            let mut duration = self.render_settings.duration.mseconds();
+            if self
+                .pipeline
+                .as_ref()
+                .map(|info| info.metadata.lock().unwrap())
+                .is_some_and(|metadata| metadata.is_still_image == Some(true) || metadata.is_live)
+            {
+                ui.horizontal(|ui| {
+                    ui.label(tr!("Duration:"));
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut duration)
+                                .custom_formatter(|value, _| {
+                                    clock_time_format(
+                                        (value * ClockTime::MSECOND.nseconds() as f64)
+                                            as u64,
+                                    )
+                                })
+                                .custom_parser(clock_time_parser)
+                                .speed(100.0),
+                        )
+                        .changed()
+                    {
+                        self.render_settings.duration =
+                            ClockTime::from_mseconds(duration);
+                    }
+                });
+            }
+
+            ui
+                .add_enabled(
+                    self.interlaced_output_allowed(),
+                    egui::Checkbox::new(&mut self.render_settings.interlaced, tr!("Interlaced output"))
+                )
+                .on_hover_text(tr!("Interpret the source as interlaced, with its fields in the order that the \"Use field\" setting selects them, and keep those fields separate in the output. With \"Both\", the source's rows aren't split into fields, so they're treated as top field first."))
+                .on_disabled_hover_text(tr!("To enable interlaced output, set the \"Use field\" setting to \"Alternating\", \"Both\", or \"Interleaved\". \"Upper only\" and \"Lower only\" only process half the rows, so there are no fields to interlace."));
+
+            if ui
+                .checkbox(&mut self.render_settings.preserve_alpha, tr!("Preserve transparency"))
+                .on_hover_text(tr!("Keep the source's alpha channel, applying the effect only to its color. Only saved images and image sequences can store transparency; rendered videos are always opaque."))
+                .changed()
+            {
+                self.update_preserve_alpha();
+            }
+
+            ui.checkbox(&mut self.render_settings.keep_partial_output, tr!("Keep partial files"))
+                .on_hover_text(tr!("Renders are written to a temporary \".part\" file and only given their real name once they finish. Turn this on to keep the \".part\" file if a render fails or is cancelled, instead of deleting it."));
+
+            ui.checkbox(&mut self.render_settings.randomize_seed, tr!("Randomize seed for this render"))
+                .on_hover_text(tr!("Render with a new random seed each time, so several renders of the same settings each get different noise. The seed in the effect settings is left as it is."));
+
+            ui.checkbox(&mut self.render_settings.dither, tr!("Dither 8-bit output"))
+                .on_hover_text(tr!("Use error-diffusion dithering when reducing the effect's output to 8 bits per channel, which avoids banding in smooth gradients. Has no effect on 10- and 12-bit output."));
+
+            let hdr_transfer = self
+                .pipeline
+                .as_ref()
+                .and_then(|info| info.metadata.lock().unwrap().hdr_transfer);
+            egui::CollapsingHeader::new(tr!("HDR"))
+                .show(ui, |ui| {
+                    let mut changed = ui
+                        .checkbox(&mut self.render_settings.tonemap.enabled, tr!("Tone-map HDR input"))
+                        .on_hover_text(tr!("Convert HDR (PQ or HLG) video to SDR before applying the effect. Without this, HDR video comes out washed-out and with the wrong colors. SDR video is unaffected."))
+                        .changed();
+                    ui.add_enabled_ui(self.render_settings.tonemap.enabled, |ui| {
+                        ui.horizontal(|ui| {
+                            changed |= ui
+                                .add(
+                                    egui::DragValue::new(&mut self.render_settings.tonemap.target_nits)
+                                        .clamp_range(10.0..=1000.0)
+                                        .speed(1.0)
+                                        .suffix(tr!(" nits")),
+                                )
+                                .on_hover_text(tr!("How bright SDR white is assumed to be. Lower values make the tone-mapped video brighter."))
+                                .changed();
+                            ui.label(tr!("Target brightness"));
+                        });
+                    });
+                    if changed {
+                        self.update_tonemap();
+                    }
+                });
+
+            if let Some(transfer) = hdr_transfer {
+                if !self.render_settings.tonemap.enabled {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        tr!(
+                            "This video is HDR ({transfer}), but isn't being tone-mapped. The effect will be applied to HDR data, so colors and brightness will be wrong.",
+                            transfer = transfer.label()
+                        ),
+                    );
+                }
+            }
+
+            egui::CollapsingHeader::new(tr!("Advanced"))
+                .show(ui, |ui| {
+                    ui.checkbox(&mut self.render_settings.low_memory, tr!("Low memory mode"))
+                        .on_hover_text(tr!("Apply the effect to each frame a strip at a time instead of all at once. This renders more slowly, but uses much less memory for very large frames. The output is the same either way."));
+                });
+
+            egui::CollapsingHeader::new(tr!("Keyframes"))
+                .show(ui, |ui| {
+                    ui.label(tr!("Animate settings over the course of the render, fading linearly between keyframes. Keyframed settings aren't affected by the effect intensity, and the preview doesn't show them."));
+                    self.show_keyframes(ui);
+                });
+
+            let missing_elements = self
+                .render_pipeline_settings(&self.render_settings)
+                .missing_elements();
+            if !missing_elements.is_empty() {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    tr!(
+                        "Can't render to this format. Missing GStreamer elements: {elements}",
+                        elements = format_missing_elements(&missing_elements)
+                    ),
+                );
+            }
+
+            let missing_output_directory = self
+                .render_settings
+                .missing_output_directory()
+                .map(Path::to_path_buf);
+            if let Some(directory) = &missing_output_directory {
+                ui.horizontal_wrapped(|ui| {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        tr!("The folder \"{folder}\" doesn't exist.", folder = directory.display()),
+                    );
+                    if ui.button(tr!("Create folder")).clicked() {
+                        let res = std::fs::create_dir_all(directory).context(CreateOutputDirectorySnafu);
+                        self.handle_result(res);
+                    }
+                });
+            }
+
+            if ui
+                .add_enabled(
+                    !self.render_settings.output_path.as_os_str().is_empty()
+                        && src_path.is_some()
+                        && missing_elements.is_empty()
+                        && missing_output_directory.is_none(),
+                    egui::Button::new(tr!("Render")),
+                )
+                .on_disabled_hover_text(tr!("Choose a destination file in a folder that exists, and make sure a video is loaded."))
+                .clicked()
+            {
+                let render_job = self.create_render_job(
+                    ui.ctx(),
+                    &src_path.unwrap().clone(),
+                    self.render_pipeline_settings(&self.render_settings),
+                );
+                match render_job {
+                    Ok(render_job) => {
+                        self.render_jobs.push(render_job);
+                    }
+                    Err(err) => {
+                        self.handle_error(&err);
+                    }
+                }
+            }
+
+            if ui
+                .button(tr!("Batch render folder..."))
+                .on_hover_text(tr!("Render every video in a folder with the current settings. You'll be asked for the folder to read from, then the folder to save the results to."))
+                .clicked()
+            {
+                let input_dialog = rfd::AsyncFileDialog::new()
+                    .set_title(tr!("Folder of videos to render"))
+                    .pick_folder();
+                self.spawn(async move {
+                    let input_directory: PathBuf = input_dialog.await?.into();
+                    Some(Box::new(move |app: &mut NtscApp| {
+                        let output_dialog = rfd::AsyncFileDialog::new()
+                            .set_title(tr!("Folder to save rendered videos to"))
+                            .set_directory(&input_directory)
+                            .pick_folder();
+                        app.spawn(async move {
+                            let output_directory: PathBuf = output_dialog.await?.into();
+                            Some(Box::new(move |app: &mut NtscApp| {
+                                app.start_batch_render(&input_directory, &output_directory)
+                            }) as _)
+                        });
+                        Ok(())
+                    }) as _)
+                });
+            }
+
+            ui.horizontal(|ui| {
+                let src_path = self.pipeline.as_ref().map(|info| info.path.clone());
+                if ui
+                    .add_enabled(
+                        !self.render_settings.output_path.as_os_str().is_empty()
+                            && src_path.is_some()
+                            && self.render_settings.missing_output_directory().is_none(),
+                        egui::Button::new(tr!("Render variations")),
+                    )
+                    .on_hover_text(tr!("Queue several renders of this video, each with a different random seed. They're saved next to the destination file, numbered from 1."))
+                    .clicked()
+                {
+                    self.queue_render_variations(&src_path.unwrap());
+                }
+                ui.add(
+                    egui::DragValue::new(&mut self.render_variations.count)
+                        .clamp_range(1..=100)
+                        .suffix(tr!(" renders")),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut self.render_variations.jitter)
+                        .clamp_range(0.0..=0.5)
+                        .speed(0.001)
+                        .custom_formatter(format_percentage)
+                        .prefix(tr!("Vary settings ±")),
+                )
+                .on_hover_text(tr!("Also nudge every continuous setting by a random amount of up to this much of its range, so each render looks a little different."));
+            });
+
+            let has_queued_jobs = !self.render_jobs.is_empty()
+                || self
+                    .batch_render
+                    .as_ref()
+                    .is_some_and(|batch| !batch.pending.is_empty());
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(has_queued_jobs, egui::Button::new(tr!("Export queue...")))
+                    .on_hover_text(tr!("Save every render job and its settings to a file. Paths in the same folder as the file are saved relative to it, so the file can be moved along with the videos."))
+                    .clicked()
+                {
+                    let handle = rfd::AsyncFileDialog::new()
+                        .set_file_name("render_queue.json")
+                        .save_file();
+                    self.spawn(async move {
+                        let handle = handle.await?;
+                        Some(Box::new(move |app: &mut NtscApp| {
+                            let base_directory =
+                                handle.path().parent().unwrap_or(Path::new(""));
+                            let json = app.render_queue_to_json(base_directory);
+                            let mut file =
+                                File::create(handle.path()).context(JSONSaveSnafu)?;
+                            json.write_to(&mut file).context(JSONSaveSnafu)?;
+                            Ok(())
+                        }) as _)
+                    });
+                }
+
+                if ui
+                    .button(tr!("Import queue..."))
+                    .on_hover_text(tr!("Load render jobs from a file saved with \"Export queue\". Relative paths are resolved from the file's folder."))
+                    .clicked()
+                {
+                    let handle = rfd::AsyncFileDialog::new()
+                        .add_filter("JSON", &["json"])
+                        .pick_file();
+                    self.spawn(async move {
+                        let handle = handle.await?;
+                        Some(Box::new(move |app: &mut NtscApp| {
+                            let mut file = File::open(handle.path()).context(JSONReadSnafu)?;
+                            let mut buf = String::new();
+                            file.read_to_string(&mut buf).context(JSONReadSnafu)?;
+                            let base_directory =
+                                handle.path().parent().unwrap_or(Path::new(""));
+                            app.load_render_queue(&buf, base_directory)
+                                .context(JSONParseSnafu)?;
+                            Ok(())
+                        }) as _)
+                    });
+                }
+            });
+
+            if let Some((watcher, _)) = &self.watch_folder {
+                let mut stop = false;
+                ui.horizontal(|ui| {
+                    stop = ui.button(tr!("Stop watching")).clicked();
+                    ui.add(
+                        egui::Label::new(tr!(
+                            "Watching {folder}",
+                            folder = watcher.directory().to_string_lossy()
+                        ))
+                        .truncate(true),
+                    );
+                });
+                if stop {
+                    self.watch_folder = None;
+                    self.watch_folder_log.push(tr!("Stopped watching").to_string());
+                }
+            } else if ui
+                .button(tr!("Watch folder..."))
+                .on_hover_text(tr!("Automatically render new videos as they appear in a folder, using the settings at the time they appear. You'll be asked for the folder to watch, then the folder to save the results to."))
+                .clicked()
+            {
+                let ctx = ui.ctx().clone();
+                let input_dialog = rfd::AsyncFileDialog::new()
+                    .set_title(tr!("Folder to watch for new videos"))
+                    .pick_folder();
+                self.spawn(async move {
+                    let input_directory: PathBuf = input_dialog.await?.into();
+                    Some(Box::new(move |app: &mut NtscApp| {
+                        let output_dialog = rfd::AsyncFileDialog::new()
+                            .set_title(tr!("Folder to save rendered videos to"))
+                            .pick_folder();
+                        app.spawn(async move {
+                            let output_directory: PathBuf = output_dialog.await?.into();
+                            Some(Box::new(move |app: &mut NtscApp| {
+                                app.start_watch_folder(&ctx, input_directory, output_directory)
+                            }) as _)
+                        });
+                        Ok(())
+                    }) as _)
+                });
+            }
+
+            if !self.watch_folder_log.is_empty() {
+                egui::CollapsingHeader::new(tr!("Watch folder log")).show(ui, |ui| {
+                    egui::ScrollArea::vertical()
+                        .max_height(100.0)
+                        .stick_to_bottom(true)
+                        .show(ui, |ui| {
+                            for entry in &self.watch_folder_log {
+                                ui.label(entry);
+                            }
                         });
+                });
+            }
+
+            if let Some(batch) = &self.batch_render {
+                // Jobs that were removed from the list count as finished
+                let (num_batch_jobs, batch_progress) = self
+                    .render_jobs
+                    .iter()
+                    .filter(|job| job.in_batch)
+                    .fold((0, 0.0), |(count, progress), job| {
+                        (count + 1, progress + job.last_progress)
+                    });
+                let progress = (batch_progress + (batch.started - num_batch_jobs) as f64)
+                    / batch.total as f64;
+
+                let mut dismiss = false;
+                ui.horizontal(|ui| {
+                    dismiss = ui
+                        .button("🗙")
+                        .on_hover_text(tr!("Stop starting new jobs from this batch"))
+                        .clicked();
+                    ui.label(tr!(
+                        "Batch: {started} of {total} videos started",
+                        started = batch.started,
+                        total = batch.total
+                    ));
+                });
+                ui.add(egui::ProgressBar::new(progress as f32).show_percentage());
+                if dismiss {
+                    self.batch_render = None;
+                }
+            }
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                if self.confirm_clear_render_jobs {
+                    ui.label(tr!("Stop and remove every job?"));
+                    if ui.button(tr!("Clear all")).clicked() {
+                        for job in self.render_jobs.drain(..) {
+                            job.cancel();
+                        }
+                        self.batch_render = None;
+                        self.confirm_clear_render_jobs = false;
+                    }
+                    if ui.button(tr!("Cancel")).clicked() {
+                        self.confirm_clear_render_jobs = false;
+                    }
+                } else {
+                    let has_finished_jobs = self.render_jobs.iter().any(|job| {
+                        matches!(
+                            *job.state.lock().unwrap(),
+                            RenderJobState::Complete { .. } | RenderJobState::Error(_)
+                        )
+                    });
+                    if ui
+                        .add_enabled(has_finished_jobs, egui::Button::new(tr!("Clear completed")))
+                        .on_hover_text(tr!("Remove jobs that have finished or failed"))
+                        .clicked()
+                    {
+                        let (finished, running): (Vec<_>, Vec<_>) =
+                            std::mem::take(&mut self.render_jobs)
+                                .into_iter()
+                                .partition(|job| {
+                                    matches!(
+                                        *job.state.lock().unwrap(),
+                                        RenderJobState::Complete { .. } | RenderJobState::Error(_)
+                                    )
+                                });
+                        self.render_jobs = running;
+                        for job in finished {
+                            job.cancel();
+                        }
+                    }
+                    if ui
+                        .add_enabled(
+                            !self.render_jobs.is_empty() || self.batch_render.is_some(),
+                            egui::Button::new(tr!("Clear all")),
+                        )
+                        .on_hover_text(tr!("Stop any running jobs and remove every job, including ones waiting in a batch"))
+                        .clicked()
+                    {
+                        self.confirm_clear_render_jobs = true;
+                    }
+                }
+            });
+
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    let mut removed_job_idx = None;
+                    for (idx, job) in self.render_jobs.iter_mut().enumerate() {
+                        if Self::show_render_job(ui, job, self.progress_json) {
+                            removed_job_idx = Some(idx);
+                        }
+                    }
+
+                    if let Some(remove_idx) = removed_job_idx {
+                        self.render_jobs.remove(remove_idx).cancel();
+                    }
+
+                    // Batch jobs that haven't started yet come after the running ones, in the order they'll start
+                    if let Some(batch) = &mut self.batch_render {
+                        let num_pending = batch.pending.len();
+                        let mut queued_action = None;
+                        for (idx, (_, settings)) in batch.pending.iter().enumerate() {
+                            if let Some(action) = Self::show_queued_render_job(
+                                ui,
+                                settings,
+                                idx > 0,
+                                idx + 1 < num_pending,
+                            ) {
+                                queued_action = Some((idx, action));
+                            }
+                        }
+
+                        match queued_action {
+                            Some((idx, QueuedJobAction::MoveUp)) => batch.pending.swap(idx, idx - 1),
+                            Some((idx, QueuedJobAction::MoveDown)) => {
+                                batch.pending.swap(idx, idx + 1)
+                            }
+                            Some((idx, QueuedJobAction::Remove)) => {
+                                batch.pending.remove(idx);
+                                batch.total -= 1;
+                            }
+                            None => {}
+                        }
+                    }
+                });
+        });
+    }
+
+    fn show_video_pane(&mut self, ui: &mut egui::Ui) {
+        let ctx = ui.ctx().clone();
+        let last_seek_pos = if let Some(info) = &mut self.pipeline {
+            // While seeking, GStreamer sometimes doesn't return a timecode. In that case, use the last timecode it
+            // did respond with.
+            let queried_pos = info.pipeline.query_position::<ClockTime>();
+            if let Some(position) = queried_pos {
+                info.last_seek_pos = position;
+            }
+            info.last_seek_pos
+        } else {
+            ClockTime::ZERO
+        };
+
+        let framerate = self.pipeline.as_ref().and_then(|info| info.framerate());
+
+        egui::TopBottomPanel::top("video_info").show_inside(ui, |ui| {
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let mut remove_pipeline = false;
+                let mut change_framerate_res = None;
+                let mut save_image_to: Option<(PathBuf, PathBuf, StillImageSettings)> = None;
+                let save_frame_requested = std::mem::take(&mut self.save_frame_requested);
+                let mut copy_image_res: Option<Result<ColorImage, GstreamerError>> = None;
+                let mut grab_full_frame_from = None;
+                let mut saved_frame = None;
+                let mut open_variation_grid = false;
+                if let Some(info) = &mut self.pipeline {
+                    let mut metadata = info.metadata.lock().unwrap();
+                    if ui.button("🗙").clicked() {
+                        remove_pipeline = true;
+                    }
+
+                    ui.separator();
+
+                    if ui.button(tr!("Save frame")).clicked() || save_frame_requested {
+                        let src_path = info.path.clone();
+
+                        let dst_path = src_path.with_extension("");
+                        save_image_to = Some((
+                            src_path,
+                            dst_path,
+                            self.render_settings.still_image.clone(),
+                        ));
+
+                        let egui_sink =
+                            info.egui_sink.downcast_ref::<elements::EguiSink>().unwrap();
+                        saved_frame = EguiSink::from_obj(egui_sink).get_image().ok();
+                    }
+
+                    let still_image = &mut self.render_settings.still_image;
+                    egui::ComboBox::from_id_source("still_image_format")
+                        .width(60.0)
+                        .selected_text(tr!(still_image.format.label()))
+                        .show_ui(ui, |ui| {
+                            for format in StillImageFormat::ALL {
+                                let response = ui
+                                    .add_enabled(
+                                        format.is_available(),
+                                        egui::SelectableLabel::new(
+                                            still_image.format == *format,
+                                            tr!(format.label()),
+                                        ),
+                                    )
+                                    .on_disabled_hover_text(tr!(
+                                        "Missing GStreamer element: {elements}",
+                                        elements = format_missing_elements(&[format.encoder()])
+                                    ));
+                                if response.clicked() {
+                                    still_image.format = *format;
+                                }
+                            }
+                        })
+                        .response
+                        .on_hover_text(tr!("The format that \"Save frame\" writes"));
+                    if still_image.format.has_lossless() {
+                        ui.checkbox(&mut still_image.lossless, tr!("Lossless"));
+                    }
+                    if still_image.format.has_quality()
+                        && !(still_image.format.has_lossless() && still_image.lossless)
+                    {
+                        ui.add(
+                            egui::DragValue::new(&mut still_image.quality)
+                                .clamp_range(1..=100)
+                                .prefix(tr!("Quality ")),
+                        );
                     }
 
-                    let btn = ui.button("📄 Paste");
-
-                    let paste_popup_id = ui.make_persistent_id("paste_popup_open");
+                    if ui.button(tr!("Copy frame")).clicked() {
+                        if self.full_resolution_frames {
+                            grab_full_frame_from = Some(info.path.clone());
+                        } else {
+                            let egui_sink =
+                                info.egui_sink.downcast_ref::<elements::EguiSink>().unwrap();
 
-                    if btn.clicked() {
-                        ui.ctx().data_mut(|map| {
-                            let old_value =
-                                map.get_temp_mut_or_insert_with(paste_popup_id, || false);
-                            *old_value = !*old_value;
-                        });
+                            let egui_sink = EguiSink::from_obj(egui_sink);
+                            copy_image_res = Some(egui_sink.get_image().map_err(|e| e.into()));
+                        }
                     }
 
+                    ui.checkbox(&mut self.full_resolution_frames, tr!("Full resolution"))
+                        .on_hover_text(
+                            tr!("Copy and save frames at the source's resolution instead of the preview's, which may be \
+                             scaled down"),
+                        );
+
                     if ui
-                        .ctx()
-                        .data(|map| map.get_temp(paste_popup_id).unwrap_or(false))
+                        .button(tr!("Variation grid..."))
+                        .on_hover_text(tr!("Save an image of the current frame rendered with a range of values for one setting, to compare them side by side"))
+                        .clicked()
                     {
-                        let mut is_open = true;
-                        egui::Window::new("Paste JSON")
-                            .default_pos(btn.rect.center_top())
-                            .open(&mut is_open)
-                            .show(ui.ctx(), |ui| {
-                                ui.with_layout(egui::Layout::bottom_up(egui::Align::Min), |ui| {
-                                    if ui.button("Load").clicked() {
-                                        match self
-                                            .settings_list
-                                            .from_json(&self.settings_json_paste)
-                                        {
-                                            Ok(settings) => {
-                                                self.effect_settings = settings;
-                                                self.update_effect();
-                                                // Close the popup if the JSON was successfully loaded
-                                                ui.ctx().data_mut(|map| {
-                                                    map.insert_temp(paste_popup_id, false)
-                                                });
-                                            }
-                                            Err(e) => {
-                                                self.handle_error(&e);
-                                            }
+                        open_variation_grid = true;
+                    }
+
+                    ui.menu_button("ℹ", |ui| {
+                        Self::show_media_info(ui, &metadata);
+                    })
+                    .response
+                    .on_hover_text(tr!("Media info"));
+
+                    if let Some(current_framerate) = metadata.framerate {
+                        let framerate_adjustable = metadata
+                            .is_still_image
+                            .map(|is_still_image| is_still_image || metadata.is_image_sequence);
+                        match framerate_adjustable {
+                            Some(true) => {
+                                ui.separator();
+                                let mut new_framerate = current_framerate.numer() as f64
+                                    / current_framerate.denom() as f64;
+                                ui.label(tr!("fps"));
+                                if ui
+                                    .add(
+                                        egui::DragValue::new(&mut new_framerate)
+                                            .clamp_range(0.0..=240.0),
+                                    )
+                                    .changed()
+                                {
+                                    let framerate_fraction =
+                                        gstreamer::Fraction::approximate_f64(new_framerate);
+                                    if let Some(f) = framerate_fraction {
+                                        let changed_framerate =
+                                            Self::set_still_image_framerate(&info.pipeline, f);
+                                        if let Ok(Some(new_framerate)) = changed_framerate {
+                                            metadata.framerate = Some(new_framerate);
                                         }
-                                    }
-                                    ui.with_layout(
-                                        egui::Layout::top_down(egui::Align::Min),
-                                        |ui| {
-                                            egui::ScrollArea::new([false, true])
-                                                .auto_shrink([true, false])
-                                                .show(ui, |ui| {
-                                                    ui.add_sized(
-                                                        ui.available_size(),
-                                                        egui::TextEdit::multiline(
-                                                            &mut self.settings_json_paste,
-                                                        ),
-                                                    );
-                                                });
-                                        },
-                                    );
-                                });
-                            });
 
-                        if !is_open {
-                            ui.ctx()
-                                .data_mut(|map| map.insert_temp(paste_popup_id, false));
+                                        change_framerate_res = Some(changed_framerate);
+                                    }
+                                }
+                            }
+                            // Shown in the status bar instead
+                            Some(false) | None => {}
                         }
                     }
 
-                    if ui.button("Reset").clicked() {
-                        self.effect_settings = NtscEffectFullSettings::default();
-                        self.update_effect();
-                    }
-                });
-            });
-        egui::CentralPanel::default().show_inside(ui, |ui| {
-            ui.visuals_mut().clip_rect_margin = 4.0;
-            egui::ScrollArea::vertical()
-                .auto_shrink([false, true])
-                .show(ui, |ui| {
-                    Self::setup_control_rows(ui);
+                    ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
+                        ui.add(egui::Label::new(info.path.to_string_lossy()).truncate(true));
+                    });
+                }
 
-                    let Self {
-                        settings_list,
-                        effect_settings,
-                        pipeline,
-                        ..
-                    } = self;
-                    let interlace_mode = pipeline
-                        .as_ref()
-                        .and_then(|pipeline| pipeline.metadata.lock().unwrap().interlace_mode)
-                        .unwrap_or(VideoInterlaceMode::Progressive);
-                    let settings_changed = Self::settings_from_descriptors(
-                        effect_settings,
-                        ui,
-                        &settings_list.settings,
-                        interlace_mode,
-                    );
-                    if settings_changed {
-                        self.update_effect();
-                    }
-                });
-        });
-    }
+                if let Some(res) = change_framerate_res {
+                    self.handle_result(res);
+                }
 
-    fn setup_control_rows(ui: &mut egui::Ui) {
-        const LABEL_WIDTH: f32 = 180.0;
+                if open_variation_grid && self.variation_grid.is_none() {
+                    let mut descriptors = Vec::new();
+                    numeric_descriptors(&self.settings_list.settings, &mut descriptors);
+                    self.variation_grid = descriptors
+                        .first()
+                        .map(|descriptor| VariationGridOptions::for_descriptor(descriptor, 8));
+                }
 
-        let remaining_width = ui.max_rect().width() - LABEL_WIDTH;
+                if let Some(res) = copy_image_res {
+                    self.copy_frame(&ctx, res, last_seek_pos);
+                }
 
-        let spacing = ui.spacing_mut();
-        spacing.slider_width = remaining_width - 48.0;
-        spacing.interact_size.x = 48.0;
-        spacing.combo_width =
-            spacing.slider_width + spacing.interact_size.x + spacing.item_spacing.x;
-    }
+                if let Some(path) = grab_full_frame_from {
+                    self.grab_full_resolution_frame(&ctx, path, last_seek_pos);
+                }
 
-    fn show_render_job(ui: &mut egui::Ui, job: &mut RenderJob) -> bool {
-        let mut remove_job = false;
-        ui.with_layout(egui::Layout::top_down_justified(egui::Align::Min), |ui| {
-            let fill = ui.style().visuals.faint_bg_color;
-            egui::Frame::none()
-                .fill(fill)
-                .stroke(ui.style().visuals.window_stroke)
-                .rounding(ui.style().noninteractive().rounding)
-                .inner_margin(ui.style().spacing.window_margin)
-                .show(ui, |ui| {
-                    let job_state = &*job.state.lock().unwrap();
+                if let Some(image) = saved_frame {
+                    self.add_saved_frame(&ctx, image, last_seek_pos);
+                }
 
-                    let (progress, job_position, job_duration) = match job_state {
-                        RenderJobState::Waiting => (0.0, None, None),
-                        RenderJobState::Paused
-                        | RenderJobState::Rendering
-                        | RenderJobState::Error(_) => {
-                            let job_position = job.pipeline.query_position::<ClockTime>();
-                            let job_duration = job.pipeline.query_duration::<ClockTime>();
+                if remove_pipeline {
+                    self.handle_result_with(|app| app.remove_pipeline());
+                }
 
-                            (
-                                if let (Some(job_position), Some(job_duration)) =
-                                    (job_position, job_duration)
-                                {
-                                    job_position.nseconds() as f64 / job_duration.nseconds() as f64
-                                } else {
-                                    job.last_progress
-                                },
-                                job_position,
-                                job_duration,
-                            )
-                        }
-                        RenderJobState::Complete { .. } => (1.0, None, None),
-                    };
+                if let Some((src_path, dst_path, still_image)) = save_image_to {
+                    let ctx = ctx.clone();
+                    let full_resolution = self.full_resolution_frames;
+                    self.spawn(async move {
+                        let handle = rfd::AsyncFileDialog::new()
+                            .set_directory(dst_path.parent().unwrap_or(Path::new("/")))
+                            .set_file_name(format!(
+                                "{}_ntsc.{}",
+                                dst_path.file_name().to_owned().unwrap().to_string_lossy(),
+                                still_image.format.extension()
+                            ))
+                            .save_file()
+                            .await;
 
-                    if matches!(
-                        job_state,
-                        RenderJobState::Rendering | RenderJobState::Waiting
-                    ) {
-                        let current_time = ui.ctx().input(|input| input.time);
-                        let most_recent_sample = job.progress_samples.back().copied();
-                        let should_update_estimate =
-                            if let Some((_, sample_time)) = most_recent_sample {
-                                current_time - sample_time > PROGRESS_SAMPLE_TIME_DELTA
-                            } else {
-                                true
-                            };
-                        if should_update_estimate {
-                            if job.start_time.is_none() {
-                                job.start_time = Some(current_time);
-                            }
-                            let new_sample = (progress, current_time);
-                            let oldest_sample =
-                                if job.progress_samples.len() >= NUM_PROGRESS_SAMPLES {
-                                    job.progress_samples.pop_front()
+                        handle.map(|handle| {
+                            Box::new(move |app: &mut NtscApp| {
+                                let effect_settings = app.applied_effect_settings();
+                                let res = app.create_render_job(
+                                    &ctx,
+                                    &src_path.clone(),
+                                    RenderPipelineSettings {
+                                        codec_settings: RenderPipelineCodec::Still(still_image),
+                                        output_path: handle.into(),
+                                        duration: ClockTime::from_seconds(1),
+                                        interlacing: RenderInterlaceMode::Progressive,
+                                        audio_codec: None,
+                                        audio_track: 0,
+                                        timecode_overlay: None,
+                                        text_overlay: None,
+                                        output_framerate: None,
+                                        preserve_alpha: app.render_settings.preserve_alpha,
+                                        keep_partial_output: false,
+                                        dither: app.render_settings.dither,
+                                        low_memory: app.render_settings.low_memory,
+                                        tonemap: app.render_settings.tonemap,
+                                        full_resolution,
+                                        effect_scanlines: app
+                                            .effect_resolution
+                                            .enabled
+                                            .then_some(app.effect_resolution.scanlines),
+                                        region: app.effect_region.region(),
+                                        color_matrix: app.render_settings.color_matrix,
+                                        color_range: app.render_settings.color_range,
+                                        pass: 0,
+                                        job_id: next_render_job_id(),
+                                        seed: effect_settings.random_seed,
+                                        effect_settings,
+                                        keyframes: app.keyframes.clone(),
+                                    },
+                                );
+                                if let Ok(job) = res {
+                                    app.render_jobs.push(job);
                                 } else {
-                                    job.progress_samples.front().copied()
-                                };
-                            job.progress_samples.push_back(new_sample);
-                            if let Some((old_progress, old_sample_time)) = oldest_sample {
-                                let time_estimate = (current_time - old_sample_time)
-                                    / (progress - old_progress)
-                                    + job.start_time.unwrap();
-                                if time_estimate.is_finite() {
-                                    job.estimated_completion_time = Some(time_estimate);
+                                    app.handle_result(res);
                                 }
+                                Ok(())
+                            }) as _
+                        })
+                    });
+                }
+            });
+        });
+
+        // Added before the video controls so that it ends up underneath them
+        egui::TopBottomPanel::bottom("status_bar").show_inside(ui, |ui| {
+            self.show_status_bar(ui);
+        });
+
+        egui::TopBottomPanel::bottom("video_controls")
+            .exact_height(ui.spacing().interact_size.y * 2.0)
+            .show_inside(ui, |ui| {
+                ui.set_enabled(self.pipeline.is_some());
+                ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
+                    ui.spacing_mut().item_spacing.x = 6.0;
+                    let btn_widget = egui::Button::new(match &self.pipeline {
+                        Some(PipelineInfo { pipeline, .. }) => {
+                            let state = pipeline.current_state();
+                            match state {
+                                gstreamer::State::Paused | gstreamer::State::Ready => "▶",
+                                gstreamer::State::Playing => "⏸",
+                                _ => "▶",
+                            }
+                        }
+                        None => "▶",
+                    });
+                    let btn = ui.add_sized(
+                        vec2(
+                            ui.spacing().interact_size.y * 1.5,
+                            ui.spacing().interact_size.y * 1.5,
+                        ),
+                        btn_widget,
+                    );
+
+                    if btn.clicked() {
+                        let res = self.pipeline.as_mut().map(|p| p.toggle_playing());
+                        if let Some(res) = res {
+                            self.handle_result(res);
+                        }
+                    }
+
+                    let reverse = self.pipeline.as_ref().map_or(false, |info| info.reverse);
+                    if ui
+                        .selectable_label(reverse, "⏪")
+                        .on_hover_text(tr!("Play backwards. Audio is muted while playing backwards. Many video formats can't be played backwards, or only play back very slowly."))
+                        .clicked()
+                    {
+                        self.set_reverse_playback(!reverse);
+                    }
+
+                    let duration = if let Some(info) = &self.pipeline {
+                        info.pipeline.query_duration::<ClockTime>()
+                    } else {
+                        None
+                    };
+
+                    let mut timecode_ms =
+                        last_seek_pos.nseconds() as f64 / ClockTime::MSECOND.nseconds() as f64;
+                    let frame_pace = if let Some(framerate) = framerate {
+                        framerate.denom() as f64 / framerate.numer() as f64
+                    } else {
+                        1f64 / 30f64
+                    };
+
+                    // Still images have a framerate of 0, which SMPTE timecodes can't represent
+                    let smpte_framerate = framerate
+                        .filter(|framerate| self.smpte_timecode && framerate.numer() > 0);
+                    let mut drag_value = egui::DragValue::new(&mut timecode_ms)
+                        .custom_formatter(|value, _| {
+                            let value = (value * ClockTime::MSECOND.nseconds() as f64) as u64;
+                            match smpte_framerate {
+                                Some(framerate) => smpte_timecode_format(value, framerate),
+                                None => clock_time_format(value),
                             }
+                        })
+                        .custom_parser(|input| match smpte_framerate {
+                            Some(framerate) => smpte_timecode_parser(input, framerate)
+                                .map(|value| value as f64 / ClockTime::MSECOND.nseconds() as f64),
+                            None => clock_time_parser(input),
+                        })
+                        .speed(frame_pace * 1000.0 * 0.5);
+
+                    if let Some(duration) = duration {
+                        drag_value = drag_value.clamp_range(0..=duration.mseconds());
+                    }
+
+                    let seekable = self
+                        .pipeline
+                        .as_ref()
+                        .map_or(true, |info| info.is_seekable());
+                    if ui
+                        .add_enabled(seekable, drag_value)
+                        .on_disabled_hover_text(tr!("This stream can't be seeked in."))
+                        .changed()
+                    {
+                        if let Some(info) = &self.pipeline {
+                            let _ = info.seek(ClockTime::from_nseconds(
+                                (timecode_ms * ClockTime::MSECOND.nseconds() as f64) as u64,
+                            ));
                         }
                     }
 
-                    ui.horizontal(|ui| {
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            remove_job = ui.button("🗙").clicked();
-                            ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
-                                ui.add(
-                                    egui::Label::new(job.settings.output_path.to_string_lossy())
-                                        .truncate(true),
-                                );
-                            })
-                        });
-                    });
+                    if ui
+                        .selectable_label(self.smpte_timecode, "SMPTE")
+                        .on_hover_text(tr!("Show the current position as an HH:MM:SS:FF timecode, counted in frames, instead of in hours, minutes, and seconds."))
+                        .clicked()
+                    {
+                        self.smpte_timecode = !self.smpte_timecode;
+                    }
 
                     ui.separator();
 
-                    ui.add(egui::ProgressBar::new(progress as f32).show_percentage());
-                    if let RenderJobState::Rendering = job_state {
-                        ui.ctx().request_repaint();
+                    ui.label("🔎");
+                    ui.add_enabled(
+                        !self.video_zoom.fit,
+                        egui::DragValue::new(&mut self.video_zoom.scale)
+                            .clamp_range(0.0..=8.0)
+                            .speed(0.01)
+                            .custom_formatter(format_percentage)
+                            // Treat as a percentage above 8x zoom
+                            .custom_parser(|input| parse_decimal_or_percentage(input, 8.0)),
+                    );
+                    if ui
+                        .button("1:1")
+                        .on_hover_text(tr!("Zoom to 100%, so each pixel of the video is one pixel on screen"))
+                        .clicked()
+                    {
+                        self.video_zoom.scale = 1.0;
+                        self.video_zoom.fit = false;
                     }
+                    ui.checkbox(&mut self.video_zoom.fit, tr!("Fit"));
+                    ui.add_enabled_ui(self.video_zoom.fit, |ui| {
+                        egui::ComboBox::from_id_source("zoom_fit_mode")
+                            .selected_text(tr!(self.video_zoom.fit_mode.label()))
+                            .show_ui(ui, |ui| {
+                                for fit_mode in ZoomFit::ALL {
+                                    ui.selectable_value(
+                                        &mut self.video_zoom.fit_mode,
+                                        *fit_mode,
+                                        tr!(fit_mode.label()),
+                                    );
+                                }
+                            });
+                    });
 
-                    ui.label(match job_state {
-                        RenderJobState::Waiting => Cow::Borrowed("Waiting..."),
-                        RenderJobState::Rendering => {
-                            if let (Some(position), Some(duration)) = (job_position, job_duration) {
-                                Cow::Owned(format!(
-                                    "Rendering... ({:.2} / {:.2})",
-                                    position, duration
-                                ))
-                            } else {
-                                Cow::Borrowed("Rendering...")
+                    ui.separator();
+
+                    let scale_checkbox = ui.checkbox(&mut self.video_scale.enabled, tr!("Scale to"));
+                    ui.add_enabled_ui(self.video_scale.enabled, |ui| {
+                        let drag_resp = ui.add(
+                            egui::DragValue::new(&mut self.video_scale.scale)
+                                .clamp_range(1..=usize::MAX),
+                        );
+                        if drag_resp.changed() || scale_checkbox.changed() {
+                            if let Some(pipeline) = &self.pipeline {
+                                let res = Self::rescale_video(
+                                    &pipeline.pipeline,
+                                    pipeline.last_seek_pos,
+                                    self.preview_scanlines(),
+                                    self.video_scale.square_pixels,
+                                );
+                                self.handle_result(res);
                             }
+                            // Lay the preview out at its new size right away
+                            ui.ctx().request_repaint();
                         }
-                        RenderJobState::Paused => Cow::Borrowed("Paused"),
-                        // if the job's start_time is missing, it's probably because it never got a chance to update--in that case, just say it took 0 seconds
-                        RenderJobState::Complete { end_time } => Cow::Owned(format!(
-                            "Completed in {:.2}",
-                            ClockTime::from_mseconds(
-                                ((*end_time - job.start_time.unwrap_or(*end_time)) * 1000.0) as u64
-                            )
-                        )),
-                        RenderJobState::Error(err) => Cow::Owned(format!("Error: {err}")),
+                        ui.label(tr!("scanlines"));
                     });
 
-                    if matches!(
-                        job_state,
-                        RenderJobState::Rendering | RenderJobState::Paused
-                    ) {
-                        if let Some(estimated_completion_time) = job.estimated_completion_time {
-                            let current_time = ui.ctx().input(|input| input.time);
-                            let time_remaining =
-                                (estimated_completion_time - current_time).max(0.0).ceil();
-                            ui.label(format!("Time remaining: {time_remaining:.0} seconds"));
+                    if ui
+                        .checkbox(&mut self.video_scale.square_pixels, tr!("Square pixels"))
+                        .on_hover_text(tr!("Correct anamorphic video, like DVDs, to square pixels. If unchecked, the source's pixel aspect ratio is carried through to the output instead."))
+                        .changed()
+                    {
+                        if let Some(pipeline) = &self.pipeline {
+                            let res = Self::rescale_video(
+                                &pipeline.pipeline,
+                                pipeline.last_seek_pos,
+                                self.preview_scanlines(),
+                                self.video_scale.square_pixels,
+                            );
+                            self.handle_result(res);
                         }
+                        ui.ctx().request_repaint();
                     }
 
-                    job.last_progress = progress;
-                });
-        });
-
-        remove_job
-    }
-
-    fn show_render_settings(&mut self, ui: &mut egui::Ui) {
-        egui::Frame::central_panel(ui.style()).show(ui, |ui| {
-            Self::setup_control_rows(ui);
-            let mut codec_changed = false;
-            egui::ComboBox::from_label("Codec")
-                .selected_text(self.render_settings.output_codec.label())
-                .show_ui(ui, |ui| {
-                    codec_changed |= ui.selectable_value(
-                        &mut self.render_settings.output_codec,
-                        OutputCodec::H264,
-                        OutputCodec::H264.label(),
-                    ).changed();
-                    codec_changed |= ui.selectable_value(
-                        &mut self.render_settings.output_codec,
-                        OutputCodec::Ffv1,
-                        OutputCodec::Ffv1.label(),
-                    ).changed();
-                });
+                    ui.separator();
 
-            if codec_changed {
-                self.render_settings.output_path.set_extension(self.render_settings.output_codec.extension());
-            }
+                    let effect_checkbox = ui
+                        .checkbox(&mut self.effect_resolution.enabled, tr!("Apply effect at"))
+                        .on_hover_text(tr!("Apply the effect at a lower resolution and scale it back up afterwards, for a chunkier look. Unlike the bandwidth scale, this changes how many pixels the effect works with."));
+                    ui.add_enabled_ui(self.effect_resolution.enabled, |ui| {
+                        let drag_resp = ui.add(
+                            egui::DragValue::new(&mut self.effect_resolution.scanlines)
+                                .clamp_range(1..=usize::MAX),
+                        );
+                        if drag_resp.changed() || effect_checkbox.changed() {
+                            if let Some(pipeline) = &self.pipeline {
+                                let res = Self::set_effect_resolution(
+                                    &pipeline.pipeline,
+                                    pipeline.last_seek_pos,
+                                    self.effect_resolution
+                                        .enabled
+                                        .then_some(self.effect_resolution.scanlines),
+                                );
+                                self.handle_result(res);
+                            }
+                        }
+                        ui.label(tr!("scanlines"));
+                    });
 
-            match self.render_settings.output_codec {
-                OutputCodec::H264 => {
-                    ui.add(
-                        egui::Slider::new(&mut self.render_settings.h264_settings.crf, 0..=50)
-                            .text("Quality"),
-                    ).on_hover_text("Video quality factor, where 0 is the worst quality and 50 is the best. Higher quality videos take up more space.");
-                    ui.add(
-                        egui::Slider::new(
-                            &mut self.render_settings.h264_settings.encode_speed,
-                            0..=8,
-                        )
-                        .text("Encoding speed"),
-                    ).on_hover_text("Encoding speed preset. Higher encoding speeds provide a worse compression ratio, resulting in larger videos at a given quality.");
-                    // Disabled for now until I can find a way to query for 10-bit support
-                    /*ui.checkbox(
-                        &mut self.render_settings.h264_settings.ten_bit,
-                        "10-bit color",
-                    );*/
-                    ui.checkbox(
-                        &mut self.render_settings.h264_settings.chroma_subsampling,
-                        "4:2:0 chroma subsampling",
-                    ).on_hover_text("Subsample the chrominance planes to half the resolution of the luminance plane. Increases playback compatibility.");
-                }
+                    ui.separator();
 
-                OutputCodec::Ffv1 => {
-                    egui::ComboBox::from_label("Bit depth")
-                        .selected_text(self.render_settings.ffv1_settings.bit_depth.label())
-                        .show_ui(ui, |ui| {
-                            ui.selectable_value(
-                                &mut self.render_settings.ffv1_settings.bit_depth,
-                                Ffv1BitDepth::Bits8,
-                                Ffv1BitDepth::Bits8.label(),
-                            );
-                            ui.selectable_value(
-                                &mut self.render_settings.ffv1_settings.bit_depth,
-                                Ffv1BitDepth::Bits10,
-                                Ffv1BitDepth::Bits10.label(),
-                            );
-                            ui.selectable_value(
-                                &mut self.render_settings.ffv1_settings.bit_depth,
-                                Ffv1BitDepth::Bits12,
-                                Ffv1BitDepth::Bits12.label(),
-                            );
-                        });
+                    let mut region_changed = ui
+                        .checkbox(&mut self.effect_region.enabled, tr!("Region"))
+                        .on_hover_text(tr!("Only apply the effect within a rectangle of the video. Drag its edges on the preview to move it."))
+                        .changed();
+                    ui.add_enabled_ui(self.effect_region.enabled, |ui| {
+                        region_changed |= ui
+                            .add(
+                                egui::DragValue::new(&mut self.effect_region.feather)
+                                    .clamp_range(0.0..=1.0)
+                                    .speed(0.001)
+                                    .prefix(tr!("Feather: ")),
+                            )
+                            .changed();
+                        region_changed |= ui
+                            .checkbox(&mut self.effect_region.invert, tr!("Invert"))
+                            .on_hover_text(tr!("Apply the effect everywhere except inside the rectangle."))
+                            .changed();
+                    });
+                    if region_changed {
+                        self.update_effect_region();
+                    }
 
-                    ui.checkbox(
-                        &mut self.render_settings.ffv1_settings.chroma_subsampling,
-                        "4:2:0 chroma subsampling",
-                    ).on_hover_text("Subsample the chrominance planes to half the resolution of the luminance plane. Results in smaller files.");
-                }
-            }
+                    ui.checkbox(&mut self.fast_scrubbing, tr!("Fast scrubbing"))
+                        .on_hover_text(tr!("Lower the preview resolution while scrubbing through or playing the video, so the preview can keep up on slower computers."));
 
-            ui.separator();
+                    ui.separator();
 
-            ui.rtl(|ui| {
-                let save_file = ui.button("📁").on_hover_text("Browse for a path").clicked();
+                    let has_audio = self
+                        .pipeline
+                        .as_ref()
+                        .map(|info| info.metadata.lock().unwrap())
+                        .and_then(|metadata| metadata.has_audio)
+                        .unwrap_or(false);
 
-                ui.ltr(|ui| {
-                    ui.label("Destination file:");
-                    let mut path = self.render_settings.output_path.to_string_lossy();
-                    if ui.add_sized(ui.available_size(), egui::TextEdit::singleline(&mut path)).changed() {
-                        self.render_settings.output_path = PathBuf::from(OsStr::new(path.as_ref()));
-                    }
-                });
+                    ui.add_enabled_ui(has_audio, |ui| {
+                        let mut update_volume = false;
 
-                if save_file {
-                    let mut dialog_path = &self.render_settings.output_path;
-                    if dialog_path.components().next().is_none() {
-                        if let Some(PipelineInfo { path, .. }) = &self.pipeline {
-                            dialog_path = path;
+                        // Not actually being made into an error and some want to remove the lint entirely
+                        #[allow(illegal_floating_point_literal_pattern)]
+                        if ui
+                            .button(if self.audio_volume.mute {
+                                "🔇"
+                            } else {
+                                match self.audio_volume.gain {
+                                    0.0 => "🔇",
+                                    0.0..=0.33 => "🔈",
+                                    0.0..=0.67 => "🔉",
+                                    _ => "🔊",
+                                }
+                            })
+                            .on_hover_text(if self.audio_volume.mute {
+                                "Unmute"
+                            } else {
+                                "Mute"
+                            })
+                            .clicked()
+                        {
+                            self.audio_volume.mute = !self.audio_volume.mute;
+                            // "<= 0.0" to handle negative zero (not sure if it'll ever happen; better safe than sorry)
+                            if !self.audio_volume.mute && self.audio_volume.gain <= 0.0 {
+                                // Restore the previous gain after the user mutes by dragging the slider to 0 then unmutes
+                                self.audio_volume.gain = self.audio_volume.gain_pre_mute;
+                            }
+                            update_volume = true;
                         }
-                    }
-                    let mut file_dialog = rfd::AsyncFileDialog::new();
 
-                    if dialog_path.components().next().is_some() {
-                        if let Some(parent) = dialog_path.parent() {
-                            file_dialog = file_dialog.set_directory(parent);
-                        }
-                        if let Some(file_name) = dialog_path.file_stem() {
-                            file_dialog = file_dialog.set_file_name(format!(
-                                "{}_ntsc.{}",
-                                file_name.to_string_lossy(),
-                                self.render_settings.output_codec.extension()
-                            ));
-                        }
-                    }
+                        let resp = ui.add_enabled(
+                            !self.audio_volume.mute,
+                            egui::Slider::new(&mut self.audio_volume.gain, 0.0..=1.25)
+                                // Treat as a percentage above 125% volume
+                                .custom_parser(|input| parse_decimal_or_percentage(input, 1.25))
+                                .custom_formatter(format_percentage),
+                        );
 
-                    let file_dialog = file_dialog.save_file();
-                    self.spawn(async move {
-                        let handle = file_dialog.await;
-                        Some(Box::new(|app: &mut NtscApp| {
-                            if let Some(handle) = handle {
-                                let mut output_path: PathBuf = handle.into();
-                                if output_path.extension().is_none() {
-                                    output_path.set_extension(app.render_settings.output_codec.extension());
-                                }
-                                app.render_settings.output_path = output_path;
+                        if resp.drag_stopped() {
+                            if self.audio_volume.gain > 0.0 {
+                                // Set the gain to restore after dragging the slider to 0
+                                self.audio_volume.gain_pre_mute = self.audio_volume.gain;
+                            } else {
+                                // Wait for drag release to mute because it disables the slider
+                                self.audio_volume.mute = true;
                             }
+                        }
 
-                            Ok(())
-                        }) as _)
-                    });
-                }
-            });
+                        if resp.changed() {
+                            update_volume = true;
+                        }
 
-            let src_path = self.pipeline.as_ref().map(|info| &info.path);
+                        if update_volume {
+                            self.update_volume();
+                        }
+                    });
 
-            let mut duration = self.render_settings.duration.mseconds();
-            if self
-                .pipeline
-                .as_ref()
-                .map(|info| info.metadata.lock().unwrap())
-                .and_then(|metadata| metadata.is_still_image)
-                .unwrap_or(false)
-            {
-                ui.horizontal(|ui| {
-                    ui.label("Duration:");
-                    if ui
-                        .add(
-                            egui::DragValue::new(&mut duration)
-                                .custom_formatter(|value, _| {
-                                    clock_time_format(
-                                        (value * ClockTime::MSECOND.nseconds() as f64)
-                                            as u64,
-                                    )
-                                })
-                                .custom_parser(clock_time_parser)
-                                .speed(100.0),
+                    let audio_tracks = self.pipeline.as_ref().map(|info| {
+                        (
+                            info.audio_track,
+                            info.metadata.lock().unwrap().audio_tracks.clone(),
                         )
-                        .changed()
+                    });
+                    if let Some((selected, audio_tracks)) =
+                        audio_tracks.filter(|(_, audio_tracks)| audio_tracks.len() > 1)
                     {
-                        self.render_settings.duration =
-                            ClockTime::from_mseconds(duration);
+                        let mut new_track = selected;
+                        egui::ComboBox::from_id_source("audio_track")
+                            .selected_text(
+                                audio_tracks
+                                    .get(selected)
+                                    .map(|track| track.label(selected))
+                                    .unwrap_or_default(),
+                            )
+                            .show_ui(ui, |ui| {
+                                for (index, track) in audio_tracks.iter().enumerate() {
+                                    ui.selectable_value(&mut new_track, index, track.label(index));
+                                }
+                            })
+                            .response
+                            .on_hover_text(tr!("Audio track to play and render"));
+                        if new_track != selected {
+                            self.set_audio_track(new_track);
+                        }
                     }
-                });
-            }
 
-            ui
-                .add_enabled(
-                    self.interlaced_output_allowed(),
-                    egui::Checkbox::new(&mut self.render_settings.interlaced, "Interlaced output")
-                )
-                .on_disabled_hover_text("To enable interlaced output, set the \"Use field\" setting to \"Interleaved\".");
+                    ui.separator();
 
+                    let mut update_effect_preview = false;
+                    ui.label("✨").on_hover_text(tr!("Effect preview (E to cycle)"));
+                    update_effect_preview |= ui
+                        .selectable_value(
+                            &mut self.effect_preview.mode,
+                            EffectPreviewMode::Enabled,
+                            tr!("Enable"),
+                        )
+                        .changed();
+                    update_effect_preview |= ui
+                        .selectable_value(
+                            &mut self.effect_preview.mode,
+                            EffectPreviewMode::Disabled,
+                            tr!("Disable"),
+                        )
+                        .changed();
+                    update_effect_preview |= ui
+                        .selectable_value(
+                            &mut self.effect_preview.mode,
+                            EffectPreviewMode::SplitScreen,
+                            tr!("Split"),
+                        )
+                        .changed();
+                    if self.effect_preview.mode == EffectPreviewMode::SplitScreen {
+                        update_effect_preview |= ui
+                            .toggle_value(&mut self.effect_preview.invert_split, "⇄")
+                            .on_hover_text(tr!("Swap which side of the split shows the effect"))
+                            .changed();
+                    }
+                    update_effect_preview |= ui
+                        .selectable_value(
+                            &mut self.effect_preview.mode,
+                            EffectPreviewMode::Channel,
+                            tr!("Channel"),
+                        )
+                        .on_hover_text(tr!("Show only a single YIQ channel"))
+                        .changed();
 
-            if ui
-                .add_enabled(
-                    !self.render_settings.output_path.as_os_str().is_empty() && src_path.is_some(),
-                    egui::Button::new("Render"),
-                )
-                .clicked()
-            {
-                let render_job = self.create_render_job(
-                    ui.ctx(),
-                    &src_path.unwrap().clone(),
-                    RenderPipelineSettings {
-                        codec_settings: (&self.render_settings).into(),
-                        output_path: self.render_settings.output_path.clone(),
-                        duration: self.render_settings.duration,
-                        interlacing: match (
-                            self.interlaced_output_allowed() && self.render_settings.interlaced,
-                            self.effect_settings.use_field
-                        ) {
-                            (true, UseField::InterleavedUpper) => RenderInterlaceMode::TopFieldFirst,
-                            (true, UseField::InterleavedLower) => RenderInterlaceMode::BottomFieldFirst,
-                            _ => RenderInterlaceMode::Progressive,
-                        },
-                        effect_settings: (&self.effect_settings).into(),
-                    },
-                );
-                match render_job {
-                    Ok(render_job) => {
-                        self.render_jobs.push(render_job);
+                    if self.effect_preview.mode == EffectPreviewMode::Channel {
+                        egui::ComboBox::from_id_source("preview_channel")
+                            .selected_text(match self.effect_preview.channel {
+                                PreviewChannel::Luma => "Y",
+                                PreviewChannel::I => "I",
+                                PreviewChannel::Q => "Q",
+                                PreviewChannel::Chroma => "Chroma",
+                            })
+                            .width(60.0)
+                            .show_ui(ui, |ui| {
+                                for (channel, label) in [
+                                    (PreviewChannel::Luma, "Y"),
+                                    (PreviewChannel::I, "I"),
+                                    (PreviewChannel::Q, "Q"),
+                                    (PreviewChannel::Chroma, "Chroma"),
+                                ] {
+                                    update_effect_preview |= ui
+                                        .selectable_value(
+                                            &mut self.effect_preview.channel,
+                                            channel,
+                                            label,
+                                        )
+                                        .changed();
+                                }
+                            });
                     }
-                    Err(err) => {
-                        self.handle_error(&err);
+
+                    if update_effect_preview {
+                        self.update_effect_preview();
                     }
-                }
-            }
+                });
+            });
 
-            ui.separator();
+        let video_rect = egui::CentralPanel::default()
+            .frame(egui::Frame::side_top_panel(&ctx.style()).inner_margin(0.0))
+            .show_inside(ui, |ui| {
+                ui.visuals_mut().clip_rect_margin = 0.0;
+                ui.with_layout(egui::Layout::bottom_up(egui::Align::Min), |ui| {
+                    let was_scrubbing = self.scrubbing;
+                    if let Some(info) = &mut self.pipeline {
+                        let mut timecode = info.last_seek_pos.nseconds();
 
-            egui::ScrollArea::vertical()
-                .auto_shrink([false, false])
-                .show(ui, |ui| {
-                    let mut removed_job_idx = None;
-                    for (idx, job) in self.render_jobs.iter_mut().enumerate() {
-                        if Self::show_render_job(ui, job) {
-                            removed_job_idx = Some(idx);
+                        let duration = info.pipeline.query_duration::<ClockTime>();
+
+                        self.scrubbing = false;
+                        if let Some(duration) = duration {
+                            let thumbnails = self
+                                .thumbnails
+                                .as_ref()
+                                .map(|thumbnails| thumbnails.thumbnails());
+                            let seekable = info.is_seekable();
+                            let timeline_response = ui.add_enabled(
+                                seekable,
+                                Timeline::new(&mut timecode, 0..=duration.nseconds(), framerate)
+                                    .thumbnails(thumbnails.as_deref().map_or(&[], Vec::as_slice))
+                                    .markers(&mut info.markers),
+                            );
+                            self.scrubbing = timeline_response.dragged();
+                            if timeline_response.changed() {
+                                let _ = info.seek(ClockTime::from_nseconds(timecode));
+                            }
                         }
                     }
-
-                    if let Some(remove_idx) = removed_job_idx {
-                        self.render_jobs.remove(remove_idx);
+                    // Scrubbing plays short, choppy bursts of audio, so keep it quiet until the timeline is let go of
+                    if self.scrubbing != was_scrubbing {
+                        self.update_volume();
                     }
+                    egui::ScrollArea::both()
+                        .auto_shrink([false, false])
+                        .show(ui, |ui| {
+                            // Pan around the zoomed-in video by dragging it with the middle mouse button. This reads
+                            // the pointer directly instead of sensing drags, so it works over the split-screen divider
+                            // without taking its left-button drags away.
+                            let viewport = ui.clip_rect();
+                            let pan_delta = ui.input(|input| {
+                                let panning = input.pointer.middle_down()
+                                    && input
+                                        .pointer
+                                        .press_origin()
+                                        .is_some_and(|origin| viewport.contains(origin));
+                                panning.then(|| input.pointer.delta())
+                            });
+                            if let Some(delta) = pan_delta {
+                                ui.scroll_with_delta(delta);
+                                ui.ctx().set_cursor_icon(egui::CursorIcon::Grabbing);
+                            }
+
+                            ui.with_layout(
+                                egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
+                                |ui| {
+                                    let checkerboard_texture = (self.video_background
+                                        == VideoBackground::Checkerboard)
+                                        .then(|| self.checkerboard_texture(ui.ctx()));
+                                    let Some(PipelineInfo {
+                                        preview,
+                                        egui_sink,
+                                        metadata,
+                                        ..
+                                    }) = &mut self.pipeline
+                                    else {
+                                        ui.add(
+                                            egui::Label::new(
+                                                egui::RichText::new(tr!("No media loaded")).heading(),
+                                            )
+                                            .selectable(false),
+                                        );
+                                        return;
+                                    };
+
+                                    // Size the preview from the source's dimensions rather than the texture's, which
+                                    // keep their old values until the first frame at a new scale arrives. Otherwise,
+                                    // changing the scale briefly lays the preview out at the wrong size.
+                                    let (resolution, par) = {
+                                        let metadata = metadata.lock().unwrap();
+                                        (metadata.resolution, metadata.pixel_aspect_ratio)
+                                    };
+                                    let source_size = resolution
+                                        .map(|(width, height)| {
+                                            let par = par
+                                                .filter(|_| self.video_scale.square_pixels)
+                                                .map_or(1.0, |par| {
+                                                    par.numer() as f32 / par.denom() as f32
+                                                });
+                                            vec2((width as f32 * par).round(), height as f32)
+                                        })
+                                        .unwrap_or_else(|| preview.size_vec2());
+                                    let texture_size = if self.video_scale.enabled {
+                                        let scale_factor =
+                                            self.video_scale.scale as f32 / source_size.y;
+                                        vec2(
+                                            (source_size.x * scale_factor).round(),
+                                            self.video_scale.scale as f32,
+                                        )
+                                    } else {
+                                        source_size
+                                    };
+                                    let scale_factor = if self.video_zoom.fit {
+                                        // Due to floating-point error, a scrollbar may appear even if we scale down. To
+                                        // avoid the scrollbar popping in and out of existence, subtract a constant value
+                                        // from available_size.
+                                        let ratio =
+                                            (ui.available_size() - vec2(1.0, 1.0)) / texture_size;
+                                        match self.video_zoom.fit_mode {
+                                            ZoomFit::All => ratio.min_elem().min(1.0),
+                                            // These are for filling the pane along one axis and scrolling along the
+                                            // other, so they scale small videos up too
+                                            ZoomFit::Width => ratio.x,
+                                            ZoomFit::Height => ratio.y,
+                                        }
+                                    } else {
+                                        self.video_zoom.scale as f32
+                                    };
+
+                                    // We need to render the splitscreen bar in the same area as the image. The
+                                    // Response returned from ui.image() fills the entire scroll area, so we need
+                                    // to do the layout ourselves.
+                                    let image = egui::Image::from_texture((
+                                        preview.id(),
+                                        texture_size * scale_factor,
+                                    ));
+                                    let (rect, _) = ui.allocate_exact_size(
+                                        texture_size * scale_factor,
+                                        egui::Sense::hover(),
+                                    );
+                                    // Avoid texture sampling at non-integer coordinates (causes jaggies)
+                                    let rect = egui::Rect::from_points(&[
+                                        rect.min.floor(),
+                                        rect.max.floor(),
+                                    ]);
+
+                                    match self.video_background {
+                                        VideoBackground::None => {}
+                                        VideoBackground::Checkerboard => {
+                                            if let Some(texture) = checkerboard_texture {
+                                                // Each texel is one square of the checkerboard
+                                                const SQUARE_SIZE: f32 = 8.0;
+                                                ui.painter().image(
+                                                    texture.id(),
+                                                    rect,
+                                                    egui::Rect::from_min_size(
+                                                        pos2(0.0, 0.0),
+                                                        rect.size() / (SQUARE_SIZE * 2.0),
+                                                    ),
+                                                    egui::Color32::WHITE,
+                                                );
+                                            }
+                                        }
+                                        VideoBackground::Solid(color) => {
+                                            ui.painter().rect_filled(
+                                                rect,
+                                                egui::Rounding::ZERO,
+                                                color,
+                                            );
+                                        }
+                                    }
+
+                                    ui.put(rect, image);
+                                    self.guides.paint(&ui.painter().with_clip_rect(rect), rect);
+
+                                    if self.effect_preview.mode == EffectPreviewMode::SplitScreen
+                                        && ui
+                                            .put(
+                                                rect,
+                                                SplitScreen::new(
+                                                    &mut self.effect_preview.preview_rect,
+                                                ),
+                                            )
+                                            .changed()
+                                    {
+                                        egui_sink
+                                            .downcast_ref::<elements::EguiSink>()
+                                            .unwrap()
+                                            .set_preview_mode(Self::sink_preview_mode(
+                                                &self.effect_preview,
+                                            ));
+                                    }
+
+                                    // The split-screen divider has the same handles, so only show one set at a time
+                                    if self.effect_region.enabled
+                                        && self.effect_preview.mode
+                                            != EffectPreviewMode::SplitScreen
+                                        && ui
+                                            .put(
+                                                rect,
+                                                SplitScreen::new(&mut self.effect_region.rect),
+                                            )
+                                            .changed()
+                                    {
+                                        self.update_effect_region();
+                                    }
+                                },
+                            );
+                        });
                 });
-        });
+            })
+            .response
+            .rect;
+
+        if self.show_performance_overlay {
+            self.show_performance_overlay(ui, video_rect);
+        }
     }
 
-    fn show_video_pane(&mut self, ui: &mut egui::Ui) {
-        let ctx = ui.ctx().clone();
-        let last_seek_pos = if let Some(info) = &mut self.pipeline {
-            // While seeking, GStreamer sometimes doesn't return a timecode. In that case, use the last timecode it
-            // did respond with.
-            let queried_pos = info.pipeline.query_position::<ClockTime>();
-            if let Some(position) = queried_pos {
-                info.last_seek_pos = position;
-            }
-            info.last_seek_pos
-        } else {
-            ClockTime::ZERO
+    /// Draw the preview's framerate and how long the last frame took to process in the corner of the video pane.
+    fn show_performance_overlay(&self, ui: &mut egui::Ui, video_rect: Rect) {
+        let Some(info) = &self.pipeline else {
+            return;
         };
+        let egui_sink = info.egui_sink.downcast_ref::<elements::EguiSink>().unwrap();
+        let egui_sink = EguiSink::from_obj(egui_sink);
+        let frame_time = egui_sink.last_frame_time();
 
-        let framerate = (|| {
-            let caps = self
-                .pipeline
-                .as_ref()?
-                .pipeline
-                .by_name("video_queue")?
-                .static_pad("sink")?
-                .current_caps()?;
-            let framerate = caps
-                .structure(0)?
-                .get::<gstreamer::Fraction>("framerate")
-                .ok()?;
-            Some(framerate)
-        })();
-
-        egui::TopBottomPanel::top("video_info").show_inside(ui, |ui| {
-            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                let mut remove_pipeline = false;
-                let mut change_framerate_res = None;
-                let mut save_image_to: Option<(PathBuf, PathBuf)> = None;
-                let mut copy_image_res: Option<Result<ColorImage, GstreamerError>> = None;
-                if let Some(info) = &mut self.pipeline {
-                    let mut metadata = info.metadata.lock().unwrap();
-                    if ui.button("🗙").clicked() {
-                        remove_pipeline = true;
-                    }
-
-                    ui.separator();
+        let mut text = match self.preview_fps.fps {
+            Some(fps) => format!("{fps:.1} fps"),
+            None => String::from("-- fps"),
+        };
+        if let Some(frame_time) = frame_time {
+            text.push_str(&format!(
+                "\n{:.1} ms/frame",
+                frame_time.as_secs_f64() * 1000.0
+            ));
+        }
+        text.push_str(&format!("\n{} dropped", egui_sink.dropped_frames()));
+        if let Some(timings) = egui_sink.stage_timings() {
+            text.push('\n');
+            // Skip the stages that are switched off, which take next to no time
+            for (stage, duration) in timings
+                .stages()
+                .iter()
+                .filter(|(_, duration)| *duration >= std::time::Duration::from_micros(50))
+            {
+                text.push_str(&format!(
+                    "\n{stage}: {:.1} ms",
+                    duration.as_secs_f64() * 1000.0
+                ));
+            }
+        }
 
-                    if ui.button("Save frame").clicked() {
-                        let src_path = info.path.clone();
+        const MARGIN: f32 = 8.0;
+        let painter = ui.painter().with_clip_rect(video_rect);
+        let galley =
+            painter.layout_no_wrap(text, egui::FontId::monospace(12.0), egui::Color32::WHITE);
+        let text_pos = video_rect.min + vec2(MARGIN * 2.0, MARGIN * 2.0);
+        painter.rect_filled(
+            Rect::from_min_size(text_pos, galley.size()).expand(MARGIN),
+            egui::Rounding::same(4.0),
+            egui::Color32::from_black_alpha(160),
+        );
+        painter.galley(text_pos, galley, egui::Color32::WHITE);
+    }
 
-                        let dst_path = src_path.with_extension("");
-                        save_image_to = Some((src_path, dst_path));
-                    }
+    fn show_status_bar(&mut self, ui: &mut egui::Ui) {
+        let Some(info) = &self.pipeline else {
+            self.preview_fps.reset();
+            ui.horizontal(|ui| {
+                ui.weak(tr!("No video loaded"));
+            });
+            return;
+        };
 
-                    if ui.button("Copy frame").clicked() {
-                        let egui_sink =
-                            info.egui_sink.downcast_ref::<elements::EguiSink>().unwrap();
+        let egui_sink = info.egui_sink.downcast_ref::<elements::EguiSink>().unwrap();
+        self.preview_fps
+            .update(EguiSink::from_obj(egui_sink).frames_rendered());
+
+        let state = info.pipeline.current_state();
+        let loading = matches!(*info.state.lock().unwrap(), PipelineInfoState::Loading);
+        let state_text = match &*info.state.lock().unwrap() {
+            PipelineInfoState::Loading => tr!("Loading"),
+            PipelineInfoState::Error(_) => tr!("Error"),
+            PipelineInfoState::Loaded => match state {
+                gstreamer::State::Playing if info.reverse => tr!("Playing backwards"),
+                gstreamer::State::Playing => tr!("Playing"),
+                gstreamer::State::Paused => tr!("Paused"),
+                _ => tr!("Stopped"),
+            },
+        };
+        if state == gstreamer::State::Playing {
+            // Keep the position and fps readouts current
+            ui.ctx().request_repaint();
+        }
 
-                        let egui_sink = EguiSink::from_obj(egui_sink);
-                        copy_image_res = Some(egui_sink.get_image().map_err(|e| e.into()));
-                    }
+        let metadata = info.metadata.lock().unwrap();
+        let framerate = info.framerate();
+        let smpte_framerate =
+            framerate.filter(|framerate| self.smpte_timecode && framerate.numer() > 0);
+        let format_time = |time: ClockTime| match smpte_framerate {
+            Some(framerate) => smpte_timecode_format(time.nseconds(), framerate),
+            None => clock_time_format(time.nseconds()),
+        };
 
-                    if let Some(current_framerate) = metadata.framerate {
-                        ui.separator();
-                        match metadata.is_still_image {
-                            Some(true) => {
-                                let mut new_framerate = current_framerate.numer() as f64
-                                    / current_framerate.denom() as f64;
-                                ui.label("fps");
-                                if ui
-                                    .add(
-                                        egui::DragValue::new(&mut new_framerate)
-                                            .clamp_range(0.0..=240.0),
-                                    )
-                                    .changed()
-                                {
-                                    let framerate_fraction =
-                                        gstreamer::Fraction::approximate_f64(new_framerate);
-                                    if let Some(f) = framerate_fraction {
-                                        let changed_framerate =
-                                            Self::set_still_image_framerate(&info.pipeline, f);
-                                        if let Ok(Some(new_framerate)) = changed_framerate {
-                                            metadata.framerate = Some(new_framerate);
-                                        }
+        let mut match_field = None;
+        let mut cancel_loading = false;
+        ui.horizontal(|ui| {
+            ui.label(state_text);
+            if loading
+                && ui
+                    .small_button(tr!("Cancel"))
+                    .on_hover_text(tr!("Stop opening this file"))
+                    .clicked()
+            {
+                cancel_loading = true;
+            }
 
-                                        change_framerate_res = Some(changed_framerate);
-                                    }
-                                }
-                            }
-                            Some(false) => {
-                                let mut fps_display = format!(
-                                    "{:.2} fps",
-                                    current_framerate.numer() as f64
-                                        / current_framerate.denom() as f64
-                                );
-                                if let Some(interlace_mode) = metadata.interlace_mode {
-                                    fps_display.push_str(match interlace_mode {
-                                        VideoInterlaceMode::Progressive => " (progressive)",
-                                        VideoInterlaceMode::Interleaved => " (interlaced)",
-                                        VideoInterlaceMode::Mixed => " (telecined)",
-                                        _ => "",
-                                    });
-                                }
-                                ui.label(fps_display);
-                            }
-                            None => {}
-                        }
-                    }
+            ui.separator();
+            let position = info
+                .pipeline
+                .query_position::<ClockTime>()
+                .unwrap_or(info.last_seek_pos);
+            match info.pipeline.query_duration::<ClockTime>() {
+                Some(duration) => ui.label(format!(
+                    "{} / {}",
+                    format_time(position),
+                    format_time(duration)
+                )),
+                None => ui.label(format_time(position)),
+            };
 
-                    if let Some((width, height)) = metadata.resolution {
-                        ui.separator();
-                        ui.label(format!("{}x{}", width, height));
-                    }
+            if let Some((width, height)) = metadata.resolution {
+                ui.separator();
+                let label = ui.label(format!("{}x{}", width, height));
+                if let Some(par) = metadata.pixel_aspect_ratio.filter(|par| par.numer() != par.denom()) {
+                    label.on_hover_text(tr!(
+                        "Pixel aspect ratio {numer}:{denom}",
+                        numer = par.numer(),
+                        denom = par.denom()
+                    ));
+                }
+            }
 
-                    ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
-                        ui.add(egui::Label::new(info.path.to_string_lossy()).truncate(true));
+            if let Some(framerate) = metadata.framerate.filter(|framerate| framerate.denom() > 0) {
+                ui.separator();
+                let mut fps_display = tr!(
+                    "{fps} fps",
+                    fps = format!("{:.2}", framerate.numer() as f64 / framerate.denom() as f64)
+                );
+                if let Some(interlace_mode) = metadata.interlace_mode {
+                    fps_display.push_str(match interlace_mode {
+                        VideoInterlaceMode::Progressive => tr!(" (progressive)"),
+                        VideoInterlaceMode::Interleaved => tr!(" (interlaced)"),
+                        VideoInterlaceMode::Mixed => tr!(" (telecined)"),
+                        _ => "",
                     });
                 }
+                ui.label(fps_display);
+            }
 
-                if let Some(res) = change_framerate_res {
-                    self.handle_result(res);
+            if let Some(source_field) = metadata
+                .source_use_field()
+                .filter(|source_field| *source_field != self.effect_settings.use_field)
+            {
+                ui.separator();
+                ui.label(match source_field {
+                    UseField::InterleavedLower => tr!("Source is bottom field first"),
+                    _ => tr!("Source is top field first"),
+                });
+                if ui
+                    .button(tr!("Match source"))
+                    .on_hover_text(
+                        tr!("Use the source's field order for the effect. Getting the field order wrong makes motion look \
+                        jittery."),
+                    )
+                    .clicked()
+                {
+                    match_field = Some(source_field);
                 }
+            }
 
-                if let Some(res) = copy_image_res {
-                    match res {
-                        Ok(image) => {
-                            let res = arboard::Clipboard::new().and_then(|mut cb| {
-                                let data = arboard::ImageData {
-                                    width: image.width(),
-                                    height: image.height(),
-                                    bytes: Cow::from(image.as_raw()),
-                                };
-                                cb.set_image(data)?;
-                                Ok(())
-                            });
-                            self.handle_result(res);
-                        }
-                        Err(e) => {
-                            self.handle_error(&e);
-                        }
-                    }
-                }
+            if let Some(preview_fps) = self.preview_fps.fps {
+                ui.separator();
+                ui.label(tr!("Preview: {fps} fps", fps = format!("{:.1}", preview_fps)))
+                    .on_hover_text(tr!("How many frames per second the preview is rendering"));
+            }
 
-                if remove_pipeline {
-                    self.handle_result_with(|app| app.remove_pipeline());
+            let sink = EguiSink::from_obj(egui_sink);
+            if let Some(noise_frame_num) = sink.noise_frame_num() {
+                ui.separator();
+                let pinned = egui_sink.noise_pin().0.is_some();
+                ui.label(tr!(
+                    "Noise: seed {seed}, frame {frame}",
+                    seed = self.effect_settings.random_seed,
+                    frame = noise_frame_num
+                ))
+                .on_hover_text(
+                    tr!("The noise on this frame is generated from the random seed and this frame number. Frames with the \
+                    same seed and frame number get exactly the same noise."),
+                );
+                if ui
+                    .selectable_label(pinned, "📌")
+                    .on_hover_text(
+                        tr!("Pin this frame's noise, so that scrubbing to other frames shows them with the same noise"),
+                    )
+                    .clicked()
+                {
+                    let pin = if pinned { None } else { sink.last_frame_pts() };
+                    egui_sink.set_noise_pin(NoisePin(pin));
                 }
+            }
+        });
+        drop(metadata);
 
-                if let Some((src_path, dst_path)) = save_image_to {
-                    let ctx = ctx.clone();
-                    self.spawn(async move {
-                        let handle = rfd::AsyncFileDialog::new()
-                            .set_directory(dst_path.parent().unwrap_or(Path::new("/")))
-                            .set_file_name(format!(
-                                "{}_ntsc.png",
-                                dst_path.file_name().to_owned().unwrap().to_string_lossy()
-                            ))
-                            .save_file()
-                            .await;
+        if let Some(use_field) = match_field {
+            self.effect_settings.use_field = use_field;
+            self.update_effect();
+        }
 
-                        handle.map(|handle| {
-                            Box::new(move |app: &mut NtscApp| {
-                                let res = app.create_render_job(
-                                    &ctx,
-                                    &src_path.clone(),
-                                    RenderPipelineSettings {
-                                        codec_settings: RenderPipelineCodec::Png,
-                                        output_path: handle.into(),
-                                        duration: ClockTime::from_seconds(1),
-                                        interlacing: RenderInterlaceMode::Progressive,
-                                        effect_settings: (&app.effect_settings).into(),
-                                    },
-                                );
-                                if let Ok(job) = res {
-                                    app.render_jobs.push(job);
-                                } else {
-                                    app.handle_result(res);
-                                }
-                                Ok(())
-                            }) as _
+        if cancel_loading {
+            self.cancel_loading();
+        }
+    }
+
+    fn show_media_info(ui: &mut egui::Ui, metadata: &PipelineMetadata) {
+        let media_info = &metadata.media_info;
+        let unknown = || tr!("Unknown").to_string();
+
+        ui.heading(tr!("Media info"));
+        egui::Grid::new("media_info")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label(tr!("Container"));
+                ui.label(media_info.container_format.clone().unwrap_or_else(unknown));
+                ui.end_row();
+
+                ui.label(tr!("Video codec"));
+                ui.label(media_info.video_codec.clone().unwrap_or_else(unknown));
+                ui.end_row();
+
+                ui.label(tr!("Pixel format"));
+                ui.label(
+                    media_info
+                        .pixel_format
+                        .map(|format| {
+                            let format_info = gstreamer_video::VideoFormatInfo::from_format(format);
+                            format!("{} ({}-bit)", format_info.name(), format_info.depth()[0])
                         })
-                    });
+                        .unwrap_or_else(unknown),
+                );
+                ui.end_row();
+
+                ui.label(tr!("Resolution"));
+                ui.label(
+                    metadata
+                        .resolution
+                        .map(|(width, height)| format!("{width}x{height}"))
+                        .unwrap_or_else(unknown),
+                );
+                ui.end_row();
+
+                ui.label(tr!("Pixel aspect ratio"));
+                ui.label(
+                    metadata
+                        .pixel_aspect_ratio
+                        .map(|par| format!("{}:{}", par.numer(), par.denom()))
+                        .unwrap_or_else(unknown),
+                );
+                ui.end_row();
+
+                ui.label(tr!("Frame rate"));
+                ui.label(
+                    metadata
+                        .framerate
+                        .filter(|framerate| framerate.numer() > 0)
+                        .map(|framerate| {
+                            format!(
+                                "{:.3} fps",
+                                framerate.numer() as f64 / framerate.denom() as f64
+                            )
+                        })
+                        .unwrap_or_else(unknown),
+                );
+                ui.end_row();
+
+                if metadata.has_audio == Some(true) {
+                    ui.label(tr!("Audio codec"));
+                    ui.label(media_info.audio_codec.clone().unwrap_or_else(unknown));
+                    ui.end_row();
+
+                    ui.label(tr!("Sample rate"));
+                    ui.label(
+                        media_info
+                            .audio_sample_rate
+                            .map(|rate| format!("{rate} Hz"))
+                            .unwrap_or_else(unknown),
+                    );
+                    ui.end_row();
+
+                    ui.label(tr!("Channels"));
+                    ui.label(
+                        media_info
+                            .audio_channels
+                            .map(|channels| channels.to_string())
+                            .unwrap_or_else(unknown),
+                    );
+                    ui.end_row();
+                } else {
+                    ui.label(tr!("Audio"));
+                    ui.label(tr!("None"));
+                    ui.end_row();
                 }
             });
-        });
+    }
 
-        egui::TopBottomPanel::bottom("video_controls")
-            .exact_height(ui.spacing().interact_size.y * 2.0)
-            .show_inside(ui, |ui| {
-                ui.set_enabled(self.pipeline.is_some());
-                ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
-                    ui.spacing_mut().item_spacing.x = 6.0;
-                    let btn_widget = egui::Button::new(match &self.pipeline {
-                        Some(PipelineInfo { pipeline, .. }) => {
-                            let state = pipeline.current_state();
-                            match state {
-                                gstreamer::State::Paused | gstreamer::State::Ready => "▶",
-                                gstreamer::State::Playing => "⏸",
-                                _ => "▶",
-                            }
-                        }
-                        None => "▶",
-                    });
-                    let btn = ui.add_sized(
-                        vec2(
-                            ui.spacing().interact_size.y * 1.5,
-                            ui.spacing().interact_size.y * 1.5,
-                        ),
-                        btn_widget,
-                    );
+    fn show_credits_dialog(&mut self, ctx: &egui::Context) {
+        egui::Window::new(tr!("About + Credits"))
+            .open(&mut self.credits_dialog_open)
+            .default_width(400.0)
+            .show(ctx, |ui| {
+                const VERSION: &str = env!("CARGO_PKG_VERSION");
+                ui.heading(format!("pal-rs v{VERSION}"));
 
-                    if !ctx.wants_keyboard_input()
-                        && ctx.input(|i| {
-                            i.events.iter().any(|event| {
-                                if let egui::Event::Key {
-                                    key,
-                                    pressed,
-                                    repeat,
-                                    modifiers,
-                                    ..
-                                } = event
-                                {
-                                    *key == egui::Key::Space
-                                        && *pressed
-                                        && !repeat
-                                        && modifiers.is_none()
-                                } else {
-                                    false
+                ui.separator();
+
+                ui.horizontal_wrapped(|ui| {
+                    ui.spacing_mut().item_spacing.x = 0.0;
+                    ui.label(tr!("by "));
+                    ui.add(egui::Hyperlink::from_label_and_url(
+                        "LucianoTheWindowsFan",
+                        "https://github.com/LucianoTheWindowsFan/",
+                    ));
+                });
+
+                ui.horizontal_wrapped(|ui| {
+                    ui.spacing_mut().item_spacing.x = 0.0;
+                    ui.label(tr!("...loosely based on "));
+                    ui.add(egui::Hyperlink::from_label_and_url(
+                        "valadaptive/ntsc-rs",
+                        "https://github.com/valadaptive/ntsc-rs/",
+                    ));
+                });
+
+                ui.horizontal_wrapped(|ui| {
+                    ui.spacing_mut().item_spacing.x = 0.0;
+                    ui.label(tr!("...itself based on "));
+                    ui.add(egui::Hyperlink::from_label_and_url(
+                        "JargeZ/ntscqt",
+                        "https://github.com/JargeZ/ntscqt/",
+                    ));
+                });
+
+                ui.horizontal_wrapped(|ui| {
+                    ui.spacing_mut().item_spacing.x = 0.0;
+                    ui.label(tr!("...which is a GUI for "));
+                    ui.add(egui::Hyperlink::from_label_and_url(
+                        "zhuker/ntsc",
+                        "https://github.com/zhuker/ntsc/",
+                    ));
+                });
+
+                ui.horizontal_wrapped(|ui| {
+                    ui.spacing_mut().item_spacing.x = 0.0;
+                    ui.label(tr!("...which is a port of "));
+                    ui.add(egui::Hyperlink::from_label_and_url(
+                        "joncampbell123/composite-video-simulator",
+                        "https://github.com/joncampbell123/composite-video-simulator/",
+                    ));
+                });
+            });
+    }
+
+    fn show_open_url_dialog(&mut self, ctx: &egui::Context) {
+        let mut is_open = true;
+        let mut load_url = None;
+        egui::Window::new(tr!("Open URL"))
+            .open(&mut is_open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let Some(url) = &mut self.open_url else {
+                    return;
+                };
+                ui.label(tr!("HTTP(S) or RTSP address of the video:"));
+                let url_response = ui.add(
+                    egui::TextEdit::singleline(url)
+                        .hint_text("http://example.com/video.mp4")
+                        .desired_width(400.0),
+                );
+                let submitted = url_response.lost_focus()
+                    && ui.input(|input| input.key_pressed(egui::Key::Enter));
+                let is_valid = is_url(Path::new(url.trim()));
+                if ui
+                    .add_enabled(is_valid, egui::Button::new(tr!("Open")))
+                    .clicked()
+                    || (submitted && is_valid)
+                {
+                    load_url = Some(PathBuf::from(url.trim()));
+                }
+            });
+
+        if let Some(url) = load_url {
+            self.open_url = None;
+            let res = self.load_video(ctx, url);
+            self.handle_result(res);
+        } else if !is_open {
+            self.open_url = None;
+        }
+    }
+
+    fn show_variation_grid_dialog(&mut self, ctx: &egui::Context) {
+        let mut is_open = true;
+        let mut save = false;
+        let mut descriptors = Vec::new();
+        numeric_descriptors(&self.settings_list.settings, &mut descriptors);
+        egui::Window::new(tr!("Variation grid"))
+            .open(&mut is_open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let Some(options) = &mut self.variation_grid else {
+                    return;
+                };
+                let selected_label = descriptors
+                    .iter()
+                    .find(|descriptor| descriptor.id == options.setting)
+                    .map_or("", |descriptor| tr!(descriptor.label));
+                egui::Grid::new("variation_grid_options")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label(tr!("Setting"));
+                        egui::ComboBox::from_id_source("variation_grid_setting")
+                            .selected_text(selected_label)
+                            .show_ui(ui, |ui| {
+                                for descriptor in &descriptors {
+                                    if ui
+                                        .selectable_label(
+                                            descriptor.id == options.setting,
+                                            tr!(descriptor.label),
+                                        )
+                                        .clicked()
+                                    {
+                                        *options = VariationGridOptions::for_descriptor(
+                                            descriptor,
+                                            options.count,
+                                        );
+                                    }
                                 }
-                            })
-                        })
-                    {
-                        let res = self.pipeline.as_mut().map(|p| p.toggle_playing());
-                        if let Some(res) = res {
-                            self.handle_result(res);
-                        }
-                    }
+                            });
+                        ui.end_row();
 
-                    if btn.clicked() {
-                        let res = self.pipeline.as_mut().map(|p| p.toggle_playing());
-                        if let Some(res) = res {
-                            self.handle_result(res);
-                        }
-                    }
+                        ui.label(tr!("From"));
+                        ui.add(egui::DragValue::new(&mut options.start).speed(0.01));
+                        ui.end_row();
 
-                    let duration = if let Some(info) = &self.pipeline {
-                        info.pipeline.query_duration::<ClockTime>()
-                    } else {
-                        None
-                    };
+                        ui.label(tr!("To"));
+                        ui.add(egui::DragValue::new(&mut options.end).speed(0.01));
+                        ui.end_row();
 
-                    let mut timecode_ms =
-                        last_seek_pos.nseconds() as f64 / ClockTime::MSECOND.nseconds() as f64;
-                    let frame_pace = if let Some(framerate) = framerate {
-                        framerate.denom() as f64 / framerate.numer() as f64
-                    } else {
-                        1f64 / 30f64
-                    };
+                        ui.label(tr!("Steps"));
+                        ui.add(egui::DragValue::new(&mut options.count).clamp_range(2..=64));
+                        ui.end_row();
+                    });
 
-                    let mut drag_value = egui::DragValue::new(&mut timecode_ms)
-                        .custom_formatter(|value, _| {
-                            clock_time_format((value * ClockTime::MSECOND.nseconds() as f64) as u64)
-                        })
-                        .custom_parser(clock_time_parser)
-                        .speed(frame_pace * 1000.0 * 0.5);
+                if ui
+                    .add_enabled(self.pipeline.is_some(), egui::Button::new(tr!("Save...")))
+                    .clicked()
+                {
+                    save = true;
+                }
+            });
+
+        if save {
+            if let Some(options) = self.variation_grid.take() {
+                let file_dialog = rfd::AsyncFileDialog::new()
+                    .add_filter("PNG", &["png"])
+                    .set_file_name("variations.png")
+                    .save_file();
+                self.spawn(async move {
+                    let handle = file_dialog.await?;
+                    Some(Box::new(move |app: &mut NtscApp| {
+                        app.render_variation_grid(&options, handle.path())
+                    }) as _)
+                });
+            }
+        } else if !is_open {
+            self.variation_grid = None;
+        }
+    }
+
+    /// Render the preview's current frame once for each value in the variation grid options, and save them all to one
+    /// labeled image.
+    fn render_variation_grid(
+        &mut self,
+        options: &VariationGridOptions,
+        path: &Path,
+    ) -> Result<(), ApplicationError> {
+        let Some(info) = &self.pipeline else {
+            return Ok(());
+        };
+        let egui_sink =
+            EguiSink::from_obj(info.egui_sink.downcast_ref::<elements::EguiSink>().unwrap());
+        let mut descriptors = Vec::new();
+        numeric_descriptors(&self.settings_list.settings, &mut descriptors);
+        let Some(descriptor) = descriptors
+            .into_iter()
+            .find(|descriptor| descriptor.id == options.setting)
+        else {
+            return Ok(());
+        };
 
-                    if let Some(duration) = duration {
-                        drag_value = drag_value.clamp_range(0..=duration.mseconds());
+        let cells = variation_values(options.start, options.end, options.count)
+            .into_iter()
+            .map(|value| {
+                let mut settings = self.effect_settings.clone();
+                let label = match descriptor.kind {
+                    SettingKind::IntRange { .. } => {
+                        let value = value.round() as i32;
+                        if let Some(field) = descriptor.id.get_field_mut::<u32>(&mut settings) {
+                            *field = value.max(0) as u32;
+                        } else if let Some(field) =
+                            descriptor.id.get_field_mut::<i32>(&mut settings)
+                        {
+                            *field = value;
+                        }
+                        value.to_string()
                     }
-
-                    if ui.add(drag_value).changed() {
-                        if let Some(info) = &self.pipeline {
-                            // don't use KEY_UNIT here; it causes seeking to often be very inaccurate (almost a second of deviation)
-                            let _ = info.pipeline.seek_simple(
-                                gstreamer::SeekFlags::FLUSH | gstreamer::SeekFlags::ACCURATE,
-                                ClockTime::from_nseconds(
-                                    (timecode_ms * ClockTime::MSECOND.nseconds() as f64) as u64,
-                                ),
-                            );
+                    _ => {
+                        if let Some(field) = descriptor.id.get_field_mut::<f32>(&mut settings) {
+                            *field = value;
+                        }
+                        if matches!(descriptor.kind, SettingKind::Percentage { .. }) {
+                            format!("{:.0}%", value * 100.0)
+                        } else {
+                            format!("{value:.2}")
                         }
                     }
+                };
+                let image = egui_sink
+                    .get_image_with_settings(&scale_effect_intensity(
+                        &settings,
+                        self.effect_intensity,
+                    ))
+                    .context(VariationGridRenderSnafu)?;
+                Ok((image, label))
+            })
+            .collect::<Result<Vec<_>, ApplicationError>>()?;
+
+        compose_variation_grid(&cells)
+            .save(path)
+            .context(VariationGridSaveSnafu)?;
+        Ok(())
+    }
 
-                    ui.separator();
+    fn show_keyboard_shortcuts_dialog(&mut self, ctx: &egui::Context) {
+        egui::Window::new(tr!("Keyboard shortcuts"))
+            .open(&mut self.keyboard_shortcuts_open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.label(tr!("Click a shortcut to change it, then press the new key combination. Press Escape to cancel."));
+                ui.separator();
 
-                    ui.label("🔎");
-                    ui.add_enabled(
-                        !self.video_zoom.fit,
-                        egui::DragValue::new(&mut self.video_zoom.scale)
-                            .clamp_range(0.0..=8.0)
-                            .speed(0.01)
-                            .custom_formatter(format_percentage)
-                            // Treat as a percentage above 8x zoom
-                            .custom_parser(|input| parse_decimal_or_percentage(input, 8.0)),
-                    );
-                    ui.checkbox(&mut self.video_zoom.fit, "Fit");
+                egui::Grid::new("keyboard_shortcuts_grid")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for action in ShortcutAction::ALL.iter().copied() {
+                            ui.label(tr!(action.label()));
+
+                            ui.horizontal_wrapped(|ui| {
+                                let shortcuts = self.keyboard_shortcuts.shortcuts(action).to_vec();
+                                let mut remove = None;
+                                for (index, shortcut) in shortcuts.iter().enumerate() {
+                                    let recording = self.rebinding_shortcut == Some((action, Some(index)));
+                                    let conflicts = self.keyboard_shortcuts.conflicts(action, shortcut);
+                                    let text = if recording {
+                                        egui::RichText::new(tr!("Press a key..."))
+                                    } else if conflicts.is_empty() {
+                                        egui::RichText::new(ctx.format_shortcut(shortcut))
+                                    } else {
+                                        egui::RichText::new(format!("⚠ {}", ctx.format_shortcut(shortcut)))
+                                            .color(ui.visuals().warn_fg_color)
+                                    };
+                                    let mut response = ui
+                                        .add(egui::Button::new(text).selected(recording))
+                                        .on_hover_text(tr!("Click to change, right-click to remove"));
+                                    if !conflicts.is_empty() {
+                                        let conflicts = conflicts
+                                            .iter()
+                                            .map(|action| tr!(action.label()))
+                                            .collect::<Vec<_>>()
+                                            .join(", ");
+                                        response = response.on_hover_text(tr!("Also bound to: {actions}", actions = conflicts));
+                                    }
+                                    if response.clicked() {
+                                        self.rebinding_shortcut = Some((action, Some(index)));
+                                    }
+                                    if response.secondary_clicked() {
+                                        remove = Some(index);
+                                    }
+                                }
 
-                    ui.separator();
+                                let adding = self.rebinding_shortcut == Some((action, None));
+                                if ui
+                                    .add(
+                                        egui::Button::new(if adding { tr!("Press a key...") } else { "+" })
+                                            .selected(adding),
+                                    )
+                                    .on_hover_text(tr!("Add another shortcut"))
+                                    .clicked()
+                                {
+                                    self.rebinding_shortcut = Some((action, None));
+                                }
 
-                    let scale_checkbox = ui.checkbox(&mut self.video_scale.enabled, "Scale to");
-                    ui.add_enabled_ui(self.video_scale.enabled, |ui| {
-                        let drag_resp = ui.add(
-                            egui::DragValue::new(&mut self.video_scale.scale)
-                                .clamp_range(1..=usize::MAX),
-                        );
-                        if drag_resp.changed() || scale_checkbox.changed() {
-                            if let Some(pipeline) = &self.pipeline {
-                                let res = Self::rescale_video(
-                                    &pipeline.pipeline,
-                                    pipeline.last_seek_pos,
-                                    if self.video_scale.enabled {
-                                        Some(self.video_scale.scale)
-                                    } else {
-                                        None
-                                    },
-                                );
-                                self.handle_result(res);
+                                if let Some(index) = remove {
+                                    self.keyboard_shortcuts.remove(action, index);
+                                    self.rebinding_shortcut = None;
+                                }
+                            });
+
+                            if ui.button(tr!("Reset")).clicked() {
+                                self.keyboard_shortcuts.reset(action);
+                                self.rebinding_shortcut = None;
                             }
+                            ui.end_row();
                         }
-                        ui.label("scanlines");
                     });
 
-                    ui.separator();
+                ui.separator();
+                if ui.button(tr!("Reset all")).clicked() {
+                    self.keyboard_shortcuts = KeyboardShortcuts::default();
+                    self.rebinding_shortcut = None;
+                }
+            });
+    }
 
-                    let has_audio = self
-                        .pipeline
-                        .as_ref()
-                        .map(|info| info.metadata.lock().unwrap())
-                        .and_then(|metadata| metadata.has_audio)
-                        .unwrap_or(false);
+    /// Copy a captured frame to the clipboard and add it to the saved frames gallery.
+    fn copy_frame(
+        &mut self,
+        ctx: &egui::Context,
+        res: Result<ColorImage, GstreamerError>,
+        position: ClockTime,
+    ) {
+        match res {
+            Ok(image) => {
+                let res = arboard::Clipboard::new().and_then(|mut cb| {
+                    let data = arboard::ImageData {
+                        width: image.width(),
+                        height: image.height(),
+                        bytes: Cow::from(image.as_raw()),
+                    };
+                    cb.set_image(data)?;
+                    Ok(())
+                });
+                self.handle_result(res);
+                self.add_saved_frame(ctx, image, position);
+            }
+            Err(e) => {
+                self.handle_error(&e);
+            }
+        }
+    }
 
-                    ui.add_enabled_ui(has_audio, |ui| {
-                        let mut update_volume = false;
+    /// Decode the frame at `position` again at the source's full resolution on a background thread, then copy it to
+    /// the clipboard once it's ready.
+    fn grab_full_resolution_frame(
+        &mut self,
+        ctx: &egui::Context,
+        path: PathBuf,
+        position: ClockTime,
+    ) {
+        let still_image_framerate = self
+            .pipeline
+            .as_ref()
+            .and_then(|info| info.metadata.lock().unwrap().framerate)
+            .unwrap_or(self.render_settings.still_image_framerate());
+        let tonemap = self.render_settings.tonemap;
+        let square_pixels = self.video_scale.square_pixels;
+        let settings = self.applied_effect_settings();
+        let region = self.effect_region.region();
+        let exec = self.execute_fn_next_frame();
+        let ctx = ctx.clone();
+
+        std::thread::spawn(move || {
+            let res = grab_frame(
+                &path,
+                position,
+                still_image_framerate,
+                tonemap,
+                square_pixels,
+                &settings,
+                region,
+            );
+            exec(async move {
+                Some(Box::new(move |app: &mut NtscApp| {
+                    app.copy_frame(&ctx, res, position);
+                    Ok(())
+                }) as _)
+            });
+        });
+    }
 
-                        // Not actually being made into an error and some want to remove the lint entirely
-                        #[allow(illegal_floating_point_literal_pattern)]
-                        if ui
-                            .button(if self.audio_volume.mute {
-                                "🔇"
-                            } else {
-                                match self.audio_volume.gain {
-                                    0.0 => "🔇",
-                                    0.0..=0.33 => "🔈",
-                                    0.0..=0.67 => "🔉",
-                                    _ => "🔊",
-                                }
-                            })
-                            .on_hover_text(if self.audio_volume.mute {
-                                "Unmute"
-                            } else {
-                                "Mute"
-                            })
-                            .clicked()
-                        {
-                            self.audio_volume.mute = !self.audio_volume.mute;
-                            // "<= 0.0" to handle negative zero (not sure if it'll ever happen; better safe than sorry)
-                            if !self.audio_volume.mute && self.audio_volume.gain <= 0.0 {
-                                // Restore the previous gain after the user mutes by dragging the slider to 0 then unmutes
-                                self.audio_volume.gain = self.audio_volume.gain_pre_mute;
-                            }
-                            update_volume = true;
-                        }
+    fn add_saved_frame(&mut self, ctx: &egui::Context, image: ColorImage, position: ClockTime) {
+        if self.saved_frames.len() >= MAX_SAVED_FRAMES {
+            self.saved_frames.pop_front();
+        }
+        let texture = ctx.load_texture("saved_frame", image, egui::TextureOptions::LINEAR);
+        self.saved_frames.push_back(SavedFrame {
+            texture,
+            settings_hash: self.settings_list.settings_hash(&self.effect_settings),
+            settings: self.effect_settings.clone(),
+            position,
+        });
+    }
 
-                        let resp = ui.add_enabled(
-                            !self.audio_volume.mute,
-                            egui::Slider::new(&mut self.audio_volume.gain, 0.0..=1.25)
-                                // Treat as a percentage above 125% volume
-                                .custom_parser(|input| parse_decimal_or_percentage(input, 1.25))
-                                .custom_formatter(format_percentage),
-                        );
+    /// Replace the effect settings with those from a settings JSON file.
+    fn load_settings_file(&mut self, path: &Path) -> Result<(), ApplicationError> {
+        let mut file = File::open(path).context(JSONReadSnafu)?;
 
-                        if resp.drag_stopped() {
-                            if self.audio_volume.gain > 0.0 {
-                                // Set the gain to restore after dragging the slider to 0
-                                self.audio_volume.gain_pre_mute = self.audio_volume.gain;
-                            } else {
-                                // Wait for drag release to mute because it disables the slider
-                                self.audio_volume.mute = true;
-                            }
-                        }
+        let mut buf = String::new();
+        file.read_to_string(&mut buf).context(JSONReadSnafu)?;
 
-                        if resp.changed() {
-                            update_volume = true;
-                        }
+        self.apply_settings_json(&buf, "Settings loaded from file")
+            .context(JSONParseSnafu)?;
 
-                        if update_volume {
-                            if let Some(pipeline_info) = &self.pipeline {
-                                NtscApp::set_volume(
-                                    &pipeline_info.pipeline,
-                                    // Unlogarithmify volume (at least to my ears, this gives more control at the low end
-                                    // of the slider)
-                                    10f64.powf(self.audio_volume.gain - 1.0).max(0.0),
-                                    self.audio_volume.mute,
-                                );
-                            }
-                        }
-                    });
+        Ok(())
+    }
 
-                    ui.separator();
+    /// Replace the effect settings with those from a settings JSON string. Any values that were out of range are
+    /// clamped, and pointed out in a toast. If this would change any settings, they're listed for the user to confirm
+    /// first.
+    fn apply_settings_json(
+        &mut self,
+        json: &str,
+        applied_message: &'static str,
+    ) -> Result<(), ParseSettingsError> {
+        let (settings, clamped) = self.settings_list.from_json_reporting_clamped(json)?;
+        if !clamped.is_empty() {
+            let labels = clamped
+                .iter()
+                .map(|descriptor| tr!(descriptor.label))
+                .collect::<Vec<_>>();
+            let message = tr!("Adjusted out-of-range settings: {settings}", settings = labels.join(", "));
+            self.show_toast(message);
+        }
 
-                    let mut update_effect_preview = false;
-                    ui.label("✨").on_hover_text("Effect preview");
-                    update_effect_preview |= ui
-                        .selectable_value(
-                            &mut self.effect_preview.mode,
-                            EffectPreviewMode::Enabled,
-                            "Enable",
-                        )
-                        .changed();
-                    update_effect_preview |= ui
-                        .selectable_value(
-                            &mut self.effect_preview.mode,
-                            EffectPreviewMode::Disabled,
-                            "Disable",
-                        )
-                        .changed();
-                    update_effect_preview |= ui
-                        .selectable_value(
-                            &mut self.effect_preview.mode,
-                            EffectPreviewMode::SplitScreen,
-                            "Split",
-                        )
-                        .changed();
+        self.offer_preset(PendingPreset {
+            settings,
+            metadata: PresetMetadata::from_json(json).unwrap_or_default(),
+            applied_message,
+        });
 
-                    if update_effect_preview {
-                        if let Some(PipelineInfo { egui_sink, .. }) = &self.pipeline {
-                            egui_sink.set_property(
-                                "preview-mode",
-                                Self::sink_preview_mode(&self.effect_preview),
-                            );
-                        }
+        Ok(())
+    }
+
+    /// Ask before applying a preset that would change the current settings, showing what it would change.
+    fn offer_preset(&mut self, preset: PendingPreset) {
+        if self.effect_settings == preset.settings {
+            self.apply_preset(preset);
+        } else {
+            self.pending_preset = Some(preset);
+        }
+    }
+
+    /// The default settings with the "no signal" static turned on, for a dead channel look.
+    fn no_signal_preset() -> PendingPreset {
+        let mut settings = NtscEffectFullSettings::default();
+        settings.no_signal.enabled = true;
+        PendingPreset {
+            settings,
+            metadata: PresetMetadata {
+                name: Some(tr!("No signal").to_string()),
+                ..Default::default()
+            },
+            applied_message: "Preset applied",
+        }
+    }
+
+    fn apply_preset(&mut self, preset: PendingPreset) {
+        self.loaded_preset = Some(preset.settings.clone());
+        self.effect_settings = preset.settings;
+        self.preset_metadata = preset.metadata;
+        self.update_effect();
+        self.show_toast(tr!(preset.applied_message));
+    }
+
+    fn show_preset_diff_dialog(&mut self, ctx: &egui::Context) {
+        let Some(preset) = &self.pending_preset else {
+            return;
+        };
+        let changed = self
+            .settings_list
+            .changed_settings(&self.effect_settings, &preset.settings);
+
+        let mut apply = None;
+        egui::Window::new(tr!("Apply settings?"))
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                if let Some(name) = &preset.metadata.name {
+                    ui.strong(name);
+                }
+                ui.label(match changed.len() {
+                    1 => tr!("1 setting will change:").to_string(),
+                    n => tr!("{count} settings will change:", count = n),
+                });
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("preset_diff")
+                            .num_columns(2)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for descriptor in &changed {
+                                    ui.label(tr!(descriptor.label));
+                                    ui.label(format!(
+                                        "{} → {}",
+                                        descriptor.format_value(&self.effect_settings),
+                                        descriptor.format_value(&preset.settings)
+                                    ));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("Apply")).clicked() {
+                        apply = Some(true);
+                    }
+                    if ui.button(tr!("Cancel")).clicked() {
+                        apply = Some(false);
                     }
                 });
             });
 
-        egui::CentralPanel::default()
-            .frame(egui::Frame::side_top_panel(&ctx.style()).inner_margin(0.0))
-            .show_inside(ui, |ui| {
-                ui.visuals_mut().clip_rect_margin = 0.0;
-                ui.with_layout(egui::Layout::bottom_up(egui::Align::Min), |ui| {
-                    if let Some(info) = &mut self.pipeline {
-                        let mut timecode = info.last_seek_pos.nseconds();
-
-                        let duration = info.pipeline.query_duration::<ClockTime>();
+        let Some(apply) = apply else {
+            return;
+        };
+        if let Some(preset) = self.pending_preset.take().filter(|_| apply) {
+            self.apply_preset(preset);
+        }
+    }
 
-                        if let Some(duration) = duration {
-                            if ui
-                                .add(Timeline::new(
-                                    &mut timecode,
-                                    0..=duration.nseconds(),
-                                    framerate,
-                                ))
-                                .changed()
-                            {
-                                let _ = info.pipeline.seek_simple(
-                                    gstreamer::SeekFlags::FLUSH | gstreamer::SeekFlags::ACCURATE,
-                                    ClockTime::from_nseconds(timecode),
-                                );
-                            }
-                        }
-                    }
-                    egui::ScrollArea::both()
-                        .auto_shrink([false, false])
-                        .show(ui, |ui| {
-                            ui.with_layout(
-                                egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
-                                |ui| {
-                                    let Some(PipelineInfo {
-                                        preview, egui_sink, ..
-                                    }) = &mut self.pipeline
-                                    else {
-                                        ui.add(
-                                            egui::Label::new(
-                                                egui::RichText::new("No media loaded").heading(),
-                                            )
-                                            .selectable(false),
-                                        );
-                                        return;
-                                    };
+    /// Open files dropped onto the window. Settings files are applied to the effect; anything else is opened as a
+    /// video.
+    fn handle_dropped_files(&mut self, ctx: &egui::Context) {
+        let dropped_files = ctx.input_mut(|input| std::mem::take(&mut input.raw.dropped_files));
+        let mut video_path = None;
+        for path in dropped_files.into_iter().filter_map(|file| file.path) {
+            let is_settings_file = path
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+            if is_settings_file {
+                let res = self.load_settings_file(&path);
+                self.handle_result(res);
+            } else if video_path.is_none() {
+                // Only one video can be open at a time
+                video_path = Some(path);
+            }
+        }
 
-                                    let texture_size = if self.video_scale.enabled {
-                                        let texture_actual_size = preview.size_vec2();
-                                        let scale_factor =
-                                            self.video_scale.scale as f32 / texture_actual_size.y;
-                                        vec2(
-                                            (texture_actual_size.x * scale_factor).round(),
-                                            self.video_scale.scale as f32,
-                                        )
-                                    } else {
-                                        preview.size_vec2()
-                                    };
-                                    let scale_factor = if self.video_zoom.fit {
-                                        // Due to floating-point error, a scrollbar may appear even if we scale down. To
-                                        // avoid the scrollbar popping in and out of existence, subtract a constant value
-                                        // from available_size.
-                                        ((ui.available_size() - vec2(1.0, 1.0)) / texture_size)
-                                            .min_elem()
-                                            .min(1.0)
-                                    } else {
-                                        self.video_zoom.scale as f32
-                                    };
+        if let Some(path) = video_path {
+            let res = self.load_video(ctx, path);
+            self.handle_result(res);
+        }
+    }
 
-                                    // We need to render the splitscreen bar in the same area as the image. The
-                                    // Response returned from ui.image() fills the entire scroll area, so we need
-                                    // to do the layout ourselves.
-                                    let image = egui::Image::from_texture((
-                                        preview.id(),
-                                        texture_size * scale_factor,
-                                    ));
-                                    let (rect, _) = ui.allocate_exact_size(
-                                        texture_size * scale_factor,
-                                        egui::Sense::hover(),
-                                    );
-                                    // Avoid texture sampling at non-integer coordinates (causes jaggies)
-                                    let rect = egui::Rect::from_points(&[
-                                        rect.min.floor(),
-                                        rect.max.floor(),
-                                    ]);
-                                    ui.put(rect, image);
+    fn show_save_preset(&mut self, ctx: &egui::Context) {
+        // Edit an optional field, treating blank text as unset
+        fn metadata_field(ui: &mut egui::Ui, field: &mut Option<String>, multiline: bool) {
+            let mut text = field.clone().unwrap_or_default();
+            let edit = if multiline {
+                egui::TextEdit::multiline(&mut text)
+            } else {
+                egui::TextEdit::singleline(&mut text)
+            };
+            if ui.add(edit.desired_width(f32::INFINITY)).changed() {
+                *field = (!text.trim().is_empty()).then_some(text);
+            }
+        }
 
-                                    if self.effect_preview.mode == EffectPreviewMode::SplitScreen
-                                        && ui
-                                            .put(
-                                                rect,
-                                                SplitScreen::new(
-                                                    &mut self.effect_preview.preview_rect,
-                                                ),
-                                            )
-                                            .changed()
-                                    {
-                                        egui_sink.set_property(
-                                            "preview_mode",
-                                            Self::sink_preview_mode(&self.effect_preview),
-                                        )
-                                    }
-                                },
-                            );
-                        });
-                });
+        let mut save = false;
+        egui::Window::new(tr!("Save preset"))
+            .open(&mut self.save_preset_open)
+            .default_width(350.0)
+            .show(ctx, |ui| {
+                ui.label(tr!("These are optional, and are shown to whoever loads the preset."));
+                egui::Grid::new("preset_metadata")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label(tr!("Name"));
+                        metadata_field(ui, &mut self.preset_metadata.name, false);
+                        ui.end_row();
+
+                        ui.label(tr!("Author"));
+                        metadata_field(ui, &mut self.preset_metadata.author, false);
+                        ui.end_row();
+
+                        ui.label(tr!("Description"));
+                        metadata_field(ui, &mut self.preset_metadata.description, true);
+                        ui.end_row();
+                    });
+
+                if ui.button(tr!("Save...")).clicked() {
+                    save = true;
+                }
             });
+
+        if !save {
+            return;
+        }
+        self.save_preset_open = false;
+
+        let json = self
+            .settings_list
+            .to_json_with_metadata(&self.effect_settings, &self.preset_metadata);
+        let file_name = self
+            .preset_metadata
+            .name
+            .as_deref()
+            .map(|name| {
+                name.chars()
+                    .filter(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+                    .collect::<String>()
+            })
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| String::from("settings"));
+        let handle = rfd::AsyncFileDialog::new()
+            .set_file_name(format!("{}.json", file_name.trim()))
+            .save_file();
+        self.spawn(async move {
+            let handle = handle.await?;
+
+            Some(Box::new(move |_: &mut NtscApp| {
+                let mut file = File::create(handle.path()).context(JSONSaveSnafu)?;
+                json.write_to(&mut file).context(JSONSaveSnafu)?;
+                Ok(())
+            }) as _)
+        });
     }
 
-    fn show_credits_dialog(&mut self, ctx: &egui::Context) {
-        egui::Window::new("About + Credits")
-            .open(&mut self.credits_dialog_open)
-            .default_width(400.0)
+    fn show_saved_frames(&mut self, ctx: &egui::Context) {
+        let mut clear_frames = false;
+        let mut remove_frame = None;
+        let mut restore_settings = None;
+        egui::Window::new(tr!("Saved frames"))
+            .open(&mut self.saved_frames_open)
+            .default_width(500.0)
+            .default_height(400.0)
             .show(ctx, |ui| {
-                const VERSION: &str = env!("CARGO_PKG_VERSION");
-                ui.heading(format!("pal-rs v{VERSION}"));
+                if self.saved_frames.is_empty() {
+                    ui.label(tr!("Frames you save or copy will show up here, along with the settings they were rendered with."));
+                    return;
+                }
 
+                if ui.button(tr!("Clear")).clicked() {
+                    clear_frames = true;
+                }
                 ui.separator();
 
-                ui.horizontal_wrapped(|ui| {
-                    ui.spacing_mut().item_spacing.x = 0.0;
-                    ui.label("by ");
-                    ui.add(egui::Hyperlink::from_label_and_url(
-                        "LucianoTheWindowsFan",
-                        "https://github.com/LucianoTheWindowsFan/",
-                    ));
-                });
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        ui.horizontal_wrapped(|ui| {
+                            // Newest first
+                            for (index, frame) in self.saved_frames.iter().enumerate().rev() {
+                                ui.vertical(|ui| {
+                                    ui.set_width(160.0);
+                                    ui.add(egui::Image::new(&frame.texture).max_width(160.0).max_height(120.0))
+                                        .on_hover_ui(|ui| {
+                                            ui.add(egui::Image::new(&frame.texture).max_width(640.0).max_height(480.0));
+                                        });
+                                    ui.horizontal(|ui| {
+                                        ui.label(clock_time_format(frame.position.nseconds()));
+                                        ui.label(egui::RichText::new(format!("#{}", &frame.settings_hash[..8])).monospace())
+                                            .on_hover_text(tr!("Settings hash: {settings_hash}", settings_hash = frame.settings_hash));
+                                    });
+                                    ui.horizontal(|ui| {
+                                        if ui
+                                            .button(tr!("Restore settings"))
+                                            .on_hover_text(tr!("Go back to the settings this frame was rendered with"))
+                                            .clicked()
+                                        {
+                                            restore_settings = Some(frame.settings.clone());
+                                        }
+                                        if ui.button("🗙").on_hover_text(tr!("Remove from the gallery")).clicked() {
+                                            remove_frame = Some(index);
+                                        }
+                                    });
+                                });
+                            }
+                        });
+                    });
+            });
 
-                ui.horizontal_wrapped(|ui| {
-                    ui.spacing_mut().item_spacing.x = 0.0;
-                    ui.label("...loosely based on ");
-                    ui.add(egui::Hyperlink::from_label_and_url(
-                        "valadaptive/ntsc-rs",
-                        "https://github.com/valadaptive/ntsc-rs/",
-                    ));
-                });
+        if clear_frames {
+            self.saved_frames.clear();
+        }
+        if let Some(index) = remove_frame {
+            self.saved_frames.remove(index);
+        }
+        if let Some(settings) = restore_settings {
+            self.effect_settings = settings;
+            self.update_effect();
+        }
+    }
 
-                ui.horizontal_wrapped(|ui| {
-                    ui.spacing_mut().item_spacing.x = 0.0;
-                    ui.label("...irself based on ");
-                    ui.add(egui::Hyperlink::from_label_and_url(
-                        "JargeZ/ntscqt",
-                        "https://github.com/JargeZ/ntscqt/",
-                    ));
-                });
+    fn show_error_log(&mut self, ctx: &egui::Context) {
+        let mut clear_errors = false;
+        egui::Window::new(tr!("Errors"))
+            .open(&mut self.error_log_open)
+            .default_width(400.0)
+            .default_height(300.0)
+            .show(ctx, |ui| {
+                if self.error_log.is_empty() {
+                    ui.label(tr!("No errors so far."));
+                    return;
+                }
 
-                ui.horizontal_wrapped(|ui| {
-                    ui.spacing_mut().item_spacing.x = 0.0;
-                    ui.label("...which is a GUI for ");
-                    ui.add(egui::Hyperlink::from_label_and_url(
-                        "zhuker/ntsc",
-                        "https://github.com/zhuker/ntsc/",
-                    ));
-                });
+                if ui.button(tr!("Clear")).clicked() {
+                    clear_errors = true;
+                }
+                ui.separator();
 
-                ui.horizontal_wrapped(|ui| {
-                    ui.spacing_mut().item_spacing.x = 0.0;
-                    ui.label("...which is a port of ");
-                    ui.add(egui::Hyperlink::from_label_and_url(
-                        "joncampbell123/composite-video-simulator",
-                        "https://github.com/joncampbell123/composite-video-simulator/",
-                    ));
-                });
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        // Newest first
+                        for (i, entry) in self.error_log.iter().rev().enumerate() {
+                            if i != 0 {
+                                ui.separator();
+                            }
+                            ui.horizontal(|ui| {
+                                ui.weak(entry.age());
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        if ui
+                                            .button(tr!("Copy"))
+                                            .on_hover_text(tr!("Copy error details"))
+                                            .clicked()
+                                        {
+                                            ui.output_mut(|output| {
+                                                output.copied_text = entry.details.clone()
+                                            });
+                                        }
+                                    },
+                                );
+                            });
+                            ui.label(&entry.message);
+                        }
+                    });
             });
+
+        if clear_errors {
+            self.error_log.clear();
+            self.unseen_errors = 0;
+        }
     }
 
     fn show_licenses_dialog(&mut self, ctx: &egui::Context) {
-        egui::Window::new("Licenses")
+        egui::Window::new(tr!("Licenses"))
             .open(&mut self.licenses_dialog_open)
             .default_width(400.0)
             .default_height(400.0)
@@ -2765,7 +9961,7 @@ impl NtscApp {
                                     ui.label(&license.text);
                                 });
                             ui.indent(i, |ui| {
-                                ui.label("Used by:");
+                                ui.label(tr!("Used by:"));
                                 for used_by in license.used_by.iter() {
                                     ui.add(egui::Hyperlink::from_label_and_url(
                                         format!("{} {}", used_by.name, used_by.version),
@@ -2775,98 +9971,383 @@ impl NtscApp {
                             });
                         }
                     });
-            });
-    }
+            });
+    }
+
+    fn show_app(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
+            ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
+                ui.menu_button(tr!("File"), |ui| {
+                    if ui.button(tr!("Open")).clicked() {
+                        let file_dialog = rfd::AsyncFileDialog::new().pick_file();
+                        let ctx = ctx.clone();
+                        self.spawn(async move {
+                            let handle = file_dialog.await;
+
+                            Some(Box::new(move |app: &mut NtscApp| match handle {
+                                Some(handle) => app.load_video(&ctx, handle.into()),
+                                None => Ok(()),
+                            }) as _)
+                        });
+
+                        ui.close_menu();
+                    }
+                    if ui
+                        .button(tr!("Open URL"))
+                        .on_hover_text(tr!("Open a video streamed over HTTP or RTSP."))
+                        .clicked()
+                    {
+                        self.open_url = Some(String::new());
+                        ui.close_menu();
+                    }
+                    if ui
+                        .button(tr!("Capture from camera"))
+                        .on_hover_text(tr!("Preview the effect live on your camera's video. Rendering records from the camera for the chosen duration."))
+                        .clicked()
+                    {
+                        let res = self.load_camera(ctx);
+                        self.handle_result(res);
+                        ui.close_menu();
+                    }
+                    if ui
+                        .button(tr!("Open image sequence"))
+                        .on_hover_text(
+                            tr!("Open a folder of numbered images (e.g. frame_0001.png, frame_0002.png, ...) as a video. \
+                            Pick any one of the frames."),
+                        )
+                        .clicked()
+                    {
+                        let file_dialog = rfd::AsyncFileDialog::new()
+                            .add_filter("Images", &["png", "jpg", "jpeg", "tif", "tiff", "bmp", "webp"])
+                            .pick_file();
+                        let ctx = ctx.clone();
+                        self.spawn(async move {
+                            let handle = file_dialog.await;
+
+                            Some(Box::new(move |app: &mut NtscApp| {
+                                let Some(handle) = handle else {
+                                    return Ok(());
+                                };
+                                let frame_path = PathBuf::from(handle);
+                                // Fall back to opening the single image if its name isn't numbered
+                                let path =
+                                    sequence_pattern_for_frame(&frame_path).unwrap_or(frame_path);
+                                app.load_video(&ctx, path)
+                            }) as _)
+                        });
+
+                        ui.close_menu();
+                    }
+                    if ui
+                        .add_enabled(
+                            self.pipeline.is_some(),
+                            egui::Button::new(tr!("Copy pipeline description")),
+                        )
+                        .on_hover_text(
+                            tr!("Copy the preview's GStreamer pipeline in gst-launch-1.0 syntax, for bug reports. Render \
+                            jobs' pipelines can be copied by right-clicking them."),
+                        )
+                        .clicked()
+                    {
+                        if let Some(info) = &self.pipeline {
+                            let description = describe_pipeline(&info.pipeline);
+                            ui.output_mut(|output| output.copied_text = description);
+                        }
+                        ui.close_menu();
+                    }
+                    if ui.button(tr!("Quit")).clicked() {
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                        ui.close_menu();
+                    }
+                });
+
+                ui.menu_button(tr!("Edit"), |ui| {
+                    if ui
+                        .add_enabled(
+                            self.undo_history.has_undo(&self.effect_settings),
+                            egui::Button::new(tr!("Undo")),
+                        )
+                        .clicked()
+                    {
+                        self.undo();
+                        ui.close_menu();
+                    }
+                    if ui
+                        .add_enabled(
+                            self.undo_history.has_redo(&self.effect_settings),
+                            egui::Button::new(tr!("Redo")),
+                        )
+                        .clicked()
+                    {
+                        self.redo();
+                        ui.close_menu();
+                    }
+
+                    ui.separator();
+
+                    if ui.button(tr!("Keyboard shortcuts...")).clicked() {
+                        self.keyboard_shortcuts_open = true;
+                        ui.close_menu();
+                    }
+
+                    let mut max_undos = self.undo_history.max_undos;
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("Undo steps:"));
+                        if ui
+                            .add(egui::DragValue::new(&mut max_undos).clamp_range(1..=10000))
+                            .on_hover_text(tr!("Maximum number of undo steps to keep. Changing this clears the undo history."),
+                            )
+                            .changed()
+                        {
+                            self.set_max_undos(max_undos);
+                        }
+                    });
+
+                    #[cfg(feature = "gpu")]
+                    {
+                        let mut use_gpu = self.use_gpu;
+                        if ui
+                            .checkbox(&mut use_gpu, tr!("GPU acceleration (experimental)"))
+                            .on_hover_text(tr!("Run the effect's most expensive filters on the GPU. The rest of the effect still runs on the CPU. Stays off if no usable GPU is found. Output may differ imperceptibly from the CPU's."))
+                            .changed()
+                        {
+                            self.set_use_gpu(use_gpu);
+                        }
+                    }
+                });
+
+                ui.menu_button(tr!("View"), |ui| {
+                    ui.menu_button(tr!("Theme"), |ui| {
+                        let mut color_theme_changed = false;
+                        color_theme_changed |= ui
+                            .selectable_value(&mut self.color_theme, ColorTheme::System, tr!("System"))
+                            .on_hover_text(tr!("Follow system color theme"))
+                            .changed();
+                        color_theme_changed |= ui
+                            .selectable_value(&mut self.color_theme, ColorTheme::Light, tr!("Light"))
+                            .on_hover_text(tr!("Use light mode"))
+                            .changed();
+                        color_theme_changed |= ui
+                            .selectable_value(&mut self.color_theme, ColorTheme::Dark, tr!("Dark"))
+                            .on_hover_text(tr!("Use dark mode"))
+                            .changed();
+
+                        ui.separator();
+
+                        let mut accent_changed = false;
+                        ui.horizontal(|ui| {
+                            let mut custom_accent = self.accent_color.is_some();
+                            if ui
+                                .checkbox(&mut custom_accent, tr!("Accent color"))
+                                .on_hover_text(tr!("Use a custom color for selections, links, and highlighted controls"))
+                                .changed()
+                            {
+                                self.accent_color = custom_accent
+                                    .then(|| ui.visuals().selection.bg_fill);
+                                accent_changed = true;
+                            }
+                            if let Some(accent_color) = &mut self.accent_color {
+                                accent_changed |= ui.color_edit_button_srgba(accent_color).changed();
+                            }
+                        });
+
+                        if color_theme_changed || accent_changed {
+                            // Results in a bit of "theme tearing" since every widget rendered after this will use a
+                            // different color scheme than those rendered before it. Not really noticeable in practice.
+                            ui.ctx()
+                                .set_visuals(self.color_theme.visuals(frame.info(), self.accent_color));
+                        }
+                        // Keep the menu open while the accent color is being picked
+                        if color_theme_changed {
+                            ui.close_menu();
+                        }
+                    });
+
+                    ui.menu_button(tr!("Language"), |ui| {
+                        let current_code = i18n::current_language().code;
+                        for language in i18n::LANGUAGES {
+                            if ui
+                                .selectable_label(language.code == current_code, language.name)
+                                .clicked()
+                            {
+                                i18n::set_language(language.code);
+                                ui.close_menu();
+                            }
+                        }
+                    });
 
-    fn show_app(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
-        egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
-            ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
-                ui.menu_button("File", |ui| {
-                    if ui.button("Open").clicked() {
-                        let file_dialog = rfd::AsyncFileDialog::new().pick_file();
-                        let ctx = ctx.clone();
-                        self.spawn(async move {
-                            let handle = file_dialog.await;
+                    if ui
+                        .checkbox(&mut self.show_thumbnails, tr!("Timeline thumbnails"))
+                        .on_hover_text(tr!("Show frames from the video along the timeline. Generating them takes some extra CPU time when loading a video."))
+                        .changed()
+                    {
+                        self.update_thumbnails(ui.ctx());
+                    }
 
-                            Some(Box::new(move |app: &mut NtscApp| match handle {
-                                Some(handle) => app.load_video(&ctx, handle.into()),
-                                None => Ok(()),
-                            }) as _)
+                    ui.menu_button(tr!("Video background"), |ui| {
+                        ui.radio_value(&mut self.video_background, VideoBackground::None, tr!("None"));
+                        ui.radio_value(
+                            &mut self.video_background,
+                            VideoBackground::Checkerboard,
+                            tr!("Checkerboard"),
+                        )
+                        .on_hover_text(tr!("Show a checkerboard behind the video, to make transparent areas visible"));
+                        ui.horizontal(|ui| {
+                            let mut color = match self.video_background {
+                                VideoBackground::Solid(color) => color,
+                                _ => egui::Color32::BLACK,
+                            };
+                            let radio_clicked = ui
+                                .radio(
+                                    matches!(self.video_background, VideoBackground::Solid(_)),
+                                    tr!("Solid color"),
+                                )
+                                .clicked();
+                            let color_changed = ui.color_edit_button_srgba(&mut color).changed();
+                            if radio_clicked || color_changed {
+                                self.video_background = VideoBackground::Solid(color);
+                            }
                         });
+                    });
 
-                        ui.close_menu();
-                    }
-                    if ui.button("Quit").clicked() {
-                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
-                        ui.close_menu();
+                    let overlay_changed = ui
+                        .checkbox(&mut self.show_performance_overlay, tr!("Performance overlay"))
+                        .on_hover_text(tr!("Show how many frames per second the preview is rendering, and how long each frame takes to process"))
+                        .changed();
+                    let profile_changed = ui
+                        .add_enabled(
+                            self.show_performance_overlay,
+                            egui::Checkbox::new(&mut self.profile_effect_stages, tr!("Profile effect stages")),
+                        )
+                        .on_hover_text(tr!("Also show how long each stage of the effect takes, to find out which settings are expensive"))
+                        .changed();
+                    if overlay_changed || profile_changed {
+                        self.update_profile_stages();
                     }
-                });
 
-                ui.menu_button("Edit", |ui| {
+                    ui.menu_button(tr!("Guides"), |ui| {
+                        ui.checkbox(&mut self.guides.thirds, tr!("Rule of thirds"));
+                        ui.checkbox(&mut self.guides.center, tr!("Center cross"));
+                        ui.checkbox(&mut self.guides.safe_4_3, tr!("4:3 safe areas"))
+                            .on_hover_text(tr!("Action and title safe areas for 4:3 video"));
+                        ui.checkbox(&mut self.guides.safe_16_9, tr!("16:9 safe areas"))
+                            .on_hover_text(tr!("Action and title safe areas for 16:9 video"));
+                    });
+
                     if ui
-                        .add_enabled(
-                            self.undoer.has_undo(&mut self.effect_settings),
-                            egui::Button::new("Undo"),
-                        )
+                        .button(tr!("Reset view"))
+                        .on_hover_text(tr!("Reset the preview's zoom, scale, volume, and effect preview mode. The effect settings are left alone."))
                         .clicked()
                     {
-                        self.undo();
+                        self.reset_view();
+                        ui.close_menu();
+                    }
+
+                    if ui.button(tr!("Errors")).clicked() {
+                        self.error_log_open = true;
+                        self.unseen_errors = 0;
                         ui.close_menu();
                     }
+
                     if ui
-                        .add_enabled(
-                            self.undoer.has_redo(&mut self.effect_settings),
-                            egui::Button::new("Redo"),
-                        )
+                        .button(tr!("Saved frames"))
+                        .on_hover_text(tr!("Show the frames saved or copied this session, to compare them and restore the settings they were rendered with"))
                         .clicked()
                     {
-                        self.redo();
+                        self.saved_frames_open = true;
                         ui.close_menu();
                     }
-                });
-
-                ui.menu_button("View", |ui| {
-                    ui.menu_button("Theme", |ui| {
-                        let mut color_theme_changed = false;
-                        color_theme_changed |= ui
-                            .selectable_value(&mut self.color_theme, ColorTheme::System, "System")
-                            .on_hover_text("Follow system color theme")
-                            .changed();
-                        color_theme_changed |= ui
-                            .selectable_value(&mut self.color_theme, ColorTheme::Light, "Light")
-                            .on_hover_text("Use light mode")
-                            .changed();
-                        color_theme_changed |= ui
-                            .selectable_value(&mut self.color_theme, ColorTheme::Dark, "Dark")
-                            .on_hover_text("Use dark mode")
-                            .changed();
 
-                        if color_theme_changed {
-                            // Results in a bit of "theme tearing" since every widget rendered after this will use a
-                            // different color scheme than those rendered before it. Not really noticeable in practice.
-                            ui.ctx().set_visuals(self.color_theme.visuals(frame.info()));
-                            ui.close_menu();
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("UI scale:"));
+                        let response = ui.add(
+                            egui::Slider::new(&mut self.ui_scale, UI_SCALE_RANGE)
+                                .step_by(0.05)
+                                .custom_formatter(|value, _| format!("{value:.2}x")),
+                        );
+                        // Rescaling the UI mid-drag would move the slider out from under the pointer, so wait until
+                        // it's let go of
+                        if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                            ui.ctx().set_zoom_factor(self.ui_scale);
+                        }
+                        if ui
+                            .add_enabled(self.ui_scale != 1.0, egui::Button::new(tr!("Reset")))
+                            .clicked()
+                        {
+                            self.ui_scale = 1.0;
+                            ui.ctx().set_zoom_factor(self.ui_scale);
                         }
                     });
+
+                    ui.checkbox(&mut self.pause_when_unfocused, tr!("Pause when in background"))
+                        .on_hover_text(tr!("Pause the preview while the window isn't focused, and resume it when it's focused again. Renders keep running."));
+
+                    ui.checkbox(&mut self.remember_positions, tr!("Remember playback positions"))
+                        .on_hover_text(tr!("When reopening a video, pick up where you left off"));
                 });
 
-                ui.menu_button("Help", |ui| {
-                    if ui.button("Online Documentation ⤴").clicked() {
+                ui.menu_button(tr!("Help"), |ui| {
+                    if ui.button(tr!("Online Documentation ⤴")).clicked() {
                         ui.ctx().open_url(egui::OpenUrl::new_tab(
                             "https://ntsc.rs/docs/standalone-application/",
                         ));
                         ui.close_menu();
                     }
 
-                    if ui.button("Third-Party Licenses").clicked() {
+                    if ui.button(tr!("Third-Party Licenses")).clicked() {
                         self.licenses_dialog_open = true;
                         ui.close_menu();
                     }
 
-                    if ui.button("About + Credits").clicked() {
+                    if ui.button(tr!("About + Credits")).clicked() {
                         self.credits_dialog_open = true;
                         ui.close_menu();
                     }
+
+                    ui.separator();
+
+                    ui.menu_button(tr!("GStreamer debug log"), |ui| {
+                        for level in DebugLogLevel::ALL {
+                            if ui
+                                .radio_value(&mut self.debug_log_level, *level, tr!(level.label()))
+                                .changed()
+                            {
+                                level.apply();
+                            }
+                        }
+
+                        ui.separator();
+
+                        if let Some(debug_log_file) = &self.debug_log_file {
+                            if ui
+                                .button(tr!("Stop saving log"))
+                                .on_hover_text(debug_log_file.path().to_string_lossy())
+                                .clicked()
+                            {
+                                self.debug_log_file = None;
+                                ui.close_menu();
+                            }
+                        } else if ui
+                            .button(tr!("Save log to file..."))
+                            .on_hover_text(tr!("Copy GStreamer's log messages into a file, for attaching to bug reports."))
+                            .clicked()
+                        {
+                            let file_dialog = rfd::AsyncFileDialog::new()
+                                .set_file_name("gstreamer.log")
+                                .save_file();
+                            self.spawn(async move {
+                                let handle = file_dialog.await?;
+                                Some(Box::new(move |app: &mut NtscApp| {
+                                    app.debug_log_file = Some(
+                                        DebugLogFile::create(handle.path())
+                                            .context(DebugLogCreateSnafu)?,
+                                    );
+                                    Ok(())
+                                }) as _)
+                            });
+                            ui.close_menu();
+                        }
+                    });
                 });
 
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
@@ -2874,30 +10355,56 @@ impl NtscApp {
                     ui.label(format!("ntsc-rs v{VERSION}"));
 
                     let mut close_error = false;
-                    if let Some(error) = self.last_error.as_ref() {
+                    let mut open_error_log = false;
+                    if let Some(error) = self
+                        .error_log
+                        .back()
+                        .filter(|_| self.unseen_errors > 0)
+                    {
                         egui::Frame::none()
                             .rounding(3.0)
                             .stroke(ui.style().noninteractive().fg_stroke)
                             .inner_margin(ui.style().spacing.button_padding)
                             .show(ui, |ui| {
-                                if ui.button("OK").clicked() {
+                                if ui.button(tr!("OK")).clicked() {
                                     close_error = true;
                                 }
-                                ui.label(error);
+                                if ui
+                                    .button("📋")
+                                    .on_hover_text(tr!("Copy error details"))
+                                    .clicked()
+                                {
+                                    ui.output_mut(|output| {
+                                        output.copied_text = error.details.clone()
+                                    });
+                                }
+                                if self.unseen_errors > 1
+                                    && ui
+                                        .button(tr!("{count} errors", count = self.unseen_errors))
+                                        .on_hover_text(tr!("Show all recent errors"))
+                                        .clicked()
+                                {
+                                    open_error_log = true;
+                                }
+                                ui.label(&error.message);
                                 ui.colored_label(egui::Color32::YELLOW, "⚠");
                             });
                     }
                     if close_error {
-                        self.last_error = None;
+                        self.unseen_errors = 0;
+                    }
+                    if open_error_log {
+                        self.error_log_open = true;
+                        self.unseen_errors = 0;
                     }
                 });
             });
         });
 
-        egui::SidePanel::left("controls")
+        let left_panel = egui::SidePanel::left("controls")
             .frame(egui::Frame::side_top_panel(&ctx.style()).inner_margin(0.0))
             .resizable(true)
-            .default_width(425.0)
+            .default_width(self.left_panel_width)
             .width_range(300.0..=800.0)
             .show(ctx, |ui| {
                 ui.visuals_mut().clip_rect_margin = 0.0;
@@ -2906,12 +10413,17 @@ impl NtscApp {
                         ui.selectable_value(
                             &mut self.left_panel_state,
                             LeftPanelState::EffectSettings,
-                            "Effect",
+                            tr!("Effect"),
                         );
                         ui.selectable_value(
                             &mut self.left_panel_state,
                             LeftPanelState::RenderSettings,
-                            "Render",
+                            tr!("Render"),
+                        );
+                        ui.selectable_value(
+                            &mut self.left_panel_state,
+                            LeftPanelState::History,
+                            tr!("History"),
                         );
                     });
                 });
@@ -2925,8 +10437,12 @@ impl NtscApp {
                         LeftPanelState::RenderSettings => {
                             self.show_render_settings(ui);
                         }
+                        LeftPanelState::History => {
+                            self.show_undo_history(ui);
+                        }
                     });
             });
+        self.left_panel_width = left_panel.response.rect.width();
 
         egui::CentralPanel::default()
             .frame(egui::Frame::side_top_panel(&ctx.style()).inner_margin(0.0))
@@ -2942,6 +10458,134 @@ impl NtscApp {
         if self.licenses_dialog_open {
             self.show_licenses_dialog(ctx);
         }
+
+        if self.error_log_open {
+            self.show_error_log(ctx);
+        }
+
+        if self.saved_frames_open {
+            self.show_saved_frames(ctx);
+        }
+
+        if self.save_preset_open {
+            self.show_save_preset(ctx);
+        }
+
+        if self.keyboard_shortcuts_open {
+            self.show_keyboard_shortcuts_dialog(ctx);
+        } else {
+            self.rebinding_shortcut = None;
+        }
+
+        if self.recovered_settings.is_some() {
+            self.show_recovery_dialog(ctx);
+        }
+
+        if self.confirm_reset_settings {
+            self.show_reset_settings_dialog(ctx);
+        }
+
+        if self.pending_preset.is_some() {
+            self.show_preset_diff_dialog(ctx);
+        }
+
+        if self.variation_grid.is_some() {
+            self.show_variation_grid_dialog(ctx);
+        }
+
+        if self.open_url.is_some() {
+            self.show_open_url_dialog(ctx);
+        }
+
+        self.show_toasts(ctx);
+    }
+
+    fn show_toasts(&mut self, ctx: &egui::Context) {
+        self.toasts
+            .retain(|toast| toast.time.elapsed() < TOAST_DURATION);
+        if self.toasts.is_empty() {
+            return;
+        }
+
+        // Keep them clear of the status bar
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, vec2(-12.0, -40.0))
+            .order(egui::Order::Foreground)
+            .interactable(false)
+            .show(ctx, |ui| {
+                for toast in &self.toasts {
+                    // Fade out over the last half second
+                    let remaining = TOAST_DURATION
+                        .saturating_sub(toast.time.elapsed())
+                        .as_secs_f32();
+                    ui.set_opacity((remaining / 0.5).min(1.0));
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.label(&toast.message);
+                    });
+                }
+            });
+
+        ctx.request_repaint();
+    }
+
+    /// Show the average progress of the renders that haven't finished yet in the window title, so it can be seen in the
+    /// taskbar while the window is minimized.
+    fn update_window_title(&mut self, ctx: &egui::Context) {
+        let (num_active_jobs, total_progress) = self
+            .render_jobs
+            .iter()
+            .filter(|job| {
+                matches!(
+                    *job.state.lock().unwrap(),
+                    RenderJobState::Waiting | RenderJobState::Rendering | RenderJobState::Paused
+                )
+            })
+            .fold((0, 0.0), |(count, progress), job| {
+                (count + 1, progress + job.last_progress)
+            });
+        let title = if num_active_jobs > 0 {
+            tr!(
+                "ntsc-rs — rendering {percent}%",
+                percent = format!("{:.0}", total_progress / num_active_jobs as f64 * 100.0)
+            )
+        } else {
+            String::from("ntsc-rs")
+        };
+
+        if title != self.window_title {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(title.clone()));
+            self.window_title = title;
+        }
+    }
+
+    /// Show a toast for each render job that has finished since the last frame.
+    fn announce_finished_renders(&mut self) {
+        let mut messages = Vec::new();
+        for job in self
+            .render_jobs
+            .iter_mut()
+            .filter(|job| !job.announced_finish)
+        {
+            let file_name = job
+                .settings
+                .output_path
+                .file_name()
+                .map(|file_name| file_name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            match *job.state.lock().unwrap() {
+                RenderJobState::Complete { .. } => {
+                    messages.push(tr!("Finished rendering {file_name}", file_name = file_name))
+                }
+                // Errors already show up in the render queue
+                RenderJobState::Error(_) => {}
+                _ => continue,
+            }
+            job.announced_finish = true;
+        }
+
+        for message in messages {
+            self.show_toast(message);
+        }
     }
 
     fn show_loading_screen(&mut self, ctx: &egui::Context) {
@@ -2952,36 +10596,194 @@ impl NtscApp {
         });
     }
 
+    fn show_initialization_error_screen(&mut self, ctx: &egui::Context, err: &GstreamerError) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(ui.available_height() * 0.3);
+                ui.heading(tr!("GStreamer failed to initialize"));
+                ui.label(
+                    tr!("pal-rs needs GStreamer and its plugins to work. Your GStreamer installation may be missing or \
+                    incomplete."),
+                );
+                ui.add_space(8.0);
+                ui.colored_label(ui.visuals().error_fg_color, err.to_string());
+                ui.add_space(8.0);
+                if ui.button(tr!("Copy details")).clicked() {
+                    ui.output_mut(|output| output.copied_text = format!("{err:?}"));
+                }
+                if ui.button(tr!("Quit")).clicked() {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                }
+            });
+        });
+    }
+
+    /// While a shortcut is being rebound, take the next key press as its new shortcut. Escape cancels.
+    fn record_shortcut(&mut self, ctx: &egui::Context) {
+        let Some((action, index)) = self.rebinding_shortcut else {
+            return;
+        };
+        let pressed = ctx.input(|input| {
+            input.events.iter().find_map(|event| match event {
+                egui::Event::Key {
+                    key,
+                    pressed: true,
+                    repeat: false,
+                    modifiers,
+                    ..
+                } => Some((*key, *modifiers)),
+                _ => None,
+            })
+        });
+        match pressed {
+            Some((egui::Key::Escape, modifiers)) if modifiers.is_none() => {
+                self.rebinding_shortcut = None;
+            }
+            Some((key, modifiers)) => {
+                self.keyboard_shortcuts.set(
+                    action,
+                    index,
+                    egui::KeyboardShortcut::new(shortcut_modifiers(modifiers), key),
+                );
+                self.rebinding_shortcut = None;
+            }
+            None => {}
+        }
+    }
+
     fn handle_keyboard_shortcuts(&mut self, ctx: &egui::Context) {
-        // Seems to deadlock if we call undo() / redo() inside the ctx.input callback, probably due to Undoer accessing
-        // context state from behind a mutex.
-        let (should_undo, should_redo) = ctx.input(|input| {
-            (
-                // Note that we match command/ctrl *only*; otherwise Ctrl+Shift+Z would count as undo since Ctrl+Z is a subset of Ctrl+Shift+Z
-                input.modifiers.command_only() && input.key_pressed(egui::Key::Z),
-                (input.modifiers.command_only() && input.key_pressed(egui::Key::Y))
-                    || (input
-                        .modifiers
-                        .matches_exact(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT)
-                        && input.key_pressed(egui::Key::Z)),
-            )
+        if self.rebinding_shortcut.is_some() {
+            self.record_shortcut(ctx);
+            return;
+        }
+
+        let wants_keyboard_input = ctx.wants_keyboard_input();
+        // Don't call undo() / redo() inside the ctx.input callback. Updating the effect touches context state, which
+        // is behind the same lock the callback holds.
+        let pressed = ctx.input(|input| {
+            ShortcutAction::ALL
+                .iter()
+                .copied()
+                .filter(|action| {
+                    (action.works_while_typing() || !wants_keyboard_input)
+                        && self.keyboard_shortcuts.pressed(input, *action)
+                })
+                .collect::<Vec<_>>()
         });
-        if should_undo {
+        let is_pressed = |action: ShortcutAction| pressed.contains(&action);
+
+        if is_pressed(ShortcutAction::Undo) {
             self.undo();
-        } else if should_redo {
+        } else if is_pressed(ShortcutAction::Redo) {
             self.redo();
         }
+
+        if is_pressed(ShortcutAction::PlayPause) {
+            if let Some(res) = self.pipeline.as_ref().map(|info| info.toggle_playing()) {
+                self.handle_result(res);
+            }
+        }
+
+        if is_pressed(ShortcutAction::StepBackward) || is_pressed(ShortcutAction::StepForward) {
+            let forward = is_pressed(ShortcutAction::StepForward);
+            if let Some(res) = self.pipeline.as_ref().map(|info| info.step_frame(forward)) {
+                self.handle_result(res);
+            }
+        }
+
+        if is_pressed(ShortcutAction::SaveFrame) && self.pipeline.is_some() {
+            self.save_frame_requested = true;
+        }
+
+        if is_pressed(ShortcutAction::ZoomActualSize) {
+            self.video_zoom.scale = 1.0;
+            self.video_zoom.fit = false;
+        } else if is_pressed(ShortcutAction::ZoomToFit) {
+            self.video_zoom.fit = true;
+        }
+
+        if is_pressed(ShortcutAction::CyclePreview) {
+            self.effect_preview.mode = match self.effect_preview.mode {
+                EffectPreviewMode::Enabled => EffectPreviewMode::Disabled,
+                EffectPreviewMode::Disabled => EffectPreviewMode::SplitScreen,
+                EffectPreviewMode::SplitScreen | EffectPreviewMode::Channel => {
+                    EffectPreviewMode::Enabled
+                }
+            };
+            self.update_effect_preview();
+        }
+
+        let (jump_to_start, jump_to_end) = (
+            is_pressed(ShortcutAction::JumpToStart),
+            is_pressed(ShortcutAction::JumpToEnd),
+        );
+        if let Some(info) = &self.pipeline {
+            let seek_to = if jump_to_start {
+                Some(ClockTime::ZERO)
+            } else if jump_to_end {
+                info.last_frame_position()
+            } else {
+                None
+            };
+            if let Some(seek_to) = seek_to {
+                let _ = info.seek(seek_to);
+            }
+        }
+
+        let (previous_marker, next_marker) = (
+            is_pressed(ShortcutAction::PreviousMarker),
+            is_pressed(ShortcutAction::NextMarker),
+        );
+        if let Some(info) = &self.pipeline {
+            let position = info
+                .pipeline
+                .query_position::<ClockTime>()
+                .unwrap_or(info.last_seek_pos);
+            // Accurate seeks don't always land exactly on the requested time, so leave some slack to avoid jumping to
+            // the marker we're already at
+            let tolerance = ClockTime::MSECOND;
+            let seek_to = if previous_marker {
+                info.markers
+                    .iter()
+                    .rev()
+                    .find(|(time, _)| *time + tolerance < position)
+            } else if next_marker {
+                info.markers
+                    .iter()
+                    .find(|(time, _)| *time > position + tolerance)
+            } else {
+                None
+            };
+            if let Some((seek_to, _)) = seek_to {
+                let _ = info.seek(*seek_to);
+            }
+        }
     }
 }
 
 impl eframe::App for NtscApp {
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
-        if !self.gstreamer_initialized.load(Ordering::Acquire) {
-            self.show_loading_screen(ctx);
-            return;
+        match self.gstreamer_initialized.get() {
+            None => {
+                self.show_loading_screen(ctx);
+                return;
+            }
+            Some(Err(err)) => {
+                let err = err.clone();
+                self.show_initialization_error_screen(ctx, &err);
+                return;
+            }
+            Some(Ok(())) => {}
         }
 
         self.tick();
+        self.tick_autosave(ctx);
+
+        // The zoom can also be changed with Ctrl +/-. Don't pick that up mid-drag, since the UI scale slider only
+        // applies its value once it's let go of.
+        if ctx.dragged_id().is_none() {
+            self.ui_scale = ctx.zoom_factor();
+        }
 
         let mut pipeline_error = None::<PipelineError>;
         if let Some(pipeline) = &self.pipeline {
@@ -3003,17 +10805,52 @@ impl eframe::App for NtscApp {
             };
         }
 
+        // Seeking only works once the pipeline has reached PAUSED
+        if let Some(info) = &mut self.pipeline {
+            let loaded = matches!(*info.state.lock().unwrap(), PipelineInfoState::Loaded);
+            if let Some(position) = info.resume_position.filter(|_| loaded) {
+                info.resume_position = None;
+                info.last_seek_pos = position;
+                let res = info.seek(position);
+                self.handle_result(res);
+            }
+        }
+
         if let Some(err) = pipeline_error {
+            let missing_codec = self
+                .pipeline
+                .as_ref()
+                .and_then(|info| info.metadata.lock().unwrap().missing_codec.clone());
             let _ = self.remove_pipeline();
-            self.handle_error(&err);
+            if missing_codec.is_some() || is_decode_error(&err) {
+                self.handle_error(&ApplicationError::UnsupportedFile {
+                    codec: missing_codec,
+                });
+            } else {
+                self.handle_error(&err);
+            }
         }
 
+        self.update_focus_pause(ctx);
         self.handle_keyboard_shortcuts(ctx);
+        self.handle_dropped_files(ctx);
 
         self.show_app(ctx, frame);
-
-        self.undoer
-            .feed_state(ctx.input(|input| input.time), &self.effect_settings);
+        self.update_snapshot_compare(ctx);
+        self.update_fast_scrubbing();
+        self.update_auto_bandwidth_scale();
+        self.update_watch_folder();
+        self.update_batch_render(ctx);
+        self.announce_finished_renders();
+        self.update_window_title(ctx);
+
+        // Don't record intermediate states while a slider is being dragged or a value is being typed in, so that each
+        // of those interactions becomes a single undo step.
+        let interaction_in_progress = ctx.dragged_id().is_some() || ctx.wants_keyboard_input();
+        if !interaction_in_progress {
+            self.undo_history
+                .feed_state(&self.settings_list, &self.effect_settings);
+        }
     }
 
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
@@ -3029,11 +10866,152 @@ impl eframe::App for NtscApp {
             "color_theme",
             <&ColorTheme as Into<&str>>::into(&self.color_theme).to_owned(),
         );
+        storage.set_string(
+            "accent_color",
+            self.accent_color
+                .map(accent_color_to_hex)
+                .unwrap_or_default(),
+        );
+
+        storage.set_string("left_panel_width", self.left_panel_width.to_string());
+        storage.set_string("max_undos", self.undo_history.max_undos.to_string());
+        if let Ok(settings_slots_json) = self.settings_slots_to_json().stringify() {
+            storage.set_string("settings_slots", settings_slots_json);
+        }
+        storage.set_string("active_slot", self.active_slot.to_string());
+        storage.set_string("smpte_timecode", self.smpte_timecode.to_string());
+        storage.set_string("show_thumbnails", self.show_thumbnails.to_string());
+        storage.set_string("use_gpu", self.use_gpu.to_string());
+        storage.set_string("language", i18n::current_language().code.to_owned());
+        storage.set_string(
+            "show_performance_overlay",
+            self.show_performance_overlay.to_string(),
+        );
+        storage.set_string(
+            "profile_effect_stages",
+            self.profile_effect_stages.to_string(),
+        );
+        storage.set_string("guides", self.guides.to_storage());
+        storage.set_string(
+            "favorite_settings",
+            self.settings_panel_prefs.favorites.to_storage(),
+        );
+        if let Ok(keyboard_shortcuts_json) = self.keyboard_shortcuts.to_json().stringify() {
+            storage.set_string("keyboard_shortcuts", keyboard_shortcuts_json);
+        }
+        storage.set_string("fast_scrubbing", self.fast_scrubbing.to_string());
+        storage.set_string(
+            "auto_bandwidth_scale",
+            self.auto_bandwidth_scale.to_string(),
+        );
+        storage.set_string("square_pixels", self.video_scale.square_pixels.to_string());
+        storage.set_string("zoom_fit_mode", self.video_zoom.fit_mode.name().to_owned());
+        storage.set_string(
+            "full_resolution_frames",
+            self.full_resolution_frames.to_string(),
+        );
+        storage.set_string(
+            "pause_when_unfocused",
+            self.pause_when_unfocused.to_string(),
+        );
+        storage.set_string("remember_positions", self.remember_positions.to_string());
+        self.remember_position();
+        let playback_positions = JsonValue::Array(
+            self.playback_positions
+                .iter()
+                .map(|(path, position)| {
+                    JsonValue::Object(HashMap::from([
+                        (
+                            String::from("path"),
+                            JsonValue::String(path.to_string_lossy().into_owned()),
+                        ),
+                        (
+                            String::from("position_ms"),
+                            JsonValue::Number(position.mseconds() as f64),
+                        ),
+                    ]))
+                })
+                .collect(),
+        );
+        if let Ok(playback_positions) = playback_positions.stringify() {
+            storage.set_string("playback_positions", playback_positions);
+        }
+        storage.set_string("ui_scale", self.ui_scale.to_string());
+        storage.set_string(
+            "gstreamer_debug_level",
+            self.debug_log_level.name().to_owned(),
+        );
+
+        if let Ok(render_settings_json) = self.render_settings.to_json().stringify() {
+            storage.set_string("render_settings", render_settings_json);
+        }
     }
 }
 
 impl Drop for NtscApp {
     fn drop(&mut self) {
         let _ = self.remove_pipeline();
+        // eframe has already saved the settings by now, so there's nothing left to recover
+        if let Some(recovery_path) = recovery_file_path() {
+            let _ = std::fs::remove_file(recovery_path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_queue_keeps_keyframes() {
+        let settings_list = SettingsList::new();
+        let mut keyframes = KeyframeTracks::default();
+        let track = keyframes.track_mut(SettingID::SNOW_INTENSITY);
+        track.insert(Keyframe {
+            time: ClockTime::ZERO,
+            value: 0.0,
+        });
+        track.insert(Keyframe {
+            time: ClockTime::from_mseconds(2500),
+            value: 0.5,
+        });
+        let job = RenderPipelineSettings {
+            codec_settings: (&RenderSettings::default()).into(),
+            output_path: PathBuf::from("queue/out.mp4"),
+            duration: ClockTime::from_seconds(5),
+            interlacing: RenderInterlaceMode::Progressive,
+            audio_codec: None,
+            audio_track: 0,
+            timecode_overlay: None,
+            text_overlay: None,
+            output_framerate: None,
+            preserve_alpha: false,
+            keep_partial_output: false,
+            dither: false,
+            low_memory: false,
+            tonemap: TonemapSettings::default(),
+            full_resolution: false,
+            effect_scanlines: None,
+            region: None,
+            color_matrix: OutputColorMatrix::default(),
+            color_range: OutputColorRange::default(),
+            pass: 0,
+            job_id: next_render_job_id(),
+            effect_settings: NtscEffect::default(),
+            seed: 0,
+            keyframes,
+        };
+
+        let base_directory = Path::new("queue");
+        let json = job
+            .to_json(&base_directory.join("in.mp4"), base_directory, &settings_list)
+            .stringify()
+            .unwrap();
+        let JsonValue::Object(map) = json.parse::<JsonValue>().unwrap() else {
+            unreachable!();
+        };
+        let (_, restored) =
+            RenderPipelineSettings::from_json(&map, base_directory, &settings_list).unwrap();
+        assert_eq!(restored.keyframes, job.keyframes);
     }
 }
@@ -0,0 +1,28 @@
+use std::path::Path;
+
+use gstreamer::{glib, prelude::*};
+
+use super::gstreamer_error::GstreamerError;
+
+/// Stands in for a file path when the source is the camera, so that render jobs know to capture from it again.
+pub const CAMERA_PATH: &str = "camera:";
+
+/// Name of the camera's source element.
+pub const CAMERA_SRC_NAME: &str = "camera_src";
+
+pub fn is_camera(path: &Path) -> bool {
+    path == Path::new(CAMERA_PATH)
+}
+
+/// Create a source element for the system's first camera. The device monitor picks the right source for the platform
+/// (e.g. `v4l2src` or `pipewiresrc` on Linux, `mfvideosrc` or `ksvideosrc` on Windows, and `avfvideosrc` on macOS).
+pub fn create_camera_source() -> Result<gstreamer::Element, GstreamerError> {
+    let monitor = gstreamer::DeviceMonitor::new();
+    monitor.add_filter(Some("Video/Source"), None);
+    monitor.start()?;
+    let device = monitor.devices().into_iter().next();
+    monitor.stop();
+
+    let device = device.ok_or_else(|| glib::bool_error!("No camera was found"))?;
+    Ok(device.create_element(Some(CAMERA_SRC_NAME))?)
+}
@@ -30,3 +30,60 @@ pub fn clock_time_parser(input: &str) -> Option<f64> {
         });
     out_value.map(|value| value as f64)
 }
+
+/// The number of frames counted per second of SMPTE timecode. Fractional framerates like 29.97 count at the next
+/// whole framerate, without drop-frame compensation.
+fn timecode_frames_per_second(framerate: gstreamer::Fraction) -> u64 {
+    let (numer, denom) = (framerate.numer() as u64, framerate.denom() as u64);
+    ((numer + denom - 1) / denom).max(1)
+}
+
+/// Format a time as a HH:MM:SS:FF SMPTE timecode, using the frame that's being shown at that time.
+pub fn smpte_timecode_format(value: u64, framerate: gstreamer::Fraction) -> String {
+    if framerate.numer() <= 0 || framerate.denom() <= 0 {
+        return clock_time_format(value);
+    }
+
+    let frame = (value as u128 * framerate.numer() as u128
+        / (framerate.denom() as u128 * gstreamer::ClockTime::SECOND.nseconds() as u128))
+        as u64;
+    let frames_per_second = timecode_frames_per_second(framerate);
+    let seconds = frame / frames_per_second;
+    format!(
+        "{:02}:{:02}:{:02}:{:02}",
+        seconds / 3600,
+        (seconds / 60) % 60,
+        seconds % 60,
+        frame % frames_per_second
+    )
+}
+
+/// Parse a HH:MM:SS:FF SMPTE timecode (leading fields can be left out) into a time in nanoseconds. The time returned is
+/// halfway through the frame, so that rounding errors don't land on the previous frame.
+pub fn smpte_timecode_parser(input: &str, framerate: gstreamer::Fraction) -> Option<u64> {
+    if framerate.numer() <= 0 || framerate.denom() <= 0 {
+        return None;
+    }
+
+    let frames_per_second = timecode_frames_per_second(framerate);
+    let mut fields = input.trim().rsplit(':');
+    let frames = fields.next()?.trim().parse::<u64>().ok()?;
+    if frames >= frames_per_second {
+        return None;
+    }
+    let mut seconds = 0u64;
+    for (index, field) in fields.enumerate() {
+        if index > 2 {
+            return None;
+        }
+        const MULTIPLIERS: &[u64] = &[1, 60, 60 * 60];
+        seconds += field.trim().parse::<u64>().ok()? * MULTIPLIERS[index];
+    }
+
+    let frame = (seconds * frames_per_second + frames) as u128;
+    let time = (frame * 2 + 1)
+        * framerate.denom() as u128
+        * gstreamer::ClockTime::SECOND.nseconds() as u128
+        / (framerate.numer() as u128 * 2);
+    time.try_into().ok()
+}
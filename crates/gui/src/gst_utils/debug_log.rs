@@ -0,0 +1,122 @@
+use std::{
+    fs::File,
+    io::{self, LineWriter, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use gstreamer::{log::DebugLogFunction, DebugLevel};
+
+/// How much GStreamer logs, from nothing at all up to debug messages.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugLogLevel {
+    #[default]
+    None,
+    Error,
+    Warning,
+    Info,
+    Debug,
+}
+
+impl DebugLogLevel {
+    pub const ALL: &'static [DebugLogLevel] = &[
+        Self::None,
+        Self::Error,
+        Self::Warning,
+        Self::Info,
+        Self::Debug,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::Error => "Error",
+            Self::Warning => "Warning",
+            Self::Info => "Info",
+            Self::Debug => "Debug",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Info => "info",
+            Self::Debug => "debug",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|level| level.name() == name)
+    }
+
+    fn gst_level(&self) -> DebugLevel {
+        match self {
+            Self::None => DebugLevel::None,
+            Self::Error => DebugLevel::Error,
+            Self::Warning => DebugLevel::Warning,
+            Self::Info => DebugLevel::Info,
+            Self::Debug => DebugLevel::Debug,
+        }
+    }
+
+    /// Make this the threshold for GStreamer debug categories that haven't been given their own through the
+    /// `GST_DEBUG` environment variable.
+    pub fn apply(&self) {
+        gstreamer::debug_set_default_threshold(self.gst_level());
+    }
+}
+
+/// A file that GStreamer's debug log is copied into, in addition to being printed to stderr. Logging to the file stops
+/// once this is dropped.
+pub struct DebugLogFile {
+    path: PathBuf,
+    log_function: Option<DebugLogFunction>,
+}
+
+impl DebugLogFile {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        // Write each line out as it comes in, so the log is complete even if the app crashes
+        let writer = Mutex::new(LineWriter::new(File::create(path)?));
+        let log_function = gstreamer::log::add_log_function(
+            move |category, level, file, function, line, object, message| {
+                let Some(message) = message.get() else {
+                    return;
+                };
+                let object_name = object.map(|object| object.to_string()).unwrap_or_default();
+                let Ok(mut writer) = writer.lock() else {
+                    return;
+                };
+                let _ = writeln!(
+                    writer,
+                    "{:<7} {:<20} {}:{}:{}:<{}> {}",
+                    level.name(),
+                    category.name(),
+                    file,
+                    line,
+                    function,
+                    object_name,
+                    message,
+                );
+            },
+        );
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            log_function: Some(log_function),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for DebugLogFile {
+    fn drop(&mut self) {
+        if let Some(log_function) = self.log_function.take() {
+            gstreamer::log::remove_log_function(log_function);
+        }
+    }
+}
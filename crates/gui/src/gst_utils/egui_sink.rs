@@ -1,29 +1,79 @@
 use eframe::egui::{Context, Rect, TextureFilter, TextureOptions};
-use eframe::epaint::{Color32, ColorImage, TextureHandle};
+use eframe::epaint::{Color32, ColorImage, ImageData, TextureHandle};
 use gstreamer::glib::once_cell::sync::Lazy;
 use gstreamer::prelude::*;
 use gstreamer::{glib, PadTemplate};
 use gstreamer_video::subclass::prelude::*;
 use gstreamer_video::video_frame::Readable;
 use gstreamer_video::VideoFrame;
+use ntscrs::ntsc::{NtscEffect, StageTimings};
 use ntscrs::yiq_fielding::{self, Rgbx8};
 use std::fmt::Debug;
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use super::ntscrs_filter::NtscFilterSettings;
-use super::process_gst_frame::process_gst_frame;
+use super::frame_cache::{FrameCache, FrameKey};
+use super::ntscrs_filter::{NtscFilterRegion, NtscFilterSettings};
+use super::process_gst_frame::{frame_timing, process_gst_frame, process_gst_frame_at};
 
 #[derive(Clone, glib::Boxed, Default)]
 #[boxed_type(name = "SinkTexture")]
 pub struct SinkTexture(pub Option<TextureHandle>);
 
+/// If set, every frame is previewed with the noise of the frame shown at this timestamp, so that one frame's noise can
+/// be looked at on others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, glib::Boxed, Default)]
+#[boxed_type(name = "NoisePin")]
+pub struct NoisePin(pub Option<gstreamer::ClockTime>);
+
 #[derive(Debug, Clone, Copy, PartialEq, glib::Boxed, Default)]
 #[boxed_type(name = "VideoPreviewSetting")]
 pub enum EffectPreviewSetting {
     #[default]
     Enabled,
     Disabled,
-    SplitScreen(Rect),
+    /// Show the effect only within `rect`, or everywhere except within it if `invert` is set.
+    SplitScreen {
+        rect: Rect,
+        invert: bool,
+    },
+    Channel(PreviewChannel),
+}
+
+/// A single YIQ channel (or the combined chroma channels) to isolate when previewing the effect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PreviewChannel {
+    #[default]
+    Luma,
+    I,
+    Q,
+    Chroma,
+}
+
+impl PreviewChannel {
+    /// The largest magnitudes that the I and Q channels reach for RGB colors in 0-1, from the coefficients in
+    /// [`yiq_fielding::rgb_to_yiq`].
+    const MAX_I: f32 = 0.436;
+    const MAX_Q: f32 = 0.615;
+
+    /// Replace each pixel in an RGBX buffer with only the selected channel. Single channels are displayed as
+    /// grayscale, with chrominance channels centered around 50% gray and scaled so that they don't clip.
+    fn isolate(&self, buf: &mut [u8]) {
+        buf.chunks_exact_mut(4).for_each(|pixel| {
+            let rgb = [pixel[0], pixel[1], pixel[2]].map(|c| c as f32 / 255.0);
+            let [y, i, q] = yiq_fielding::rgb_to_yiq(rgb);
+            let out = match self {
+                PreviewChannel::Luma => [y; 3],
+                PreviewChannel::I => [0.5 + i * 0.5 / Self::MAX_I; 3],
+                PreviewChannel::Q => [0.5 + q * 0.5 / Self::MAX_Q; 3],
+                PreviewChannel::Chroma => yiq_fielding::yiq_to_rgb([0.5, i, q]),
+            };
+            for (dst, src) in pixel.iter_mut().zip(out) {
+                *dst = (src * 255.0).round().clamp(0.0, 255.0) as u8;
+            }
+        });
+    }
 }
 
 impl Debug for SinkTexture {
@@ -54,8 +104,25 @@ pub struct EguiSink {
     settings: Mutex<NtscFilterSettings>,
     #[property(get, set = Self::set_video_preview_mode)]
     preview_mode: Mutex<EffectPreviewSetting>,
+    #[property(get, set = Self::set_preserve_alpha)]
+    preserve_alpha: Mutex<bool>,
+    #[property(get, set = Self::set_noise_pin)]
+    noise_pin: Mutex<NoisePin>,
+    #[property(get, set = Self::set_region)]
+    region: Mutex<NtscFilterRegion>,
+    #[property(get, set = Self::set_profile_stages)]
+    profile_stages: Mutex<bool>,
 
     video_info: Mutex<Option<gstreamer_video::VideoInfo>>,
+    // frames that have already been processed with the current settings, so that going back to them while scrubbing
+    // doesn't mean processing them all over again
+    frame_cache: Mutex<FrameCache>,
+    // number of frames rendered to the texture so far, for measuring the preview's framerate
+    frames_rendered: AtomicU64,
+    // how long the most recently processed frame took, in nanoseconds (0 if no frame has been processed yet)
+    last_frame_time: AtomicU64,
+    // how long each stage of the effect took on the most recently processed frame, if profiling is on
+    stage_timings: Mutex<Option<StageTimings>>,
     last_frame: Mutex<
         Option<(
             gstreamer_video::VideoFrame<gstreamer_video::video_frame::Readable>,
@@ -71,14 +138,79 @@ pub struct EguiCtx(pub Option<Context>);
 impl EguiSink {
     fn set_settings(&self, value: NtscFilterSettings) {
         *self.settings.lock().unwrap() = value;
+        self.frame_cache.lock().unwrap().clear();
         let _ = self.update_texture();
     }
 
     fn set_video_preview_mode(&self, value: EffectPreviewSetting) {
         *self.preview_mode.lock().unwrap() = value;
+        self.frame_cache.lock().unwrap().clear();
+        let _ = self.update_texture();
+    }
+
+    fn set_preserve_alpha(&self, value: bool) {
+        *self.preserve_alpha.lock().unwrap() = value;
+        self.frame_cache.lock().unwrap().clear();
         let _ = self.update_texture();
     }
 
+    fn set_profile_stages(&self, value: bool) {
+        *self.profile_stages.lock().unwrap() = value;
+        *self.stage_timings.lock().unwrap() = None;
+        // Process the current frame again so there's something to show right away
+        self.frame_cache.lock().unwrap().clear();
+        let _ = self.update_texture();
+    }
+
+    fn set_region(&self, value: NtscFilterRegion) {
+        *self.region.lock().unwrap() = value;
+        self.frame_cache.lock().unwrap().clear();
+        let _ = self.update_texture();
+    }
+
+    fn set_noise_pin(&self, value: NoisePin) {
+        *self.noise_pin.lock().unwrap() = value;
+        self.frame_cache.lock().unwrap().clear();
+        let _ = self.update_texture();
+    }
+
+    /// The timestamp of the last frame that was shown, if any.
+    pub fn last_frame_pts(&self) -> Option<gstreamer::ClockTime> {
+        let last_frame = self.last_frame.lock().unwrap();
+        last_frame
+            .as_ref()
+            .and_then(|(vframe, ..)| vframe.buffer().pts())
+    }
+
+    /// The number that the last frame's noise is generated from with the current settings, taking the noise pin into
+    /// account. See [`NtscEffect::noise_frame_num`].
+    pub fn noise_frame_num(&self) -> Option<u64> {
+        let last_frame = self.last_frame.lock().unwrap();
+        let (vframe, ..) = last_frame.as_ref()?;
+        let pts = self.noise_pin.lock().unwrap().0.or(vframe.buffer().pts())?;
+        let (frame_num, time) = frame_timing(vframe.info(), vframe.buffer(), pts);
+        Some(
+            self.settings
+                .lock()
+                .unwrap()
+                .0
+                .noise_frame_num(frame_num, Some(time)),
+        )
+    }
+
+    /// egui textures use premultiplied alpha, but GStreamer frames don't. Frames copied straight from the source also
+    /// carry its alpha channel along, which should be discarded unless we're preserving it.
+    fn finish_alpha(image: &mut ColorImage, preserve_alpha: bool) {
+        image.pixels.iter_mut().for_each(|pixel| {
+            let [r, g, b, a] = pixel.to_array();
+            *pixel = if preserve_alpha {
+                Color32::from_rgba_unmultiplied(r, g, b, a)
+            } else {
+                Color32::from_rgb(r, g, b)
+            };
+        });
+    }
+
     fn apply_effect(
         &self,
         vframe: &VideoFrame<Readable>,
@@ -86,13 +218,33 @@ impl EguiSink {
         rect: Option<yiq_fielding::Rect>,
     ) -> Result<(), gstreamer::FlowError> {
         let out_stride = image.width() * 4;
-        process_gst_frame::<Rgbx8>(
+        let pts = self
+            .noise_pin
+            .lock()
+            .unwrap()
+            .0
+            .or(vframe.buffer().pts())
+            .ok_or(gstreamer::FlowError::Error)?;
+        let mut timings = self
+            .profile_stages
+            .lock()
+            .unwrap()
+            .then(StageTimings::default);
+        process_gst_frame_at::<Rgbx8>(
             &vframe.as_video_frame_ref(),
             image.as_raw_mut(),
             out_stride,
             rect,
             &self.settings.lock().unwrap().0,
+            self.region.lock().unwrap().0.as_ref(),
+            *self.preserve_alpha.lock().unwrap(),
+            false,
+            pts,
+            timings.as_mut(),
         )?;
+        if timings.is_some() {
+            *self.stage_timings.lock().unwrap() = timings;
+        }
 
         Ok(())
     }
@@ -108,11 +260,82 @@ impl EguiSink {
         Ok(image)
     }
 
+    /// Like [`Self::get_image`], but applies the given settings instead of the ones the preview is using.
+    pub fn get_image_with_settings(
+        &self,
+        settings: &NtscEffect,
+    ) -> Result<ColorImage, gstreamer::FlowError> {
+        let vframe = self.last_frame.lock().unwrap();
+        let (vframe, ..) = vframe.as_ref().ok_or(gstreamer::FlowError::Error)?;
+
+        let width = vframe.width() as usize;
+        let height = vframe.height() as usize;
+        let mut image = ColorImage::new([width, height], Color32::BLACK);
+        process_gst_frame::<Rgbx8>(
+            &vframe.as_video_frame_ref(),
+            image.as_raw_mut(),
+            width * 4,
+            None,
+            settings,
+            self.region.lock().unwrap().0.as_ref(),
+            false,
+            false,
+        )?;
+        Ok(image)
+    }
+
     pub fn update_texture(&self) -> Result<(), gstreamer::FlowError> {
         let mut tex = self.texture.lock().unwrap();
         let vframe = self.last_frame.lock().unwrap();
         let (vframe, ..) = vframe.as_ref().ok_or(gstreamer::FlowError::Error)?;
+        let image = self.render_frame_cached(vframe)?;
+
+        tex.0.as_mut().ok_or(gstreamer::FlowError::Error)?.set(
+            ImageData::Color(image),
+            TextureOptions {
+                magnification: TextureFilter::Nearest,
+                minification: TextureFilter::Linear,
+                ..Default::default()
+            },
+        );
+        self.frames_rendered.fetch_add(1, Ordering::Relaxed);
+        if let Some(ctx) = &self.ctx.lock().unwrap().0 {
+            ctx.request_repaint();
+        }
 
+        Ok(())
+    }
+
+    /// Process a frame for the preview, or fetch it from the frame cache if it's already been processed with the
+    /// current settings.
+    fn render_frame_cached(
+        &self,
+        vframe: &VideoFrame<Readable>,
+    ) -> Result<Arc<ColorImage>, gstreamer::FlowError> {
+        let key = vframe.buffer().pts().map(|pts| FrameKey {
+            pts: pts.nseconds(),
+            width: vframe.width() as usize,
+            height: vframe.height() as usize,
+        });
+        if let Some(image) = key.and_then(|key| self.frame_cache.lock().unwrap().get(key)) {
+            return Ok(image);
+        }
+
+        let start_time = Instant::now();
+        let image = Arc::new(self.render_frame(vframe)?);
+        self.last_frame_time
+            .store(start_time.elapsed().as_nanos() as u64, Ordering::Relaxed);
+        if let Some(key) = key {
+            self.frame_cache.lock().unwrap().insert(key, image.clone());
+        }
+        Ok(image)
+    }
+
+    /// Process a frame for the preview, according to the current preview mode.
+    fn render_frame(
+        &self,
+        vframe: &VideoFrame<Readable>,
+    ) -> Result<ColorImage, gstreamer::FlowError> {
         let width = vframe.width() as usize;
         let height = vframe.height() as usize;
         let mut image = ColorImage::new([width, height], Color32::BLACK);
@@ -126,7 +349,10 @@ impl EguiSink {
                 let src_buf = vframe.plane_data(0).or(Err(gstreamer::FlowError::Error))?;
                 image.as_raw_mut().copy_from_slice(src_buf);
             }
-            EffectPreviewSetting::SplitScreen(split) => {
+            EffectPreviewSetting::SplitScreen {
+                rect: split,
+                invert,
+            } => {
                 let src_buf = vframe.plane_data(0).or(Err(gstreamer::FlowError::Error))?;
                 image.as_raw_mut().copy_from_slice(src_buf);
 
@@ -141,23 +367,56 @@ impl EguiSink {
                     rect_to_blit_coord(split.right(), width),
                 );
 
-                self.apply_effect(vframe, &mut image, Some(rect))?;
+                if invert {
+                    // Process the whole frame, then put the original back inside the rect
+                    self.apply_effect(vframe, &mut image, None)?;
+                    let row_bytes = width * 4;
+                    let dst_buf = image.as_raw_mut();
+                    for row in rect.top..rect.bottom {
+                        let span =
+                            row * row_bytes + rect.left * 4..row * row_bytes + rect.right * 4;
+                        dst_buf[span.clone()].copy_from_slice(&src_buf[span]);
+                    }
+                } else {
+                    self.apply_effect(vframe, &mut image, Some(rect))?;
+                }
+            }
+            EffectPreviewSetting::Channel(channel) => {
+                self.apply_effect(vframe, &mut image, None)?;
+                channel.isolate(image.as_raw_mut());
             }
         }
 
-        tex.0.as_mut().ok_or(gstreamer::FlowError::Error)?.set(
-            image,
-            TextureOptions {
-                magnification: TextureFilter::Nearest,
-                minification: TextureFilter::Linear,
-                ..Default::default()
-            },
-        );
-        if let Some(ctx) = &self.ctx.lock().unwrap().0 {
-            ctx.request_repaint();
+        if vframe.format() == gstreamer_video::VideoFormat::Rgba {
+            Self::finish_alpha(&mut image, *self.preserve_alpha.lock().unwrap());
         }
 
-        Ok(())
+        Ok(image)
+    }
+
+    pub fn frames_rendered(&self) -> u64 {
+        self.frames_rendered.load(Ordering::Relaxed)
+    }
+
+    /// How many frames the sink has dropped so far because they arrived too late to be shown on time.
+    pub fn dropped_frames(&self) -> u64 {
+        self.obj()
+            .property::<gstreamer::Structure>("stats")
+            .get::<u64>("dropped")
+            .unwrap_or_default()
+    }
+
+    /// How long each stage of the effect took on the most recently processed frame. Only measured while the
+    /// "profile-stages" property is set.
+    pub fn stage_timings(&self) -> Option<StageTimings> {
+        self.stage_timings.lock().unwrap().clone()
+    }
+
+    pub fn last_frame_time(&self) -> Option<Duration> {
+        match self.last_frame_time.load(Ordering::Relaxed) {
+            0 => None,
+            nanos => Some(Duration::from_nanos(nanos)),
+        }
     }
 }
 
@@ -190,7 +449,10 @@ impl ElementImpl for EguiSink {
     fn pad_templates() -> &'static [gstreamer::PadTemplate] {
         static PAD_TEMPLATES: Lazy<Vec<PadTemplate>> = Lazy::new(|| {
             let caps = gstreamer_video::VideoCapsBuilder::new()
-                .format(gstreamer_video::VideoFormat::Rgbx)
+                .format_list([
+                    gstreamer_video::VideoFormat::Rgbx,
+                    gstreamer_video::VideoFormat::Rgba,
+                ])
                 .build();
             let pad_template = gstreamer::PadTemplate::builder(
                 "sink",
@@ -214,6 +476,21 @@ impl BaseSinkImpl for EguiSink {
         *video_info = Some(gstreamer_video::VideoInfo::from_caps(caps)?);
         Ok(())
     }
+
+    /// Called with each buffer before waiting for its presentation time, while the previous frame is still being
+    /// shown. Processing the frame here and putting it in the frame cache means that `show_frame` usually only has to
+    /// upload it, so the processing time no longer eats into the time the frame is supposed to be on screen.
+    fn prepare(
+        &self,
+        buffer: &gstreamer::Buffer,
+    ) -> Result<gstreamer::FlowSuccess, gstreamer::FlowError> {
+        let video_info = self.video_info.lock().unwrap().clone();
+        let video_info = video_info.as_ref().ok_or(gstreamer::FlowError::Error)?;
+        let vframe = gstreamer_video::VideoFrame::from_buffer_readable(buffer.copy(), video_info)
+            .map_err(|_| gstreamer::FlowError::Error)?;
+        self.render_frame_cached(&vframe)?;
+        Ok(gstreamer::FlowSuccess::Ok)
+    }
 }
 
 impl VideoSinkImpl for EguiSink {
@@ -0,0 +1,86 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use eframe::epaint::ColorImage;
+
+/// Identifies a processed frame: its timestamp and the size it was rendered at (which changes when the preview
+/// resolution does).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameKey {
+    pub pts: u64,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// A least-recently-used cache of processed preview frames, bounded by how much memory the frames take up. The
+/// frames are only valid for the effect settings they were rendered with, so the cache needs to be cleared whenever
+/// those change.
+#[derive(Debug)]
+pub struct FrameCache {
+    // least recently used first
+    frames: VecDeque<(FrameKey, Arc<ColorImage>)>,
+    size_bytes: usize,
+    max_bytes: usize,
+}
+
+impl FrameCache {
+    /// Enough for about 30 1080p frames.
+    pub const DEFAULT_MAX_BYTES: usize = 256 * 1024 * 1024;
+
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            frames: VecDeque::new(),
+            size_bytes: 0,
+            max_bytes,
+        }
+    }
+
+    fn image_size(image: &ColorImage) -> usize {
+        image.pixels.len() * std::mem::size_of::<eframe::epaint::Color32>()
+    }
+
+    pub fn get(&mut self, key: FrameKey) -> Option<Arc<ColorImage>> {
+        let index = self
+            .frames
+            .iter()
+            .position(|(frame_key, _)| *frame_key == key)?;
+        let entry = self.frames.remove(index)?;
+        let image = entry.1.clone();
+        self.frames.push_back(entry);
+        Some(image)
+    }
+
+    pub fn insert(&mut self, key: FrameKey, image: Arc<ColorImage>) {
+        let size = Self::image_size(&image);
+        if size > self.max_bytes {
+            return;
+        }
+        if let Some(index) = self
+            .frames
+            .iter()
+            .position(|(frame_key, _)| *frame_key == key)
+        {
+            if let Some((_, old_image)) = self.frames.remove(index) {
+                self.size_bytes -= Self::image_size(&old_image);
+            }
+        }
+        while self.size_bytes + size > self.max_bytes {
+            let Some((_, evicted)) = self.frames.pop_front() else {
+                break;
+            };
+            self.size_bytes -= Self::image_size(&evicted);
+        }
+        self.frames.push_back((key, image));
+        self.size_bytes += size;
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.size_bytes = 0;
+    }
+}
+
+impl Default for FrameCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_MAX_BYTES)
+    }
+}
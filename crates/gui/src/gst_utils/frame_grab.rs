@@ -0,0 +1,104 @@
+use std::path::Path;
+
+use eframe::egui::{Color32, ColorImage};
+use gstreamer::{glib, prelude::*, ClockTime};
+use ntscrs::{ntsc::NtscEffect, yiq_fielding::Rgbx8};
+
+use super::{
+    gstreamer_error::GstreamerError,
+    hdr_tonemap::TonemapSettings,
+    pipeline_utils::{create_pipeline, create_source, PipelineError},
+    process_gst_frame::{process_gst_frame, EffectRegion},
+};
+
+/// How long to wait for the pipeline to load and seek before giving up.
+const GRAB_TIMEOUT: ClockTime = ClockTime::from_seconds(10);
+
+/// Decode the frame at the given position at the source's full resolution and apply the effect to it. This blocks
+/// until the frame is decoded, so it should be called from a background thread.
+///
+/// The frame is decoded with its own pipeline, so the preview's scale doesn't affect it.
+pub fn grab_frame(
+    path: &Path,
+    position: ClockTime,
+    still_image_framerate: gstreamer::Fraction,
+    tonemap: TonemapSettings,
+    square_pixels: bool,
+    settings: &NtscEffect,
+    region: Option<EffectRegion>,
+) -> Result<ColorImage, GstreamerError> {
+    let src = create_source(path, still_image_framerate)?;
+    let sink = gstreamer::ElementFactory::make("fakesink")
+        .property("enable-last-sample", true)
+        .property("sync", false)
+        .build()?;
+    let sink_for_closure = sink.clone();
+
+    let pipeline = create_pipeline(
+        src,
+        |_| Ok(None),
+        move |pipeline| {
+            let caps_filter = gstreamer::ElementFactory::make("capsfilter")
+                .property(
+                    "caps",
+                    gstreamer_video::VideoCapsBuilder::new()
+                        .format(gstreamer_video::VideoFormat::Rgbx)
+                        .build(),
+                )
+                .build()?;
+            pipeline.add_many([&caps_filter, &sink_for_closure])?;
+            caps_filter.link(&sink_for_closure)?;
+            sink_for_closure.sync_state_with_parent()?;
+            Ok(caps_filter)
+        },
+        |_, _| gstreamer::BusSyncReply::Drop,
+        None,
+        None,
+        square_pixels,
+        still_image_framerate,
+        tonemap,
+        0,
+        None::<fn(Result<gstreamer::Pipeline, PipelineError>)>,
+    )?;
+
+    let res = (|| -> Result<ColorImage, GstreamerError> {
+        pipeline.set_state(gstreamer::State::Paused)?;
+        pipeline.state(GRAB_TIMEOUT).0?;
+        pipeline.seek_simple(
+            gstreamer::SeekFlags::FLUSH | gstreamer::SeekFlags::ACCURATE,
+            position,
+        )?;
+        pipeline.state(GRAB_TIMEOUT).0?;
+
+        let sample = sink
+            .property::<Option<gstreamer::Sample>>("last-sample")
+            .ok_or_else(|| glib::bool_error!("No frame was decoded"))?;
+        let info = sample
+            .caps()
+            .and_then(|caps| gstreamer_video::VideoInfo::from_caps(caps).ok())
+            .ok_or_else(|| glib::bool_error!("The decoded frame has no video info"))?;
+        let buffer = sample
+            .buffer_owned()
+            .ok_or_else(|| glib::bool_error!("The decoded frame has no buffer"))?;
+        let frame = gstreamer_video::VideoFrame::from_buffer_readable(buffer, &info)
+            .map_err(|_| glib::bool_error!("Couldn't read the decoded frame"))?;
+
+        let width = frame.width() as usize;
+        let height = frame.height() as usize;
+        let mut image = ColorImage::new([width, height], Color32::BLACK);
+        process_gst_frame::<Rgbx8>(
+            &frame.as_video_frame_ref(),
+            image.as_raw_mut(),
+            width * 4,
+            None,
+            settings,
+            region.as_ref(),
+            false,
+            false,
+        )?;
+        Ok(image)
+    })();
+
+    pipeline.set_state(gstreamer::State::Null)?;
+    res
+}
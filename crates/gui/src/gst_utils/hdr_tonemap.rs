@@ -0,0 +1,303 @@
+use std::sync::RwLock;
+
+use gstreamer::glib;
+use gstreamer::glib::once_cell::sync::Lazy;
+use gstreamer::prelude::{ParamSpecBuilderExt, ToValue};
+use gstreamer_base::prelude::BaseTransformExt;
+use gstreamer_video::subclass::prelude::*;
+use gstreamer_video::{VideoFormat, VideoTransferFunction};
+
+/// Luminance that HDR sources are assumed to be mastered to. PQ can encode up to 10,000 nits, but almost nothing is
+/// mastered that bright, and HLG's reference display is 1,000 nits.
+const SOURCE_PEAK_NITS: f32 = 1000.0;
+
+/// The HDR transfer characteristics that the tone-mapper understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, glib::Enum)]
+#[enum_type(name = "NtscrsHdrTransfer")]
+pub enum HdrTransfer {
+    /// SMPTE ST 2084 (perceptual quantizer), used by HDR10 and Dolby Vision.
+    #[default]
+    #[enum_value(name = "PQ", nick = "pq")]
+    Pq,
+    /// ARIB STD-B67 (hybrid log-gamma), used mostly for broadcast.
+    #[enum_value(name = "HLG", nick = "hlg")]
+    Hlg,
+}
+
+impl HdrTransfer {
+    /// Detect whether video with the given caps uses an HDR transfer function.
+    pub fn from_caps(caps: &gstreamer::CapsRef) -> Option<Self> {
+        let info = gstreamer_video::VideoInfo::from_caps(caps).ok()?;
+        match info.colorimetry().transfer() {
+            VideoTransferFunction::Smpte2084 => Some(Self::Pq),
+            VideoTransferFunction::AribStdB67 => Some(Self::Hlg),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Pq => "PQ",
+            Self::Hlg => "HLG",
+        }
+    }
+}
+
+/// How HDR input should be brought into SDR before the effect is applied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TonemapSettings {
+    pub enabled: bool,
+    /// Luminance that SDR white is mapped to. Lower values make the output brighter.
+    pub target_nits: f64,
+}
+
+impl Default for TonemapSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            target_nits: 100.0,
+        }
+    }
+}
+
+fn pq_to_linear(value: f32) -> f32 {
+    const M1: f32 = 2610.0 / 16384.0;
+    const M2: f32 = 2523.0 / 4096.0 * 128.0;
+    const C1: f32 = 3424.0 / 4096.0;
+    const C2: f32 = 2413.0 / 4096.0 * 32.0;
+    const C3: f32 = 2392.0 / 4096.0 * 32.0;
+
+    let e = value.powf(1.0 / M2);
+    let y = ((e - C1).max(0.0) / (C2 - C3 * e)).powf(1.0 / M1);
+    // Absolute luminance, in nits
+    y * 10000.0
+}
+
+fn hlg_to_scene_linear(value: f32) -> f32 {
+    const A: f32 = 0.17883277;
+    const B: f32 = 1.0 - 4.0 * A;
+    // 0.5 - A * ln(4 * A)
+    const C: f32 = 0.5599107;
+
+    if value <= 0.5 {
+        value * value / 3.0
+    } else {
+        (((value - C) / A).exp() + B) / 12.0
+    }
+}
+
+fn build_lut(f: fn(f32) -> f32) -> Vec<f32> {
+    (0..=u16::MAX)
+        .map(|value| f(value as f32 / u16::MAX as f32))
+        .collect()
+}
+
+static PQ_LUT: Lazy<Vec<f32>> = Lazy::new(|| build_lut(pq_to_linear));
+static HLG_LUT: Lazy<Vec<f32>> = Lazy::new(|| build_lut(hlg_to_scene_linear));
+
+/// Convert linear light from BT.2020 primaries to BT.709 ones.
+#[rustfmt::skip]
+const BT2020_TO_BT709: [[f32; 3]; 3] = [
+    [ 1.6605, -0.5876, -0.0728],
+    [-0.1246,  1.1329, -0.0083],
+    [-0.0182, -0.1006,  1.1187],
+];
+
+fn tonemap_pixel(pixel: &mut [u16], transfer: HdrTransfer, target_nits: f32) {
+    let [_, r, g, b] = pixel else {
+        return;
+    };
+
+    let (mut rgb, scale) = match transfer {
+        HdrTransfer::Pq => (
+            [
+                PQ_LUT[*r as usize],
+                PQ_LUT[*g as usize],
+                PQ_LUT[*b as usize],
+            ],
+            1.0,
+        ),
+        HdrTransfer::Hlg => {
+            let rgb = [
+                HLG_LUT[*r as usize],
+                HLG_LUT[*g as usize],
+                HLG_LUT[*b as usize],
+            ];
+            // The HLG reference OOTF, for a 1000-nit display (system gamma 1.2)
+            let ys = 0.2627 * rgb[0] + 0.6780 * rgb[1] + 0.0593 * rgb[2];
+            (rgb, SOURCE_PEAK_NITS * ys.powf(0.2))
+        }
+    };
+
+    let [r_in, g_in, b_in] = rgb;
+    for (out, row) in rgb.iter_mut().zip(BT2020_TO_BT709) {
+        *out = (row[0] * r_in + row[1] * g_in + row[2] * b_in).max(0.0) * scale / target_nits;
+    }
+
+    // Extended Reinhard on luminance, so the source's peak lands exactly on SDR white and hues are kept
+    let luma = 0.2126 * rgb[0] + 0.7152 * rgb[1] + 0.0722 * rgb[2];
+    if luma > 0.0 {
+        let white = SOURCE_PEAK_NITS / target_nits;
+        let mapped = luma * (1.0 + luma / (white * white)) / (1.0 + luma);
+        rgb.iter_mut().for_each(|c| *c *= mapped / luma);
+    }
+
+    for (out, c) in [r, g, b].into_iter().zip(rgb) {
+        *out = (c.clamp(0.0, 1.0).powf(1.0 / 2.2) * u16::MAX as f32).round() as u16;
+    }
+}
+
+pub struct HdrTonemapFilter {
+    transfer: RwLock<HdrTransfer>,
+    target_nits: RwLock<f64>,
+}
+
+impl Default for HdrTonemapFilter {
+    fn default() -> Self {
+        Self {
+            transfer: RwLock::default(),
+            target_nits: RwLock::new(TonemapSettings::default().target_nits),
+        }
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for HdrTonemapFilter {
+    const NAME: &'static str = "ntscrs_hdr_tonemap";
+    type Type = super::elements::HdrTonemapFilter;
+    type ParentType = gstreamer_video::VideoFilter;
+}
+
+impl ObjectImpl for HdrTonemapFilter {
+    fn properties() -> &'static [glib::ParamSpec] {
+        static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+            vec![
+                glib::ParamSpecEnum::builder::<HdrTransfer>("transfer")
+                    .nick("Transfer")
+                    .blurb("The input's HDR transfer function")
+                    .mutable_playing()
+                    .build(),
+                glib::ParamSpecDouble::builder("target-nits")
+                    .nick("Target nits")
+                    .blurb("Luminance that SDR white is mapped to")
+                    .minimum(10.0)
+                    .maximum(1000.0)
+                    .default_value(TonemapSettings::default().target_nits)
+                    .mutable_playing()
+                    .build(),
+                glib::ParamSpecBoolean::builder("enabled")
+                    .nick("Enabled")
+                    .blurb("Tone-map the input; if unset, it's passed through untouched")
+                    .default_value(true)
+                    .mutable_playing()
+                    .build(),
+            ]
+        });
+
+        PROPERTIES.as_ref()
+    }
+
+    fn set_property(&self, _id: usize, value: &glib::Value, pspec: &glib::ParamSpec) {
+        match pspec.name() {
+            "transfer" => {
+                *self.transfer.write().unwrap() = value.get().unwrap();
+            }
+            "target-nits" => {
+                *self.target_nits.write().unwrap() = value.get().unwrap();
+            }
+            "enabled" => {
+                self.obj().set_passthrough(!value.get::<bool>().unwrap());
+            }
+            name => panic!("Incorrect param spec name {}", name),
+        }
+    }
+
+    fn property(&self, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+        match pspec.name() {
+            "transfer" => self.transfer.read().unwrap().to_value(),
+            "target-nits" => self.target_nits.read().unwrap().to_value(),
+            "enabled" => (!self.obj().is_passthrough()).to_value(),
+            name => panic!("Incorrect param spec name {}", name),
+        }
+    }
+}
+
+impl GstObjectImpl for HdrTonemapFilter {}
+
+impl ElementImpl for HdrTonemapFilter {
+    fn metadata() -> Option<&'static gstreamer::subclass::ElementMetadata> {
+        static ELEMENT_METADATA: Lazy<gstreamer::subclass::ElementMetadata> = Lazy::new(|| {
+            gstreamer::subclass::ElementMetadata::new(
+                "HDR Tone-mapper",
+                "Filter/Effect/Converter/Video",
+                "Tone-maps PQ or HLG video into SDR",
+                "valadaptive",
+            )
+        });
+
+        Some(&*ELEMENT_METADATA)
+    }
+
+    fn pad_templates() -> &'static [gstreamer::PadTemplate] {
+        static PAD_TEMPLATES: Lazy<Vec<gstreamer::PadTemplate>> = Lazy::new(|| {
+            // 16 bits per channel, since 8 bits of PQ or HLG would band badly once expanded
+            let caps = gstreamer_video::VideoCapsBuilder::new()
+                .format(VideoFormat::Argb64)
+                .build();
+
+            let src_pad_template = gstreamer::PadTemplate::builder(
+                "src",
+                gstreamer::PadDirection::Src,
+                gstreamer::PadPresence::Always,
+                &caps,
+            )
+            .build()
+            .unwrap();
+
+            let sink_pad_template = gstreamer::PadTemplate::builder(
+                "sink",
+                gstreamer::PadDirection::Sink,
+                gstreamer::PadPresence::Always,
+                &caps,
+            )
+            .build()
+            .unwrap();
+
+            vec![src_pad_template, sink_pad_template]
+        });
+
+        PAD_TEMPLATES.as_ref()
+    }
+}
+
+impl BaseTransformImpl for HdrTonemapFilter {
+    const MODE: gstreamer_base::subclass::BaseTransformMode =
+        gstreamer_base::subclass::BaseTransformMode::AlwaysInPlace;
+    const PASSTHROUGH_ON_SAME_CAPS: bool = false;
+    const TRANSFORM_IP_ON_PASSTHROUGH: bool = false;
+}
+
+impl VideoFilterImpl for HdrTonemapFilter {
+    fn transform_frame_ip(
+        &self,
+        frame: &mut gstreamer_video::VideoFrameRef<&mut gstreamer::BufferRef>,
+    ) -> Result<gstreamer::FlowSuccess, gstreamer::FlowError> {
+        let transfer = *self.transfer.read().unwrap();
+        let target_nits = *self.target_nits.read().unwrap() as f32;
+
+        let width = frame.width() as usize;
+        let stride = frame.plane_stride()[0] as usize;
+        let data = frame
+            .plane_data_mut(0)
+            .or(Err(gstreamer::FlowError::Error))?;
+        let data_16 = unsafe { data.align_to_mut::<u16>() }.1;
+
+        for row in data_16.chunks_mut(stride / std::mem::size_of::<u16>()) {
+            for pixel in row[..width * 4].chunks_exact_mut(4) {
+                tonemap_pixel(pixel, transfer, target_nits);
+            }
+        }
+
+        Ok(gstreamer::FlowSuccess::Ok)
+    }
+}
@@ -0,0 +1,162 @@
+use std::path::{Path, PathBuf};
+
+use gstreamer::{glib, prelude::*};
+
+use super::gstreamer_error::GstreamerError;
+
+/// Name of the source element that reads image sequences, so that it can be found again to change the framerate.
+pub const IMAGE_SEQUENCE_SRC_NAME: &str = "image_sequence_src";
+
+/// A file name containing a printf-style frame number placeholder like `%04d`, split around the placeholder.
+struct SequencePattern<'a> {
+    prefix: &'a str,
+    // the number of digits the frame number is zero-padded to, if any
+    width: Option<usize>,
+    suffix: &'a str,
+}
+
+impl<'a> SequencePattern<'a> {
+    fn parse(file_name: &'a str) -> Option<Self> {
+        let start = file_name.find('%')?;
+        let rest = &file_name[start + 1..];
+        let end = rest.find('d')?;
+        let spec = &rest[..end];
+        let width = match spec {
+            "" => None,
+            _ if spec.starts_with('0') && spec.chars().all(|c| c.is_ascii_digit()) => {
+                Some(spec.parse().ok()?)
+            }
+            _ => return None,
+        };
+
+        Some(Self {
+            prefix: &file_name[..start],
+            width,
+            suffix: &rest[end + 1..],
+        })
+    }
+
+    /// The frame number of a file in the sequence, or `None` if the file isn't part of it.
+    fn frame_index(&self, file_name: &str) -> Option<u32> {
+        let digits = file_name
+            .strip_prefix(self.prefix)?
+            .strip_suffix(self.suffix)?;
+        if digits.is_empty()
+            || !digits.chars().all(|c| c.is_ascii_digit())
+            || self.width.is_some_and(|width| digits.len() != width)
+        {
+            return None;
+        }
+        digits.parse().ok()
+    }
+}
+
+/// Whether the path is an image sequence pattern like `frame_%04d.png` rather than a single file.
+pub fn is_image_sequence(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|file_name| file_name.to_str())
+        .and_then(SequencePattern::parse)
+        .is_some()
+}
+
+/// Turn the path of one numbered frame (e.g. `frame_0001.png`) into a pattern matching the whole sequence (e.g.
+/// `frame_%04d.png`). Returns `None` if the file name doesn't end in a number.
+pub fn sequence_pattern_for_frame(path: &Path) -> Option<PathBuf> {
+    let stem = path.file_stem()?.to_str()?;
+    let prefix = stem.trim_end_matches(|c: char| c.is_ascii_digit());
+    let num_digits = stem.len() - prefix.len();
+    if num_digits == 0 {
+        return None;
+    }
+
+    let placeholder = if num_digits > 1 && stem[prefix.len()..].starts_with('0') {
+        format!("%0{num_digits}d")
+    } else {
+        String::from("%d")
+    };
+    let mut file_name = format!("{prefix}{placeholder}");
+    if let Some(extension) = path.extension().and_then(|extension| extension.to_str()) {
+        file_name.push('.');
+        file_name.push_str(extension);
+    }
+
+    Some(path.with_file_name(file_name))
+}
+
+/// The first and last frame numbers of the sequence that exist on disk.
+fn frame_range(path: &Path) -> Option<(u32, u32)> {
+    let pattern = SequencePattern::parse(path.file_name()?.to_str()?)?;
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    std::fs::read_dir(directory)
+        .ok()?
+        .filter_map(|entry| pattern.frame_index(entry.ok()?.file_name().to_str()?))
+        .fold(None, |range, index| match range {
+            None => Some((index, index)),
+            Some((first, last)) => Some((first.min(index), last.max(index))),
+        })
+}
+
+fn image_caps(
+    path: &Path,
+    framerate: gstreamer::Fraction,
+) -> Result<gstreamer::Caps, GstreamerError> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let media_type = match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "tif" | "tiff" => "image/tiff",
+        "bmp" => "image/bmp",
+        "webp" => "image/webp",
+        _ => {
+            return Err(
+                glib::bool_error!("Unsupported image sequence format \"{}\"", extension).into(),
+            )
+        }
+    };
+
+    Ok(gstreamer::Caps::builder(media_type)
+        .field("framerate", framerate)
+        .build())
+}
+
+/// Create a source element that reads each frame of an image sequence in turn, at the given framerate.
+pub fn create_image_sequence_source(
+    path: &Path,
+    framerate: gstreamer::Fraction,
+) -> Result<gstreamer::Element, GstreamerError> {
+    let (first_index, last_index) = frame_range(path).ok_or_else(|| {
+        glib::bool_error!("No frames of the image sequence {} exist", path.display())
+    })?;
+
+    Ok(gstreamer::ElementFactory::make("multifilesrc")
+        .name(IMAGE_SEQUENCE_SRC_NAME)
+        .property("location", path.to_string_lossy().as_ref())
+        .property("start-index", first_index as i32)
+        .property("stop-index", last_index as i32)
+        .property("caps", image_caps(path, framerate)?)
+        .build()?)
+}
+
+/// Change the framerate that an image sequence source plays back at. Returns false if the pipeline isn't playing an
+/// image sequence.
+pub fn set_image_sequence_framerate(
+    pipeline: &gstreamer::Pipeline,
+    framerate: gstreamer::Fraction,
+) -> bool {
+    let Some(src) = pipeline.by_name(IMAGE_SEQUENCE_SRC_NAME) else {
+        return false;
+    };
+
+    let mut caps = src.property::<gstreamer::Caps>("caps");
+    caps.make_mut().set("framerate", framerate);
+    src.set_property("caps", caps);
+    true
+}
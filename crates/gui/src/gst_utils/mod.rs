@@ -1,13 +1,21 @@
+pub mod camera;
 pub mod clock_format;
+pub mod debug_log;
 pub mod egui_sink;
+pub mod frame_cache;
+pub mod frame_grab;
 pub mod gstreamer_error;
+pub mod hdr_tonemap;
+pub mod image_sequence;
 pub mod ntscrs_filter;
+pub mod pipeline_description;
 pub mod pipeline_utils;
 pub mod process_gst_frame;
+pub mod thumbnails;
 pub mod video_pad_filter;
 
 pub mod elements {
-    use super::{egui_sink, ntscrs_filter, video_pad_filter};
+    use super::{egui_sink, hdr_tonemap, ntscrs_filter, video_pad_filter};
     use gstreamer::glib;
     glib::wrapper! {
         pub struct EguiSink(ObjectSubclass<egui_sink::EguiSink>) @extends gstreamer_video::VideoSink, gstreamer_base::BaseSink, gstreamer::Element, gstreamer::Object;
@@ -17,18 +25,74 @@ pub mod elements {
         pub struct NtscFilter(ObjectSubclass<ntscrs_filter::NtscFilter>) @extends gstreamer_base::BaseTransform, gstreamer::Element, gstreamer::Object;
     }
 
+    glib::wrapper! {
+        pub struct HdrTonemapFilter(ObjectSubclass<hdr_tonemap::HdrTonemapFilter>) @extends gstreamer_base::BaseTransform, gstreamer::Element, gstreamer::Object;
+    }
+
     glib::wrapper! {
         pub struct VideoPadFilter(ObjectSubclass<video_pad_filter::VideoPadFilter>) @extends gstreamer_base::BaseTransform, gstreamer::Element, gstreamer::Object;
     }
 }
 
-pub fn scale_from_caps(caps: &gstreamer::Caps, scanlines: usize) -> Option<(i32, i32)> {
+/// The pixel aspect ratio given in a video's caps. Video that doesn't specify one has square pixels.
+pub fn pixel_aspect_ratio(caps: &gstreamer::Caps) -> gstreamer::Fraction {
+    caps.structure(0)
+        .and_then(|structure| {
+            structure
+                .get::<gstreamer::Fraction>("pixel-aspect-ratio")
+                .ok()
+        })
+        .filter(|par| par.numer() > 0 && par.denom() > 0)
+        .unwrap_or(gstreamer::Fraction::new(1, 1))
+}
+
+pub fn scale_from_caps(
+    caps: &gstreamer::Caps,
+    scanlines: usize,
+    square_pixels: bool,
+) -> Option<(i32, i32)> {
     let caps_structure = caps.structure(0)?;
-    let src_width = caps_structure.get::<i32>("width").ok()?;
+    let mut src_width = caps_structure.get::<i32>("width").ok()? as f32;
     let src_height = caps_structure.get::<i32>("height").ok()?;
 
+    if square_pixels {
+        let par = pixel_aspect_ratio(caps);
+        src_width *= par.numer() as f32 / par.denom() as f32;
+    }
+
     let scale_factor = scanlines as f32 / src_height as f32;
-    let dst_width = (src_width as f32 * scale_factor).round() as i32;
+    let dst_width = (src_width * scale_factor).round() as i32;
 
     Some((dst_width, scanlines as i32))
 }
+
+/// Caps that scale video down to at most `scanlines` tall, keeping its aspect ratio. Video that's already shorter than
+/// that passes through at its own size.
+pub fn max_height_caps(scanlines: Option<usize>) -> gstreamer::Caps {
+    let builder = gstreamer_video::VideoCapsBuilder::default();
+    match scanlines {
+        Some(scanlines) => builder.height_range(1..=scanlines as i32).build(),
+        None => builder.build(),
+    }
+}
+
+/// Caps for the capsfilter after the videoscale element, scaling video with the given `caps` to `scanlines` if set.
+/// With `square_pixels`, anamorphic video is stretched out to square pixels. Otherwise, its pixel aspect ratio is
+/// carried through to the output.
+pub fn scaled_caps(
+    caps: &gstreamer::Caps,
+    scanlines: Option<usize>,
+    square_pixels: bool,
+) -> gstreamer::Caps {
+    let mut builder = gstreamer_video::VideoCapsBuilder::default();
+    if let Some((width, height)) =
+        scanlines.and_then(|scanlines| scale_from_caps(caps, scanlines, square_pixels))
+    {
+        builder = builder.width(width).height(height);
+    }
+    if square_pixels {
+        // With only the height fixed, videoscale picks the width that keeps the display aspect ratio
+        builder = builder.pixel_aspect_ratio(gstreamer::Fraction::new(1, 1));
+    }
+    builder.build()
+}
@@ -7,18 +7,33 @@ use gstreamer_video::subclass::prelude::*;
 use gstreamer_video::VideoFormat;
 
 use ntscrs::ntsc::NtscEffect;
+use ntscrs::settings::{NtscEffectFullSettings, SettingsList};
 use ntscrs::yiq_fielding::{Bgrx8, Rgbx8, Xbgr8, Xrgb16, Xrgb8};
 
-use super::process_gst_frame::process_gst_frame;
+use super::process_gst_frame::{process_gst_frame, EffectRegion};
+use crate::keyframes::KeyframeTracks;
 
 #[derive(Clone, glib::Boxed, Default)]
 #[boxed_type(name = "NtscFilterSettings")]
 pub struct NtscFilterSettings(pub NtscEffect);
 
+/// If set, the effect is only applied within this region of the frame.
+#[derive(Debug, Clone, Copy, PartialEq, glib::Boxed, Default)]
+#[boxed_type(name = "NtscFilterRegion")]
+pub struct NtscFilterRegion(pub Option<EffectRegion>);
+
+#[derive(Clone, glib::Boxed, Default)]
+#[boxed_type(name = "NtscFilterKeyframes")]
+pub struct NtscFilterKeyframes(pub KeyframeTracks);
+
 #[derive(Default)]
 pub struct NtscFilter {
     info: RwLock<Option<gstreamer_video::VideoInfo>>,
     settings: RwLock<NtscFilterSettings>,
+    keyframes: RwLock<NtscFilterKeyframes>,
+    region: RwLock<NtscFilterRegion>,
+    preserve_alpha: RwLock<bool>,
+    low_memory: RwLock<bool>,
 }
 
 impl NtscFilter {}
@@ -40,6 +55,33 @@ impl ObjectImpl for NtscFilter {
                     .mutable_playing()
                     .controllable()
                     .build(),
+                // The boxed settings can't be given as text, so this lets the filter be set up from a pipeline
+                // description string too
+                glib::ParamSpecString::builder("settings-json")
+                    .nick("Settings JSON")
+                    .blurb("ntsc-rs settings, in the same JSON format as the settings presets")
+                    .mutable_playing()
+                    .build(),
+                glib::ParamSpecBoxed::builder::<NtscFilterKeyframes>("keyframes")
+                    .nick("Keyframes")
+                    .blurb("Per-setting keyframes that override the settings block depending on each frame's timestamp")
+                    .mutable_playing()
+                    .build(),
+                glib::ParamSpecBoxed::builder::<NtscFilterRegion>("region")
+                    .nick("Region")
+                    .blurb("Rectangle of the frame to confine the effect to, passing the rest of the frame through")
+                    .mutable_playing()
+                    .build(),
+                glib::ParamSpecBoolean::builder("preserve-alpha")
+                    .nick("Preserve alpha")
+                    .blurb("Pass the input's alpha channel through untouched")
+                    .mutable_playing()
+                    .build(),
+                glib::ParamSpecBoolean::builder("low-memory")
+                    .nick("Low memory")
+                    .blurb("Process each frame in strips instead of all at once, using less memory for large frames")
+                    .mutable_playing()
+                    .build(),
             ]
         });
 
@@ -47,22 +89,63 @@ impl ObjectImpl for NtscFilter {
     }
 
     fn set_property(&self, _id: usize, value: &glib::Value, pspec: &glib::ParamSpec) {
-        if pspec.name() != "settings" {
-            panic!("Incorrect param spec name {}", pspec.name());
+        match pspec.name() {
+            "settings" => {
+                let mut settings = self.settings.write().unwrap();
+                let new_settings = value.get().unwrap();
+                *settings = new_settings;
+            }
+            "settings-json" => {
+                let Some(json) = value.get::<Option<String>>().unwrap() else {
+                    return;
+                };
+                match SettingsList::new().from_json(&json) {
+                    Ok(settings) => {
+                        *self.settings.write().unwrap() = NtscFilterSettings(settings.into());
+                    }
+                    Err(err) => gstreamer::element_imp_warning!(
+                        self,
+                        gstreamer::LibraryError::Settings,
+                        ("Invalid settings JSON: {}", err)
+                    ),
+                }
+            }
+            "keyframes" => {
+                *self.keyframes.write().unwrap() = value.get().unwrap();
+            }
+            "region" => {
+                *self.region.write().unwrap() = value.get().unwrap();
+            }
+            "preserve-alpha" => {
+                *self.preserve_alpha.write().unwrap() = value.get().unwrap();
+            }
+            "low-memory" => {
+                *self.low_memory.write().unwrap() = value.get().unwrap();
+            }
+            name => panic!("Incorrect param spec name {}", name),
         }
-
-        let mut settings = self.settings.write().unwrap();
-        let new_settings = value.get().unwrap();
-        *settings = new_settings;
     }
 
     fn property(&self, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
-        if pspec.name() != "settings" {
-            panic!("Incorrect param spec name {}", pspec.name());
+        match pspec.name() {
+            "settings" => {
+                let settings = self.settings.read().unwrap();
+                settings.to_value()
+            }
+            "settings-json" => {
+                let settings = NtscEffectFullSettings::from(&self.settings.read().unwrap().0);
+                SettingsList::new()
+                    .to_json(&settings)
+                    .stringify()
+                    .ok()
+                    .to_value()
+            }
+            "keyframes" => self.keyframes.read().unwrap().to_value(),
+            "region" => self.region.read().unwrap().to_value(),
+            "preserve-alpha" => self.preserve_alpha.read().unwrap().to_value(),
+            "low-memory" => self.low_memory.read().unwrap().to_value(),
+            name => panic!("Incorrect param spec name {}", name),
         }
-
-        let settings = self.settings.read().unwrap();
-        settings.to_value()
     }
 }
 
@@ -152,6 +235,24 @@ impl VideoFilterImpl for NtscFilter {
             .or(Err(gstreamer::FlowError::Error))?
             .clone()
             .0;
+        let settings = match in_frame.buffer().pts() {
+            Some(pts) => self
+                .keyframes
+                .read()
+                .or(Err(gstreamer::FlowError::Error))?
+                .0
+                .apply(&settings, pts),
+            None => settings,
+        };
+        let region = self.region.read().or(Err(gstreamer::FlowError::Error))?.0;
+        let preserve_alpha = *self
+            .preserve_alpha
+            .read()
+            .or(Err(gstreamer::FlowError::Error))?;
+        let low_memory = *self
+            .low_memory
+            .read()
+            .or(Err(gstreamer::FlowError::Error))?;
 
         let out_stride = out_frame.plane_stride()[0] as usize;
         let out_format = out_frame.format();
@@ -161,20 +262,65 @@ impl VideoFilterImpl for NtscFilter {
 
         match out_format {
             VideoFormat::Rgbx | VideoFormat::Rgba => {
-                process_gst_frame::<Rgbx8>(in_frame, out_data, out_stride, None, &settings)?;
+                process_gst_frame::<Rgbx8>(
+                    in_frame,
+                    out_data,
+                    out_stride,
+                    None,
+                    &settings,
+                    region.as_ref(),
+                    preserve_alpha,
+                    low_memory,
+                )?;
             }
             VideoFormat::Bgrx | VideoFormat::Bgra => {
-                process_gst_frame::<Bgrx8>(in_frame, out_data, out_stride, None, &settings)?;
+                process_gst_frame::<Bgrx8>(
+                    in_frame,
+                    out_data,
+                    out_stride,
+                    None,
+                    &settings,
+                    region.as_ref(),
+                    preserve_alpha,
+                    low_memory,
+                )?;
             }
             VideoFormat::Xrgb | VideoFormat::Argb => {
-                process_gst_frame::<Xrgb8>(in_frame, out_data, out_stride, None, &settings)?;
+                process_gst_frame::<Xrgb8>(
+                    in_frame,
+                    out_data,
+                    out_stride,
+                    None,
+                    &settings,
+                    region.as_ref(),
+                    preserve_alpha,
+                    low_memory,
+                )?;
             }
             VideoFormat::Xbgr | VideoFormat::Abgr => {
-                process_gst_frame::<Xbgr8>(in_frame, out_data, out_stride, None, &settings)?;
+                process_gst_frame::<Xbgr8>(
+                    in_frame,
+                    out_data,
+                    out_stride,
+                    None,
+                    &settings,
+                    region.as_ref(),
+                    preserve_alpha,
+                    low_memory,
+                )?;
             }
             VideoFormat::Argb64 => {
                 let data_16 = unsafe { out_data.align_to_mut::<u16>() }.1;
-                process_gst_frame::<Xrgb16>(in_frame, data_16, out_stride, None, &settings)?;
+                process_gst_frame::<Xrgb16>(
+                    in_frame,
+                    data_16,
+                    out_stride,
+                    None,
+                    &settings,
+                    region.as_ref(),
+                    preserve_alpha,
+                    low_memory,
+                )?;
             }
             _ => Err(gstreamer::FlowError::NotSupported)?,
         };
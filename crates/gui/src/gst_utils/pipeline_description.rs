@@ -0,0 +1,112 @@
+use gstreamer::{glib, prelude::*};
+
+/// Properties that are set by the structure of the pipeline rather than by the element's configuration.
+const IGNORED_PROPERTIES: &[&str] = &["name", "parent"];
+
+/// Describe a pipeline's current element graph in `gst-launch-1.0` syntax, for debugging and bug reports. Each element
+/// is listed along with any properties that differ from their defaults, followed by the links between them.
+///
+/// Elements that decodebin adds internally aren't included, since it'll add them again when the description is
+/// launched. Properties whose values can't be represented as text (e.g. the effect settings block, which is also
+/// available as `settings-json`) are left out.
+pub fn describe_pipeline(pipeline: &gstreamer::Pipeline) -> String {
+    // iterate_sorted goes from the sinks to the sources, and the description reads better the other way around
+    let mut elements = pipeline
+        .iterate_sorted()
+        .into_iter()
+        .filter_map(Result::ok)
+        .collect::<Vec<_>>();
+    elements.reverse();
+
+    let mut parts = elements.iter().map(describe_element).collect::<Vec<_>>();
+
+    for element in &elements {
+        for src_pad in element.src_pads() {
+            let Some(sink_pad) = src_pad.peer() else {
+                continue;
+            };
+            let Some(sink_element) = sink_pad.parent_element() else {
+                continue;
+            };
+            if sink_element.parent().as_ref() != Some(pipeline.upcast_ref::<gstreamer::Object>()) {
+                continue;
+            }
+
+            parts.push(format!(
+                "{}.{} ! {}.{}",
+                element.name(),
+                pad_reference(&src_pad),
+                sink_element.name(),
+                pad_reference(&sink_pad)
+            ));
+        }
+    }
+
+    parts.join("  ")
+}
+
+fn describe_element(element: &gstreamer::Element) -> String {
+    let factory_name = element
+        .factory()
+        .map(|factory| factory.name().to_string())
+        .unwrap_or_else(|| element.type_().name().to_string());
+    let mut description = format!("{factory_name} name={}", element.name());
+
+    for pspec in element.list_properties().iter() {
+        let flags = pspec.flags();
+        if !flags.contains(glib::ParamFlags::READWRITE)
+            || flags.contains(glib::ParamFlags::DEPRECATED)
+            || IGNORED_PROPERTIES.contains(&pspec.name())
+        {
+            continue;
+        }
+
+        let Ok(value) = element.property_value(pspec.name()).serialize() else {
+            continue;
+        };
+        if pspec
+            .default_value()
+            .serialize()
+            .is_ok_and(|default_value| default_value == value)
+        {
+            continue;
+        }
+
+        description.push_str(&format!(" {}={}", pspec.name(), quote_value(&value)));
+    }
+
+    description
+}
+
+/// The pad name to use for a link. Pads that only exist sometimes (like decodebin's outputs) are left unnamed so that
+/// the link is made once they appear, and request pads are referred to by their template so they'll be requested anew.
+fn pad_reference(pad: &gstreamer::Pad) -> String {
+    match pad.pad_template() {
+        Some(template) if template.presence() == gstreamer::PadPresence::Sometimes => String::new(),
+        Some(template) if template.presence() == gstreamer::PadPresence::Request => {
+            template.name_template().to_string()
+        }
+        _ => pad.name().to_string(),
+    }
+}
+
+fn quote_value(value: &str) -> String {
+    let is_plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-+./:".contains(c));
+    if is_plain {
+        return value.to_string();
+    }
+
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
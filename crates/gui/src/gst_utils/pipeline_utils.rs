@@ -1,9 +1,16 @@
-use super::{gstreamer_error::GstreamerError, scale_from_caps};
+use super::{
+    camera::{create_camera_source, is_camera},
+    gstreamer_error::GstreamerError,
+    hdr_tonemap::{HdrTransfer, TonemapSettings},
+    image_sequence::{create_image_sequence_source, is_image_sequence},
+    scaled_caps,
+};
 use gstreamer::{element_error, element_warning, glib, prelude::*};
 use log::debug;
 use std::{
     error::Error,
     fmt::Display,
+    path::Path,
     sync::{atomic::AtomicBool, Arc, Mutex},
 };
 
@@ -41,6 +48,119 @@ impl From<glib::Error> for PipelineError {
     }
 }
 
+/// Whether the "path" is actually a URL like `http://...` or `rtsp://...`, to be streamed over the network.
+pub fn is_url(path: &Path) -> bool {
+    let Some((scheme, _)) = path.to_str().and_then(|path| path.split_once("://")) else {
+        return false;
+    };
+    scheme.len() > 1
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+}
+
+/// Create the source element for a video file, network stream, image sequence, or the camera. Image sequences are
+/// played back at the given framerate, since image files don't have one of their own.
+pub fn create_source(
+    path: &Path,
+    framerate: gstreamer::Fraction,
+) -> Result<gstreamer::Element, GstreamerError> {
+    if is_url(path) {
+        // Picks the right source for the protocol, e.g. souphttpsrc or rtspsrc
+        Ok(gstreamer::Element::make_from_uri(
+            gstreamer::URIType::Src,
+            &path.to_string_lossy(),
+            None,
+        )?)
+    } else if is_camera(path) {
+        create_camera_source()
+    } else if is_image_sequence(path) {
+        create_image_sequence_source(path, framerate)
+    } else {
+        Ok(gstreamer::ElementFactory::make("filesrc")
+            .property("location", path)
+            .build()?)
+    }
+}
+
+/// Link a source element to the element that parses or decodes it. Some sources (like rtspsrc) only add their pads
+/// once they know what streams there are; for those, the first video stream is linked and the rest are discarded.
+pub fn link_source(
+    src: &gstreamer::Element,
+    sink: &gstreamer::Element,
+) -> Result<(), GstreamerError> {
+    if src.static_pad("src").is_some() {
+        src.link(sink)?;
+        return Ok(());
+    }
+
+    let sink = sink.clone();
+    src.connect_pad_added(move |src, pad| {
+        let is_video = pad
+            .current_caps()
+            .and_then(|caps| {
+                let structure = caps.structure(0)?;
+                Some(
+                    structure.name().starts_with("video/")
+                        || structure
+                            .get::<&str>("media")
+                            .is_ok_and(|media| media == "video"),
+                )
+            })
+            .unwrap_or(false);
+
+        let res = (|| -> Result<(), GstreamerError> {
+            match sink.static_pad("sink") {
+                Some(sink_pad) if is_video && !sink_pad.is_linked() => {
+                    pad.link(&sink_pad)?;
+                }
+                _ => {
+                    let Some(bin) = src.parent().and_downcast::<gstreamer::Bin>() else {
+                        return Ok(());
+                    };
+                    let fakesink = gstreamer::ElementFactory::make("fakesink")
+                        .property("async", false)
+                        .build()?;
+                    bin.add(&fakesink)?;
+                    fakesink.sync_state_with_parent()?;
+                    pad.link(&fakesink.static_pad("sink").unwrap())?;
+                }
+            }
+            Ok(())
+        })();
+
+        if let Err(err) = res {
+            element_error!(
+                src,
+                gstreamer::CoreError::Negotiation,
+                ("Failed to link stream {}: {}", pad.name(), err)
+            );
+        }
+    });
+
+    Ok(())
+}
+
+/// Describe an audio track for the "audio-track" message that lets the application list the tracks to choose from.
+/// The language and title come from the stream's tags, if the demuxer has sent them yet.
+fn audio_track_structure(pad: &gstreamer::Pad, index: usize) -> gstreamer::Structure {
+    let mut structure = gstreamer::Structure::builder("audio-track")
+        .field("index", index as u32)
+        .build();
+    if let Some(tags) = pad.sticky_event::<gstreamer::event::Tag>(0) {
+        let tags = tags.tag();
+        if let Some(language) = tags.get::<gstreamer::tags::LanguageCode>() {
+            structure.set("language", language.get());
+        }
+        if let Some(title) = tags.get::<gstreamer::tags::Title>() {
+            structure.set("title", title.get());
+        }
+    }
+    structure
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn create_pipeline<
     AudioElemCallback: FnOnce(&gstreamer::Pipeline) -> Result<Option<gstreamer::Element>, GstreamerError>
         + Send
@@ -59,20 +179,24 @@ pub fn create_pipeline<
     bus_handler: BusHandler,
     duration: Option<gstreamer::ClockTime>,
     initial_scale: Option<usize>,
+    square_pixels: bool,
     initial_still_image_framerate: gstreamer::Fraction,
+    tonemap: TonemapSettings,
+    audio_track: usize,
     callback: Option<PipelineCallback>,
 ) -> Result<gstreamer::Pipeline, GstreamerError> {
     let pipeline = gstreamer::Pipeline::default();
     let decodebin = gstreamer::ElementFactory::make("decodebin").build()?;
     pipeline.add_many([&src_pad, &decodebin])?;
-    gstreamer::Element::link_many([&src_pad, &decodebin])?;
+    link_source(&src_pad, &decodebin)?;
 
     let has_audio = Mutex::new(false);
+    let num_audio_tracks = Mutex::new(0usize);
+    let audio_selector = Mutex::new(None::<gstreamer::Element>);
     let has_video = Arc::new(Mutex::new(false));
     let has_video_for_bus_handler = Arc::clone(&has_video);
 
-    let handler_id: Arc<Mutex<Option<glib::SignalHandlerId>>> = Arc::new(Mutex::new(None));
-    let handler_id_for_handler = Arc::clone(&handler_id);
+    let handler_id: Mutex<Option<glib::SignalHandlerId>> = Mutex::new(None);
 
     let audio_sink = Mutex::new(Some(audio_sink));
     let video_sink = Mutex::new(Some(video_sink));
@@ -80,7 +204,6 @@ pub fn create_pipeline<
     let pipeline_weak = gstreamer::prelude::ObjectExt::downgrade(&pipeline);
     let pad_added_handler = decodebin.connect_pad_added(move |dbin, src_pad| {
         let pipeline = &pipeline_weak;
-        let handler_id = &handler_id_for_handler;
         // Try to detect whether the raw stream decodebin provided us with
         // just now is either audio or video (or none of both, e.g. subtitles).
         let (is_audio, is_video) = {
@@ -109,47 +232,93 @@ pub fn create_pipeline<
         let insert_sink = |is_audio, is_video| -> Result<(), GstreamerError> {
             let mut has_audio = has_audio.lock().unwrap();
             let mut has_video = has_video.lock().unwrap();
-            if is_audio && !*has_audio {
-                debug!("connected audio");
+            if is_audio {
+                let track_index = {
+                    let mut num_audio_tracks = num_audio_tracks.lock().unwrap();
+                    *num_audio_tracks += 1;
+                    *num_audio_tracks - 1
+                };
+                debug!("found audio track {}", track_index);
+                let _ = dbin.post_message(
+                    gstreamer::message::Element::builder(audio_track_structure(
+                        src_pad,
+                        track_index,
+                    ))
+                    .src(dbin)
+                    .build(),
+                );
 
                 if let Some(pipeline) = pipeline.upgrade() {
-                    let audio_sink = audio_sink.lock().unwrap().take();
-                    if let Some(sink) = audio_sink.map(|sink| sink(&pipeline)) {
-                        if let Some(sink) = sink? {
-                            let audio_queue = gstreamer::ElementFactory::make("queue").build()?;
-                            let audio_convert =
-                                gstreamer::ElementFactory::make("audioconvert").build()?;
-                            let audio_resample =
-                                gstreamer::ElementFactory::make("audioresample").build()?;
-                            let audio_volume = gstreamer::ElementFactory::make("volume")
-                                .name("audio_volume")
-                                .build()?;
-
-                            let audio_elements =
-                                &[&audio_queue, &audio_convert, &audio_resample, &audio_volume];
-                            pipeline.add_many(audio_elements)?;
-                            gstreamer::Element::link_many(audio_elements)?;
-
-                            audio_volume.link(&sink)?;
-                            sink.sync_state_with_parent()?;
-
-                            for e in audio_elements {
-                                e.sync_state_with_parent()?
+                    let mut audio_selector = audio_selector.lock().unwrap();
+                    if !*has_audio {
+                        debug!("connected audio");
+
+                        let audio_sink = audio_sink.lock().unwrap().take();
+                        if let Some(sink) = audio_sink.map(|sink| sink(&pipeline)) {
+                            if let Some(sink) = sink? {
+                                // All the audio tracks go through a selector, so the track can be switched without
+                                // rebuilding the pipeline
+                                let selector = gstreamer::ElementFactory::make("input-selector")
+                                    .name("audio_selector")
+                                    .build()?;
+                                let audio_queue = gstreamer::ElementFactory::make("queue")
+                                    .name("audio_queue")
+                                    .build()?;
+                                let audio_convert =
+                                    gstreamer::ElementFactory::make("audioconvert").build()?;
+                                let audio_resample =
+                                    gstreamer::ElementFactory::make("audioresample").build()?;
+                                let audio_volume = gstreamer::ElementFactory::make("volume")
+                                    .name("audio_volume")
+                                    .build()?;
+
+                                let audio_elements = &[
+                                    &selector,
+                                    &audio_queue,
+                                    &audio_convert,
+                                    &audio_resample,
+                                    &audio_volume,
+                                ];
+                                pipeline.add_many(audio_elements)?;
+                                gstreamer::Element::link_many(audio_elements)?;
+
+                                audio_volume.link(&sink)?;
+                                sink.sync_state_with_parent()?;
+
+                                for e in audio_elements {
+                                    e.sync_state_with_parent()?
+                                }
+
+                                *audio_selector = Some(selector);
                             }
-
-                            // Get the queue element's sink pad and link the decodebin's newly created
-                            // src pad for the audio stream to it.
-                            let sink_pad = audio_queue
-                                .static_pad("sink")
-                                .expect("queue has no sinkpad");
-                            src_pad.link(&sink_pad)?;
                         }
+
+                        *has_audio = true;
                     }
 
-                    *has_audio = true;
+                    if let Some(selector) = audio_selector.as_ref() {
+                        // Link the decodebin's newly created src pad for the audio stream to the selector
+                        let sink_pad = selector
+                            .request_pad_simple(&format!("sink_{}", track_index))
+                            .expect("input-selector has no sinkpad");
+                        src_pad.link(&sink_pad)?;
+                        if track_index == audio_track {
+                            selector.set_property("active-pad", &sink_pad);
+                        }
+                    } else {
+                        // The audio isn't wanted. Discard it instead of leaving the pad unlinked, which would
+                        // cause the decoder to return a not-linked error.
+                        let audio_fakesink = gstreamer::ElementFactory::make("fakesink")
+                            .property("async", false)
+                            .build()?;
+                        pipeline.add(&audio_fakesink)?;
+                        audio_fakesink.sync_state_with_parent()?;
+                        let sink_pad = audio_fakesink
+                            .static_pad("sink")
+                            .expect("fakesink has no sinkpad");
+                        src_pad.link(&sink_pad)?;
+                    }
                 }
-
-                // has_audio
             } else if is_video && !*has_video {
                 debug!("connected video");
 
@@ -183,18 +352,52 @@ pub fn create_pipeline<
                             .name("framerate_caps_filter")
                             .build()?;
 
-                        let video_elements = &[
+                        let caps = src_pad.current_caps();
+                        let caps = caps.as_ref();
+
+                        // HDR video would otherwise be treated as SDR and come out washed-out and dim. The tone-mapper
+                        // is inserted even if it's disabled so the preview can switch it on and off.
+                        let hdr_transfer = caps.and_then(|caps| HdrTransfer::from_caps(caps));
+                        let hdr_tonemap = hdr_transfer
+                            .map(|transfer| {
+                                gstreamer::ElementFactory::make("hdrtonemap")
+                                    .name("hdr_tonemap")
+                                    .property("transfer", transfer)
+                                    .property("target-nits", tonemap.target_nits)
+                                    .property("enabled", tonemap.enabled)
+                                    .build()
+                            })
+                            .transpose()?;
+
+                        let mut video_elements = vec![
                             &video_queue,
                             //&video_flip,
                             &video_convert,
+                        ];
+                        video_elements.extend(hdr_tonemap.as_ref());
+                        video_elements.extend([
                             &video_rate,
                             &video_scale,
                             &caps_filter,
                             &framerate_caps_filter,
-                        ];
+                        ]);
+                        let video_elements = video_elements.as_slice();
                         pipeline.add_many(video_elements)?;
                         gstreamer::Element::link_many(video_elements)?;
 
+                        if let Some(transfer) = hdr_transfer {
+                            // Let the application know, so it can warn if the video isn't being tone-mapped
+                            let _ = dbin.post_message(
+                                gstreamer::message::Element::builder(
+                                    gstreamer::Structure::builder("hdr-input")
+                                        .field("transfer", transfer)
+                                        .build(),
+                                )
+                                .src(dbin)
+                                .build(),
+                            );
+                        }
+
                         let video_sink = video_sink(&pipeline)?;
                         framerate_caps_filter.link(&video_sink)?;
 
@@ -209,9 +412,6 @@ pub fn create_pipeline<
                             .static_pad("sink")
                             .expect("queue has no sinkpad");
 
-                        let caps = src_pad.current_caps();
-                        let caps = caps.as_ref();
-
                         let framerate = caps.and_then(|caps| {
                             caps.structure(0)?
                                 .get::<gstreamer::Fraction>("framerate")
@@ -223,16 +423,11 @@ pub fn create_pipeline<
                             None => false,
                         };
 
-                        if let (Some(caps), Some(initial_scale)) = (caps, initial_scale) {
-                            if let Some((width, height)) = scale_from_caps(caps, initial_scale) {
-                                caps_filter.set_property(
-                                    "caps",
-                                    gstreamer_video::VideoCapsBuilder::default()
-                                        .width(width)
-                                        .height(height)
-                                        .build(),
-                                );
-                            }
+                        if let Some(caps) = caps {
+                            caps_filter.set_property(
+                                "caps",
+                                scaled_caps(caps, initial_scale, square_pixels),
+                            );
                         }
 
                         if is_still_image {
@@ -270,15 +465,6 @@ pub fn create_pipeline<
                 *has_video = true;
             }
 
-            // We have both streams. No need to search for more.
-            if *has_audio && *has_video {
-                let mut id = handler_id.lock().unwrap();
-                let id = id.take();
-                if let Some(id) = id {
-                    dbin.disconnect(id);
-                }
-            }
-
             Ok(())
         };
 
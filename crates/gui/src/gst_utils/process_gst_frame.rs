@@ -1,12 +1,16 @@
-use std::convert::identity;
+use std::{
+    convert::identity,
+    time::{Duration, Instant},
+};
 
 use gstreamer::{BufferRef, ClockTime, FlowError};
-use gstreamer_video::{VideoFormat, VideoFrameRef, VideoInterlaceMode};
+use gstreamer_video::{VideoFormat, VideoFrameRef, VideoInfo, VideoInterlaceMode};
 use ntscrs::{
+    ntsc::{FrameTime, StageTimings},
     settings::NtscEffect,
     yiq_fielding::{
-        Bgrx8, BlitInfo, DeinterlaceMode, PixelFormat, Rect, Rgbx8, Xbgr8, Xrgb16, Xrgb8, YiqField,
-        YiqOwned, YiqView,
+        Bgrx8, BlitInfo, DeinterlaceMode, Normalize, PixelFormat, Rect, Rgbx8, Xbgr8, Xrgb16,
+        Xrgb8, YiqField, YiqOwned, YiqView,
     },
 };
 
@@ -41,19 +45,297 @@ fn frame_to_yiq(
     })
 }
 
-pub fn process_gst_frame<S: PixelFormat>(
+/// How many rows of the frame are processed at once in low-memory mode. Each strip also reads some rows past its
+/// edges, so this shouldn't be too small or most of the work goes into those.
+const LOW_MEMORY_STRIP_ROWS: usize = 64;
+
+/// Apply the effect to the frame a strip of rows at a time, so that the whole frame never has to be converted to YIQ at
+/// once. See [`NtscEffect::apply_effect_in_strips`].
+#[allow(clippy::too_many_arguments)]
+fn apply_effect_in_strips<S: PixelFormat>(
     in_frame: &VideoFrameRef<&BufferRef>,
     out_frame: &mut [S::DataFormat],
     out_stride: usize,
-    out_rect: Option<Rect>,
     settings: &NtscEffect,
+    field: YiqField,
+    frame_num: usize,
+    time: FrameTime,
+    deinterlace_mode: DeinterlaceMode,
+) -> Result<(), FlowError> {
+    let dimensions = (in_frame.width() as usize, in_frame.height() as usize);
+    let in_stride = in_frame.plane_stride()[0] as usize;
+    let in_data = in_frame.plane_data(0).or(Err(FlowError::Error))?;
+    match in_frame.format() {
+        VideoFormat::Rgbx | VideoFormat::Rgba => settings.apply_effect_in_strips::<Rgbx8, S>(
+            in_data,
+            in_stride,
+            out_frame,
+            out_stride,
+            dimensions,
+            field,
+            frame_num,
+            Some(time),
+            LOW_MEMORY_STRIP_ROWS,
+            deinterlace_mode,
+        ),
+        VideoFormat::Bgrx | VideoFormat::Bgra => settings.apply_effect_in_strips::<Bgrx8, S>(
+            in_data,
+            in_stride,
+            out_frame,
+            out_stride,
+            dimensions,
+            field,
+            frame_num,
+            Some(time),
+            LOW_MEMORY_STRIP_ROWS,
+            deinterlace_mode,
+        ),
+        VideoFormat::Xrgb | VideoFormat::Argb => settings.apply_effect_in_strips::<Xrgb8, S>(
+            in_data,
+            in_stride,
+            out_frame,
+            out_stride,
+            dimensions,
+            field,
+            frame_num,
+            Some(time),
+            LOW_MEMORY_STRIP_ROWS,
+            deinterlace_mode,
+        ),
+        VideoFormat::Xbgr | VideoFormat::Abgr => settings.apply_effect_in_strips::<Xbgr8, S>(
+            in_data,
+            in_stride,
+            out_frame,
+            out_stride,
+            dimensions,
+            field,
+            frame_num,
+            Some(time),
+            LOW_MEMORY_STRIP_ROWS,
+            deinterlace_mode,
+        ),
+        VideoFormat::Argb64 => {
+            let data_16 = unsafe { in_data.align_to::<u16>() }.1;
+            settings.apply_effect_in_strips::<Xrgb16, S>(
+                data_16,
+                in_stride,
+                out_frame,
+                out_stride,
+                dimensions,
+                field,
+                frame_num,
+                Some(time),
+                LOW_MEMORY_STRIP_ROWS,
+                deinterlace_mode,
+            )
+        }
+        _ => Err(FlowError::NotSupported)?,
+    }
+
+    Ok(())
+}
+
+/// The effect only operates on color, so it writes out fully opaque pixels. Copy the input frame's alpha channel over
+/// them instead. Does nothing if either format lacks an alpha channel.
+fn copy_alpha<S: PixelFormat>(
+    in_frame: &VideoFrameRef<&BufferRef>,
+    out_frame: &mut [S::DataFormat],
+    blit_info: &BlitInfo,
 ) -> Result<(), FlowError> {
-    let info = in_frame.info();
+    let Some(out_alpha_index) = S::ORDER.rgba_indices().3 else {
+        return Ok(());
+    };
+    let (in_alpha_index, in_is_16_bit) = match in_frame.format() {
+        VideoFormat::Rgba | VideoFormat::Bgra => (3, false),
+        VideoFormat::Argb | VideoFormat::Abgr => (0, false),
+        VideoFormat::Argb64 => (0, true),
+        _ => return Ok(()),
+    };
 
-    let timestamp = in_frame.buffer().pts().ok_or(FlowError::Error)?.nseconds();
+    let in_stride = in_frame.plane_stride()[0] as usize;
+    let in_data = in_frame.plane_data(0).or(Err(FlowError::Error))?;
+    let out_row_length = blit_info.row_bytes / std::mem::size_of::<S::DataFormat>();
+    let num_components = S::ORDER.num_components();
+
+    let rect = &blit_info.rect;
+    for y in rect.top..rect.bottom {
+        for x in rect.left..rect.right {
+            let alpha = if in_is_16_bit {
+                let offset = y * in_stride + (x * 4 + in_alpha_index) * 2;
+                u16::from_ne_bytes([in_data[offset], in_data[offset + 1]]).to_norm()
+            } else {
+                in_data[y * in_stride + x * 4 + in_alpha_index].to_norm()
+            };
+            out_frame[y * out_row_length + x * num_components + out_alpha_index] =
+                S::DataFormat::from_norm(alpha);
+        }
+    }
+
+    Ok(())
+}
+
+/// A rectangle of the frame to confine the effect to. The rest of the frame is passed through untouched.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EffectRegion {
+    /// Edges of the rectangle, as fractions of the frame's width and height.
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    /// Width of the soft edge between the processed and unprocessed parts, as a fraction of the frame's height.
+    pub feather: f32,
+    /// Apply the effect everywhere except inside the rectangle instead.
+    pub invert: bool,
+}
+
+impl EffectRegion {
+    /// How much of the effect shows at the given pixel, from 0 (none of it) to 1 (all of it).
+    fn mask(&self, x: usize, y: usize, width: usize, height: usize) -> f32 {
+        let u = (x as f32 + 0.5) / width as f32;
+        let v = (y as f32 + 0.5) / height as f32;
+        let aspect = width as f32 / height as f32;
+        // Distance to the nearest edge in units of the frame's height, so the feathering is the same horizontally and
+        // vertically. It's negative outside the rectangle.
+        let distance = f32::min(
+            f32::min(u - self.left, self.right - u) * aspect,
+            f32::min(v - self.top, self.bottom - v),
+        );
+        let mask = if self.feather > 0.0 {
+            (distance / self.feather + 0.5).clamp(0.0, 1.0)
+        } else if distance >= 0.0 {
+            1.0
+        } else {
+            0.0
+        };
+
+        if self.invert {
+            1.0 - mask
+        } else {
+            mask
+        }
+    }
+}
+
+/// Blend the effect's output back into the input frame outside of `region`, so only the part of the frame inside it
+/// looks processed.
+fn blend_region<S: PixelFormat>(
+    in_frame: &VideoFrameRef<&BufferRef>,
+    out_frame: &mut [S::DataFormat],
+    blit_info: &BlitInfo,
+    region: &EffectRegion,
+) -> Result<(), FlowError> {
+    let (in_rgb_indices, in_is_16_bit) = match in_frame.format() {
+        VideoFormat::Rgbx | VideoFormat::Rgba => ([0, 1, 2], false),
+        VideoFormat::Bgrx | VideoFormat::Bgra => ([2, 1, 0], false),
+        VideoFormat::Xrgb | VideoFormat::Argb => ([1, 2, 3], false),
+        VideoFormat::Xbgr | VideoFormat::Abgr => ([3, 2, 1], false),
+        VideoFormat::Argb64 => ([1, 2, 3], true),
+        _ => Err(FlowError::NotSupported)?,
+    };
+    let (out_r, out_g, out_b, _) = S::ORDER.rgba_indices();
+
+    let width = in_frame.width() as usize;
+    let height = in_frame.height() as usize;
+    let in_stride = in_frame.plane_stride()[0] as usize;
+    let in_data = in_frame.plane_data(0).or(Err(FlowError::Error))?;
+    let out_row_length = blit_info.row_bytes / std::mem::size_of::<S::DataFormat>();
+    let num_components = S::ORDER.num_components();
+
+    let rect = &blit_info.rect;
+    for y in rect.top..rect.bottom {
+        for x in rect.left..rect.right {
+            let mask = region.mask(x, y, width, height);
+            if mask >= 1.0 {
+                continue;
+            }
+
+            for (in_index, out_index) in in_rgb_indices.into_iter().zip([out_r, out_g, out_b]) {
+                let original = if in_is_16_bit {
+                    let offset = y * in_stride + (x * 4 + in_index) * 2;
+                    u16::from_ne_bytes([in_data[offset], in_data[offset + 1]]).to_norm()
+                } else {
+                    in_data[y * in_stride + x * 4 + in_index].to_norm()
+                };
+                let out = &mut out_frame[y * out_row_length + x * num_components + out_index];
+                *out = S::DataFormat::from_norm(original + (out.to_norm() - original) * mask);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The number of the frame shown at `pts`, and when it's shown, for a buffer with the given video info.
+pub fn frame_timing(info: &VideoInfo, buffer: &BufferRef, pts: ClockTime) -> (usize, FrameTime) {
+    let timestamp = pts.nseconds();
     let frame = (info.fps().numer() as u128 * (timestamp + 100) as u128
         / info.fps().denom() as u128) as u64
         / ClockTime::SECOND.nseconds();
+    // Buffers don't always say how long they last, so fall back to the frame rate
+    let duration = buffer
+        .duration()
+        .map(ClockTime::nseconds)
+        .or_else(|| {
+            (info.fps().numer() > 0).then(|| {
+                ClockTime::SECOND.nseconds() * info.fps().denom() as u64 / info.fps().numer() as u64
+            })
+        })
+        .unwrap_or_default();
+    let time = FrameTime {
+        timestamp: Duration::from_nanos(timestamp),
+        duration: Duration::from_nanos(duration),
+    };
+
+    // Each field of an interlaced frame counts separately
+    match info.interlace_mode() {
+        VideoInterlaceMode::Progressive => (frame as usize, time),
+        _ => (frame as usize * 2, time),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn process_gst_frame<S: PixelFormat>(
+    in_frame: &VideoFrameRef<&BufferRef>,
+    out_frame: &mut [S::DataFormat],
+    out_stride: usize,
+    out_rect: Option<Rect>,
+    settings: &NtscEffect,
+    region: Option<&EffectRegion>,
+    preserve_alpha: bool,
+    low_memory: bool,
+) -> Result<(), FlowError> {
+    let pts = in_frame.buffer().pts().ok_or(FlowError::Error)?;
+    process_gst_frame_at::<S>(
+        in_frame,
+        out_frame,
+        out_stride,
+        out_rect,
+        settings,
+        region,
+        preserve_alpha,
+        low_memory,
+        pts,
+        None,
+    )
+}
+
+/// Like [`process_gst_frame`], but processes the frame as if it were shown at `pts` instead of its own timestamp. Its
+/// noise, and which field is used, then match the frame at `pts`. If `timings` is given, how long each stage of the
+/// effect takes is added to it, along with the time spent converting to and from YIQ. Low-memory mode isn't timed.
+#[allow(clippy::too_many_arguments)]
+pub fn process_gst_frame_at<S: PixelFormat>(
+    in_frame: &VideoFrameRef<&BufferRef>,
+    out_frame: &mut [S::DataFormat],
+    out_stride: usize,
+    out_rect: Option<Rect>,
+    settings: &NtscEffect,
+    region: Option<&EffectRegion>,
+    preserve_alpha: bool,
+    low_memory: bool,
+    pts: ClockTime,
+    timings: Option<&mut StageTimings>,
+) -> Result<(), FlowError> {
+    let (frame_num, time) = frame_timing(in_frame.info(), in_frame.buffer(), pts);
 
     let blit_info = out_rect
         .map(|rect| BlitInfo::new(rect, out_stride, false))
@@ -65,19 +347,11 @@ pub fn process_gst_frame<S: PixelFormat>(
             )
         });
 
-    match in_frame.info().interlace_mode() {
-        VideoInterlaceMode::Progressive => {
-            let field = settings.use_field.to_yiq_field(frame as usize);
-            let mut yiq = frame_to_yiq(in_frame, field)?;
-            let mut view = YiqView::from(&mut yiq);
-            settings.apply_effect_to_yiq(&mut view, frame as usize);
-            view.write_to_strided_buffer::<S, _>(
-                out_frame,
-                blit_info,
-                DeinterlaceMode::Bob,
-                identity,
-            );
-        }
+    let (field, deinterlace_mode) = match in_frame.info().interlace_mode() {
+        VideoInterlaceMode::Progressive => (
+            settings.use_field.to_yiq_field(frame_num),
+            DeinterlaceMode::Bob,
+        ),
         VideoInterlaceMode::Interleaved | VideoInterlaceMode::Mixed => {
             let field = match (in_frame.is_tff(), in_frame.is_onefield()) {
                 (true, true) => YiqField::Upper,
@@ -85,18 +359,58 @@ pub fn process_gst_frame<S: PixelFormat>(
                 (true, false) => YiqField::InterleavedUpper,
                 (false, false) => YiqField::InterleavedLower,
             };
-
-            let mut yiq = frame_to_yiq(in_frame, field)?;
-            let mut view = YiqView::from(&mut yiq);
-            settings.apply_effect_to_yiq(&mut view, frame as usize * 2);
-            view.write_to_strided_buffer::<S, _>(
-                out_frame,
-                blit_info,
-                DeinterlaceMode::Skip,
-                identity,
-            );
+            (field, DeinterlaceMode::Skip)
         }
         _ => Err(FlowError::NotSupported)?,
+    };
+
+    // Strips always cover the whole frame, so rendering into just part of the output goes through the full-frame path
+    if low_memory && out_rect.is_none() {
+        apply_effect_in_strips::<S>(
+            in_frame,
+            out_frame,
+            out_stride,
+            settings,
+            field,
+            frame_num,
+            time,
+            deinterlace_mode,
+        )?;
+    } else {
+        let start = Instant::now();
+        let mut yiq = frame_to_yiq(in_frame, field)?;
+        let mut view = YiqView::from(&mut yiq);
+        match timings {
+            Some(timings) => {
+                timings.add("color conversion", start.elapsed());
+                settings.apply_effect_to_yiq_profiled(&mut view, frame_num, Some(time), timings);
+                let start = Instant::now();
+                view.write_to_strided_buffer::<S, _>(
+                    out_frame,
+                    blit_info,
+                    deinterlace_mode,
+                    identity,
+                );
+                timings.add("color conversion", start.elapsed());
+            }
+            None => {
+                settings.apply_effect_to_yiq_at(&mut view, frame_num, time);
+                view.write_to_strided_buffer::<S, _>(
+                    out_frame,
+                    blit_info,
+                    deinterlace_mode,
+                    identity,
+                );
+            }
+        }
+    }
+
+    if let Some(region) = region {
+        blend_region::<S>(in_frame, out_frame, &blit_info, region)?;
+    }
+
+    if preserve_alpha {
+        copy_alpha::<S>(in_frame, out_frame, &blit_info)?;
     }
 
     Ok(())
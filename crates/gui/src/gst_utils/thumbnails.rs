@@ -0,0 +1,186 @@
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    thread,
+};
+
+use eframe::egui::{self, ColorImage, TextureOptions};
+use gstreamer::{prelude::*, ClockTime};
+use log::debug;
+
+use super::{
+    gstreamer_error::GstreamerError,
+    hdr_tonemap::TonemapSettings,
+    pipeline_utils::{create_pipeline, create_source, PipelineError},
+};
+use crate::timeline::TimelineThumbnail;
+
+/// Height, in pixels, that thumbnails are decoded at.
+const THUMBNAIL_HEIGHT: usize = 64;
+
+/// How long to wait for the thumbnail pipeline to seek before giving up.
+const SEEK_TIMEOUT: ClockTime = ClockTime::from_seconds(10);
+
+/// Decodes low-resolution frames at regular intervals throughout a video, for display along the timeline. Frames are
+/// taken from the source before the effect is applied, so they don't need to be regenerated when the settings change.
+///
+/// The frames are decoded in a background thread with their own pipeline, so this doesn't disturb the preview.
+/// Dropping the generator stops it.
+pub struct ThumbnailGenerator {
+    thumbnails: Arc<Mutex<Vec<TimelineThumbnail>>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl ThumbnailGenerator {
+    pub fn new(ctx: &egui::Context, path: PathBuf, count: usize) -> Self {
+        let thumbnails = Arc::new(Mutex::new(Vec::new()));
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let thumbnails_for_thread = Arc::clone(&thumbnails);
+        let cancelled_for_thread = Arc::clone(&cancelled);
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            if let Err(err) = Self::generate(
+                &ctx,
+                path,
+                count,
+                &thumbnails_for_thread,
+                &cancelled_for_thread,
+            ) {
+                debug!("failed to generate thumbnails: {:?}", err);
+            }
+        });
+
+        Self {
+            thumbnails,
+            cancelled,
+        }
+    }
+
+    /// The thumbnails generated so far, in order of their timestamps.
+    pub fn thumbnails(&self) -> MutexGuard<'_, Vec<TimelineThumbnail>> {
+        self.thumbnails.lock().unwrap()
+    }
+
+    fn generate(
+        ctx: &egui::Context,
+        path: PathBuf,
+        count: usize,
+        thumbnails: &Mutex<Vec<TimelineThumbnail>>,
+        cancelled: &AtomicBool,
+    ) -> Result<(), GstreamerError> {
+        let src = create_source(&path, gstreamer::Fraction::from(30))?;
+        let sink = gstreamer::ElementFactory::make("fakesink")
+            .property("enable-last-sample", true)
+            .property("sync", false)
+            .build()?;
+        let sink_for_closure = sink.clone();
+
+        let pipeline = create_pipeline(
+            src,
+            |_| Ok(None),
+            move |pipeline| {
+                let caps_filter = gstreamer::ElementFactory::make("capsfilter")
+                    .property(
+                        "caps",
+                        gstreamer_video::VideoCapsBuilder::new()
+                            .format(gstreamer_video::VideoFormat::Rgba)
+                            .build(),
+                    )
+                    .build()?;
+                pipeline.add_many([&caps_filter, &sink_for_closure])?;
+                caps_filter.link(&sink_for_closure)?;
+                sink_for_closure.sync_state_with_parent()?;
+                Ok(caps_filter)
+            },
+            |_, _| gstreamer::BusSyncReply::Drop,
+            None,
+            Some(THUMBNAIL_HEIGHT),
+            true,
+            gstreamer::Fraction::from(30),
+            TonemapSettings::default(),
+            0,
+            None::<fn(Result<gstreamer::Pipeline, PipelineError>)>,
+        )?;
+
+        let res = (|| -> Result<(), GstreamerError> {
+            pipeline.set_state(gstreamer::State::Paused)?;
+            pipeline.state(SEEK_TIMEOUT).0?;
+
+            let Some(duration) = pipeline.query_duration::<ClockTime>() else {
+                return Ok(());
+            };
+            // Still images only need the one thumbnail
+            let count = if pipeline.by_name("still_image_freeze").is_some() {
+                1
+            } else {
+                count
+            };
+
+            for i in 0..count {
+                if cancelled.load(Ordering::Acquire) {
+                    break;
+                }
+
+                // Take each thumbnail from the middle of the span of time it represents
+                let timestamp = ClockTime::from_nseconds(
+                    (duration.nseconds() as f64 * (i as f64 + 0.5) / count as f64) as u64,
+                );
+                // Snapping to keyframes is much cheaper, and thumbnails don't need to be exact
+                pipeline.seek_simple(
+                    gstreamer::SeekFlags::FLUSH
+                        | gstreamer::SeekFlags::KEY_UNIT
+                        | gstreamer::SeekFlags::SNAP_NEAREST,
+                    timestamp,
+                )?;
+                pipeline.state(SEEK_TIMEOUT).0?;
+
+                let Some(image) = sink
+                    .property::<Option<gstreamer::Sample>>("last-sample")
+                    .and_then(|sample| Self::sample_to_image(&sample))
+                else {
+                    continue;
+                };
+
+                let texture =
+                    ctx.load_texture(format!("thumbnail_{i}"), image, TextureOptions::LINEAR);
+                thumbnails.lock().unwrap().push(TimelineThumbnail {
+                    timestamp: timestamp.nseconds(),
+                    texture,
+                });
+                ctx.request_repaint();
+            }
+
+            Ok(())
+        })();
+
+        pipeline.set_state(gstreamer::State::Null)?;
+        res
+    }
+
+    fn sample_to_image(sample: &gstreamer::Sample) -> Option<ColorImage> {
+        let info = gstreamer_video::VideoInfo::from_caps(sample.caps()?).ok()?;
+        let buffer = sample.buffer_owned()?;
+        let frame = gstreamer_video::VideoFrame::from_buffer_readable(buffer, &info).ok()?;
+
+        let width = frame.width() as usize;
+        let height = frame.height() as usize;
+        let stride = frame.plane_stride()[0] as usize;
+        let data = frame.plane_data(0).ok()?;
+        let pixels = (0..height)
+            .flat_map(|y| &data[y * stride..y * stride + width * 4])
+            .copied()
+            .collect::<Vec<_>>();
+
+        Some(ColorImage::from_rgba_unmultiplied([width, height], &pixels))
+    }
+}
+
+impl Drop for ThumbnailGenerator {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+}
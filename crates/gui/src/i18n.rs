@@ -0,0 +1,89 @@
+use std::{
+    collections::HashMap,
+    sync::{OnceLock, RwLock},
+};
+
+use tinyjson::JsonValue;
+
+/// A language the UI can be shown in. Its catalog is a JSON object mapping each English UI string to its translation.
+pub struct Language {
+    /// IETF language tag, which is what the chosen language is persisted as.
+    pub code: &'static str,
+    /// The language's name in that language, for the language picker.
+    pub name: &'static str,
+    catalog_json: &'static str,
+}
+
+/// Every language with a catalog. To add a translation, copy `locales/en.json`, translate its values (leaving the keys
+/// alone, and keeping each value's `{placeholders}`), and list it here. Strings missing from a catalog are shown in
+/// English.
+pub static LANGUAGES: &[Language] = &[Language {
+    code: "en",
+    name: "English",
+    catalog_json: include_str!("../locales/en.json"),
+}];
+
+static CATALOGS: OnceLock<Vec<HashMap<String, String>>> = OnceLock::new();
+static CURRENT_LANGUAGE: RwLock<usize> = RwLock::new(0);
+
+fn catalogs() -> &'static [HashMap<String, String>] {
+    CATALOGS.get_or_init(|| {
+        LANGUAGES
+            .iter()
+            .map(|language| {
+                let Ok(JsonValue::Object(entries)) = language.catalog_json.parse::<JsonValue>()
+                else {
+                    return HashMap::new();
+                };
+                entries
+                    .into_iter()
+                    .filter_map(|(key, value)| match value {
+                        JsonValue::String(value) => Some((key, value)),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .collect()
+    })
+}
+
+pub fn current_language() -> &'static Language {
+    &LANGUAGES[*CURRENT_LANGUAGE.read().unwrap()]
+}
+
+/// Switch the UI to the language with the given code. Returns false if there's no such language.
+pub fn set_language(code: &str) -> bool {
+    let Some(index) = LANGUAGES.iter().position(|language| language.code == code) else {
+        return false;
+    };
+    *CURRENT_LANGUAGE.write().unwrap() = index;
+    true
+}
+
+/// Translate a UI string into the current language, falling back to the (English) string itself if there's no
+/// translation for it.
+pub fn tr(key: &'static str) -> &'static str {
+    catalogs()[*CURRENT_LANGUAGE.read().unwrap()]
+        .get(key)
+        .map_or(key, |translated| translated.as_str())
+}
+
+/// Translate a UI string containing named `{placeholders}`, then fill each placeholder in with its value. Translations
+/// can move the placeholders around, but must keep their names.
+pub fn tr_format(key: &'static str, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+    args.iter().fold(tr(key).to_owned(), |text, (name, value)| {
+        text.replace(&format!("{{{name}}}"), &value.to_string())
+    })
+}
+
+/// Translate a UI string literal. See [`tr`](crate::i18n::tr). Placeholders can be filled in by name, as in
+/// `tr!("Queued {name}", name = file_name)`; see [`tr_format`](crate::i18n::tr_format).
+#[macro_export]
+macro_rules! tr {
+    ($key:expr) => {
+        $crate::i18n::tr($key)
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::tr_format($key, &[$((stringify!($name), &$value as &dyn ::std::fmt::Display)),+])
+    };
+}
@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+
+use gstreamer::ClockTime;
+use ntscrs::{
+    ntsc::NtscEffect,
+    settings::{NtscEffectFullSettings, SettingDescriptor, SettingID, SettingKind, SettingsList},
+};
+use tinyjson::JsonValue;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe {
+    pub time: ClockTime,
+    pub value: f32,
+}
+
+/// The keyframes for one setting, kept sorted by time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingKeyframes {
+    pub setting: SettingID,
+    keyframes: Vec<Keyframe>,
+}
+
+impl SettingKeyframes {
+    pub fn new(setting: SettingID) -> Self {
+        Self {
+            setting,
+            keyframes: Vec::new(),
+        }
+    }
+
+    pub fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    /// Add a keyframe, replacing any that's already at the same time.
+    pub fn insert(&mut self, keyframe: Keyframe) {
+        match self
+            .keyframes
+            .binary_search_by_key(&keyframe.time, |existing| existing.time)
+        {
+            Ok(index) => self.keyframes[index] = keyframe,
+            Err(index) => self.keyframes.insert(index, keyframe),
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        self.keyframes.remove(index);
+    }
+
+    /// Change a keyframe's value. Its time can't be changed in-place since that could put the keyframes out of order;
+    /// remove it and insert a new one instead.
+    pub fn set_value(&mut self, index: usize, value: f32) {
+        self.keyframes[index].value = value;
+    }
+
+    /// The setting's value at the given time, linearly interpolated between the surrounding keyframes. Before the first
+    /// keyframe and after the last one, the value holds steady.
+    pub fn value_at(&self, time: ClockTime) -> Option<f32> {
+        let next_index = self
+            .keyframes
+            .partition_point(|keyframe| keyframe.time <= time);
+        match (
+            next_index
+                .checked_sub(1)
+                .and_then(|index| self.keyframes.get(index)),
+            self.keyframes.get(next_index),
+        ) {
+            (Some(prev), Some(next)) => {
+                let t = (time - prev.time).nseconds() as f64
+                    / (next.time - prev.time).nseconds() as f64;
+                Some(prev.value + (next.value - prev.value) * t as f32)
+            }
+            (Some(keyframe), None) | (None, Some(keyframe)) => Some(keyframe.value),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Keyframes for any number of settings, which override those settings' values over the course of a video. Only
+/// settings with `f32` values (percentages and float ranges) can be keyframed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeyframeTracks(pub Vec<SettingKeyframes>);
+
+impl KeyframeTracks {
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|track| track.keyframes.is_empty())
+    }
+
+    pub fn track_mut(&mut self, setting: SettingID) -> &mut SettingKeyframes {
+        match self.0.iter().position(|track| track.setting == setting) {
+            Some(index) => &mut self.0[index],
+            None => {
+                self.0.push(SettingKeyframes::new(setting));
+                self.0.last_mut().unwrap()
+            }
+        }
+    }
+
+    /// The given effect settings, with every keyframed setting set to its value at the given time.
+    pub fn apply(&self, settings: &NtscEffect, time: ClockTime) -> NtscEffect {
+        if self.is_empty() {
+            return settings.clone();
+        }
+
+        let mut full_settings = NtscEffectFullSettings::from(settings);
+        for track in &self.0 {
+            let Some(value) = track.value_at(time) else {
+                continue;
+            };
+            if let Some(field) = track.setting.get_field_mut::<f32>(&mut full_settings) {
+                *field = value;
+            }
+        }
+        NtscEffect::from(&full_settings)
+    }
+
+    /// Serialize the tracks as an object mapping each setting's name to its keyframes, each of which is a
+    /// `[time_ms, value]` pair. Times are written with fractional milliseconds so that they come back unchanged.
+    pub fn to_json(&self) -> JsonValue {
+        JsonValue::Object(
+            self.0
+                .iter()
+                .filter(|track| !track.keyframes.is_empty())
+                .map(|track| {
+                    let keyframes = track
+                        .keyframes
+                        .iter()
+                        .map(|keyframe| {
+                            JsonValue::Array(vec![
+                                JsonValue::Number(keyframe.time.nseconds() as f64 / 1_000_000.0),
+                                JsonValue::Number(keyframe.value as f64),
+                            ])
+                        })
+                        .collect();
+                    (track.setting.name().to_string(), JsonValue::Array(keyframes))
+                })
+                .collect(),
+        )
+    }
+
+    /// Restore tracks saved with `to_json`. Settings that don't exist (or no longer exist) and malformed keyframes
+    /// are skipped.
+    pub fn from_json(json: &JsonValue, settings_list: &SettingsList) -> Self {
+        let Some(map) = json.get::<HashMap<String, JsonValue>>() else {
+            return Self::default();
+        };
+
+        let mut tracks = Self::default();
+        for (name, keyframes) in map {
+            let (Some(setting), Some(keyframes)) = (
+                find_setting(&settings_list.settings, name),
+                keyframes.get::<Vec<JsonValue>>(),
+            ) else {
+                continue;
+            };
+            let track = tracks.track_mut(setting);
+            for keyframe in keyframes {
+                let keyframe = keyframe
+                    .get::<Vec<JsonValue>>()
+                    .and_then(|pair| match pair.as_slice() {
+                        [time, value] => Some(Keyframe {
+                            time: ClockTime::from_nseconds(
+                                (time.get::<f64>()?.max(0.0) * 1_000_000.0).round() as u64,
+                            ),
+                            value: *value.get::<f64>()? as f32,
+                        }),
+                        _ => None,
+                    });
+                if let Some(keyframe) = keyframe {
+                    track.insert(keyframe);
+                }
+            }
+        }
+        // Tracks come out of the map in arbitrary order
+        tracks.0.sort_by_key(|track| track.setting.name());
+        tracks
+    }
+}
+
+fn find_setting(descriptors: &[SettingDescriptor], name: &str) -> Option<SettingID> {
+    descriptors.iter().find_map(|descriptor| {
+        if descriptor.id.name() == name {
+            return Some(descriptor.id);
+        }
+        match &descriptor.kind {
+            SettingKind::Group { children, .. } => find_setting(children, name),
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyframes_json_round_trip() {
+        let settings_list = SettingsList::new();
+        let mut tracks = KeyframeTracks::default();
+        let snow = tracks.track_mut(SettingID::SNOW_INTENSITY);
+        snow.insert(Keyframe {
+            time: ClockTime::ZERO,
+            value: 0.0,
+        });
+        snow.insert(Keyframe {
+            time: ClockTime::from_nseconds(1_234_567_891),
+            value: 0.75,
+        });
+        // Inside a group
+        tracks.track_mut(SettingID::RINGING_FREQUENCY).insert(Keyframe {
+            time: ClockTime::from_mseconds(500),
+            value: 0.1,
+        });
+        tracks.0.sort_by_key(|track| track.setting.name());
+
+        let json = tracks.to_json().stringify().unwrap();
+        let restored = KeyframeTracks::from_json(&json.parse().unwrap(), &settings_list);
+        assert_eq!(restored, tracks);
+    }
+}
@@ -1,5 +1,10 @@
 pub mod expression_parser;
 pub mod gst_utils;
+pub mod i18n;
+pub mod keyframes;
+pub mod shortcuts;
 pub mod splitscreen;
 pub mod third_party_licenses;
 pub mod timeline;
+pub mod variation_grid;
+pub mod watch_folder;
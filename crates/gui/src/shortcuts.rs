@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+
+use eframe::egui::{self, Key, KeyboardShortcut, Modifiers};
+use tinyjson::JsonValue;
+
+/// Something that can be done with a keyboard shortcut.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShortcutAction {
+    PlayPause,
+    StepBackward,
+    StepForward,
+    JumpToStart,
+    JumpToEnd,
+    PreviousMarker,
+    NextMarker,
+    Undo,
+    Redo,
+    CyclePreview,
+    SaveFrame,
+    ZoomActualSize,
+    ZoomToFit,
+}
+
+impl ShortcutAction {
+    pub const ALL: &'static [ShortcutAction] = &[
+        Self::PlayPause,
+        Self::StepBackward,
+        Self::StepForward,
+        Self::JumpToStart,
+        Self::JumpToEnd,
+        Self::PreviousMarker,
+        Self::NextMarker,
+        Self::Undo,
+        Self::Redo,
+        Self::CyclePreview,
+        Self::SaveFrame,
+        Self::ZoomActualSize,
+        Self::ZoomToFit,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::PlayPause => "Play/pause",
+            Self::StepBackward => "Previous frame",
+            Self::StepForward => "Next frame",
+            Self::JumpToStart => "Jump to start",
+            Self::JumpToEnd => "Jump to end",
+            Self::PreviousMarker => "Previous marker",
+            Self::NextMarker => "Next marker",
+            Self::Undo => "Undo",
+            Self::Redo => "Redo",
+            Self::CyclePreview => "Toggle effect preview",
+            Self::SaveFrame => "Save frame",
+            Self::ZoomActualSize => "Zoom to 100%",
+            Self::ZoomToFit => "Zoom to fit",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PlayPause => "play_pause",
+            Self::StepBackward => "step_backward",
+            Self::StepForward => "step_forward",
+            Self::JumpToStart => "jump_to_start",
+            Self::JumpToEnd => "jump_to_end",
+            Self::PreviousMarker => "previous_marker",
+            Self::NextMarker => "next_marker",
+            Self::Undo => "undo",
+            Self::Redo => "redo",
+            Self::CyclePreview => "cycle_preview",
+            Self::SaveFrame => "save_frame",
+            Self::ZoomActualSize => "zoom_actual_size",
+            Self::ZoomToFit => "zoom_to_fit",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|action| action.name() == name)
+    }
+
+    fn default_shortcuts(&self) -> Vec<KeyboardShortcut> {
+        let command_shift = Modifiers::COMMAND | Modifiers::SHIFT;
+        match self {
+            Self::PlayPause => vec![KeyboardShortcut::new(Modifiers::NONE, Key::Space)],
+            Self::StepBackward => vec![KeyboardShortcut::new(Modifiers::NONE, Key::Comma)],
+            Self::StepForward => vec![KeyboardShortcut::new(Modifiers::NONE, Key::Period)],
+            Self::JumpToStart => vec![KeyboardShortcut::new(Modifiers::NONE, Key::Home)],
+            Self::JumpToEnd => vec![KeyboardShortcut::new(Modifiers::NONE, Key::End)],
+            Self::PreviousMarker => vec![KeyboardShortcut::new(Modifiers::NONE, Key::PageUp)],
+            Self::NextMarker => vec![KeyboardShortcut::new(Modifiers::NONE, Key::PageDown)],
+            Self::Undo => vec![KeyboardShortcut::new(Modifiers::COMMAND, Key::Z)],
+            Self::Redo => vec![
+                KeyboardShortcut::new(Modifiers::COMMAND, Key::Y),
+                KeyboardShortcut::new(command_shift, Key::Z),
+            ],
+            Self::CyclePreview => vec![KeyboardShortcut::new(Modifiers::NONE, Key::E)],
+            Self::SaveFrame => vec![KeyboardShortcut::new(command_shift, Key::S)],
+            Self::ZoomActualSize => vec![KeyboardShortcut::new(Modifiers::NONE, Key::Num1)],
+            Self::ZoomToFit => vec![KeyboardShortcut::new(Modifiers::NONE, Key::Num0)],
+        }
+    }
+
+    /// Whether holding the shortcut down should repeat the action.
+    fn repeats(&self) -> bool {
+        matches!(
+            self,
+            Self::StepBackward | Self::StepForward | Self::Undo | Self::Redo
+        )
+    }
+
+    /// Whether the shortcut still works while a text field has focus. Undo and redo apply to the effect settings even
+    /// while one of their number fields is being edited.
+    pub fn works_while_typing(&self) -> bool {
+        matches!(self, Self::Undo | Self::Redo)
+    }
+}
+
+/// Turn the modifiers held down while a key was pressed into ones suitable for a shortcut. Ctrl on Windows and Linux
+/// and Cmd on macOS become the platform-independent "command" modifier, so that shortcuts carry over between them.
+pub fn shortcut_modifiers(modifiers: Modifiers) -> Modifiers {
+    if modifiers.command {
+        Modifiers {
+            ctrl: false,
+            mac_cmd: false,
+            ..modifiers
+        }
+    } else {
+        modifiers
+    }
+}
+
+fn shortcut_to_string(shortcut: &KeyboardShortcut) -> String {
+    let modifiers = shortcut.modifiers;
+    let mut parts = Vec::new();
+    for (held, name) in [
+        (modifiers.command, "Command"),
+        (modifiers.ctrl, "Ctrl"),
+        (modifiers.mac_cmd, "MacCmd"),
+        (modifiers.alt, "Alt"),
+        (modifiers.shift, "Shift"),
+    ] {
+        if held {
+            parts.push(name);
+        }
+    }
+    parts.push(shortcut.logical_key.name());
+    parts.join("+")
+}
+
+fn shortcut_from_string(text: &str) -> Option<KeyboardShortcut> {
+    let mut parts = text.split('+').collect::<Vec<_>>();
+    let key = Key::from_name(parts.pop()?)?;
+    let mut modifiers = Modifiers::NONE;
+    for part in parts {
+        match part {
+            "Command" => modifiers.command = true,
+            "Ctrl" => modifiers.ctrl = true,
+            "MacCmd" => modifiers.mac_cmd = true,
+            "Alt" => modifiers.alt = true,
+            "Shift" => modifiers.shift = true,
+            _ => return None,
+        }
+    }
+    Some(KeyboardShortcut::new(modifiers, key))
+}
+
+/// The keyboard shortcuts bound to each action. An action can have any number of shortcuts, including none.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyboardShortcuts {
+    bindings: HashMap<ShortcutAction, Vec<KeyboardShortcut>>,
+}
+
+impl Default for KeyboardShortcuts {
+    fn default() -> Self {
+        Self {
+            bindings: ShortcutAction::ALL
+                .iter()
+                .map(|action| (*action, action.default_shortcuts()))
+                .collect(),
+        }
+    }
+}
+
+impl KeyboardShortcuts {
+    pub fn shortcuts(&self, action: ShortcutAction) -> &[KeyboardShortcut] {
+        self.bindings
+            .get(&action)
+            .map_or(&[], |shortcuts| shortcuts)
+    }
+
+    /// Bind a shortcut to an action, either replacing one of its existing shortcuts or adding a new one.
+    pub fn set(
+        &mut self,
+        action: ShortcutAction,
+        index: Option<usize>,
+        shortcut: KeyboardShortcut,
+    ) {
+        let shortcuts = self.bindings.entry(action).or_default();
+        match index.and_then(|index| shortcuts.get_mut(index)) {
+            Some(existing) => *existing = shortcut,
+            None => shortcuts.push(shortcut),
+        }
+    }
+
+    pub fn remove(&mut self, action: ShortcutAction, index: usize) {
+        if let Some(shortcuts) = self.bindings.get_mut(&action) {
+            if index < shortcuts.len() {
+                shortcuts.remove(index);
+            }
+        }
+    }
+
+    pub fn reset(&mut self, action: ShortcutAction) {
+        self.bindings.insert(action, action.default_shortcuts());
+    }
+
+    /// The other actions that the given shortcut is also bound to.
+    pub fn conflicts(
+        &self,
+        action: ShortcutAction,
+        shortcut: &KeyboardShortcut,
+    ) -> Vec<ShortcutAction> {
+        ShortcutAction::ALL
+            .iter()
+            .copied()
+            .filter(|other| *other != action && self.shortcuts(*other).contains(shortcut))
+            .collect()
+    }
+
+    /// Whether one of the action's shortcuts was pressed this frame. The modifiers must match exactly, so that e.g.
+    /// Ctrl+Shift+Z doesn't also count as Ctrl+Z.
+    pub fn pressed(&self, input: &egui::InputState, action: ShortcutAction) -> bool {
+        let shortcuts = self.shortcuts(action);
+        input.events.iter().any(|event| {
+            let egui::Event::Key {
+                key,
+                pressed: true,
+                repeat,
+                modifiers,
+                ..
+            } = event
+            else {
+                return false;
+            };
+            if *repeat && !action.repeats() {
+                return false;
+            }
+            shortcuts.iter().any(|shortcut| {
+                shortcut.logical_key == *key && modifiers.matches_exact(shortcut.modifiers)
+            })
+        })
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        JsonValue::Object(
+            ShortcutAction::ALL
+                .iter()
+                .map(|action| {
+                    (
+                        action.name().to_string(),
+                        JsonValue::Array(
+                            self.shortcuts(*action)
+                                .iter()
+                                .map(|shortcut| JsonValue::String(shortcut_to_string(shortcut)))
+                                .collect(),
+                        ),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// Read shortcuts saved with [`KeyboardShortcuts::to_json`]. Actions missing from the JSON (e.g. ones added in a
+    /// newer version) keep their default shortcuts.
+    pub fn from_json(json: &str) -> Option<Self> {
+        let json = json.parse::<JsonValue>().ok()?;
+        let map = json.get::<HashMap<String, JsonValue>>()?;
+        let mut shortcuts = Self::default();
+        for (name, value) in map {
+            let (Some(action), Some(values)) = (
+                ShortcutAction::from_name(name),
+                value.get::<Vec<JsonValue>>(),
+            ) else {
+                continue;
+            };
+            shortcuts.bindings.insert(
+                action,
+                values
+                    .iter()
+                    .filter_map(|value| shortcut_from_string(value.get::<String>()?))
+                    .collect(),
+            );
+        }
+        Some(shortcuts)
+    }
+}
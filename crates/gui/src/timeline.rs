@@ -2,13 +2,13 @@ use std::f32;
 use std::ops::RangeInclusive;
 
 use eframe::{
-    egui::{self, vec2, Context, Id, Sense, TextStyle, Widget},
+    egui::{self, vec2, Context, Id, Rect, Sense, TextStyle, TextureHandle, Widget},
     emath::{remap, remap_clamp},
     epaint::{pos2, Color32, Pos2, Stroke},
 };
 use gstreamer::{ClockTime, Fraction};
 
-use crate::gst_utils::clock_format::clock_time_format;
+use crate::{gst_utils::clock_format::clock_time_format, tr};
 
 type GetSetValue<'a> = Box<dyn 'a + FnMut(Option<u64>) -> u64>;
 
@@ -28,12 +28,15 @@ fn wrap_modulo(n: f64, modulus: f64) -> f64 {
 struct State {
     // RangeInclusive isn't Copy
     zoom_range: [f64; 2],
+    // the time that was right-clicked on to open the context menu
+    context_menu_time: Option<u64>,
 }
 
 impl Default for State {
     fn default() -> Self {
         Self {
             zoom_range: [0.0, 1.0],
+            context_menu_time: None,
         }
     }
 }
@@ -48,13 +51,23 @@ impl State {
     }
 }
 
+/// A frame of the video to draw along the timeline, at the given timestamp in nanoseconds.
+pub struct TimelineThumbnail {
+    pub timestamp: u64,
+    pub texture: TextureHandle,
+}
+
 pub struct Timeline<'a> {
     get_set_value: GetSetValue<'a>,
     range: RangeInclusive<u64>,
     framerate: Option<Fraction>,
+    thumbnails: &'a [TimelineThumbnail],
+    markers: Option<&'a mut Vec<(ClockTime, String)>>,
 }
 
 const THICKNESS: f32 = 24.0;
+/// Height of the overview strip below the timeline, which shows which part of the timeline is zoomed into.
+const MINIMAP_HEIGHT: f32 = 8.0;
 
 impl<'a> Timeline<'a> {
     pub fn new(
@@ -71,8 +84,43 @@ impl<'a> Timeline<'a> {
                 *value
             }),
             framerate,
+            thumbnails: &[],
+            markers: None,
         }
     }
+
+    /// Draw these named markers along the timeline, and allow adding, renaming, and removing them by right-clicking.
+    /// The markers are kept sorted by time.
+    pub fn markers(mut self, markers: &'a mut Vec<(ClockTime, String)>) -> Self {
+        self.markers = Some(markers);
+        self
+    }
+
+    /// Draw these thumbnails (sorted by timestamp) along the timeline, and show the nearest one when hovering over it.
+    pub fn thumbnails(mut self, thumbnails: &'a [TimelineThumbnail]) -> Self {
+        self.thumbnails = thumbnails;
+        self
+    }
+}
+
+/// Find the thumbnail whose timestamp is closest to the given one.
+fn nearest_thumbnail(
+    thumbnails: &[TimelineThumbnail],
+    timestamp: f64,
+) -> Option<&TimelineThumbnail> {
+    let index = thumbnails.partition_point(|thumbnail| (thumbnail.timestamp as f64) < timestamp);
+    let before = index.checked_sub(1).and_then(|index| thumbnails.get(index));
+    let after = thumbnails.get(index);
+    match (before, after) {
+        (Some(before), Some(after)) => {
+            if timestamp - before.timestamp as f64 <= after.timestamp as f64 - timestamp {
+                Some(before)
+            } else {
+                Some(after)
+            }
+        }
+        (before, after) => before.or(after),
+    }
 }
 
 // egui has trouble tessellating concave shapes, so we do it manually, feathering and all
@@ -195,14 +243,18 @@ impl<'a> Widget for Timeline<'a> {
 
         let desired_size = vec2(
             ui.available_width(),
-            THICKNESS.max(ui.spacing().interact_size.y),
+            THICKNESS.max(ui.spacing().interact_size.y) + MINIMAP_HEIGHT,
         );
 
-        let mut response = ui.allocate_response(desired_size, Sense::drag());
+        let mut response = ui.allocate_response(desired_size, Sense::click_and_drag());
+        // Right-clicking opens the context menu instead of seeking
+        let secondary_down = ui.input(|input| input.pointer.secondary_down());
 
         let mut state = State::load(ui.ctx(), response.id).unwrap_or_default();
 
-        let rect = response.rect;
+        let (rect, minimap_rect) = response
+            .rect
+            .split_top_bottom_at_y(response.rect.bottom() - MINIMAP_HEIGHT);
         let position_range = rect.x_range();
         let position_range_f64 = position_range.min as f64..=position_range.max as f64;
         let range_f64 = *self.range.start() as f64..=*self.range.end() as f64;
@@ -266,11 +318,33 @@ impl<'a> Widget for Timeline<'a> {
             state.store(ui.ctx(), response.id);
         }
 
+        // Dragging on the minimap moves the zoomed-in region, centering it on the pointer
+        let dragging_minimap = ui
+            .input(|input| input.pointer.press_origin())
+            .map_or(false, |origin| minimap_rect.contains(origin));
+        if let Some(pointer_position_2d) = response
+            .interact_pointer_pos()
+            .filter(|_| dragging_minimap && !secondary_down)
+        {
+            let zoom_span = state.zoom_range[1] - state.zoom_range[0];
+            let center = remap_clamp(
+                pointer_position_2d.x as f64,
+                position_range_f64.clone(),
+                0.0..=1.0,
+            );
+            state.zoom_range[0] = (center - zoom_span * 0.5).clamp(0.0, 1.0 - zoom_span);
+            state.zoom_range[1] = state.zoom_range[0] + zoom_span;
+            state.store(ui.ctx(), response.id);
+        }
+
         let scaled_range = (range_f64.start()
             + ((range_f64.end() - range_f64.start()) * state.zoom_range[0]))
             ..=((range_f64.end() - range_f64.start()) * state.zoom_range[1] + range_f64.start());
 
-        if let Some(pointer_position_2d) = response.interact_pointer_pos() {
+        if let Some(pointer_position_2d) = response
+            .interact_pointer_pos()
+            .filter(|_| !dragging_minimap && !secondary_down)
+        {
             let position = pointer_position_2d.x;
             let normalized = remap_clamp(
                 position as f64,
@@ -299,6 +373,32 @@ impl<'a> Widget for Timeline<'a> {
             // Draw background
             painter.rect_filled(rect, egui::Rounding::ZERO, visuals.bg_fill);
 
+            // Draw thumbnails, dimmed so the tick marks and times stay readable
+            if let Some(first_thumbnail) = self.thumbnails.first() {
+                let thumbnail_width =
+                    (rect.height() * first_thumbnail.texture.aspect_ratio()).max(1.0);
+                let mut thumbnail_left = rect.left();
+                while thumbnail_left < rect.right() {
+                    let center_time = remap(
+                        (thumbnail_left + thumbnail_width * 0.5) as f64,
+                        position_range_f64.clone(),
+                        scaled_range.clone(),
+                    );
+                    if let Some(thumbnail) = nearest_thumbnail(self.thumbnails, center_time) {
+                        painter.image(
+                            thumbnail.texture.id(),
+                            Rect::from_min_size(
+                                pos2(thumbnail_left, rect.top()),
+                                vec2(thumbnail_width, rect.height()),
+                            ),
+                            Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0)),
+                            Color32::from_white_alpha(96),
+                        );
+                    }
+                    thumbnail_left += thumbnail_width;
+                }
+            }
+
             let fine_tick_mark_interval = if let Some(framerate) = self.framerate {
                 framerate.denom() as f64 * ClockTime::SECOND.nseconds() as f64
                     / framerate.numer() as f64
@@ -388,8 +488,170 @@ impl<'a> Widget for Timeline<'a> {
             );
         }
 
+        if ui.is_rect_visible(minimap_rect) {
+            let visuals = ui.style().interact(&response);
+            let painter = ui.painter().with_clip_rect(minimap_rect);
+            painter.rect_filled(
+                minimap_rect,
+                egui::Rounding::ZERO,
+                ui.visuals().extreme_bg_color,
+            );
+
+            // Draw the visible region
+            let minimap_x =
+                |normalized: f64| remap(normalized, 0.0..=1.0, position_range_f64.clone()) as f32;
+            painter.rect_filled(
+                Rect::from_x_y_ranges(
+                    minimap_x(state.zoom_range[0])..=minimap_x(state.zoom_range[1]),
+                    minimap_rect.y_range(),
+                ),
+                egui::Rounding::ZERO,
+                ui.visuals().selection.bg_fill,
+            );
+
+            // Draw the playhead's position within the whole video
+            let value = get(&mut self.get_set_value);
+            let x = remap_clamp(value as f64, range_f64.clone(), position_range_f64.clone()) as f32;
+            painter.line_segment(
+                [pos2(x, minimap_rect.top()), pos2(x, minimap_rect.bottom())],
+                Stroke {
+                    width: 2.0,
+                    color: visuals.fg_stroke.color,
+                },
+            );
+        }
+
+        let mut hovering_marker = false;
+        if let Some(markers) = self.markers.as_deref_mut() {
+            if ui.is_rect_visible(rect) {
+                let painter = ui.painter().with_clip_rect(response.rect);
+                let marker_color = ui.visuals().warn_fg_color;
+                for (time, _) in markers.iter() {
+                    let x = remap(
+                        time.nseconds() as f64,
+                        scaled_range.clone(),
+                        position_range_f64.clone(),
+                    ) as f32;
+                    painter.line_segment(
+                        [pos2(x, rect.top()), pos2(x, rect.bottom())],
+                        Stroke {
+                            width: 2.0,
+                            color: marker_color,
+                        },
+                    );
+                    let minimap_x = remap_clamp(
+                        time.nseconds() as f64,
+                        range_f64.clone(),
+                        position_range_f64.clone(),
+                    ) as f32;
+                    painter.line_segment(
+                        [
+                            pos2(minimap_x, minimap_rect.top()),
+                            pos2(minimap_x, minimap_rect.bottom()),
+                        ],
+                        Stroke {
+                            width: 1.0,
+                            color: marker_color,
+                        },
+                    );
+                }
+            }
+
+            // Markers within a few pixels of a given point on the timeline count as being under it
+            let pixels_to_time = (scaled_range.end() - scaled_range.start()) / rect.width() as f64;
+            let marker_near = |markers: &[(ClockTime, String)], time: f64| {
+                markers
+                    .iter()
+                    .enumerate()
+                    .map(|(index, (marker_time, _))| {
+                        (index, (marker_time.nseconds() as f64 - time).abs())
+                    })
+                    .filter(|(_, distance)| *distance <= pixels_to_time * 4.0)
+                    .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                    .map(|(index, _)| index)
+            };
+
+            let pointer_time = |pointer_pos: Pos2| {
+                remap_clamp(
+                    pointer_pos.x as f64,
+                    position_range_f64.clone(),
+                    scaled_range.clone(),
+                )
+            };
+
+            if response.secondary_clicked() {
+                state.context_menu_time = ui
+                    .input(|input| input.pointer.interact_pos())
+                    .map(|pos| pointer_time(pos) as u64);
+                state.store(ui.ctx(), response.id);
+            }
+
+            let hovered_marker = response
+                .hover_pos()
+                .filter(|pointer_pos| rect.contains(*pointer_pos))
+                .and_then(|pointer_pos| marker_near(markers, pointer_time(pointer_pos)));
+
+            let mut remove_marker = None;
+            let mut add_marker = None;
+            let _ = response.clone().context_menu(|ui| {
+                let Some(context_menu_time) = state.context_menu_time else {
+                    return;
+                };
+                match marker_near(markers, context_menu_time as f64) {
+                    Some(index) => {
+                        ui.horizontal(|ui| {
+                            ui.label(tr!("Marker:"));
+                            ui.text_edit_singleline(&mut markers[index].1);
+                        });
+                        if ui.button(tr!("Remove marker")).clicked() {
+                            remove_marker = Some(index);
+                            ui.close_menu();
+                        }
+                    }
+                    None => {
+                        if ui.button(tr!("Add marker here")).clicked() {
+                            add_marker = Some(context_menu_time);
+                            ui.close_menu();
+                        }
+                    }
+                }
+            });
+
+            if let Some(index) = remove_marker {
+                markers.remove(index);
+            }
+            if let Some(time) = add_marker {
+                let name = tr!("Marker {number}", number = markers.len() + 1);
+                markers.push((ClockTime::from_nseconds(time), name));
+                markers.sort_by_key(|(time, _)| *time);
+            }
+
+            if let Some(index) = hovered_marker.filter(|index| *index < markers.len()) {
+                hovering_marker = true;
+                let name = markers[index].1.clone();
+                response = response.on_hover_text_at_pointer(name);
+            }
+        }
+
         response.changed = get(&mut self.get_set_value) != old_value;
 
+        let hovered_thumbnail = response
+            .hover_pos()
+            .filter(|pointer_pos| !hovering_marker && rect.contains(*pointer_pos))
+            .map(|pointer_pos| {
+                remap_clamp(
+                    pointer_pos.x as f64,
+                    position_range_f64.clone(),
+                    scaled_range.clone(),
+                )
+            })
+            .and_then(|timestamp| nearest_thumbnail(self.thumbnails, timestamp));
+        if let Some(thumbnail) = hovered_thumbnail {
+            response = response.on_hover_ui_at_pointer(|ui| {
+                ui.image((thumbnail.texture.id(), thumbnail.texture.size_vec2()));
+            });
+        }
+
         response
     }
 }
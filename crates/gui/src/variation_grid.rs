@@ -0,0 +1,104 @@
+use eframe::epaint::ColorImage;
+use image::{Rgba, RgbaImage};
+
+/// Evenly spaced values from `start` to `end` inclusive.
+pub fn variation_values(start: f32, end: f32, count: usize) -> Vec<f32> {
+    match count {
+        0 => Vec::new(),
+        1 => vec![start],
+        _ => (0..count)
+            .map(|i| start + (end - start) * (i as f32 / (count - 1) as f32))
+            .collect(),
+    }
+}
+
+// Each glyph is 3 pixels wide and 5 tall, with one row per entry and the leftmost pixel in the highest bit. Only the
+// characters that show up in setting values are covered; anything else is drawn as a space.
+const GLYPH_WIDTH: u32 = 3;
+const GLYPH_HEIGHT: u32 = 5;
+const GLYPH_SCALE: u32 = 3;
+const LABEL_PADDING: u32 = 6;
+
+fn glyph(c: char) -> [u8; 5] {
+    match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '%' => [0b101, 0b001, 0b010, 0b100, 0b101],
+        _ => [0; 5],
+    }
+}
+
+fn draw_label(image: &mut RgbaImage, text: &str, x: u32, y: u32) {
+    let advance = (GLYPH_WIDTH + 1) * GLYPH_SCALE;
+    for (i, c) in text.chars().enumerate() {
+        let glyph_x = x + i as u32 * advance;
+        for (row, bits) in glyph(c).iter().enumerate() {
+            for col in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - col)) == 0 {
+                    continue;
+                }
+                for dy in 0..GLYPH_SCALE {
+                    for dx in 0..GLYPH_SCALE {
+                        let px = glyph_x + col * GLYPH_SCALE + dx;
+                        let py = y + row as u32 * GLYPH_SCALE + dy;
+                        if px < image.width() && py < image.height() {
+                            image.put_pixel(px, py, Rgba([255, 255, 255, 255]));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Lay out renders of the same frame in a roughly square grid, each with its label drawn underneath. The renders are
+/// expected to all be the same size.
+pub fn compose_variation_grid(cells: &[(ColorImage, String)]) -> RgbaImage {
+    let Some((first, _)) = cells.first() else {
+        return RgbaImage::new(0, 0);
+    };
+    let cell_width = first.width() as u32;
+    let image_height = first.height() as u32;
+    let cell_height = image_height + GLYPH_HEIGHT * GLYPH_SCALE + LABEL_PADDING * 2;
+
+    let columns = (cells.len() as f64).sqrt().ceil() as u32;
+    let rows = (cells.len() as u32).div_ceil(columns);
+    let mut grid = RgbaImage::from_pixel(
+        cell_width * columns,
+        cell_height * rows,
+        Rgba([0, 0, 0, 255]),
+    );
+
+    for (i, (image, label)) in cells.iter().enumerate() {
+        let cell_x = (i as u32 % columns) * cell_width;
+        let cell_y = (i as u32 / columns) * cell_height;
+        for (pixel_index, pixel) in image.pixels.iter().enumerate() {
+            let x = (pixel_index % image.width()) as u32;
+            let y = (pixel_index / image.width()) as u32;
+            if x >= cell_width || y >= image_height {
+                continue;
+            }
+            // The effect's output is opaque, but the padding byte it leaves in the alpha channel isn't always 255
+            let [r, g, b, _] = pixel.to_array();
+            grid.put_pixel(cell_x + x, cell_y + y, Rgba([r, g, b, 255]));
+        }
+        draw_label(
+            &mut grid,
+            label,
+            cell_x + LABEL_PADDING,
+            cell_y + image_height + LABEL_PADDING,
+        );
+    }
+
+    grid
+}
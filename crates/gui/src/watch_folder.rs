@@ -0,0 +1,111 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use eframe::egui;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+/// How long a file's size has to stay the same before it's considered done being copied into the folder.
+const SETTLE_TIME: Duration = Duration::from_secs(2);
+
+/// Watches a folder for new files with the given extensions. Files are only reported once their size stops changing,
+/// so that ones which are still being copied or recorded into the folder aren't picked up halfway through.
+///
+/// Files that were already in the folder when the watch started are ignored. Dropping the watcher stops watching.
+pub struct FolderWatcher {
+    ctx: egui::Context,
+    directory: PathBuf,
+    _watcher: RecommendedWatcher,
+    /// Files that have appeared or changed, along with their last known size and when it last changed. The size is
+    /// unknown until the first time we check it.
+    candidates: Arc<Mutex<HashMap<PathBuf, Option<(u64, Instant)>>>>,
+    /// Files that have already been reported, which further changes should not report again.
+    reported: HashSet<PathBuf>,
+}
+
+impl FolderWatcher {
+    pub fn new(
+        ctx: &egui::Context,
+        directory: PathBuf,
+        extensions: &'static [&'static str],
+    ) -> notify::Result<Self> {
+        let candidates = Arc::new(Mutex::new(HashMap::new()));
+        let candidates_for_handler = Arc::clone(&candidates);
+        let ctx_for_handler = ctx.clone();
+
+        let mut watcher =
+            notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+                let Ok(event) = res else {
+                    return;
+                };
+                if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    return;
+                }
+
+                let mut candidates = candidates_for_handler.lock().unwrap();
+                for path in event.paths {
+                    let has_extension = path.extension().is_some_and(|extension| {
+                        extensions
+                            .iter()
+                            .any(|ext| extension.eq_ignore_ascii_case(ext))
+                    });
+                    if has_extension {
+                        candidates.insert(path, None);
+                    }
+                }
+                ctx_for_handler.request_repaint();
+            })?;
+        watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+
+        Ok(Self {
+            ctx: ctx.clone(),
+            directory,
+            _watcher: watcher,
+            candidates,
+            reported: HashSet::new(),
+        })
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Return the files that have finished arriving since the last call.
+    pub fn take_settled_files(&mut self) -> Vec<PathBuf> {
+        let now = Instant::now();
+        let mut candidates = self.candidates.lock().unwrap();
+        let mut settled = Vec::new();
+
+        candidates.retain(|path, last_change| {
+            if self.reported.contains(path) {
+                return false;
+            }
+            // The file may have been deleted or renamed in the meantime
+            let Ok(size) = std::fs::metadata(path).map(|metadata| metadata.len()) else {
+                return false;
+            };
+
+            match last_change {
+                Some((last_size, changed_at)) if *last_size == size => {
+                    if size > 0 && now.duration_since(*changed_at) >= SETTLE_TIME {
+                        settled.push(path.clone());
+                        return false;
+                    }
+                }
+                _ => *last_change = Some((size, now)),
+            }
+            true
+        });
+
+        // Nothing else will wake the UI up once the remaining files settle
+        if !candidates.is_empty() {
+            self.ctx.request_repaint_after(SETTLE_TIME / 4);
+        }
+
+        self.reported.extend(settled.iter().cloned());
+        settled
+    }
+}
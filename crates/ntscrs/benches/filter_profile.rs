@@ -1,7 +1,7 @@
 extern crate criterion;
 use criterion::{criterion_group, criterion_main, Criterion};
 use image::io::Reader as ImageReader;
-use ntscrs::ntsc::NtscEffect;
+use ntscrs::ntsc::{NtscEffect, Precision};
 #[cfg(not(target_os = "windows"))]
 use pprof::criterion::{Output, PProfProfiler};
 
@@ -22,6 +22,19 @@ fn criterion_benchmark(c: &mut Criterion) {
                 });
         })
     });
+    c.bench_function("full effect (64-bit filters)", |b| {
+        let mut effect = NtscEffect::default();
+        effect.precision = Precision::Double;
+        b.iter(|| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(1)
+                .build()
+                .unwrap()
+                .install(|| {
+                    effect.apply_effect(&img, 0);
+                });
+        })
+    });
 }
 
 criterion_group! {
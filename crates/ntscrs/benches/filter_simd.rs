@@ -0,0 +1,42 @@
+extern crate criterion;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ntscrs::TransferFunction;
+
+fn lowpass_triple(alpha: f32) -> TransferFunction {
+    TransferFunction::new([alpha], [1.0, -(1.0 - alpha)]).cascade_self(3)
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let filter = lowpass_triple(0.3);
+    let mut group = c.benchmark_group("lowpass filter");
+    // SD, HD, and 4K row widths
+    for width in [720usize, 1920, 3840] {
+        let signal = (0..width)
+            .map(|i| (i as f32 * 0.37).sin())
+            .collect::<Vec<_>>();
+        let mut rows = [
+            signal.clone(),
+            signal.clone(),
+            signal.clone(),
+            signal.clone(),
+        ];
+
+        group.bench_with_input(BenchmarkId::new("scalar", width), &width, |b, _| {
+            b.iter(|| {
+                let [r0, r1, r2, r3] = &mut rows;
+                filter.filter_signal_in_place_scalar::<4>(&mut [r0, r1, r2, r3], [0.0; 4], 1.0, 0);
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("simd", width), &width, |b, _| {
+            b.iter(|| {
+                let [r0, r1, r2, r3] = &mut rows;
+                filter.filter_signal_in_place::<4>(&mut [r0, r1, r2, r3], [0.0; 4], 1.0, 0);
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
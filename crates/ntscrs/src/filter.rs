@@ -1,6 +1,9 @@
 use core::fmt::Debug;
+use std::iter::Sum;
 use std::mem::MaybeUninit;
 
+use num_traits::Float;
+
 use crate::f32x4::{get_supported_simd_type, F32x4, SupportedSimdType};
 
 const fn _mm_shuffle(d: i32, c: i32, b: i32, a: i32) -> i32 {
@@ -97,15 +100,20 @@ impl TransferFunction {
 
     /// Return initial conditions for the filter that results in a given steady-state value (e.g. "start" the filter as
     /// if every previous sample was the given value).
-    fn initial_condition(&self, value: f32) -> Vec<f32> {
+    pub(crate) fn initial_condition(&self, value: f32) -> Vec<f32> {
+        self.initial_condition_in::<f32>(value)
+    }
+
+    /// [`Self::initial_condition`], calculated in the given floating-point type.
+    fn initial_condition_in<T: Float + Sum>(&self, value: T) -> Vec<T> {
         // Adapted from scipy
         // https://github.com/scipy/scipy/blob/da82ac849a4ccade2d954a0998067e6aa706dd70/scipy/signal/_signaltools.py#L3609-L3742
 
         let filter_len = usize::max(self.num.len(), self.den.len());
         // The last element here will always be 0--in the loop below, we intentionally do not initialize the last
         // element of zi.
-        let mut zi = vec![0f32; filter_len];
-        if value.abs() == 0.0 {
+        let mut zi = vec![T::zero(); filter_len];
+        if value.abs() == T::zero() {
             return zi;
         }
 
@@ -120,36 +128,37 @@ impl TransferFunction {
                 }
             })
             .expect("There must be at least one nonzero coefficient in the denominator.");
+        let first_nonzero_coeff = T::from(first_nonzero_coeff).unwrap();
 
         let norm_num = self
             .num
             .iter()
-            .map(|item| *item / first_nonzero_coeff)
-            .collect::<Vec<f32>>();
+            .map(|item| T::from(*item).unwrap() / first_nonzero_coeff)
+            .collect::<Vec<T>>();
         let norm_den = self
             .den
             .iter()
-            .map(|item| *item / first_nonzero_coeff)
-            .collect::<Vec<f32>>();
+            .map(|item| T::from(*item).unwrap() / first_nonzero_coeff)
+            .collect::<Vec<T>>();
 
-        let mut b_sum = 0.0;
+        let mut b_sum = T::zero();
         for i in 1..filter_len {
-            let num_i = norm_num.get(i).unwrap_or(&0.0);
-            let den_i = norm_den.get(i).unwrap_or(&0.0);
-            b_sum += num_i - den_i * norm_num[0];
+            let num_i = norm_num.get(i).copied().unwrap_or(T::zero());
+            let den_i = norm_den.get(i).copied().unwrap_or(T::zero());
+            b_sum = b_sum + (num_i - den_i * norm_num[0]);
         }
 
-        zi[0] = b_sum / norm_den.iter().sum::<f32>();
-        let mut a_sum = 1.0;
-        let mut c_sum = 0.0;
+        zi[0] = b_sum / norm_den.iter().copied().sum::<T>();
+        let mut a_sum = T::one();
+        let mut c_sum = T::zero();
         for i in 1..filter_len - 1 {
-            let num_i = norm_num.get(i).unwrap_or(&0.0);
-            let den_i = norm_den.get(i).unwrap_or(&0.0);
-            a_sum += den_i;
-            c_sum += num_i - den_i * norm_num[0];
+            let num_i = norm_num.get(i).copied().unwrap_or(T::zero());
+            let den_i = norm_den.get(i).copied().unwrap_or(T::zero());
+            a_sum = a_sum + den_i;
+            c_sum = c_sum + (num_i - den_i * norm_num[0]);
             zi[i] = (a_sum * zi[0] - c_sum) * value;
         }
-        zi[0] *= value;
+        zi[0] = zi[0] * value;
 
         zi
     }
@@ -377,6 +386,14 @@ impl TransferFunction {
     /// - `scale` - Scale the filter output by this amount. For example, a scale of -1 turns a lowpass filter into a
     ///   highpass filter.
     /// - `delay` - Offset the filter output backwards (to the left) by this amount.
+    ///
+    /// # SIMD
+    /// Filters with 4 coefficients (which includes every 1st- to 3rd-order filter, since those are padded out to 4) are
+    /// run with SIMD instructions if the CPU supports them, and with [`Self::filter_signal_in_place_scalar`]
+    /// otherwise. The SSE4.1 implementation performs the exact same operations as the scalar one, so its output is
+    /// identical. The AVX2 and NEON implementations use fused multiply-adds, which round once instead of twice, so their
+    /// output can differ from the scalar output in the last few bits of each sample. Those differences get carried
+    /// along by the filter's feedback, but stay far below anything visible (the tests allow for 1e-4).
     pub fn filter_signal_in_place<const ROWS: usize>(
         &self,
         signal: &mut [&mut [f32]; ROWS],
@@ -411,50 +428,81 @@ impl TransferFunction {
                 }
                 return;
             }
+        }
 
-            self.filter_signal_in_place_fixed_size::<4, ROWS>(signal, initial, scale, delay);
-        } else {
-            match filter_len {
-                // Specialize fixed-size implementations for filter sizes 1-8
-                1 => {
-                    self.filter_signal_in_place_fixed_size::<1, ROWS>(signal, initial, scale, delay)
-                }
-                2 => {
-                    self.filter_signal_in_place_fixed_size::<2, ROWS>(signal, initial, scale, delay)
-                }
-                3 => {
-                    self.filter_signal_in_place_fixed_size::<3, ROWS>(signal, initial, scale, delay)
-                }
-                // 4 is covered in the branch above
-                5 => {
-                    self.filter_signal_in_place_fixed_size::<5, ROWS>(signal, initial, scale, delay)
-                }
-                6 => {
-                    self.filter_signal_in_place_fixed_size::<6, ROWS>(signal, initial, scale, delay)
-                }
-                7 => {
-                    self.filter_signal_in_place_fixed_size::<7, ROWS>(signal, initial, scale, delay)
-                }
-                8 => {
-                    self.filter_signal_in_place_fixed_size::<8, ROWS>(signal, initial, scale, delay)
-                }
-                _ => {
-                    let mut z: [Vec<f32>; ROWS] = initial
-                        .into_iter()
-                        .map(|init| self.initial_condition(init))
-                        .collect::<Vec<Vec<_>>>()
-                        .try_into()
-                        .unwrap();
-                    let z: [&mut [f32]; ROWS] = z
-                        .iter_mut()
-                        .map(|z| z.as_mut_slice())
-                        .collect::<Vec<_>>()
-                        .try_into()
-                        .unwrap();
-                    Self::filter_signal_in_place_impl::<ROWS>(
-                        signal, &self.num, &self.den, z, scale, delay,
-                    );
-                }
+        self.filter_signal_in_place_scalar::<ROWS>(signal, initial, scale, delay);
+    }
+
+    /// Filter a signal in-place without using SIMD, even if the CPU supports it. This is what
+    /// [`Self::filter_signal_in_place`] falls back to; it's exposed for testing and benchmarking the SIMD
+    /// implementations against.
+    pub fn filter_signal_in_place_scalar<const ROWS: usize>(
+        &self,
+        signal: &mut [&mut [f32]; ROWS],
+        initial: [f32; ROWS],
+        scale: f32,
+        delay: usize,
+    ) {
+        let filter_len = usize::max(self.num.len(), self.den.len());
+
+        match filter_len {
+            // Specialize fixed-size implementations for filter sizes 1-8
+            1 => self.filter_signal_in_place_fixed_size::<1, ROWS>(signal, initial, scale, delay),
+            2 => self.filter_signal_in_place_fixed_size::<2, ROWS>(signal, initial, scale, delay),
+            3 => self.filter_signal_in_place_fixed_size::<3, ROWS>(signal, initial, scale, delay),
+            4 => self.filter_signal_in_place_fixed_size::<4, ROWS>(signal, initial, scale, delay),
+            5 => self.filter_signal_in_place_fixed_size::<5, ROWS>(signal, initial, scale, delay),
+            6 => self.filter_signal_in_place_fixed_size::<6, ROWS>(signal, initial, scale, delay),
+            7 => self.filter_signal_in_place_fixed_size::<7, ROWS>(signal, initial, scale, delay),
+            8 => self.filter_signal_in_place_fixed_size::<8, ROWS>(signal, initial, scale, delay),
+            _ => {
+                let mut z: [Vec<f32>; ROWS] = initial
+                    .into_iter()
+                    .map(|init| self.initial_condition(init))
+                    .collect::<Vec<Vec<_>>>()
+                    .try_into()
+                    .unwrap();
+                let z: [&mut [f32]; ROWS] = z
+                    .iter_mut()
+                    .map(|z| z.as_mut_slice())
+                    .collect::<Vec<_>>()
+                    .try_into()
+                    .unwrap();
+                Self::filter_signal_in_place_impl::<ROWS>(
+                    signal, &self.num, &self.den, z, scale, delay,
+                );
+            }
+        }
+    }
+
+    /// Filter a single signal in-place like [`Self::filter_signal_in_place`], but with the filter's state and all of
+    /// its arithmetic in 64-bit floating point. Only the samples going in and coming out are 32-bit. This is slower,
+    /// but rounding errors don't build up in the filter's feedback loop the way they can with 32-bit state.
+    pub fn filter_signal_in_place_f64(
+        &self,
+        signal: &mut [f32],
+        initial: f32,
+        scale: f32,
+        delay: usize,
+    ) {
+        if signal.is_empty() {
+            return;
+        }
+
+        let filter_len = usize::max(self.num.len(), self.den.len());
+        let num = self.num.iter().map(|c| *c as f64).collect::<Vec<f64>>();
+        let den = self.den.iter().map(|c| *c as f64).collect::<Vec<f64>>();
+        let mut z = self.initial_condition_in::<f64>(initial as f64);
+        let scale = scale as f64;
+
+        for i in 0..(signal.len() + delay) {
+            let sample = signal[i.min(signal.len() - 1)] as f64;
+            let filt_sample = z[0] + (num[0] * sample);
+            for j in 0..filter_len - 1 {
+                z[j] = z[j + 1] + (num[j + 1] * sample) - (den[j + 1] * filt_sample);
+            }
+            if i >= delay {
+                signal[i - delay] = ((filt_sample - sample) * scale + sample) as f32;
             }
         }
     }
@@ -470,3 +518,88 @@ impl std::ops::Mul<&TransferFunction> for &TransferFunction {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_signal(width: usize, seed: usize) -> Vec<f32> {
+        (0..width)
+            .map(|i| {
+                let x = (i + seed * 7) as f32;
+                (x * 0.37).sin() * 0.8
+                    + (x * 0.051).cos() * 0.3
+                    + if i % 13 == 0 { 0.5 } else { 0.0 }
+            })
+            .collect()
+    }
+
+    fn assert_simd_matches_scalar(filter: &TransferFunction, scale: f32, delay: usize) {
+        const WIDTH: usize = 517;
+        let mut simd_rows = [
+            test_signal(WIDTH, 0),
+            test_signal(WIDTH, 1),
+            test_signal(WIDTH, 2),
+            test_signal(WIDTH, 3),
+        ];
+        let mut scalar_rows = simd_rows.clone();
+        let initial = [0.0, 0.25, -0.5, 1.0];
+
+        let [a, b, c, d] = &mut simd_rows;
+        filter.filter_signal_in_place::<4>(&mut [a, b, c, d], initial, scale, delay);
+        let [a, b, c, d] = &mut scalar_rows;
+        filter.filter_signal_in_place_scalar::<4>(&mut [a, b, c, d], initial, scale, delay);
+
+        for (simd_row, scalar_row) in simd_rows.iter().zip(&scalar_rows) {
+            for (i, (simd, scalar)) in simd_row.iter().zip(scalar_row).enumerate() {
+                assert!(
+                    (simd - scalar).abs() <= 1e-4,
+                    "sample {i}: SIMD output {simd} differs from scalar output {scalar}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn simd_matches_scalar_first_order() {
+        let alpha = 0.3;
+        let filter = TransferFunction::new([alpha], [1.0, -(1.0 - alpha)]);
+        assert_simd_matches_scalar(&filter, 1.0, 0);
+    }
+
+    #[test]
+    fn simd_matches_scalar_third_order() {
+        let alpha = 0.3;
+        let filter = TransferFunction::new([alpha], [1.0, -(1.0 - alpha)]).cascade_self(3);
+        assert_simd_matches_scalar(&filter, 1.0, 0);
+    }
+
+    #[test]
+    fn simd_matches_scalar_with_scale_and_delay() {
+        let filter = TransferFunction::new([0.2, 0.1], [1.0, -0.5, 0.2]);
+        assert_simd_matches_scalar(&filter, -1.0, 2);
+    }
+
+    #[test]
+    fn f64_is_close_to_f32() {
+        const WIDTH: usize = 4096;
+        // A long, sharply-resonant filter, where rounding errors in the feedback loop matter most
+        let filter = TransferFunction::new([0.05], [1.0, -0.95]).cascade_self(6);
+        let mut f32_row = test_signal(WIDTH, 0);
+        let mut f64_row = f32_row.clone();
+
+        filter.filter_signal_in_place_scalar::<1>(&mut [&mut f32_row], [0.25], -1.0, 1);
+        filter.filter_signal_in_place_f64(&mut f64_row, 0.25, -1.0, 1);
+
+        assert_ne!(
+            f32_row, f64_row,
+            "64-bit filtering should round differently"
+        );
+        for (i, (single, double)) in f32_row.iter().zip(&f64_row).enumerate() {
+            assert!(
+                (single - double).abs() <= 1e-3,
+                "sample {i}: 64-bit output {double} strays too far from 32-bit output {single}"
+            );
+        }
+    }
+}
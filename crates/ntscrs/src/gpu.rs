@@ -0,0 +1,468 @@
+//! Optional GPU acceleration, using compute shaders through wgpu.
+//!
+//! This is being moved over a stage at a time. So far, only the horizontal IIR filtering (the lowpass, notch, and
+//! demodulation filters, which make up most of the effect's running time) runs on the GPU; everything else still runs
+//! on the CPU. A stage's filters are sent to the GPU in one batch, and the filtered planes are copied back after it.
+//!
+//! The GPU path won't match the CPU path bit-for-bit. GPUs are free to fuse multiplies and adds and don't always round
+//! the same way, so filtered samples can differ by a few units in the last place, staying within 1e-4 of the CPU's
+//! output for samples in the usual range. The differences are far too small to see, but they mean GPU renders aren't
+//! byte-for-byte reproducible across machines the way CPU renders are.
+//!
+//! Each effect opts into the GPU separately through [`NtscEffect::use_gpu`](crate::settings::NtscEffect::use_gpu), so
+//! one caller turning it on doesn't change anyone else's output.
+
+use std::{
+    num::NonZeroU64,
+    sync::{mpsc, Mutex, OnceLock},
+};
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::filter::TransferFunction;
+
+/// Longest filter the shader can apply. Longer filters fall back to the CPU.
+const MAX_FILTER_LEN: usize = 16;
+/// Must match the shader's workgroup size.
+const WORKGROUP_SIZE: usize = 64;
+/// Bytes between one filter pass's parameters (or coefficients) and the next's. This is the largest buffer offset
+/// alignment wgpu allows, and it has room for the longest filter's coefficients.
+const PASS_STRIDE: u64 = 256;
+/// Bytes of coefficients for each filter pass: the numerator, the denominator, and the initial condition.
+const COEFFS_SIZE: u64 = (MAX_FILTER_LEN * 3 * std::mem::size_of::<f32>()) as u64;
+/// How many plane sizes to keep buffers around for. The preview and a render in the background usually differ.
+const MAX_CACHED_SIZES: usize = 4;
+
+struct GpuContext {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    filter_rows: wgpu::ComputePipeline,
+    buffers: Mutex<BufferCache>,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct FilterParams {
+    width: u32,
+    height: u32,
+    filter_len: u32,
+    delay: u32,
+    scale: f32,
+    initial_from_first_sample: u32,
+    initial_value: f32,
+    _padding: u32,
+}
+
+/// Buffers kept between calls to [`filter_planes`], so they aren't created anew for every filter.
+#[derive(Default)]
+struct BufferCache {
+    /// Buffers for the most recently used plane sizes, the most recent last.
+    planes: Vec<PlaneBuffers>,
+    passes: Option<PassBuffers>,
+}
+
+struct PlaneBuffers {
+    /// The size of each plane, in bytes.
+    plane_size: u64,
+    planes: Vec<wgpu::Buffer>,
+    /// Room for every plane, one after another, to copy them back from the GPU.
+    readback: wgpu::Buffer,
+}
+
+/// Each filter pass's parameters and coefficients, one [`PASS_STRIDE`]-sized slot per pass.
+struct PassBuffers {
+    num_passes: usize,
+    params: wgpu::Buffer,
+    coeffs: wgpu::Buffer,
+}
+
+static CONTEXT: OnceLock<Option<GpuContext>> = OnceLock::new();
+
+impl GpuContext {
+    fn new() -> Option<Self> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            ..Default::default()
+        }))?;
+        let (device, queue) = pollster::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("ntscrs"),
+                required_features: wgpu::Features::empty(),
+                required_limits: wgpu::Limits::default(),
+            },
+            None,
+        ))
+        .ok()?;
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("filter_rows"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/filter_rows.wgsl").into()),
+        });
+        let filter_rows = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("filter_rows"),
+            layout: None,
+            module: &shader,
+            entry_point: "main",
+        });
+
+        Some(GpuContext {
+            device,
+            queue,
+            filter_rows,
+            buffers: Mutex::new(BufferCache::default()),
+        })
+    }
+
+    fn get() -> Option<&'static Self> {
+        CONTEXT.get_or_init(Self::new).as_ref()
+    }
+}
+
+impl BufferCache {
+    /// Buffers for at least `num_planes` planes of `plane_size` bytes and `num_passes` filter passes, creating any that
+    /// don't exist yet.
+    fn get(
+        &mut self,
+        device: &wgpu::Device,
+        plane_size: u64,
+        num_planes: usize,
+        num_passes: usize,
+    ) -> (&PlaneBuffers, &PassBuffers) {
+        let cached = self.planes.iter().position(|buffers| {
+            buffers.plane_size == plane_size && buffers.planes.len() >= num_planes
+        });
+        let plane_buffers = match cached {
+            Some(index) => self.planes.remove(index),
+            None => {
+                // Bigger buffers for the same size replace the smaller ones
+                self.planes
+                    .retain(|buffers| buffers.plane_size != plane_size);
+                if self.planes.len() >= MAX_CACHED_SIZES {
+                    self.planes.remove(0);
+                }
+                PlaneBuffers::new(device, plane_size, num_planes)
+            }
+        };
+        self.planes.push(plane_buffers);
+
+        if !matches!(&self.passes, Some(passes) if passes.num_passes >= num_passes) {
+            self.passes = Some(PassBuffers::new(device, num_passes));
+        }
+
+        (self.planes.last().unwrap(), self.passes.as_ref().unwrap())
+    }
+}
+
+impl PlaneBuffers {
+    fn new(device: &wgpu::Device, plane_size: u64, num_planes: usize) -> Self {
+        let planes = (0..num_planes)
+            .map(|_| {
+                device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("plane"),
+                    size: plane_size,
+                    usage: wgpu::BufferUsages::STORAGE
+                        | wgpu::BufferUsages::COPY_SRC
+                        | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                })
+            })
+            .collect();
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("plane_readback"),
+            size: plane_size * num_planes as u64,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        PlaneBuffers {
+            plane_size,
+            planes,
+            readback,
+        }
+    }
+}
+
+impl PassBuffers {
+    fn new(device: &wgpu::Device, num_passes: usize) -> Self {
+        let size = PASS_STRIDE * num_passes as u64;
+        let params = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("filter_params"),
+            size,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let coeffs = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("filter_coeffs"),
+            size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        PassBuffers {
+            num_passes,
+            params,
+            coeffs,
+        }
+    }
+}
+
+/// Whether there's a usable GPU. The first call sets up the GPU, which can take a moment.
+pub fn is_available() -> bool {
+    GpuContext::get().is_some()
+}
+
+/// One filter for [`filter_planes`] to apply to each row of a plane.
+pub(crate) struct FilterPass<'a> {
+    /// Index of the plane to filter.
+    pub plane: usize,
+    pub filter: &'a TransferFunction,
+    /// The filter's initial steady-state value, or `None` to use each row's first sample.
+    pub initial: Option<f32>,
+    pub scale: f32,
+    pub delay: usize,
+}
+
+fn filter_len(filter: &TransferFunction) -> usize {
+    filter.num.len().max(filter.den.len())
+}
+
+/// Apply filters to the rows of several planes of the same size on the GPU, in the order given. The filters all go to
+/// the GPU in one batch, so each plane is only copied there and back once. Returns false without touching the planes if
+/// there's no usable GPU or it can't handle one of the filters, in which case the caller should filter them on the CPU
+/// instead.
+pub(crate) fn filter_planes(
+    planes: &mut [&mut [f32]],
+    width: usize,
+    passes: &[FilterPass],
+) -> bool {
+    let plane_len = planes.first().map_or(0, |plane| plane.len());
+    if width == 0 || plane_len == 0 || planes.iter().any(|plane| plane.len() != plane_len) {
+        return false;
+    }
+    let Some(ctx) = GpuContext::get() else {
+        return false;
+    };
+
+    let plane_size = (plane_len * std::mem::size_of::<f32>()) as u64;
+    if plane_size > ctx.device.limits().max_storage_buffer_binding_size as u64
+        || passes
+            .iter()
+            .any(|pass| filter_len(pass.filter) > MAX_FILTER_LEN)
+    {
+        return false;
+    }
+
+    let height = plane_len / width;
+    let mut params = vec![0u8; PASS_STRIDE as usize * passes.len()];
+    let mut coeffs = vec![0u8; PASS_STRIDE as usize * passes.len()];
+    for (index, pass) in passes.iter().enumerate() {
+        let filter = pass.filter;
+        let filter_len = filter_len(filter);
+        let pass_params = FilterParams {
+            width: width as u32,
+            height: height as u32,
+            filter_len: filter_len as u32,
+            delay: pass.delay as u32,
+            scale: pass.scale,
+            initial_from_first_sample: pass.initial.is_none() as u32,
+            initial_value: pass.initial.unwrap_or(0.0),
+            _padding: 0,
+        };
+        let mut pass_coeffs = Vec::with_capacity(filter_len * 3);
+        pass_coeffs.extend_from_slice(&filter.num);
+        pass_coeffs.resize(filter_len, 0.0);
+        pass_coeffs.extend_from_slice(&filter.den);
+        pass_coeffs.resize(filter_len * 2, 0.0);
+        // The initial condition scales linearly with the steady-state value, so the shader scales this by each row's
+        // value
+        pass_coeffs.extend(filter.initial_condition(1.0));
+
+        let slot = index * PASS_STRIDE as usize;
+        let pass_params = bytemuck::bytes_of(&pass_params);
+        params[slot..slot + pass_params.len()].copy_from_slice(pass_params);
+        let pass_coeffs: &[u8] = bytemuck::cast_slice(&pass_coeffs);
+        coeffs[slot..slot + pass_coeffs.len()].copy_from_slice(pass_coeffs);
+    }
+
+    let device = &ctx.device;
+    let mut cache = ctx.buffers.lock().unwrap();
+    let (plane_buffers, pass_buffers) = cache.get(device, plane_size, planes.len(), passes.len());
+
+    for (plane, buffer) in planes.iter().zip(&plane_buffers.planes) {
+        ctx.queue
+            .write_buffer(buffer, 0, bytemuck::cast_slice(&**plane));
+    }
+    ctx.queue.write_buffer(&pass_buffers.params, 0, &params);
+    ctx.queue.write_buffer(&pass_buffers.coeffs, 0, &coeffs);
+
+    let layout = ctx.filter_rows.get_bind_group_layout(0);
+    let bind_groups = passes
+        .iter()
+        .enumerate()
+        .map(|(index, pass)| {
+            let offset = index as u64 * PASS_STRIDE;
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("filter_rows"),
+                layout: &layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                            buffer: &pass_buffers.params,
+                            offset,
+                            size: NonZeroU64::new(std::mem::size_of::<FilterParams>() as u64),
+                        }),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                            buffer: &pass_buffers.coeffs,
+                            offset,
+                            size: NonZeroU64::new(COEFFS_SIZE),
+                        }),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: plane_buffers.planes[pass.plane].as_entire_binding(),
+                    },
+                ],
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("filter_rows"),
+    });
+    {
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("filter_rows"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&ctx.filter_rows);
+        // Passes that filter the same plane run one after another, since each dispatch sees the previous one's writes
+        for bind_group in &bind_groups {
+            pass.set_bind_group(0, bind_group, &[]);
+            pass.dispatch_workgroups(height.div_ceil(WORKGROUP_SIZE) as u32, 1, 1);
+        }
+    }
+    for (index, buffer) in plane_buffers.planes[..planes.len()].iter().enumerate() {
+        encoder.copy_buffer_to_buffer(
+            buffer,
+            0,
+            &plane_buffers.readback,
+            index as u64 * plane_size,
+            plane_size,
+        );
+    }
+    ctx.queue.submit(Some(encoder.finish()));
+
+    let readback = plane_buffers
+        .readback
+        .slice(..plane_size * planes.len() as u64);
+    let (sender, receiver) = mpsc::channel();
+    readback.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+    device.poll(wgpu::Maintain::Wait);
+    if !matches!(receiver.recv(), Ok(Ok(()))) {
+        return false;
+    }
+
+    {
+        let data = readback.get_mapped_range();
+        for (plane, data) in planes
+            .iter_mut()
+            .zip(data.chunks_exact(plane_size as usize))
+        {
+            plane.copy_from_slice(bytemuck::cast_slice(data));
+        }
+    }
+    plane_buffers.readback.unmap();
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// How far the GPU's output may stray from the CPU's, as promised in the module docs.
+    const TOLERANCE: f32 = 1e-4;
+
+    fn test_plane(width: usize, height: usize, seed: usize) -> Vec<f32> {
+        (0..width * height)
+            .map(|i| {
+                let x = (i + seed * 7) as f32;
+                (x * 0.37).sin() * 0.4 + (x * 0.051).cos() * 0.15 + 0.5
+            })
+            .collect()
+    }
+
+    #[test]
+    fn gpu_matches_cpu_within_tolerance() {
+        if !is_available() {
+            eprintln!("no usable GPU found; skipping");
+            return;
+        }
+
+        // More rows than one workgroup handles, and a width that isn't a multiple of anything in particular
+        const WIDTH: usize = 517;
+        const HEIGHT: usize = 70;
+        let alpha = 0.3;
+        let lowpass = TransferFunction::new([alpha], [1.0, -(1.0 - alpha)]).cascade_self(3);
+        let notch = TransferFunction::new([0.2, 0.1], [1.0, -0.5, 0.2]);
+        let passes = [
+            FilterPass {
+                plane: 0,
+                filter: &lowpass,
+                initial: Some(0.25),
+                scale: 1.0,
+                delay: 0,
+            },
+            FilterPass {
+                plane: 1,
+                filter: &notch,
+                initial: None,
+                scale: -1.0,
+                delay: 2,
+            },
+            FilterPass {
+                plane: 0,
+                filter: &notch,
+                initial: None,
+                scale: 0.5,
+                delay: 1,
+            },
+        ];
+
+        let mut gpu_planes = [test_plane(WIDTH, HEIGHT, 0), test_plane(WIDTH, HEIGHT, 1)];
+        let mut cpu_planes = gpu_planes.clone();
+
+        let [a, b] = &mut gpu_planes;
+        assert!(filter_planes(
+            &mut [a.as_mut_slice(), b.as_mut_slice()],
+            WIDTH,
+            &passes
+        ));
+        for pass in &passes {
+            for row in cpu_planes[pass.plane].chunks_exact_mut(WIDTH) {
+                let initial = pass.initial.unwrap_or(row[0]);
+                pass.filter.filter_signal_in_place_scalar::<1>(
+                    &mut [row],
+                    [initial],
+                    pass.scale,
+                    pass.delay,
+                );
+            }
+        }
+
+        for (gpu_plane, cpu_plane) in gpu_planes.iter().zip(&cpu_planes) {
+            for (i, (gpu, cpu)) in gpu_plane.iter().zip(cpu_plane).enumerate() {
+                assert!(
+                    (gpu - cpu).abs() <= TOLERANCE,
+                    "sample {i}: GPU output {gpu} differs from CPU output {cpu}"
+                );
+            }
+        }
+    }
+}
@@ -1,5 +1,7 @@
 mod f32x4;
 mod filter;
+#[cfg(feature = "gpu")]
+pub mod gpu;
 pub mod ntsc;
 mod random;
 pub mod settings;
@@ -10,3 +12,7 @@ pub mod yiq_fielding;
 extern crate num_derive;
 
 pub use num_traits::cast::{FromPrimitive, ToPrimitive};
+
+// Only for the filter benchmarks; not part of the public API
+#[doc(hidden)]
+pub use filter::TransferFunction;
@@ -1,5 +1,7 @@
 use std::cell::OnceCell;
 use std::collections::VecDeque;
+use std::convert::identity;
+use std::time::{Duration, Instant};
 
 use core::f32::consts::PI;
 use image::RgbImage;
@@ -12,7 +14,10 @@ use crate::{
     filter::TransferFunction,
     random::{Geometric, Seeder},
     shift::{shift_row, shift_row_to, BoundaryHandling},
-    yiq_fielding::{YiqField, YiqOwned, YiqView},
+    yiq_fielding::{
+        pixel_row_to_yiq, rgb_to_yiq, yiq_to_pixel, yiq_to_rgb, BlitInfo, DeinterlaceMode,
+        PixelFormat, Rgb8, Rgbx8, YiqField, YiqOwned, YiqView,
+    },
 };
 
 pub use crate::settings::*;
@@ -22,6 +27,9 @@ pub use crate::settings::*;
 // same way as we do here.
 const NTSC_RATE: f32 = (390158450.0 / 88.0) * 4.0;
 
+/// NTSC's nominal frame rate, which smoothly-moving noise is timed by when it follows timestamps.
+const NTSC_FRAME_RATE: f64 = 30000.0 / 1001.0;
+
 /// Create a simple constant-k lowpass filter with the given frequency cutoff, which can then be used to filter a signal.
 pub fn make_lowpass(cutoff: f32, rate: f32) -> TransferFunction {
     let time_interval = 1.0 / rate;
@@ -82,6 +90,7 @@ pub fn make_butterworth_filter(cutoff: f32, rate: f32) -> TransferFunction {
 
 /// Filter initial condition.
 #[allow(dead_code)]
+#[derive(Clone, Copy)]
 enum InitialCondition {
     /// Convenience value--just use 0.
     Zero,
@@ -119,6 +128,10 @@ impl ScratchBuffer {
 /// - `initial` - The initial steady-state value of the filter.
 /// - `scale` - Scale the effect of the filter on the signal by this amount.
 /// - `delay` - Offset the filter output backwards (to the left) by this amount.
+/// - `precision` - The precision of the filter's state and arithmetic. The plane itself is always 32-bit.
+/// - `use_gpu` - Filter the plane on the GPU, if there is one and it can handle this filter. Ignored without the `gpu`
+///   feature.
+#[allow(clippy::too_many_arguments)]
 fn filter_plane(
     plane: &mut [f32],
     width: usize,
@@ -126,11 +139,91 @@ fn filter_plane(
     initial: InitialCondition,
     scale: f32,
     delay: usize,
+    precision: Precision,
+    use_gpu: bool,
 ) {
-    if filter.should_use_row_chunks() {
-        filter_plane_with_rows::<8>(plane, width, filter, initial, scale, delay)
-    } else {
-        filter_plane_with_rows::<1>(plane, width, filter, initial, scale, delay)
+    filter_planes(
+        &mut [plane],
+        width,
+        &[PlaneFilter {
+            plane: 0,
+            filter,
+            initial,
+            scale,
+            delay,
+        }],
+        precision,
+        use_gpu,
+    );
+}
+
+/// One filter for [`filter_planes`] to apply. The fields other than `plane` are the same as [`filter_plane`]'s
+/// arguments.
+struct PlaneFilter<'a> {
+    /// Index of the plane to filter.
+    plane: usize,
+    filter: &'a TransferFunction,
+    initial: InitialCondition,
+    scale: f32,
+    delay: usize,
+}
+
+/// Apply several IIR filters to the rows of planes of the same size, in the order given. This does the same as calling
+/// [`filter_plane`] for each filter, but on the GPU, all the filters are sent in one batch.
+fn filter_planes(
+    planes: &mut [&mut [f32]],
+    width: usize,
+    filters: &[PlaneFilter],
+    precision: Precision,
+    use_gpu: bool,
+) {
+    // The GPU only does 32-bit arithmetic
+    #[cfg(feature = "gpu")]
+    if use_gpu && precision == Precision::Single {
+        let passes = filters
+            .iter()
+            .map(|filter| crate::gpu::FilterPass {
+                plane: filter.plane,
+                filter: filter.filter,
+                initial: match filter.initial {
+                    InitialCondition::Zero => Some(0.0),
+                    InitialCondition::Constant(c) => Some(c),
+                    InitialCondition::FirstSample => None,
+                },
+                scale: filter.scale,
+                delay: filter.delay,
+            })
+            .collect::<Vec<_>>();
+        if crate::gpu::filter_planes(planes, width, &passes) {
+            return;
+        }
+    }
+    #[cfg(not(feature = "gpu"))]
+    let _ = use_gpu;
+
+    for &PlaneFilter {
+        plane,
+        filter,
+        initial,
+        scale,
+        delay,
+    } in filters
+    {
+        let plane = &mut *planes[plane];
+        if precision == Precision::Double {
+            plane.par_chunks_exact_mut(width).for_each(|row| {
+                let initial = match initial {
+                    InitialCondition::Zero => 0.0,
+                    InitialCondition::Constant(c) => c,
+                    InitialCondition::FirstSample => row[0],
+                };
+                filter.filter_signal_in_place_f64(row, initial, scale, delay)
+            });
+        } else if filter.should_use_row_chunks() {
+            filter_plane_with_rows::<8>(plane, width, filter, initial, scale, delay)
+        } else {
+            filter_plane_with_rows::<1>(plane, width, filter, initial, scale, delay)
+        }
     }
 }
 
@@ -172,14 +265,82 @@ fn filter_plane_with_rows<const ROWS: usize>(
     });
 }
 
+/// See [`CommonInfo::noise_frame_num`].
+fn noise_frame_num(
+    animate_noise: bool,
+    noise_timestamp: Option<Duration>,
+    frame_num: usize,
+) -> u64 {
+    if !animate_noise {
+        return 0;
+    }
+    match noise_timestamp {
+        // Milliseconds are fine enough to tell apart every field of a 60-field-per-second video, and coarse enough
+        // that the same moment rounded slightly differently by two timelines still gets the same noise
+        Some(timestamp) => ((timestamp.as_nanos() + 500_000) / 1_000_000) as u64,
+        None => frame_num as u64,
+    }
+}
+
 /// Settings common to each invocation of the effect. Passed to each individual effect function.
 struct CommonInfo {
     seed: u64,
     frame_num: usize,
     bandwidth_scale: f32,
+    animate_noise: bool,
+    /// Index, within the whole field, of the first row in the view being processed. This and `field_rows` let a field
+    /// be processed in strips: effects that depend on a row's position use them instead of the view's own rows.
+    first_row: usize,
+    /// Number of rows in the whole field.
+    field_rows: usize,
+    precision: Precision,
+    use_gpu: bool,
+    /// When the field is shown, if noise should be generated from it instead of from the frame number.
+    noise_timestamp: Option<Duration>,
+    /// Cycles of the chroma subcarrier per sample. The signal is sampled at four times the standard subcarrier
+    /// frequency, so this is [`STANDARD_SUBCARRIER_CYCLES`] unless the subcarrier has been detuned.
+    subcarrier_cycles: f32,
 }
 
-fn luma_filter(frame: &mut YiqView, filter_mode: LumaLowpass) {
+impl CommonInfo {
+    /// The frame number that noise should be generated for. If noise is frozen, it's the same for every frame.
+    ///
+    /// This is a u64 rather than a usize so that the seeds it's mixed into don't depend on the platform's pointer
+    /// width.
+    fn noise_frame_num(&self) -> u64 {
+        noise_frame_num(self.animate_noise, self.noise_timestamp, self.frame_num)
+    }
+
+    /// How far along anything that moves smoothly over time should be, in frames. When noise follows timestamps, this
+    /// counts frames at NTSC's nominal rate, so things move at the same speed whatever the video's frame rate is.
+    fn time(&self) -> f32 {
+        match self.noise_timestamp {
+            Some(timestamp) => (timestamp.as_secs_f64() * NTSC_FRAME_RATE) as f32,
+            None => self.frame_num as f32,
+        }
+    }
+
+    /// [`CommonInfo::time`] for noise, which stands still if noise is frozen.
+    fn noise_time(&self) -> f32 {
+        if self.animate_noise {
+            self.time()
+        } else {
+            0.0
+        }
+    }
+
+    /// Offset the random seed for an individual noise source. An offset of 0 leaves the seed unchanged.
+    fn seed_with_offset(&self, offset: i32) -> u64 {
+        (self.seed as u32).wrapping_add(offset as u32) as u64
+    }
+
+    /// The index within the whole field of the view's given row.
+    fn field_row(&self, index: usize) -> usize {
+        self.first_row + index
+    }
+}
+
+fn luma_filter(frame: &mut YiqView, info: &CommonInfo, filter_mode: LumaLowpass) {
     match filter_mode {
         LumaLowpass::None => {}
         LumaLowpass::Box => {
@@ -209,6 +370,8 @@ fn luma_filter(frame: &mut YiqView, filter_mode: LumaLowpass) {
             InitialCondition::FirstSample,
             1.0,
             0,
+            info.precision,
+            info.use_gpu,
         ),
     }
 }
@@ -222,8 +385,28 @@ fn composite_chroma_lowpass(frame: &mut YiqView, info: &CommonInfo, filter_type:
 
     let width = frame.dimensions.0;
 
-    filter_plane(frame.i, width, &i_filter, InitialCondition::Zero, 1.0, 2);
-    filter_plane(frame.q, width, &q_filter, InitialCondition::Zero, 1.0, 4);
+    filter_planes(
+        &mut [frame.i, frame.q],
+        width,
+        &[
+            PlaneFilter {
+                plane: 0,
+                filter: &i_filter,
+                initial: InitialCondition::Zero,
+                scale: 1.0,
+                delay: 2,
+            },
+            PlaneFilter {
+                plane: 1,
+                filter: &q_filter,
+                initial: InitialCondition::Zero,
+                scale: 1.0,
+                delay: 4,
+            },
+        ],
+        info.precision,
+        info.use_gpu,
+    );
 }
 
 /// Apply a less intense lowpass filter to the input chroma.
@@ -232,8 +415,19 @@ fn composite_chroma_lowpass_lite(frame: &mut YiqView, info: &CommonInfo, filter_
 
     let width = frame.dimensions.0;
 
-    filter_plane(frame.i, width, &filter, InitialCondition::Zero, 1.0, 1);
-    filter_plane(frame.q, width, &filter, InitialCondition::Zero, 1.0, 1);
+    filter_planes(
+        &mut [frame.i, frame.q],
+        width,
+        &[0, 1].map(|plane| PlaneFilter {
+            plane,
+            filter: &filter,
+            initial: InitialCondition::Zero,
+            scale: 1.0,
+            delay: 1,
+        }),
+        info.precision,
+        info.use_gpu,
+    );
 }
 
 /// Calculate the chroma subcarrier phase for a given row/field
@@ -255,13 +449,36 @@ fn chroma_phase_shift(
 const I_MULT: [f32; 4] = [1.0, 0.0, -1.0, 0.0];
 const Q_MULT: [f32; 4] = [0.0, 1.0, 0.0, -1.0];
 
-fn chroma_into_luma_line(y: &mut [f32], i: &mut [f32], q: &mut [f32], xi: usize) {
+const STANDARD_SUBCARRIER_CYCLES: f32 = 0.25;
+
+/// The I and Q carriers' values at a given sample. At the standard subcarrier frequency, these are a quarter cycle
+/// apart and can be looked up exactly; otherwise they're calculated from the phase.
+#[inline(always)]
+fn subcarrier(index: usize, xi: usize, cycles_per_sample: f32) -> (f32, f32) {
+    if cycles_per_sample == STANDARD_SUBCARRIER_CYCLES {
+        let phase = (index + (xi & 3)) & 3;
+        return (I_MULT[phase], Q_MULT[phase]);
+    }
+
+    // Only the fractional part matters, and taking it in double precision keeps the phase accurate far along the row
+    let cycles = (index as f64 * cycles_per_sample as f64).fract() as f32 + (xi & 3) as f32 * 0.25;
+    let (sin, cos) = (cycles * PI * 2.0).sin_cos();
+    (cos, sin)
+}
+
+fn chroma_into_luma_line(
+    y: &mut [f32],
+    i: &mut [f32],
+    q: &mut [f32],
+    xi: usize,
+    cycles_per_sample: f32,
+) {
     y.iter_mut()
         .zip(i.iter_mut().zip(q))
         .enumerate()
         .for_each(|(index, (y, (i, q)))| {
-            let phase = (index + (xi & 3)) & 3;
-            *y += *i * I_MULT[phase] + *q * Q_MULT[phase];
+            let (i_mult, q_mult) = subcarrier(index, xi, cycles_per_sample);
+            *y += *i * i_mult + *q * q_mult;
             // *i = 0.0;
             // *q = 0.0;
         });
@@ -285,20 +502,32 @@ fn chroma_into_luma(
         .zip(i_lines.zip(q_lines))
         .enumerate()
         .for_each(|(index, (y, (i, q)))| {
-            let xi = chroma_phase_shift(phase_shift, phase_offset, info.frame_num, index * 2);
+            let xi = chroma_phase_shift(
+                phase_shift,
+                phase_offset,
+                info.frame_num,
+                info.field_row(index) * 2,
+            );
 
-            chroma_into_luma_line(y, i, q, xi);
+            chroma_into_luma_line(y, i, q, xi, info.subcarrier_cycles);
         });
 }
 
 #[inline(always)]
-fn demodulate_chroma(chroma: f32, index: usize, xi: usize, i: &mut [f32], q: &mut [f32]) {
+fn demodulate_chroma(
+    chroma: f32,
+    index: usize,
+    xi: usize,
+    cycles_per_sample: f32,
+    i: &mut [f32],
+    q: &mut [f32],
+) {
     let width = i.len();
 
-    let offset = (index + (xi & 3)) & 3;
+    let (i_mult, q_mult) = subcarrier(index, xi, cycles_per_sample);
 
-    let i_modulated = -(chroma * I_MULT[offset]);
-    let q_modulated = -(chroma * Q_MULT[offset]);
+    let i_modulated = -(chroma * i_mult);
+    let q_modulated = -(chroma * q_mult);
 
     // TODO: ntscQT seems to mess this up, giving chroma a "jagged" look reminiscent of the "dot crawl" artifact.
     // Is that worth trying to replicate, or should it just be left like this?
@@ -321,6 +550,7 @@ fn luma_into_chroma_line_box(
     q: &mut [f32],
     scratch: &mut [f32],
     xi: usize,
+    cycles_per_sample: f32,
 ) {
     let width = y.len();
     for index in 0..width {
@@ -335,7 +565,7 @@ fn luma_into_chroma_line_box(
         ];
         scratch[index] = area.iter().sum::<f32>() * 0.25;
         let chroma = c - scratch[index];
-        demodulate_chroma(chroma, index, xi, i, q);
+        demodulate_chroma(chroma, index, xi, cycles_per_sample, i, q);
     }
     y.copy_from_slice(scratch);
 }
@@ -363,17 +593,30 @@ fn luma_into_chroma(
                 .zip(i_lines.zip(q_lines.zip(scratch_lines)))
                 .enumerate()
                 .for_each(|(index, (y, (i, (q, scratch))))| {
-                    let xi =
-                        chroma_phase_shift(phase_shift, phase_offset, info.frame_num, index * 2);
+                    let xi = chroma_phase_shift(
+                        phase_shift,
+                        phase_offset,
+                        info.frame_num,
+                        info.field_row(index) * 2,
+                    );
 
-                    luma_into_chroma_line_box(y, i, q, scratch, xi);
+                    luma_into_chroma_line_box(y, i, q, scratch, xi, info.subcarrier_cycles);
                 });
         }
         ChromaDemodulationFilter::Notch => {
             let scratch = scratch_buffer.get();
             let filter: TransferFunction = make_notch_filter(0.5, 2.0);
             scratch.copy_from_slice(yiq.y);
-            filter_plane(yiq.y, width, &filter, InitialCondition::Zero, 1.0, 1);
+            filter_plane(
+                yiq.y,
+                width,
+                &filter,
+                InitialCondition::Zero,
+                1.0,
+                1,
+                info.precision,
+                info.use_gpu,
+            );
 
             let y_lines = yiq.y.par_chunks_mut(width);
             let i_lines = yiq.i.par_chunks_mut(width);
@@ -384,12 +627,16 @@ fn luma_into_chroma(
                 .zip(i_lines.zip(q_lines.zip(scratch_lines)))
                 .enumerate()
                 .for_each(|(index, (y, (i, (q, scratch))))| {
-                    let xi =
-                        chroma_phase_shift(phase_shift, phase_offset, info.frame_num, index * 2);
+                    let xi = chroma_phase_shift(
+                        phase_shift,
+                        phase_offset,
+                        info.frame_num,
+                        info.field_row(index) * 2,
+                    );
 
                     for index in 0..width {
                         let chroma = y[index] - scratch[index];
-                        demodulate_chroma(chroma, index, xi, i, q);
+                        demodulate_chroma(chroma, index, xi, info.subcarrier_cycles, i, q);
                     }
                 });
         }
@@ -417,9 +664,9 @@ fn luma_into_chroma(
                             phase_shift,
                             phase_offset,
                             info.frame_num,
-                            line_index * 2,
+                            info.field_row(line_index) * 2,
                         );
-                        demodulate_chroma(chroma, index, xi, i, q);
+                        demodulate_chroma(chroma, index, xi, info.subcarrier_cycles, i, q);
                     }
                 });
         }
@@ -462,9 +709,60 @@ fn luma_into_chroma(
                             phase_shift,
                             phase_offset,
                             info.frame_num,
-                            line_index * 2,
+                            info.field_row(line_index) * 2,
                         );
-                        demodulate_chroma(chroma, sample_index, xi, i, q);
+                        demodulate_chroma(chroma, sample_index, xi, info.subcarrier_cycles, i, q);
+                    }
+                });
+        }
+        ChromaDemodulationFilter::ThreeLineComb => {
+            let modulated = scratch_buffer.get();
+            modulated.copy_from_slice(yiq.y);
+            let height = yiq.num_rows();
+
+            let y_lines = yiq.y.par_chunks_mut(width);
+            let i_lines = yiq.i.par_chunks_mut(width);
+            let q_lines = yiq.q.par_chunks_mut(width);
+            y_lines
+                .zip(i_lines.zip(q_lines))
+                .enumerate()
+                .for_each(|(line_index, (y, (i, q)))| {
+                    // Same edge handling as the two-line comb
+                    let prev_index = if line_index == 0 { 1 } else { line_index - 1 };
+                    let next_index = if line_index == height - 1 {
+                        height - 2
+                    } else {
+                        line_index + 1
+                    };
+
+                    let prev_line = &modulated[prev_index * width..(prev_index + 1) * width];
+                    let cur_line = &modulated[line_index * width..(line_index + 1) * width];
+                    let next_line = &modulated[next_index * width..(next_index + 1) * width];
+
+                    for sample_index in 0..width {
+                        let cur_sample = cur_line[sample_index];
+                        // The chroma we'd get from combing with the line above or the line below. Where the picture
+                        // changes vertically, the line on the far side of the edge lets luma leak into the chroma and
+                        // makes it larger, so comb with whichever line gives the smaller chroma. Where neither line is
+                        // a better match, this is the same as the two-line comb.
+                        let chroma_above = (prev_line[sample_index] - cur_sample) * 0.5;
+                        let chroma_below = (next_line[sample_index] - cur_sample) * 0.5;
+                        let chroma = if chroma_above.abs() < chroma_below.abs() {
+                            chroma_above
+                        } else if chroma_below.abs() < chroma_above.abs() {
+                            chroma_below
+                        } else {
+                            (chroma_above + chroma_below) * 0.5
+                        };
+                        y[sample_index] = cur_sample + chroma;
+
+                        let xi = chroma_phase_shift(
+                            phase_shift,
+                            phase_offset,
+                            info.frame_num,
+                            info.field_row(line_index) * 2,
+                        );
+                        demodulate_chroma(chroma, sample_index, xi, info.subcarrier_cycles, i, q);
                     }
                 });
         }
@@ -480,9 +778,49 @@ fn luma_smear(yiq: &mut YiqView, info: &CommonInfo, amount: f32) {
         InitialCondition::Zero,
         1.0,
         0,
+        info.precision,
+        info.use_gpu,
     );
 }
 
+/// Exaggerate the luma signal's high frequencies around sharp edges, emulating a composite signal overshooting and
+/// undershooting its target at each transition. The bright and dark sides of each edge can be adjusted separately.
+fn luma_overshoot(
+    yiq: &mut YiqView,
+    info: &CommonInfo,
+    overshoot: f32,
+    undershoot: f32,
+    scratch_buffer: &mut ScratchBuffer,
+) {
+    let width = yiq.dimensions.0;
+    let lowpass = make_lowpass(0.1, info.bandwidth_scale);
+    let smoothed = scratch_buffer.get();
+    smoothed.copy_from_slice(yiq.y);
+    // Filter each row forwards and then backwards, so the filters' delays cancel out and the halo is the same on both
+    // sides of an edge
+    for _ in 0..2 {
+        filter_plane(
+            smoothed,
+            width,
+            &lowpass,
+            InitialCondition::FirstSample,
+            1.0,
+            0,
+            info.precision,
+            info.use_gpu,
+        );
+        smoothed.par_chunks_mut(width).for_each(|row| row.reverse());
+    }
+
+    yiq.y
+        .par_iter_mut()
+        .zip(smoothed.par_iter())
+        .for_each(|(sample, smoothed)| {
+            let detail = *sample - smoothed;
+            *sample += detail * if detail > 0.0 { overshoot } else { undershoot };
+        });
+}
+
 /// We use a seeded RNG to generate random noise deterministically, but we don't want every pass which uses noise to use
 /// the *same* noise. Each pass gets its own random seed which is mixed into the RNG.
 mod noise_seeds {
@@ -494,6 +832,10 @@ mod noise_seeds {
     pub const EDGE_WAVE: u64 = 5;
     pub const SNOW: u64 = 6;
     pub const CHROMA_LOSS: u64 = 7;
+    pub const HUM_BARS: u64 = 8;
+    pub const DROPOUT: u64 = 9;
+    pub const TBC_JITTER: u64 = 10;
+    pub const FILM_GRAIN: u64 = 11;
 }
 
 /// Helper function to apply gradient noise to a single row of a single plane.
@@ -526,11 +868,17 @@ fn video_noise_line(
 }
 
 /// Add noise to an NTSC-encoded signal.
-fn composite_noise(yiq: &mut YiqView, info: &CommonInfo, frequency: f32, intensity: f32) {
+fn composite_noise(
+    yiq: &mut YiqView,
+    info: &CommonInfo,
+    frequency: f32,
+    intensity: f32,
+    seed_offset: i32,
+) {
     let width = yiq.dimensions.0;
-    let seeder = Seeder::new(info.seed)
+    let seeder = Seeder::new(info.seed_with_offset(seed_offset))
         .mix(noise_seeds::VIDEO_COMPOSITE)
-        .mix(info.frame_num);
+        .mix(info.noise_frame_num());
 
     yiq.y
         .par_chunks_mut(width)
@@ -539,7 +887,7 @@ fn composite_noise(yiq: &mut YiqView, info: &CommonInfo, frequency: f32, intensi
             video_noise_line(
                 row,
                 &seeder,
-                index,
+                info.field_row(index),
                 frequency / info.bandwidth_scale,
                 intensity,
                 1,
@@ -550,9 +898,9 @@ fn composite_noise(yiq: &mut YiqView, info: &CommonInfo, frequency: f32, intensi
 /// Add noise to the chrominance (I and Q) planes of a de-modulated signal.
 fn chroma_noise(yiq: &mut YiqView, info: &CommonInfo, settings: &ChromaNoiseSettings) {
     let width = yiq.dimensions.0;
-    let seeder = Seeder::new(info.seed)
+    let seeder = Seeder::new(info.seed_with_offset(settings.seed))
         .mix(noise_seeds::VIDEO_CHROMA)
-        .mix(info.frame_num);
+        .mix(info.noise_frame_num());
 
     yiq.i
         .par_chunks_mut(width)
@@ -562,7 +910,7 @@ fn chroma_noise(yiq: &mut YiqView, info: &CommonInfo, settings: &ChromaNoiseSett
             video_noise_line(
                 i,
                 &seeder,
-                index,
+                info.field_row(index),
                 settings.frequency / info.bandwidth_scale,
                 settings.intensity,
                 settings.detail,
@@ -570,7 +918,7 @@ fn chroma_noise(yiq: &mut YiqView, info: &CommonInfo, settings: &ChromaNoiseSett
             video_noise_line(
                 q,
                 &seeder,
-                index,
+                info.field_row(index),
                 settings.frequency / info.bandwidth_scale,
                 settings.intensity,
                 settings.detail,
@@ -610,7 +958,7 @@ fn chroma_phase_noise(yiq: &mut YiqView, info: &CommonInfo, intensity: f32) {
     let width = yiq.dimensions.0;
     let seeder = Seeder::new(info.seed)
         .mix(noise_seeds::VIDEO_CHROMA_PHASE)
-        .mix(info.frame_num);
+        .mix(info.noise_frame_num());
 
     yiq.i
         .par_chunks_mut(width)
@@ -619,7 +967,13 @@ fn chroma_phase_noise(yiq: &mut YiqView, info: &CommonInfo, intensity: f32) {
         .for_each(|(index, (i, q))| {
             // Phase shift angle in radians. Mapped so that an intensity of 1.0 is a phase shift ranging from a full
             // rotation to the left - a full rotation to the right.
-            let phase_shift = (seeder.clone().mix(index).finalize::<f32>() - 0.5) * 2.0 * intensity;
+            let phase_shift = (seeder
+                .clone()
+                .mix(info.field_row(index) as u64)
+                .finalize::<f32>()
+                - 0.5)
+                * 2.0
+                * intensity;
 
             chroma_phase_offset_line(i, q, phase_shift);
         });
@@ -639,10 +993,9 @@ fn head_switching(
     let num_affected_rows = num_rows - offset;
 
     let width = yiq.dimensions.0;
-    let height = yiq.num_rows();
+    let height = info.field_rows;
     // Handle cases where the number of affected rows exceeds the number of actual rows in the image
     let start_row = height.max(num_affected_rows) - num_affected_rows;
-    let affected_rows = &mut yiq.y[start_row * width..];
     let cut_off_rows = if num_affected_rows > height {
         num_affected_rows - height
     } else {
@@ -651,17 +1004,20 @@ fn head_switching(
 
     let seeder = Seeder::new(info.seed)
         .mix(noise_seeds::HEAD_SWITCHING)
-        .mix(info.frame_num);
+        .mix(info.noise_frame_num());
 
-    affected_rows
+    yiq.y
         .par_chunks_mut(width)
         .enumerate()
         .for_each(|(index, row)| {
+            let Some(index) = info.field_row(index).checked_sub(start_row) else {
+                return;
+            };
             let index = num_affected_rows - (index + cut_off_rows);
             let row_shift = shift * ((index + offset) as f32 / num_rows as f32).powf(1.5);
             shift_row(
                 row,
-                (row_shift + (seeder.clone().mix(index).finalize::<f32>() - 0.5))
+                (row_shift + (seeder.clone().mix(index as u64).finalize::<f32>() - 0.5))
                     * info.bandwidth_scale,
                 BoundaryHandling::Constant(0.0),
             );
@@ -736,15 +1092,16 @@ fn tracking_noise(
     snow_intensity: f32,
     snow_anisotropy: f32,
     noise_intensity: f32,
+    seed_offset: i32,
 ) {
     let width = yiq.dimensions.0;
-    let height = yiq.num_rows();
+    let height = info.field_rows;
 
-    let mut seeder = Seeder::new(info.seed)
+    let mut seeder = Seeder::new(info.seed_with_offset(seed_offset))
         .mix(noise_seeds::TRACKING_NOISE)
-        .mix(info.frame_num);
+        .mix(info.noise_frame_num());
     let noise_seed = seeder.clone().mix(0).finalize::<i32>();
-    let offset = seeder.clone().mix(1).finalize::<f32>() * yiq.num_rows() as f32;
+    let offset = seeder.clone().mix(1).finalize::<f32>() * height as f32;
     seeder = seeder.mix(2);
     let shift_noise = NoiseBuilder::gradient_1d_offset(offset, num_rows)
         .with_seed(noise_seed)
@@ -754,17 +1111,19 @@ fn tracking_noise(
 
     // Handle cases where the number of affected rows exceeds the number of actual rows in the image
     let start_row = height.max(num_rows) - num_rows;
-    let affected_rows = &mut yiq.y[start_row * width..];
     let cut_off_rows = if num_rows > height {
         num_rows - height
     } else {
         0
     };
 
-    affected_rows
+    yiq.y
         .par_chunks_mut(width)
         .enumerate()
         .for_each(|(index, row)| {
+            let Some(index) = info.field_row(index).checked_sub(start_row) else {
+                return;
+            };
             let index = index + cut_off_rows;
             // This iterates from the top down. Increase the intensity as we approach the bottom of the picture.
             let intensity_scale = index as f32 / num_rows as f32;
@@ -785,7 +1144,7 @@ fn tracking_noise(
 
             row_speckles(
                 row,
-                &mut Xoshiro256PlusPlus::seed_from_u64(seeder.clone().mix(index).finalize()),
+                &mut Xoshiro256PlusPlus::seed_from_u64(seeder.clone().mix(index as u64).finalize()),
                 snow_intensity * intensity_scale.powi(2),
                 snow_anisotropy,
                 info.bandwidth_scale,
@@ -794,16 +1153,16 @@ fn tracking_noise(
 }
 
 /// Add random bits of "snow" to an NTSC-encoded signal.
-fn snow(yiq: &mut YiqView, info: &CommonInfo, intensity: f32, anisotropy: f32) {
-    let seeder = Seeder::new(info.seed)
+fn snow(yiq: &mut YiqView, info: &CommonInfo, intensity: f32, anisotropy: f32, seed_offset: i32) {
+    let seeder = Seeder::new(info.seed_with_offset(seed_offset))
         .mix(noise_seeds::SNOW)
-        .mix(info.frame_num);
+        .mix(info.noise_frame_num());
 
     yiq.y
         .par_chunks_mut(yiq.dimensions.0)
         .enumerate()
         .for_each(|(index, row)| {
-            let line_seed = seeder.clone().mix(index);
+            let line_seed = seeder.clone().mix(info.field_row(index) as u64);
 
             row_speckles(
                 row,
@@ -815,6 +1174,178 @@ fn snow(yiq: &mut YiqView, info: &CommonInfo, intensity: f32, anisotropy: f32) {
         });
 }
 
+/// Add horizontally-offset "ghost" copies of an NTSC-encoded signal, emulating multipath reception.
+fn ghosting(
+    yiq: &mut YiqView,
+    info: &CommonInfo,
+    settings: &GhostingSettings,
+    scratch_buffer: &mut ScratchBuffer,
+) {
+    let width = yiq.dimensions.0;
+    let shift = settings.delay * info.bandwidth_scale;
+
+    yiq.y
+        .par_chunks_mut(width)
+        .zip(scratch_buffer.get().par_chunks_mut(width))
+        .for_each(|(row, ghost)| {
+            shift_row_to(row, ghost, shift, BoundaryHandling::Constant(0.0));
+            ghost
+                .iter_mut()
+                .for_each(|sample| *sample *= settings.intensity);
+            row.iter_mut()
+                .zip(ghost.iter())
+                .for_each(|(sample, ghost_sample)| *sample += ghost_sample);
+
+            if settings.second_ghost {
+                // The second ghost is a reflection of the first, so it's twice as far away and attenuated again.
+                shift_row(ghost, shift, BoundaryHandling::Constant(0.0));
+                row.iter_mut()
+                    .zip(ghost.iter())
+                    .for_each(|(sample, ghost_sample)| {
+                        *sample += ghost_sample * settings.intensity
+                    });
+            }
+        });
+}
+
+/// Shift the whole signal horizontally by the same random amount, which changes each frame, emulating an unstable
+/// time-base corrector. Unlike the edge wave, every scanline moves together.
+fn tbc_jitter(yiq: &mut YiqView, info: &CommonInfo, max_shift: f32) {
+    let jitter = Seeder::new(info.seed)
+        .mix(noise_seeds::TBC_JITTER)
+        .mix(info.noise_frame_num())
+        .finalize::<f32>();
+    let shift = (jitter - 0.5) * 2.0 * max_shift * info.bandwidth_scale;
+
+    yiq.y.par_chunks_mut(yiq.dimensions.0).for_each(|row| {
+        shift_row(row, shift, BoundaryHandling::Constant(0.0));
+    });
+}
+
+/// Add rolling horizontal bars of brightness to an NTSC-encoded signal, emulating AC hum.
+fn hum_bars(yiq: &mut YiqView, info: &CommonInfo, settings: &HumBarSettings) {
+    let width = yiq.dimensions.0;
+    let num_rows = info.field_rows;
+
+    // The bars' starting position is random, but they only move by scrolling so that they animate smoothly.
+    let start_phase = Seeder::new(info.seed)
+        .mix(noise_seeds::HUM_BARS)
+        .finalize::<f32>();
+    let phase = start_phase + info.time() * settings.speed;
+
+    yiq.y
+        .par_chunks_mut(width)
+        .enumerate()
+        .for_each(|(index, row)| {
+            let position = info.field_row(index) as f32 / num_rows as f32;
+            let offset = ((position * settings.frequency - phase) * PI * 2.0).sin()
+                * settings.intensity
+                * 0.5;
+            row.iter_mut().for_each(|pixel| *pixel += offset);
+        });
+}
+
+/// Fade the composite signal out to a flat gray level, leaving only `ghost` of it behind.
+fn no_signal(yiq: &mut YiqView, ghost: f32) {
+    const BLANK_LEVEL: f32 = 0.5;
+
+    let ghost = ghost.clamp(0.0, 1.0);
+    yiq.y.par_iter_mut().for_each(|sample| {
+        *sample = BLANK_LEVEL + (*sample - BLANK_LEVEL) * ghost;
+    });
+    // The chrominance has already been modulated into the luma, but some of the demodulation filters add onto what's
+    // left in the I and Q planes instead of overwriting it
+    yiq.i
+        .par_iter_mut()
+        .chain(yiq.q.par_iter_mut())
+        .for_each(|sample| *sample *= ghost);
+}
+
+/// Add short horizontal streaks where the tape's signal drops out. Applied to the composite signal, so the streaks lose
+/// their color along with their picture.
+fn dropout(yiq: &mut YiqView, info: &CommonInfo, settings: &DropoutSettings) {
+    if settings.density <= 0.0 {
+        return;
+    }
+
+    let width = yiq.dimensions.0;
+    // Lay the dropouts out over the whole field so that they land in the same place if it's being processed in strips
+    let num_rows = info.field_rows;
+    let view_rows = info.first_row..info.first_row + yiq.num_rows();
+    let (min_length, max_length) = if settings.min_length <= settings.max_length {
+        (settings.min_length, settings.max_length)
+    } else {
+        (settings.max_length, settings.min_length)
+    };
+
+    let seed = Seeder::new(info.seed)
+        .mix(noise_seeds::DROPOUT)
+        .mix(info.noise_frame_num())
+        .finalize();
+    let mut rng = Xoshiro256PlusPlus::seed_from_u64(seed);
+    // Like chroma loss, use a geometric distribution to skip straight to the lines that have dropouts on them
+    let dist = Geometric::new(settings.density.min(1.0) as f64);
+
+    let mut row_idx = 0usize;
+    loop {
+        row_idx += rng.sample(&dist);
+        if row_idx >= num_rows {
+            break;
+        }
+
+        let mut start = rng.gen::<f32>() * width as f32;
+        let mut length = (min_length + rng.gen::<f32>() * (max_length - min_length)) * width as f32;
+        loop {
+            if view_rows.contains(&row_idx) {
+                let view_row_idx = row_idx - info.first_row;
+                let row = &mut yiq.y[view_row_idx * width..(view_row_idx + 1) * width];
+                let first = (start.max(0.0) as usize).min(width);
+                let last = ((start + length).max(0.0) as usize).min(width);
+                for (i, sample) in row[first..last].iter_mut().enumerate() {
+                    // Fade the end of the streak back into the picture instead of cutting it off sharply
+                    let t = (first + i) as f32 - start;
+                    let amount = 1.0 - (t / length).powi(4);
+                    *sample += (settings.brightness - *sample) * amount;
+                }
+            }
+
+            // Clumps of dropouts drift a bit and get shorter from line to line
+            row_idx += 1;
+            if row_idx >= num_rows || rng.gen::<f32>() >= settings.clustering {
+                break;
+            }
+            start += (rng.gen::<f32>() - 0.5) * length * 0.5;
+            length *= 0.5 + rng.gen::<f32>() * 0.5;
+        }
+    }
+}
+
+/// Stretch the luma so that `black_level` becomes black and `white_level` becomes white, clipping anything outside
+/// that range.
+fn signal_levels(yiq: &mut YiqView, black_level: f32, white_level: f32) {
+    // Keep the range from collapsing to nothing if the levels are set to the same value
+    let range = (white_level - black_level).max(f32::EPSILON);
+    yiq.y.par_iter_mut().for_each(|sample| {
+        *sample = ((*sample - black_level) / range).clamp(0.0, 1.0);
+    });
+}
+
+/// Raise each RGB component to the given power. Negative components (which are outside the RGB gamut but can come out
+/// of the YIQ conversion) keep their sign so that the curve stays monotonic.
+fn gamma(yiq: &mut YiqView, exponent: f32) {
+    let width = yiq.dimensions.0;
+    yiq.y
+        .par_chunks_mut(width)
+        .zip(yiq.i.par_chunks_mut(width).zip(yiq.q.par_chunks_mut(width)))
+        .for_each(|(y, (i, q))| {
+            for ((y, i), q) in y.iter_mut().zip(i.iter_mut()).zip(q.iter_mut()) {
+                let rgb = yiq_to_rgb([*y, *i, *q])
+                    .map(|component| component.abs().powf(exponent).copysign(component));
+                [*y, *i, *q] = rgb_to_yiq(rgb);
+            }
+        });
+}
+
 /// Offset the chrominance (I and Q) planes horizontally and/or vertically.
 /// Note how the horizontal shift is a float (the signal is continuous), but the vertical shift is an int (each scanline
 /// is discrete).
@@ -898,30 +1429,31 @@ fn chroma_delay(yiq: &mut YiqView, info: &CommonInfo, offset: (f32, isize)) {
 /// Emulate VHS waviness / horizontal shift noise.
 fn vhs_edge_wave(yiq: &mut YiqView, info: &CommonInfo, settings: &VHSEdgeWaveSettings) {
     let width = yiq.dimensions.0;
-    let height = yiq.num_rows();
+    let height = info.field_rows;
 
     let seeder = Seeder::new(info.seed).mix(noise_seeds::EDGE_WAVE);
     let noise_seed: i32 = seeder.clone().mix(0).finalize();
-    let offset = seeder.mix(1).finalize::<f32>() * yiq.num_rows() as f32;
-    let noise =
-        NoiseBuilder::fbm_2d_offset(offset, height, info.frame_num as f32 * settings.speed, 1)
-            .with_seed(noise_seed)
-            .with_freq(settings.frequency)
-            .with_octaves(settings.detail.clamp(1, 5) as u8)
-            // Yes, they got the lacunarity backwards by making it apply to frequency instead of scale.
-            // 2.0 *halves* the scale each time because it doubles the frequency.
-            .with_lacunarity(2.0)
-            .with_gain(std::f32::consts::FRAC_1_SQRT_2)
-            .generate()
-            .0;
+    let offset = seeder.mix(1).finalize::<f32>() * height as f32;
+    let noise = NoiseBuilder::fbm_2d_offset(offset, height, info.noise_time() * settings.speed, 1)
+        .with_seed(noise_seed)
+        .with_freq(settings.frequency)
+        .with_octaves(settings.detail.clamp(1, 5) as u8)
+        // Yes, they got the lacunarity backwards by making it apply to frequency instead of scale.
+        // 2.0 *halves* the scale each time because it doubles the frequency.
+        .with_lacunarity(2.0)
+        .with_gain(std::f32::consts::FRAC_1_SQRT_2)
+        .generate()
+        .0;
 
     for plane in [&mut yiq.y, &mut yiq.i, &mut yiq.q] {
         plane
             .par_chunks_mut(width)
             .enumerate()
             .for_each(|(index, row)| {
-                let shift =
-                    (noise[index] / 0.022) * settings.intensity * 0.5 * info.bandwidth_scale;
+                let shift = (noise[info.field_row(index)] / 0.022)
+                    * settings.intensity
+                    * 0.5
+                    * info.bandwidth_scale;
                 shift_row(row, shift, BoundaryHandling::Extend);
             })
     }
@@ -930,11 +1462,12 @@ fn vhs_edge_wave(yiq: &mut YiqView, info: &CommonInfo, settings: &VHSEdgeWaveSet
 /// Drop out the chrominance signal from random lines.
 fn chroma_loss(yiq: &mut YiqView, info: &CommonInfo, intensity: f32) {
     let width = yiq.dimensions.0;
-    let height = yiq.num_rows();
+    let height = info.field_rows;
+    let view_rows = info.first_row..info.first_row + yiq.num_rows();
 
     let seed = Seeder::new(info.seed)
         .mix(noise_seeds::CHROMA_LOSS)
-        .mix(info.frame_num)
+        .mix(info.noise_frame_num())
         .finalize();
 
     let mut rng = Xoshiro256PlusPlus::seed_from_u64(seed);
@@ -950,9 +1483,12 @@ fn chroma_loss(yiq: &mut YiqView, info: &CommonInfo, intensity: f32) {
             break;
         }
 
-        let row_range = row_idx * width..(row_idx + 1) * width;
-        yiq.i[row_range.clone()].fill(0.0);
-        yiq.q[row_range.clone()].fill(0.0);
+        if view_rows.contains(&row_idx) {
+            let view_row_idx = row_idx - info.first_row;
+            let row_range = view_row_idx * width..(view_row_idx + 1) * width;
+            yiq.i[row_range.clone()].fill(0.0);
+            yiq.q[row_range.clone()].fill(0.0);
+        }
         row_idx += 1;
     }
 }
@@ -981,8 +1517,435 @@ fn chroma_vert_blend(yiq: &mut YiqView) {
         });
 }
 
+/// Darken the image towards its edges, as CRTs and camcorder lenses do.
+fn vignette(yiq: &mut YiqView, info: &CommonInfo, settings: &VignetteSettings) {
+    let width = yiq.dimensions.0;
+    let height = info.field_rows;
+    // Avoid dividing by zero if the falloff is infinitely sharp.
+    let softness = settings.softness.max(1e-6);
+
+    // Only darken the luma; the chrominance is left alone so that the corners don't lose their color as well.
+    yiq.y
+        .par_chunks_mut(width)
+        .enumerate()
+        .for_each(|(row_idx, row)| {
+            // Map the row and column to (-1, 1) so that the vignette is elliptical and fits the frame.
+            let dy = ((info.field_row(row_idx) as f32 + 0.5) / height as f32) * 2.0 - 1.0;
+            row.iter_mut().enumerate().for_each(|(x, pixel)| {
+                let dx = ((x as f32 + 0.5) / width as f32) * 2.0 - 1.0;
+                let dist = (dx * dx + dy * dy).sqrt();
+                let t = ((dist - settings.radius) / softness).clamp(0.0, 1.0);
+                // Smoothstep the falloff so there's no visible edge where it begins.
+                let falloff = t * t * (3.0 - 2.0 * t);
+                *pixel *= 1.0 - falloff * settings.intensity;
+            });
+        });
+}
+
+/// Overlay CRT-style scanlines and (optionally) a phosphor mask on top of the image.
+fn scanlines(yiq: &mut YiqView, info: &CommonInfo, settings: &ScanlineSettings) {
+    let width = yiq.dimensions.0;
+    // Scanlines are laid out over the whole frame, so a single field's rows land on alternate frame rows
+    let (frame_rows, row_stride, row_offset) = match yiq.field {
+        YiqField::Upper => (info.field_rows * 2, 2, 0),
+        YiqField::Lower => (info.field_rows * 2, 2, 1),
+        _ => (info.field_rows, 1, 0),
+    };
+    // Each scanline is two rows tall at 480 lines, and grows proportionally for larger images so that the dark gaps
+    // stay about one pixel tall at native resolution.
+    let scanline_period = (frame_rows as f32 / 480.0).max(2.0);
+    // Likewise, each phosphor stripe is one pixel wide at the native bandwidth scale.
+    let subpixel_width = info.bandwidth_scale.max(1.0);
+    let mask_darkness = 1.0 - settings.darkness;
+
+    yiq.y
+        .par_chunks_mut(width)
+        .zip(yiq.i.par_chunks_mut(width).zip(yiq.q.par_chunks_mut(width)))
+        .enumerate()
+        .for_each(|(row_idx, (y, (i, q)))| {
+            let row_idx = info.field_row(row_idx) * row_stride + row_offset;
+            let scanline_phase = (row_idx as f32 / scanline_period).fract();
+            let row_brightness = if scanline_phase >= 1.0 - settings.thickness {
+                1.0 - settings.darkness
+            } else {
+                1.0
+            };
+
+            // Shadow masks offset their phosphor triads on alternating scanlines.
+            let scanline_idx = (row_idx as f32 / scanline_period) as usize;
+            let mask_offset = match settings.mask {
+                SubpixelMask::ShadowMask => (scanline_idx & 1) * 2,
+                _ => 0,
+            };
+
+            let pixels = y.iter_mut().zip(i.iter_mut().zip(q.iter_mut()));
+            for (x, (y, (i, q))) in pixels.enumerate() {
+                match settings.mask {
+                    SubpixelMask::None => {
+                        *y *= row_brightness;
+                        *i *= row_brightness;
+                        *q *= row_brightness;
+                    }
+                    SubpixelMask::ApertureGrille | SubpixelMask::ShadowMask => {
+                        let lit_channel = ((x as f32 / subpixel_width) as usize + mask_offset) % 3;
+                        let mut rgb = yiq_to_rgb([*y, *i, *q]);
+                        for (channel, value) in rgb.iter_mut().enumerate() {
+                            if channel != lit_channel {
+                                *value *= mask_darkness;
+                            }
+                            *value *= row_brightness;
+                        }
+                        [*y, *i, *q] = rgb_to_yiq(rgb);
+                    }
+                }
+            }
+        });
+}
+
+/// One row of smoothly-interpolated random values from -1 to 1, for film grain. The random values are placed on a grid
+/// `cell_size` pixels apart, so that each grain is about that big, and are seeded by their position on the grid so
+/// that the grain doesn't depend on how the field is split into strips.
+fn film_grain_row(seeder: &Seeder, row: f32, cell_size: f32, width: usize) -> Vec<f32> {
+    let smoothstep = |t: f32| t * t * (3.0 - 2.0 * t);
+    let lattice_value =
+        |x: usize, y: i64| seeder.clone().mix(x as u64).mix(y).finalize::<f32>() * 2.0 - 1.0;
+
+    let cell_y = row.floor();
+    let ty = smoothstep(row - cell_y);
+    let num_cells = (width as f32 / cell_size) as usize + 2;
+    let column: Vec<f32> = (0..num_cells)
+        .map(|cell_x| {
+            let top = lattice_value(cell_x, cell_y as i64);
+            let bottom = lattice_value(cell_x, cell_y as i64 + 1);
+            top + (bottom - top) * ty
+        })
+        .collect();
+
+    (0..width)
+        .map(|x| {
+            let u = x as f32 / cell_size;
+            let cell_x = u.floor();
+            let tx = smoothstep(u - cell_x);
+            let left = column[cell_x as usize];
+            left + (column[cell_x as usize + 1] - left) * tx
+        })
+        .collect()
+}
+
+/// Add grain like that of photographic film. It's weighted towards the midtones, where it's most visible on real film.
+fn film_grain(yiq: &mut YiqView, info: &CommonInfo, settings: &FilmGrainSettings) {
+    let width = yiq.dimensions.0;
+    let cell_size = (settings.size * info.bandwidth_scale).max(0.25);
+    let seeder = Seeder::new(info.seed)
+        .mix(noise_seeds::FILM_GRAIN)
+        .mix(info.noise_frame_num());
+    let chroma_intensity = if settings.monochrome {
+        0.0
+    } else {
+        settings.intensity * settings.color * 0.5
+    };
+
+    yiq.y
+        .par_chunks_mut(width)
+        .zip(yiq.i.par_chunks_mut(width).zip(yiq.q.par_chunks_mut(width)))
+        .enumerate()
+        .for_each(|(row_idx, (y, (i, q)))| {
+            let row = info.field_row(row_idx) as f32 / cell_size;
+            let luma_grain = film_grain_row(&seeder.clone().mix(0u64), row, cell_size, width);
+            let chroma_grain = (chroma_intensity > 0.0).then(|| {
+                (
+                    film_grain_row(&seeder.clone().mix(1u64), row, cell_size, width),
+                    film_grain_row(&seeder.clone().mix(2u64), row, cell_size, width),
+                )
+            });
+
+            for x in 0..width {
+                let midtones = (4.0 * y[x] * (1.0 - y[x])).clamp(0.0, 1.0);
+                y[x] += luma_grain[x] * settings.intensity * midtones;
+                if let Some((i_grain, q_grain)) = &chroma_grain {
+                    i[x] += i_grain[x] * chroma_intensity * midtones;
+                    q[x] += q_grain[x] * chroma_intensity * midtones;
+                }
+            }
+        });
+}
+
+/// Number of box blur passes that bloom uses to approximate a Gaussian blur.
+const BLOOM_PASSES: usize = 3;
+
+/// The radius, in field rows, of each of bloom's blur passes for a field with `field_rows` rows. Also the radius in
+/// pixels horizontally for a field that covers the whole image; see [`bloom`].
+fn bloom_pass_radius(settings: &BloomSettings, field_rows: usize) -> usize {
+    (settings.radius * field_rows as f32 / (480.0 * BLOOM_PASSES as f32)).ceil() as usize
+}
+
+/// Box-blur each row of a plane in place. Samples past the ends of a row are copies of the end samples.
+fn box_blur_rows(plane: &mut [f32], width: usize, radius: usize) {
+    if radius == 0 {
+        return;
+    }
+    let scale = (radius * 2 + 1) as f32;
+    plane.par_chunks_mut(width).for_each(|row| {
+        let src = row.to_vec();
+        let sample = |x: isize| src[x.clamp(0, width as isize - 1) as usize];
+        let radius = radius as isize;
+        let mut sum: f32 = (-radius..=radius).map(sample).sum();
+        for (x, pixel) in row.iter_mut().enumerate() {
+            let x = x as isize;
+            *pixel = sum / scale;
+            sum += sample(x + radius + 1) - sample(x - radius);
+        }
+    });
+}
+
+/// Box-blur each column of a plane in place. Rows past the top and bottom are copies of the top and bottom rows.
+fn box_blur_columns(plane: &mut [f32], width: usize, radius: usize) {
+    if radius == 0 {
+        return;
+    }
+    let scale = ((radius * 2 + 1) as f32).recip();
+    let src = plane.to_vec();
+    let num_rows = src.len() / width;
+    let src_row = |y: isize| {
+        let y = y.clamp(0, num_rows as isize - 1) as usize;
+        &src[y * width..(y + 1) * width]
+    };
+    let radius = radius as isize;
+
+    // Keep a running sum of the rows in the window, one column per pixel
+    let mut sum = vec![0f32; width];
+    for y in -radius..=radius {
+        sum.iter_mut()
+            .zip(src_row(y))
+            .for_each(|(sum, sample)| *sum += sample);
+    }
+    for (y, row) in plane.chunks_mut(width).enumerate() {
+        let y = y as isize;
+        row.iter_mut()
+            .zip(&sum)
+            .for_each(|(pixel, sum)| *pixel = sum * scale);
+        let (entering, leaving) = (src_row(y + radius + 1), src_row(y - radius));
+        sum.iter_mut()
+            .zip(entering.iter().zip(leaving))
+            .for_each(|(sum, (entering, leaving))| *sum += entering - leaving);
+    }
+}
+
+/// Make the bright parts of the image glow into their surroundings. The parts brighter than the threshold are blurred
+/// and added back on top of the image. The blur radius is measured against the height of the whole image, so the glow
+/// looks the same at any resolution and whether one field or both are being processed.
+fn bloom(yiq: &mut YiqView, info: &CommonInfo, settings: &BloomSettings) {
+    let width = yiq.dimensions.0;
+    let vertical_radius = bloom_pass_radius(settings, info.field_rows);
+    let horizontal_radius = match yiq.field {
+        YiqField::Both => vertical_radius,
+        // Each row of a single field is two rows of the image tall
+        _ => bloom_pass_radius(settings, info.field_rows * 2),
+    };
+    // Avoid dividing by zero if the threshold is all the way up
+    let threshold = settings.threshold.min(0.999);
+
+    let mut glow_y = yiq.y.to_vec();
+    let mut glow_i = yiq.i.to_vec();
+    let mut glow_q = yiq.q.to_vec();
+    glow_y
+        .par_iter_mut()
+        .zip(glow_i.par_iter_mut().zip(glow_q.par_iter_mut()))
+        .for_each(|(y, (i, q))| {
+            let amount = ((*y - threshold) / (1.0 - threshold)).clamp(0.0, 1.0);
+            *y *= amount;
+            *i *= amount;
+            *q *= amount;
+        });
+
+    [&mut glow_y, &mut glow_i, &mut glow_q]
+        .into_par_iter()
+        .for_each(|glow| {
+            for _ in 0..BLOOM_PASSES {
+                box_blur_rows(glow, width, horizontal_radius);
+                box_blur_columns(glow, width, vertical_radius);
+            }
+        });
+
+    for (plane, glow) in [
+        (&mut yiq.y, glow_y),
+        (&mut yiq.i, glow_i),
+        (&mut yiq.q, glow_q),
+    ] {
+        plane
+            .par_iter_mut()
+            .zip(glow.par_iter())
+            .for_each(|(pixel, glow)| *pixel += glow * settings.intensity);
+    }
+}
+
+/// How long each stage of the effect took to apply, for finding out which settings are expensive. Stages that run more
+/// than once for a frame, like once for each of an interlaced frame's fields, add up.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StageTimings {
+    stages: Vec<(&'static str, Duration)>,
+}
+
+impl StageTimings {
+    /// Add `duration` to the time taken by the given stage.
+    pub fn add(&mut self, stage: &'static str, duration: Duration) {
+        match self.stages.iter_mut().find(|(name, _)| *name == stage) {
+            Some((_, total)) => *total += duration,
+            None => self.stages.push((stage, duration)),
+        }
+    }
+
+    /// Each stage and how long it took, in the order they ran.
+    pub fn stages(&self) -> &[(&'static str, Duration)] {
+        &self.stages
+    }
+
+    pub fn total(&self) -> Duration {
+        self.stages.iter().map(|(_, duration)| *duration).sum()
+    }
+}
+
+/// Times consecutive stages of the effect into [`StageTimings`]. Without any timings to fill in, it never reads the
+/// clock, so there's no cost to leaving the stage markers in.
+struct Profiler<'a> {
+    timings: Option<&'a mut StageTimings>,
+    current: Option<(&'static str, Instant)>,
+}
+
+impl<'a> Profiler<'a> {
+    fn new(timings: Option<&'a mut StageTimings>) -> Self {
+        Self {
+            timings,
+            current: None,
+        }
+    }
+
+    /// End the current stage, if any, and start timing the next one.
+    fn stage(&mut self, name: &'static str) {
+        let Some(timings) = &mut self.timings else {
+            return;
+        };
+        let now = Instant::now();
+        if let Some((previous, start)) = self.current.replace((name, now)) {
+            timings.add(previous, now - start);
+        }
+    }
+
+    fn finish(&mut self) {
+        if let (Some(timings), Some((previous, start))) = (&mut self.timings, self.current.take()) {
+            timings.add(previous, start.elapsed());
+        }
+    }
+}
+
+/// When a frame is shown, for generating its noise from timestamps (see [`NtscEffect::apply_effect_to_yiq_at`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameTime {
+    /// The frame's presentation timestamp.
+    pub timestamp: Duration,
+    /// How long the frame is shown for.
+    pub duration: Duration,
+}
+
 impl NtscEffect {
-    fn apply_effect_to_yiq_field(&self, yiq: &mut YiqView, frame_num: usize) {
+    /// Scale the intensity of the noise, wave and signal-interference settings, which vary between fields with
+    /// `field_asymmetry`.
+    fn scale_field_noise(&mut self, scale: f32) {
+        self.composite_noise_intensity *= scale;
+        self.snow_intensity *= scale;
+        self.chroma_phase_noise_intensity *= scale;
+        self.tbc_jitter *= scale;
+        if let Some(head_switching) = &mut self.head_switching {
+            head_switching.horiz_shift *= scale;
+        }
+        if let Some(tracking_noise) = &mut self.tracking_noise {
+            tracking_noise.wave_intensity *= scale;
+            tracking_noise.snow_intensity *= scale;
+            tracking_noise.noise_intensity *= scale;
+        }
+        if let Some(chroma_noise) = &mut self.chroma_noise {
+            chroma_noise.intensity *= scale;
+        }
+        if let Some(film_grain) = &mut self.film_grain {
+            film_grain.intensity *= scale;
+        }
+        if let Some(ghosting) = &mut self.ghosting {
+            ghosting.intensity *= scale;
+        }
+        if let Some(hum_bars) = &mut self.hum_bars {
+            hum_bars.intensity *= scale;
+        }
+        if let Some(dropout) = &mut self.dropout {
+            dropout.density *= scale;
+        }
+        if let Some(edge_wave) = self
+            .vhs_settings
+            .as_mut()
+            .and_then(|vhs_settings| vhs_settings.edge_wave.as_mut())
+        {
+            edge_wave.intensity *= scale;
+        }
+    }
+
+    fn apply_effect_to_yiq_field(
+        &self,
+        yiq: &mut YiqView,
+        frame_num: usize,
+        noise_timestamp: Option<Duration>,
+        timings: Option<&mut StageTimings>,
+    ) {
+        let field_rows = yiq.num_rows();
+        self.apply_effect_to_field_rows(yiq, frame_num, noise_timestamp, 0, field_rows, timings);
+    }
+
+    /// The timestamp that one of a frame's fields should generate its noise from, if any. The second field of an
+    /// interlaced frame is shown halfway through it.
+    fn noise_timestamp(&self, time: Option<FrameTime>, second_field: bool) -> Option<Duration> {
+        if !self.timestamp_noise {
+            return None;
+        }
+        time.map(|time| {
+            if second_field {
+                time.timestamp + time.duration / 2
+            } else {
+                time.timestamp
+            }
+        })
+    }
+
+    /// Apply the effect to some consecutive rows of a field, starting at `first_row` out of `field_rows` in total.
+    /// Rows near the top and bottom of the view (unless they're also the top and bottom of the field) won't match the
+    /// whole-field output, since the filters that blend rows together can't see past them; see
+    /// [`NtscEffect::strip_overlap`]. If `timings` is given, how long each stage takes is added to it.
+    fn apply_effect_to_field_rows(
+        &self,
+        yiq: &mut YiqView,
+        frame_num: usize,
+        noise_timestamp: Option<Duration>,
+        first_row: usize,
+        field_rows: usize,
+        timings: Option<&mut StageTimings>,
+    ) {
+        let field_scale = match yiq.field {
+            YiqField::Upper => 1.0 + self.field_asymmetry,
+            YiqField::Lower => 1.0 - self.field_asymmetry,
+            _ => 1.0,
+        };
+        if field_scale != 1.0 {
+            let mut field_settings = self.clone();
+            field_settings.field_asymmetry = 0.0;
+            field_settings.scale_field_noise(field_scale);
+            field_settings.apply_effect_to_field_rows(
+                yiq,
+                frame_num,
+                noise_timestamp,
+                first_row,
+                field_rows,
+                timings,
+            );
+            return;
+        }
+
         let width = yiq.dimensions.0;
 
         let seed = self.random_seed as u32 as u64;
@@ -991,11 +1954,30 @@ impl NtscEffect {
             seed,
             frame_num,
             bandwidth_scale: self.bandwidth_scale,
+            animate_noise: self.animate_noise,
+            first_row,
+            field_rows,
+            precision: self.precision,
+            use_gpu: self.use_gpu,
+            noise_timestamp,
+            subcarrier_cycles: self.advanced.as_ref().map_or(
+                STANDARD_SUBCARRIER_CYCLES,
+                |advanced| {
+                    STANDARD_SUBCARRIER_CYCLES * advanced.subcarrier_frequency
+                        / NTSC_SUBCARRIER_FREQUENCY
+                },
+            ),
         };
 
         let mut scratch_buffer = ScratchBuffer::new(yiq.y.len());
+        let mut profiler = Profiler::new(timings);
+
+        profiler.stage("input filtering");
+        if self.input_gamma != 1.0 {
+            gamma(yiq, self.input_gamma);
+        }
 
-        luma_filter(yiq, self.input_luma_filter);
+        luma_filter(yiq, &info, self.input_luma_filter);
 
         match self.chroma_lowpass_in {
             ChromaLowpass::Full => {
@@ -1007,6 +1989,13 @@ impl NtscEffect {
             ChromaLowpass::None => {}
         };
 
+        profiler.stage("modulation");
+        let chroma_delay_offset = (self.chroma_delay.0 != 0.0 || self.chroma_delay.1 != 0)
+            .then_some((self.chroma_delay.0, self.chroma_delay.1 as isize));
+        if let (true, Some(offset)) = (self.chroma_delay_before_modulation, chroma_delay_offset) {
+            chroma_delay(yiq, &info, offset);
+        }
+
         chroma_into_luma(
             yiq,
             &info,
@@ -1026,17 +2015,58 @@ impl NtscEffect {
                 InitialCondition::Zero,
                 -self.composite_preemphasis,
                 0,
+                info.precision,
+                info.use_gpu,
+            );
+        }
+
+        profiler.stage("noise");
+        if let (false, Some(ghosting_settings)) = (self.noise_before_ghosting, &self.ghosting) {
+            ghosting(yiq, &info, ghosting_settings, &mut scratch_buffer);
+        }
+
+        // With no signal to lock onto, the receiver's gain goes all the way up and the noise is all that's left
+        let (composite_noise_intensity, snow_intensity) = match &self.no_signal {
+            Some(no_signal_settings) => {
+                no_signal(yiq, no_signal_settings.ghost);
+                (1.0, 100.0)
+            }
+            None => (self.composite_noise_intensity, self.snow_intensity),
+        };
+
+        if composite_noise_intensity > 0.0 {
+            composite_noise(
+                yiq,
+                &info,
+                0.25,
+                composite_noise_intensity,
+                self.composite_noise_seed,
+            );
+        }
+
+        if snow_intensity > 0.0 && self.bandwidth_scale > 0.0 {
+            snow(
+                yiq,
+                &info,
+                snow_intensity * 0.01,
+                self.snow_anisotropy,
+                self.snow_seed,
             );
         }
 
-        if self.composite_noise_intensity > 0.0 {
-            composite_noise(yiq, &info, 0.25, self.composite_noise_intensity);
+        if let (true, Some(ghosting_settings)) = (self.noise_before_ghosting, &self.ghosting) {
+            ghosting(yiq, &info, ghosting_settings, &mut scratch_buffer);
+        }
+
+        if let Some(hum_bar_settings) = &self.hum_bars {
+            hum_bars(yiq, &info, hum_bar_settings);
         }
 
-        if self.snow_intensity > 0.0 && self.bandwidth_scale > 0.0 {
-            snow(yiq, &info, self.snow_intensity * 0.01, self.snow_anisotropy);
+        if let Some(dropout_settings) = &self.dropout {
+            dropout(yiq, &info, dropout_settings);
         }
 
+        profiler.stage("head switching");
         if let Some(HeadSwitchingSettings {
             height,
             offset,
@@ -1046,12 +2076,14 @@ impl NtscEffect {
             head_switching(yiq, &info, height as usize, offset as usize, horiz_shift);
         }
 
+        profiler.stage("tracking noise");
         if let Some(TrackingNoiseSettings {
             height,
             wave_intensity,
             snow_intensity,
             snow_anisotropy,
             noise_intensity,
+            seed,
         }) = self.tracking_noise
         {
             tracking_noise(
@@ -1062,9 +2094,15 @@ impl NtscEffect {
                 snow_intensity,
                 snow_anisotropy,
                 noise_intensity,
+                seed,
             );
         }
 
+        if self.tbc_jitter > 0.0 {
+            tbc_jitter(yiq, &info, self.tbc_jitter);
+        }
+
+        profiler.stage("demodulation");
         luma_into_chroma(
             yiq,
             &info,
@@ -1074,25 +2112,53 @@ impl NtscEffect {
             self.video_scanline_phase_shift_offset,
         );
 
+        profiler.stage("luma smear and ringing");
         if self.luma_smear > 0.0 {
             luma_smear(yiq, &info, self.luma_smear);
         }
 
-        if let Some(ringing) = &self.ringing {
-            let notch_filter = make_notch_filter(
-                (ringing.frequency / self.bandwidth_scale).clamp(0.0, 1.0),
-                ringing.power,
+        if self.luma_overshoot > 0.0 || self.luma_undershoot > 0.0 {
+            luma_overshoot(
+                yiq,
+                &info,
+                self.luma_overshoot,
+                self.luma_undershoot,
+                &mut scratch_buffer,
             );
-            filter_plane(
-                yiq.y,
+        }
+
+        if let Some(ringing) = &self.ringing {
+            // Each harmonic rings at the next multiple of the base frequency, and more weakly than the last
+            let mut notch_filters = Vec::new();
+            for harmonic in 1..=ringing.harmonics.max(1) {
+                let frequency = ringing.frequency * harmonic as f32 / self.bandwidth_scale;
+                // Past the Nyquist frequency, so every further harmonic is too
+                if harmonic > 1 && frequency > 1.0 {
+                    break;
+                }
+                notch_filters.push(make_notch_filter(frequency.clamp(0.0, 1.0), ringing.power));
+            }
+            let harmonic_filters = notch_filters
+                .iter()
+                .zip(1..)
+                .map(|(filter, harmonic)| PlaneFilter {
+                    plane: 0,
+                    filter,
+                    initial: InitialCondition::FirstSample,
+                    scale: ringing.intensity / harmonic as f32,
+                    delay: 1,
+                })
+                .collect::<Vec<_>>();
+            filter_planes(
+                &mut [yiq.y],
                 width,
-                &notch_filter,
-                InitialCondition::FirstSample,
-                ringing.intensity,
-                1,
+                &harmonic_filters,
+                info.precision,
+                info.use_gpu,
             );
         }
 
+        profiler.stage("chroma noise");
         if let Some(chroma_noise_settings) = &self.chroma_noise {
             chroma_noise(yiq, &info, chroma_noise_settings);
         }
@@ -1105,14 +2171,11 @@ impl NtscEffect {
             chroma_phase_noise(yiq, &info, self.chroma_phase_noise_intensity);
         }
 
-        if self.chroma_delay.0 != 0.0 || self.chroma_delay.1 != 0 {
-            chroma_delay(
-                yiq,
-                &info,
-                (self.chroma_delay.0, self.chroma_delay.1 as isize),
-            );
+        if let (false, Some(offset)) = (self.chroma_delay_before_modulation, chroma_delay_offset) {
+            chroma_delay(yiq, &info, offset);
         }
 
+        profiler.stage("VHS");
         if let Some(vhs_settings) = &self.vhs_settings {
             if let Some(edge_wave) = &vhs_settings.edge_wave {
                 if edge_wave.intensity > 0.0 {
@@ -1140,31 +2203,42 @@ impl NtscEffect {
                     NTSC_RATE * self.bandwidth_scale,
                     self.filter_type,
                 );
-                filter_plane(yiq.y, width, &luma_filter, InitialCondition::Zero, 1.0, 0);
-                filter_plane(
-                    yiq.i,
-                    width,
-                    &chroma_filter,
-                    InitialCondition::Zero,
-                    1.0,
-                    chroma_delay,
-                );
-                filter_plane(
-                    yiq.q,
-                    width,
-                    &chroma_filter,
-                    InitialCondition::Zero,
-                    1.0,
-                    chroma_delay,
-                );
                 let luma_filter_single = make_lowpass(luma_cut, NTSC_RATE * self.bandwidth_scale);
-                filter_plane(
-                    yiq.y,
+                filter_planes(
+                    &mut [yiq.y, yiq.i, yiq.q],
                     width,
-                    &luma_filter_single,
-                    InitialCondition::Zero,
-                    -1.6,
-                    0,
+                    &[
+                        PlaneFilter {
+                            plane: 0,
+                            filter: &luma_filter,
+                            initial: InitialCondition::Zero,
+                            scale: 1.0,
+                            delay: 0,
+                        },
+                        PlaneFilter {
+                            plane: 1,
+                            filter: &chroma_filter,
+                            initial: InitialCondition::Zero,
+                            scale: 1.0,
+                            delay: chroma_delay,
+                        },
+                        PlaneFilter {
+                            plane: 2,
+                            filter: &chroma_filter,
+                            initial: InitialCondition::Zero,
+                            scale: 1.0,
+                            delay: chroma_delay,
+                        },
+                        PlaneFilter {
+                            plane: 0,
+                            filter: &luma_filter_single,
+                            initial: InitialCondition::Zero,
+                            scale: -1.6,
+                            delay: 0,
+                        },
+                    ],
+                    info.precision,
+                    info.use_gpu,
                 );
             }
 
@@ -1194,6 +2268,8 @@ impl NtscEffect {
                         InitialCondition::Zero,
                         -sharpen.intensity * 2.0 * sharpen.frequency,
                         0,
+                        info.precision,
+                        info.use_gpu,
                     );
                     // filter_plane_scaled(&mut yiq.i, width, &chroma_sharpen_filter, -vhs_settings.sharpen * 0.85);
                     // filter_plane_scaled(&mut yiq.q, width, &chroma_sharpen_filter, -vhs_settings.sharpen * 0.85);
@@ -1201,6 +2277,7 @@ impl NtscEffect {
             }
         }
 
+        profiler.stage("output filtering");
         if self.chroma_vert_blend {
             chroma_vert_blend(yiq);
         }
@@ -1214,17 +2291,111 @@ impl NtscEffect {
             }
             ChromaLowpass::None => {}
         };
+
+        // Only remap the levels if they've been changed, since doing so clips the luma
+        if self.black_level != 0.0 || self.white_level != 1.0 {
+            signal_levels(yiq, self.black_level, self.white_level);
+        }
+
+        profiler.stage("post-processing");
+        if let Some(film_grain_settings) = &self.film_grain {
+            film_grain(yiq, &info, film_grain_settings);
+        }
+
+        if let Some(bloom_settings) = &self.bloom {
+            bloom(yiq, &info, bloom_settings);
+        }
+
+        if let Some(vignette_settings) = &self.vignette {
+            vignette(yiq, &info, vignette_settings);
+        }
+
+        if let Some(scanline_settings) = &self.scanlines {
+            scanlines(yiq, &info, scanline_settings);
+        }
+
+        if self.output_gamma != 1.0 {
+            gamma(yiq, self.output_gamma.max(f32::EPSILON).recip());
+        }
+        profiler.finish();
     }
 
-    pub fn apply_effect_to_yiq(&self, yiq: &mut YiqView, frame_num: usize) {
-        // On Windows debug builds, the stack overflows with the default stack size
-        let pool = rayon::ThreadPoolBuilder::new()
+    /// Run `op` in a thread pool with a bigger stack, since on Windows debug builds, the stack overflows with the
+    /// default stack size. If the effect is already being applied from inside a rayon pool, that pool is used as-is
+    /// instead, so that callers can choose how many threads the effect runs on (and what their stack size is).
+    #[cfg(not(all(target_arch = "wasm32", not(target_feature = "atomics"))))]
+    fn in_thread_pool<R: Send>(op: impl FnOnce() -> R + Send) -> R {
+        if rayon::current_thread_index().is_some() {
+            return op();
+        }
+        rayon::ThreadPoolBuilder::new()
             .stack_size(2 * 1024 * 1024)
             .build()
-            .unwrap();
-        pool.scope(|_| match yiq.field {
+            .unwrap()
+            .install(op)
+    }
+
+    /// WebAssembly builds without threads can't spawn a thread pool. Rayon's global pool falls back to running
+    /// everything on the current thread there, so just use that.
+    #[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+    fn in_thread_pool<R: Send>(op: impl FnOnce() -> R + Send) -> R {
+        op()
+    }
+
+    /// The number that noise is generated from for the given frame, or for its first field if it's interlaced. Along
+    /// with [`NtscEffect::random_seed`] and the individual noise sources' seeds, this decides the frame's noise
+    /// exactly: two frames with the same noise frame number get the same noise. `time` is when the frame is shown, if
+    /// known; see [`NtscEffect::apply_effect_to_yiq_at`].
+    pub fn noise_frame_num(&self, frame_num: usize, time: Option<FrameTime>) -> u64 {
+        noise_frame_num(
+            self.animate_noise,
+            self.noise_timestamp(time, false),
+            frame_num,
+        )
+    }
+
+    pub fn apply_effect_to_yiq(&self, yiq: &mut YiqView, frame_num: usize) {
+        self.apply_effect_to_yiq_timed(yiq, frame_num, None, None);
+    }
+
+    /// Like [`NtscEffect::apply_effect_to_yiq`], for a frame shown at a given time. If
+    /// [`NtscEffect::timestamp_noise`] is set, the noise is generated from the time instead of the frame number, so
+    /// that it stays the same when frames are added or removed before this one or the video's frame rate changes. The
+    /// frame number still decides which field is processed.
+    pub fn apply_effect_to_yiq_at(&self, yiq: &mut YiqView, frame_num: usize, time: FrameTime) {
+        self.apply_effect_to_yiq_timed(yiq, frame_num, Some(time), None);
+    }
+
+    /// Like [`NtscEffect::apply_effect_to_yiq_at`], but also measures how long each stage of the effect takes, adding
+    /// it to `timings`. `time` can be `None` if it isn't known. The other ways of applying the effect don't measure
+    /// anything, so they don't pay for it.
+    ///
+    /// This reads the system clock, so it isn't available on WebAssembly without WASI.
+    pub fn apply_effect_to_yiq_profiled(
+        &self,
+        yiq: &mut YiqView,
+        frame_num: usize,
+        time: Option<FrameTime>,
+        timings: &mut StageTimings,
+    ) {
+        self.apply_effect_to_yiq_timed(yiq, frame_num, time, Some(timings));
+    }
+
+    fn apply_effect_to_yiq_timed(
+        &self,
+        yiq: &mut YiqView,
+        frame_num: usize,
+        time: Option<FrameTime>,
+        mut timings: Option<&mut StageTimings>,
+    ) {
+        Self::in_thread_pool(|| match yiq.field {
             YiqField::Upper | YiqField::Lower | YiqField::Both => {
-                self.apply_effect_to_yiq_field(yiq, frame_num);
+                self.apply_effect_to_yiq_field(
+                    yiq,
+                    frame_num,
+                    self.noise_timestamp(time, false),
+                    timings,
+                );
             }
             YiqField::InterleavedUpper | YiqField::InterleavedLower => {
                 let num_upper_rows = YiqField::Upper.num_image_rows(yiq.dimensions.1);
@@ -1243,12 +2414,237 @@ impl NtscEffect {
                     };
                 yiq_upper.field = YiqField::Upper;
                 yiq_lower.field = YiqField::Lower;
-                self.apply_effect_to_yiq_field(&mut yiq_upper, frame_num_upper);
-                self.apply_effect_to_yiq_field(&mut yiq_lower, frame_num_lower);
+                let lower_first = yiq.field == YiqField::InterleavedLower;
+                self.apply_effect_to_yiq_field(
+                    &mut yiq_upper,
+                    frame_num_upper,
+                    self.noise_timestamp(time, lower_first),
+                    timings.as_deref_mut(),
+                );
+                self.apply_effect_to_yiq_field(
+                    &mut yiq_lower,
+                    frame_num_lower,
+                    self.noise_timestamp(time, !lower_first),
+                    timings,
+                );
+            }
+        })
+    }
+
+    /// How many rows each strip in [`NtscEffect::apply_effect_in_strips`] reads past its own on either side, for a
+    /// field with `field_rows` rows. The comb filters, vertical chroma blending, vertical chroma delay, and bloom all
+    /// pull in neighboring rows, so rows near the edges of a strip come out wrong and have to be thrown away. This is
+    /// kept to a multiple of 8 rows so that rows are batched through the SIMD filters the same way they would be for
+    /// the whole field.
+    fn strip_overlap(&self, field_rows: usize) -> usize {
+        let bloom_rows = self.bloom.as_ref().map_or(0, |bloom| {
+            bloom_pass_radius(bloom, field_rows) * BLOOM_PASSES
+        });
+        (4 + self.chroma_delay.1.unsigned_abs() as usize + bloom_rows).next_multiple_of(8)
+    }
+
+    /// Apply the effect to a frame a horizontal strip of `strip_rows` rows at a time, reading it from `src` and writing
+    /// the result to `dst`. Only one strip's worth of YIQ data is held in memory at once instead of the whole frame's,
+    /// at the cost of processing the rows where the strips overlap more than once. The output is identical to
+    /// converting the whole frame to YIQ, applying [`NtscEffect::apply_effect_to_yiq`], and writing it back out with
+    /// the same deinterlace mode.
+    ///
+    /// `src` and `dst` are both `dimensions.0` by `dimensions.1` pixels, with the given number of bytes per row. Rows
+    /// that aren't written (with [`DeinterlaceMode::Skip`]) are left alone, as are the pixels' alpha channels. `time`
+    /// is when the frame is shown, if known; see [`NtscEffect::apply_effect_to_yiq_at`].
+    #[allow(clippy::too_many_arguments)]
+    pub fn apply_effect_in_strips<I: PixelFormat, O: PixelFormat>(
+        &self,
+        src: &[I::DataFormat],
+        src_row_bytes: usize,
+        dst: &mut [O::DataFormat],
+        dst_row_bytes: usize,
+        dimensions: (usize, usize),
+        field: YiqField,
+        frame_num: usize,
+        time: Option<FrameTime>,
+        strip_rows: usize,
+        deinterlace_mode: DeinterlaceMode,
+    ) {
+        Self::in_thread_pool(|| {
+            let mut apply_to_field =
+                |field: YiqField, frame_num: usize, second_field: bool, deinterlace_mode| {
+                    self.apply_effect_to_field_in_strips::<I, O>(
+                        src,
+                        src_row_bytes,
+                        dst,
+                        dst_row_bytes,
+                        dimensions,
+                        field,
+                        frame_num,
+                        self.noise_timestamp(time, second_field),
+                        strip_rows,
+                        deinterlace_mode,
+                    );
+                };
+            // Interleaved fields are written back out without any deinterlacing, since both fields are rendered
+            match field {
+                YiqField::Upper | YiqField::Lower | YiqField::Both => {
+                    apply_to_field(field, frame_num, false, deinterlace_mode);
+                }
+                YiqField::InterleavedUpper => {
+                    apply_to_field(YiqField::Upper, frame_num * 2, false, DeinterlaceMode::Skip);
+                    apply_to_field(
+                        YiqField::Lower,
+                        frame_num * 2 + 1,
+                        true,
+                        DeinterlaceMode::Skip,
+                    );
+                }
+                YiqField::InterleavedLower => {
+                    apply_to_field(
+                        YiqField::Upper,
+                        frame_num * 2 + 1,
+                        true,
+                        DeinterlaceMode::Skip,
+                    );
+                    apply_to_field(YiqField::Lower, frame_num * 2, false, DeinterlaceMode::Skip);
+                }
             }
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn apply_effect_to_field_in_strips<I: PixelFormat, O: PixelFormat>(
+        &self,
+        src: &[I::DataFormat],
+        src_row_bytes: usize,
+        dst: &mut [O::DataFormat],
+        dst_row_bytes: usize,
+        (width, height): (usize, usize),
+        field: YiqField,
+        frame_num: usize,
+        noise_timestamp: Option<Duration>,
+        strip_rows: usize,
+        deinterlace_mode: DeinterlaceMode,
+    ) {
+        let src_row_length = src_row_bytes / std::mem::size_of::<I::DataFormat>();
+        let dst_row_length = dst_row_bytes / std::mem::size_of::<O::DataFormat>();
+        let src_components = I::ORDER.num_components();
+        let dst_components = O::ORDER.num_components();
+
+        let field_rows = field.num_image_rows(height);
+        let overlap = self.strip_overlap(field_rows);
+        let strip_rows = strip_rows.max(1).next_multiple_of(8);
+        // The image row that each of the field's rows is read from
+        let image_row = |field_row: usize| {
+            match field {
+                YiqField::Upper => field_row * 2,
+                YiqField::Lower => field_row * 2 + 1,
+                _ => field_row,
+            }
+            .min(height - 1)
+        };
+        // Image rows with this parity aren't part of the field, and are either skipped or interpolated
+        let skip_parity = match field {
+            YiqField::Upper => 1,
+            YiqField::Lower => 0,
+            _ => 2,
+        };
+
+        let mut strip_start = 0;
+        while strip_start < field_rows {
+            let strip_end = (strip_start + strip_rows).min(field_rows);
+            let read_start = strip_start.saturating_sub(overlap);
+            let read_end = (strip_end + overlap).min(field_rows);
+            let num_rows = read_end - read_start;
+
+            let mut data = vec![0f32; width * num_rows * 3];
+            let (y, iq) = data.split_at_mut(width * num_rows);
+            let (i, q) = iq.split_at_mut(width * num_rows);
+            y.par_chunks_mut(width)
+                .zip(i.par_chunks_mut(width).zip(q.par_chunks_mut(width)))
+                .enumerate()
+                .for_each(|(row_idx, (y, (i, q)))| {
+                    let src_offset = image_row(read_start + row_idx) * src_row_length;
+                    pixel_row_to_yiq::<I>(
+                        &src[src_offset..src_offset + width * src_components],
+                        y,
+                        i,
+                        q,
+                    );
+                });
+
+            let mut view = YiqView {
+                y,
+                i,
+                q,
+                // A single field's view is half as tall as the image it's from
+                dimensions: (
+                    width,
+                    if field == YiqField::Both {
+                        num_rows
+                    } else {
+                        num_rows * 2
+                    },
+                ),
+                field,
+            };
+            self.apply_effect_to_field_rows(
+                &mut view,
+                frame_num,
+                noise_timestamp,
+                read_start,
+                field_rows,
+                None,
+            );
+
+            // Each strip writes out its own rows, plus the interpolated rows between them and the next strip's
+            let first_image_row = if strip_start == 0 {
+                0
+            } else {
+                image_row(strip_start)
+            };
+            let last_image_row = if strip_end == field_rows {
+                height
+            } else {
+                image_row(strip_end)
+            };
+            let view = &view;
+            let view_pixel = |field_row: usize, pixel_idx: usize| {
+                let idx = (field_row - read_start) * width + pixel_idx;
+                [view.y[idx], view.i[idx], view.q[idx]]
+            };
+            dst.par_chunks_mut(dst_row_length)
+                .enumerate()
+                .skip(first_image_row)
+                .take(last_image_row - first_image_row)
+                .for_each(|(row_idx, dst_row)| {
+                    let dst_row = &mut dst_row[..width * dst_components];
+                    let interpolate = (row_idx & 1) == skip_parity;
+                    if interpolate && deinterlace_mode == DeinterlaceMode::Skip {
+                        return;
+                    }
+                    for (pixel_idx, pixel) in dst_row.chunks_exact_mut(dst_components).enumerate() {
+                        let yiq_pixel = if interpolate && row_idx != 0 && row_idx != height - 1 {
+                            let above = view_pixel((row_idx - 1) >> 1, pixel_idx);
+                            let below = view_pixel((row_idx + 1) >> 1, pixel_idx);
+                            [
+                                (above[0] + below[0]) * 0.5,
+                                (above[1] + below[1]) * 0.5,
+                                (above[2] + below[2]) * 0.5,
+                            ]
+                        } else {
+                            let field_row = if field == YiqField::Both {
+                                row_idx
+                            } else {
+                                row_idx >> 1
+                            };
+                            view_pixel(field_row.min(field_rows - 1), pixel_idx)
+                        };
+                        yiq_to_pixel::<O>(yiq_pixel, pixel);
+                    }
+                });
+
+            strip_start = strip_end;
+        }
+    }
+
     pub fn apply_effect(&self, input_frame: &RgbImage, frame_num: usize) -> RgbImage {
         let field = self.use_field.to_yiq_field(frame_num);
         let mut yiq = YiqOwned::from_image(input_frame, field);
@@ -1256,4 +2652,69 @@ impl NtscEffect {
         self.apply_effect_to_yiq(&mut view, frame_num);
         RgbImage::from(&view)
     }
+
+    fn apply_in_place<S: PixelFormat>(
+        &self,
+        buffer: &mut [S::DataFormat],
+        width: usize,
+        height: usize,
+        frame_num: usize,
+    ) {
+        let row_length = width * S::ORDER.num_components();
+        assert_eq!(
+            buffer.len(),
+            row_length * height,
+            "Buffer length doesn't match a {width}x{height} image"
+        );
+        let row_bytes = row_length * std::mem::size_of::<S::DataFormat>();
+
+        let field = self.use_field.to_yiq_field(frame_num);
+        let mut yiq = YiqOwned::from_strided_buffer::<S>(buffer, row_bytes, width, height, field);
+        let mut view = YiqView::from(&mut yiq);
+        self.apply_effect_to_yiq(&mut view, frame_num);
+        view.write_to_strided_buffer::<S, _>(
+            buffer,
+            BlitInfo::from_full_frame(width, height, row_bytes),
+            DeinterlaceMode::Bob,
+            identity,
+        );
+    }
+
+    /// Apply the effect in-place to a single frame of tightly-packed 8-bit RGB data, `width * height * 3` bytes long.
+    /// This runs the same processing as the GStreamer filter and the plugins, with no dependencies beyond this crate.
+    ///
+    /// `frame_num` seeds the noise (if `animate_noise` is set) and decides which field is processed. Depending on
+    /// `use_field`, the effect may only process every other row of the frame--"Upper" and "Lower" process the
+    /// even-numbered and odd-numbered rows respectively, and "Alternating" switches between the two each frame. The
+    /// skipped rows are then filled in by interpolating between the rows above and below them. "Both" processes every
+    /// row as one field, and the "Interleaved" modes process both fields separately, as two successive frames, and
+    /// weave them back together.
+    ///
+    /// # Panics
+    /// If the buffer's length doesn't match the given dimensions.
+    pub fn apply_to_rgb(&self, buffer: &mut [u8], width: usize, height: usize, frame_num: usize) {
+        self.apply_in_place::<Rgb8>(buffer, width, height, frame_num);
+    }
+
+    /// Like [`NtscEffect::apply_to_rgb`], but for 8-bit RGBA data, `width * height * 4` bytes long. The effect only
+    /// processes color, so the alpha channel is left as it is.
+    pub fn apply_to_rgba(&self, buffer: &mut [u8], width: usize, height: usize, frame_num: usize) {
+        self.apply_in_place::<Rgbx8>(buffer, width, height, frame_num);
+    }
+
+    /// Apply the effect to a sequence of RGB frames (see [`NtscEffect::apply_to_rgb`]), numbering them upwards from
+    /// `first_frame_num`. After each frame is done, `progress` is called with the number of frames finished so far.
+    pub fn apply_to_rgb_frames<'a>(
+        &self,
+        frames: impl IntoIterator<Item = &'a mut [u8]>,
+        width: usize,
+        height: usize,
+        first_frame_num: usize,
+        mut progress: impl FnMut(usize),
+    ) {
+        for (i, frame) in frames.into_iter().enumerate() {
+            self.apply_to_rgb(frame, width, height, first_frame_num + i);
+            progress(i + 1);
+        }
+    }
 }
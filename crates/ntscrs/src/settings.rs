@@ -4,11 +4,13 @@ use std::{
     collections::HashMap,
     error::Error,
     fmt::Display,
+    hash::Hasher,
     ops::RangeInclusive,
 };
 
 use crate::{yiq_fielding::YiqField, FromPrimitive, ToPrimitive};
 use macros::FullSettings;
+use siphasher::sip128::{Hasher128, SipHasher13};
 use tinyjson::{JsonParseError, JsonValue};
 
 /// This is used to dynamically inform API consumers of the settings that can be passed to ntsc-rs. This lets various
@@ -52,6 +54,12 @@ pub enum FilterType {
     Butterworth,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum Precision {
+    Single = 0,
+    Double,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, ToPrimitive)]
 pub enum LumaLowpass {
     None,
@@ -170,6 +178,7 @@ pub enum ChromaDemodulationFilter {
     Notch,
     OneLineComb,
     TwoLineComb,
+    ThreeLineComb,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -196,6 +205,7 @@ pub struct TrackingNoiseSettings {
     pub snow_intensity: f32,
     pub snow_anisotropy: f32,
     pub noise_intensity: f32,
+    pub seed: i32,
 }
 
 impl Default for TrackingNoiseSettings {
@@ -206,6 +216,7 @@ impl Default for TrackingNoiseSettings {
             snow_intensity: 0.05,
             snow_anisotropy: 0.5,
             noise_intensity: 0.005,
+            seed: 0,
         }
     }
 }
@@ -215,6 +226,8 @@ pub struct RingingSettings {
     pub frequency: f32,
     pub power: f32,
     pub intensity: f32,
+    /// Number of notch filters to apply, at multiples of `frequency`. 1 is just the base frequency.
+    pub harmonics: u32,
 }
 
 impl Default for RingingSettings {
@@ -223,6 +236,7 @@ impl Default for RingingSettings {
             frequency: 0.45,
             power: 4.0,
             intensity: 4.0,
+            harmonics: 1,
         }
     }
 }
@@ -232,6 +246,7 @@ pub struct ChromaNoiseSettings {
     pub frequency: f32,
     pub intensity: f32,
     pub detail: u32,
+    pub seed: i32,
 }
 
 impl Default for ChromaNoiseSettings {
@@ -240,6 +255,177 @@ impl Default for ChromaNoiseSettings {
             frequency: 0.05,
             intensity: 0.1,
             detail: 1,
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VignetteSettings {
+    pub intensity: f32,
+    pub radius: f32,
+    pub softness: f32,
+}
+
+impl Default for VignetteSettings {
+    fn default() -> Self {
+        Self {
+            intensity: 0.5,
+            radius: 0.75,
+            softness: 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum SubpixelMask {
+    None,
+    ApertureGrille,
+    ShadowMask,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanlineSettings {
+    pub darkness: f32,
+    pub thickness: f32,
+    pub mask: SubpixelMask,
+}
+
+impl Default for ScanlineSettings {
+    fn default() -> Self {
+        Self {
+            darkness: 0.3,
+            thickness: 0.5,
+            mask: SubpixelMask::None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilmGrainSettings {
+    /// Size of each grain, in pixels at the native bandwidth scale.
+    pub size: f32,
+    pub intensity: f32,
+    /// How much the grain varies in color, relative to how much it varies in brightness.
+    pub color: f32,
+    pub monochrome: bool,
+}
+
+impl Default for FilmGrainSettings {
+    fn default() -> Self {
+        Self {
+            size: 1.5,
+            intensity: 0.1,
+            color: 0.5,
+            monochrome: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BloomSettings {
+    /// Brightness above which the image starts to glow.
+    pub threshold: f32,
+    /// How far the glow spreads, in scanlines of a 480-line image.
+    pub radius: f32,
+    pub intensity: f32,
+}
+
+impl Default for BloomSettings {
+    fn default() -> Self {
+        Self {
+            threshold: 0.7,
+            radius: 8.0,
+            intensity: 0.5,
+        }
+    }
+}
+
+/// The frequency of NTSC's chroma subcarrier, in MHz.
+pub const NTSC_SUBCARRIER_FREQUENCY: f32 = 315.0 / 88.0;
+
+/// Settings that change how the signal itself is modeled, at the expense of physical accuracy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdvancedSettings {
+    /// Frequency of the chroma subcarrier, in MHz. The receiver's filters stay tuned to the standard frequency.
+    pub subcarrier_frequency: f32,
+}
+
+impl Default for AdvancedSettings {
+    fn default() -> Self {
+        Self {
+            subcarrier_frequency: NTSC_SUBCARRIER_FREQUENCY,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HumBarSettings {
+    pub intensity: f32,
+    pub frequency: f32,
+    pub speed: f32,
+}
+
+impl Default for HumBarSettings {
+    fn default() -> Self {
+        Self {
+            intensity: 0.1,
+            frequency: 1.0,
+            speed: 0.02,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DropoutSettings {
+    /// Chance of a dropout starting on any given line.
+    pub density: f32,
+    /// Shortest and longest dropouts, as fractions of the line width.
+    pub min_length: f32,
+    pub max_length: f32,
+    /// Signal level that dropped-out parts of a line are replaced with. 0 is black and 1 is white.
+    pub brightness: f32,
+    /// Chance of a dropout continuing onto the next line.
+    pub clustering: f32,
+}
+
+impl Default for DropoutSettings {
+    fn default() -> Self {
+        Self {
+            density: 0.02,
+            min_length: 0.02,
+            max_length: 0.15,
+            brightness: 1.0,
+            clustering: 0.3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoSignalSettings {
+    /// How much of the input shows through the static. At 0, the output doesn't depend on the input at all.
+    pub ghost: f32,
+}
+
+impl Default for NoSignalSettings {
+    fn default() -> Self {
+        Self { ghost: 0.05 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GhostingSettings {
+    pub delay: f32,
+    pub intensity: f32,
+    pub second_ghost: bool,
+}
+
+impl Default for GhostingSettings {
+    fn default() -> Self {
+        Self {
+            delay: 20.0,
+            intensity: 0.25,
+            second_ghost: false,
         }
     }
 }
@@ -304,12 +490,22 @@ impl<T: Default> Default for SettingsBlock<T> {
 #[non_exhaustive]
 pub struct NtscEffect {
     pub random_seed: i32,
+    pub animate_noise: bool,
+    pub timestamp_noise: bool,
     pub use_field: UseField,
+    pub field_asymmetry: f32,
+    pub input_gamma: f32,
     pub filter_type: FilterType,
+    pub precision: Precision,
+    /// Run the most expensive filters on the GPU, if the `gpu` feature is enabled and a usable GPU is found. The output
+    /// can differ very slightly from the CPU's, so this isn't a saved setting: it's up to whoever applies the effect.
+    pub use_gpu: bool,
     pub input_luma_filter: LumaLowpass,
     pub chroma_lowpass_in: ChromaLowpass,
     pub chroma_demodulation: ChromaDemodulationFilter,
     pub luma_smear: f32,
+    pub luma_overshoot: f32,
+    pub luma_undershoot: f32,
     pub composite_preemphasis: f32,
     pub video_scanline_phase_shift: PhaseShift,
     pub video_scanline_phase_shift_offset: i32,
@@ -317,50 +513,104 @@ pub struct NtscEffect {
     pub head_switching: Option<HeadSwitchingSettings>,
     #[settings_block]
     pub tracking_noise: Option<TrackingNoiseSettings>,
+    pub tbc_jitter: f32,
+    #[settings_block]
+    pub hum_bars: Option<HumBarSettings>,
+    #[settings_block]
+    pub dropout: Option<DropoutSettings>,
+    #[settings_block]
+    pub no_signal: Option<NoSignalSettings>,
     pub composite_noise_intensity: f32,
+    pub composite_noise_seed: i32,
+    #[settings_block]
+    pub ghosting: Option<GhostingSettings>,
+    /// Add the composite noise and snow before the ghosting instead of after it, so the noise gets ghost images too.
+    pub noise_before_ghosting: bool,
     #[settings_block]
     pub ringing: Option<RingingSettings>,
     #[settings_block]
     pub chroma_noise: Option<ChromaNoiseSettings>,
     pub snow_intensity: f32,
     pub snow_anisotropy: f32,
+    pub snow_seed: i32,
     pub chroma_phase_noise_intensity: f32,
     pub chroma_phase_error: f32,
     pub chroma_delay: (f32, i32),
+    /// Delay the chrominance before it's modulated into the composite signal instead of after it's demodulated, so
+    /// the color fringing from demodulation moves along with it.
+    pub chroma_delay_before_modulation: bool,
     #[settings_block(nested)]
     pub vhs_settings: Option<VHSSettings>,
     pub chroma_vert_blend: bool,
     pub chroma_lowpass_out: ChromaLowpass,
+    pub black_level: f32,
+    pub white_level: f32,
+    pub output_gamma: f32,
     pub bandwidth_scale: f32,
+    #[settings_block]
+    pub vignette: Option<VignetteSettings>,
+    #[settings_block]
+    pub scanlines: Option<ScanlineSettings>,
+    #[settings_block]
+    pub film_grain: Option<FilmGrainSettings>,
+    #[settings_block]
+    pub bloom: Option<BloomSettings>,
+    #[settings_block]
+    pub advanced: Option<AdvancedSettings>,
 }
 
 impl Default for NtscEffect {
     fn default() -> Self {
         Self {
             random_seed: 0,
+            animate_noise: true,
+            timestamp_noise: false,
             use_field: UseField::Alternating,
+            field_asymmetry: 0.0,
+            input_gamma: 1.0,
             filter_type: FilterType::ConstantK,
+            precision: Precision::Single,
+            use_gpu: false,
             input_luma_filter: LumaLowpass::Notch,
             chroma_lowpass_in: ChromaLowpass::Full,
             chroma_demodulation: ChromaDemodulationFilter::Box,
             luma_smear: 0.0,
+            luma_overshoot: 0.0,
+            luma_undershoot: 0.0,
             chroma_lowpass_out: ChromaLowpass::Full,
+            black_level: 0.0,
+            white_level: 1.0,
+            output_gamma: 1.0,
             composite_preemphasis: 1.0,
             video_scanline_phase_shift: PhaseShift::Degrees180,
             video_scanline_phase_shift_offset: 0,
             head_switching: Some(HeadSwitchingSettings::default()),
             tracking_noise: Some(TrackingNoiseSettings::default()),
+            tbc_jitter: 0.0,
+            hum_bars: None,
+            dropout: None,
+            no_signal: None,
             ringing: Some(RingingSettings::default()),
+            ghosting: None,
+            noise_before_ghosting: false,
             snow_intensity: 0.003,
             snow_anisotropy: 0.5,
+            snow_seed: 0,
             composite_noise_intensity: 0.01,
+            composite_noise_seed: 0,
             chroma_noise: Some(ChromaNoiseSettings::default()),
             chroma_phase_noise_intensity: 0.001,
             chroma_phase_error: 0.0,
             chroma_delay: (0.0, 0),
+            chroma_delay_before_modulation: false,
             vhs_settings: Some(VHSSettings::default()),
             chroma_vert_blend: true,
             bandwidth_scale: 1.0,
+            vignette: None,
+            scanlines: None,
+            film_grain: None,
+            bloom: None,
+            advanced: None,
         }
     }
 }
@@ -408,6 +658,110 @@ pub struct SettingDescriptor {
     pub id: SettingID,
 }
 
+impl SettingDescriptor {
+    /// Returns true if this setting's value in `settings` is equal to its default value.
+    pub fn is_default(&self, settings: &NtscEffectFullSettings) -> bool {
+        let id = &self.id;
+        match &self.kind {
+            SettingKind::Enumeration { default_value, .. } => {
+                id.get_field_enum(settings) == Some(*default_value)
+            }
+            SettingKind::Percentage { default_value, .. }
+            | SettingKind::FloatRange { default_value, .. } => {
+                id.get_field_ref::<f32>(settings) == Some(default_value)
+            }
+            SettingKind::IntRange { default_value, .. } => {
+                if let Some(value) = id.get_field_ref::<u32>(settings) {
+                    *value as i64 == *default_value as i64
+                } else {
+                    id.get_field_ref::<i32>(settings) == Some(default_value)
+                }
+            }
+            SettingKind::Boolean { default_value } | SettingKind::Group { default_value, .. } => {
+                id.get_field_ref::<bool>(settings) == Some(default_value)
+            }
+        }
+    }
+
+    /// Format this setting's value in `settings` for display, e.g. when listing which settings differ between two sets
+    /// of settings. Menus show the selected item's label, and percentages are shown out of 100.
+    pub fn format_value(&self, settings: &NtscEffectFullSettings) -> String {
+        let id = &self.id;
+        match &self.kind {
+            SettingKind::Enumeration { options, .. } => {
+                let value = id.get_field_enum(settings);
+                options
+                    .iter()
+                    .find(|item| Some(item.index) == value)
+                    .map(|item| item.label.to_string())
+                    .unwrap_or_default()
+            }
+            SettingKind::Percentage { .. } => {
+                let value = id
+                    .get_field_ref::<f32>(settings)
+                    .copied()
+                    .unwrap_or_default();
+                format!("{}%", (value * 1000.0).round() / 10.0)
+            }
+            SettingKind::FloatRange { .. } => id
+                .get_field_ref::<f32>(settings)
+                .copied()
+                .unwrap_or_default()
+                .to_string(),
+            SettingKind::IntRange { .. } => {
+                if let Some(value) = id.get_field_ref::<u32>(settings) {
+                    value.to_string()
+                } else {
+                    id.get_field_ref::<i32>(settings)
+                        .copied()
+                        .unwrap_or_default()
+                        .to_string()
+                }
+            }
+            SettingKind::Boolean { .. } | SettingKind::Group { .. } => {
+                if id
+                    .get_field_ref::<bool>(settings)
+                    .copied()
+                    .unwrap_or_default()
+                {
+                    String::from("on")
+                } else {
+                    String::from("off")
+                }
+            }
+        }
+    }
+
+    /// Reset this setting's value in `settings` to its default value. For groups, this does not affect the settings
+    /// within the group.
+    pub fn reset_to_default(&self, settings: &mut NtscEffectFullSettings) {
+        let id = &self.id;
+        match &self.kind {
+            SettingKind::Enumeration { default_value, .. } => {
+                let _ = id.set_field_enum(settings, *default_value);
+            }
+            SettingKind::Percentage { default_value, .. }
+            | SettingKind::FloatRange { default_value, .. } => {
+                if let Some(value) = id.get_field_mut::<f32>(settings) {
+                    *value = *default_value;
+                }
+            }
+            SettingKind::IntRange { default_value, .. } => {
+                if let Some(value) = id.get_field_mut::<u32>(settings) {
+                    *value = *default_value as u32;
+                } else if let Some(value) = id.get_field_mut::<i32>(settings) {
+                    *value = *default_value;
+                }
+            }
+            SettingKind::Boolean { default_value } | SettingKind::Group { default_value, .. } => {
+                if let Some(value) = id.get_field_mut::<bool>(settings) {
+                    *value = *default_value;
+                }
+            }
+        }
+    }
+}
+
 /// These setting IDs uniquely identify each setting. They are all unique and cannot be reused.
 #[allow(non_camel_case_types)]
 #[derive(Debug, FromPrimitive, ToPrimitive, Clone, Copy, Hash, PartialEq, Eq)]
@@ -476,12 +830,86 @@ pub enum SettingID {
 
     VHS_SHARPEN_ENABLED,
     VHS_SHARPEN_FREQUENCY,
+
+    VIGNETTE,
+    VIGNETTE_INTENSITY,
+    VIGNETTE_RADIUS,
+    VIGNETTE_SOFTNESS,
+
+    SCANLINES,
+    SCANLINES_DARKNESS,
+    SCANLINES_THICKNESS,
+    SCANLINES_MASK,
+
+    HUM_BARS,
+    HUM_BARS_INTENSITY,
+    HUM_BARS_FREQUENCY,
+    HUM_BARS_SPEED,
+
+    GHOSTING,
+    GHOSTING_DELAY,
+    GHOSTING_INTENSITY,
+    GHOSTING_SECOND_GHOST,
+
+    COMPOSITE_NOISE_SEED,
+    SNOW_SEED,
+    CHROMA_NOISE_SEED,
+    TRACKING_NOISE_SEED,
+
+    ANIMATE_NOISE,
+
+    RINGING_HARMONICS,
+
+    DROPOUT,
+    DROPOUT_DENSITY,
+    DROPOUT_MIN_LENGTH,
+    DROPOUT_MAX_LENGTH,
+    DROPOUT_BRIGHTNESS,
+    DROPOUT_CLUSTERING,
+
+    TBC_JITTER,
+
+    LUMA_OVERSHOOT,
+    LUMA_UNDERSHOOT,
+
+    BLACK_LEVEL,
+    WHITE_LEVEL,
+
+    FIELD_ASYMMETRY,
+
+    INPUT_GAMMA,
+    OUTPUT_GAMMA,
+
+    PRECISION,
+
+    TIMESTAMP_NOISE,
+
+    NO_SIGNAL,
+    NO_SIGNAL_GHOST,
+
+    NOISE_BEFORE_GHOSTING,
+    CHROMA_DELAY_BEFORE_MODULATION,
+
+    FILM_GRAIN,
+    FILM_GRAIN_SIZE,
+    FILM_GRAIN_INTENSITY,
+    FILM_GRAIN_COLOR,
+    FILM_GRAIN_MONOCHROME,
+
+    BLOOM,
+    BLOOM_THRESHOLD,
+    BLOOM_RADIUS,
+    BLOOM_INTENSITY,
+
+    ADVANCED,
+    SUBCARRIER_FREQUENCY,
 }
 
 macro_rules! impl_get_field_ref {
     ($self:ident, $settings:ident, $borrow_op:ident) => {
         match $self {
             SettingID::USE_FIELD => $settings.use_field.$borrow_op(),
+            SettingID::FIELD_ASYMMETRY => $settings.field_asymmetry.$borrow_op(),
             SettingID::CHROMA_LOWPASS_IN => $settings.chroma_lowpass_in.$borrow_op(),
             SettingID::COMPOSITE_PREEMPHASIS => $settings.composite_preemphasis.$borrow_op(),
             SettingID::VIDEO_SCANLINE_PHASE_SHIFT => {
@@ -505,6 +933,10 @@ macro_rules! impl_get_field_ref {
             SettingID::CHROMA_DELAY_HORIZONTAL => $settings.chroma_delay.0.$borrow_op(),
             SettingID::CHROMA_DELAY_VERTICAL => $settings.chroma_delay.1.$borrow_op(),
             SettingID::CHROMA_LOWPASS_OUT => $settings.chroma_lowpass_out.$borrow_op(),
+            SettingID::BLACK_LEVEL => $settings.black_level.$borrow_op(),
+            SettingID::WHITE_LEVEL => $settings.white_level.$borrow_op(),
+            SettingID::INPUT_GAMMA => $settings.input_gamma.$borrow_op(),
+            SettingID::OUTPUT_GAMMA => $settings.output_gamma.$borrow_op(),
 
             SettingID::HEAD_SWITCHING => $settings.head_switching.enabled.$borrow_op(),
             SettingID::HEAD_SWITCHING_HEIGHT => {
@@ -546,6 +978,7 @@ macro_rules! impl_get_field_ref {
             SettingID::RINGING_FREQUENCY => $settings.ringing.settings.frequency.$borrow_op(),
             SettingID::RINGING_POWER => $settings.ringing.settings.power.$borrow_op(),
             SettingID::RINGING_SCALE => $settings.ringing.settings.intensity.$borrow_op(),
+            SettingID::RINGING_HARMONICS => $settings.ringing.settings.harmonics.$borrow_op(),
 
             SettingID::VHS_SETTINGS => $settings.vhs_settings.enabled.$borrow_op(),
             SettingID::VHS_TAPE_SPEED => $settings.vhs_settings.settings.tape_speed.$borrow_op(),
@@ -616,8 +1049,78 @@ macro_rules! impl_get_field_ref {
             SettingID::CHROMA_NOISE_DETAIL => $settings.chroma_noise.settings.detail.$borrow_op(),
 
             SettingID::LUMA_SMEAR => $settings.luma_smear.$borrow_op(),
+            SettingID::LUMA_OVERSHOOT => $settings.luma_overshoot.$borrow_op(),
+            SettingID::LUMA_UNDERSHOOT => $settings.luma_undershoot.$borrow_op(),
 
             SettingID::FILTER_TYPE => $settings.filter_type.$borrow_op(),
+            SettingID::PRECISION => $settings.precision.$borrow_op(),
+
+            SettingID::VIGNETTE => $settings.vignette.enabled.$borrow_op(),
+            SettingID::VIGNETTE_INTENSITY => $settings.vignette.settings.intensity.$borrow_op(),
+            SettingID::VIGNETTE_RADIUS => $settings.vignette.settings.radius.$borrow_op(),
+            SettingID::VIGNETTE_SOFTNESS => $settings.vignette.settings.softness.$borrow_op(),
+
+            SettingID::SCANLINES => $settings.scanlines.enabled.$borrow_op(),
+            SettingID::SCANLINES_DARKNESS => $settings.scanlines.settings.darkness.$borrow_op(),
+            SettingID::SCANLINES_THICKNESS => $settings.scanlines.settings.thickness.$borrow_op(),
+            SettingID::SCANLINES_MASK => $settings.scanlines.settings.mask.$borrow_op(),
+
+            SettingID::HUM_BARS => $settings.hum_bars.enabled.$borrow_op(),
+            SettingID::HUM_BARS_INTENSITY => $settings.hum_bars.settings.intensity.$borrow_op(),
+            SettingID::HUM_BARS_FREQUENCY => $settings.hum_bars.settings.frequency.$borrow_op(),
+            SettingID::HUM_BARS_SPEED => $settings.hum_bars.settings.speed.$borrow_op(),
+
+            SettingID::TBC_JITTER => $settings.tbc_jitter.$borrow_op(),
+
+            SettingID::DROPOUT => $settings.dropout.enabled.$borrow_op(),
+            SettingID::DROPOUT_DENSITY => $settings.dropout.settings.density.$borrow_op(),
+            SettingID::DROPOUT_MIN_LENGTH => $settings.dropout.settings.min_length.$borrow_op(),
+            SettingID::DROPOUT_MAX_LENGTH => $settings.dropout.settings.max_length.$borrow_op(),
+            SettingID::DROPOUT_BRIGHTNESS => $settings.dropout.settings.brightness.$borrow_op(),
+            SettingID::DROPOUT_CLUSTERING => $settings.dropout.settings.clustering.$borrow_op(),
+
+            SettingID::NO_SIGNAL => $settings.no_signal.enabled.$borrow_op(),
+            SettingID::NO_SIGNAL_GHOST => $settings.no_signal.settings.ghost.$borrow_op(),
+
+            SettingID::NOISE_BEFORE_GHOSTING => $settings.noise_before_ghosting.$borrow_op(),
+            SettingID::CHROMA_DELAY_BEFORE_MODULATION => {
+                $settings.chroma_delay_before_modulation.$borrow_op()
+            }
+
+            SettingID::FILM_GRAIN => $settings.film_grain.enabled.$borrow_op(),
+            SettingID::FILM_GRAIN_SIZE => $settings.film_grain.settings.size.$borrow_op(),
+            SettingID::FILM_GRAIN_INTENSITY => $settings.film_grain.settings.intensity.$borrow_op(),
+            SettingID::FILM_GRAIN_COLOR => $settings.film_grain.settings.color.$borrow_op(),
+            SettingID::FILM_GRAIN_MONOCHROME => {
+                $settings.film_grain.settings.monochrome.$borrow_op()
+            }
+
+            SettingID::BLOOM => $settings.bloom.enabled.$borrow_op(),
+            SettingID::BLOOM_THRESHOLD => $settings.bloom.settings.threshold.$borrow_op(),
+            SettingID::BLOOM_RADIUS => $settings.bloom.settings.radius.$borrow_op(),
+            SettingID::BLOOM_INTENSITY => $settings.bloom.settings.intensity.$borrow_op(),
+
+            SettingID::ADVANCED => $settings.advanced.enabled.$borrow_op(),
+            SettingID::SUBCARRIER_FREQUENCY => $settings
+                .advanced
+                .settings
+                .subcarrier_frequency
+                .$borrow_op(),
+
+            SettingID::GHOSTING => $settings.ghosting.enabled.$borrow_op(),
+            SettingID::GHOSTING_DELAY => $settings.ghosting.settings.delay.$borrow_op(),
+            SettingID::GHOSTING_INTENSITY => $settings.ghosting.settings.intensity.$borrow_op(),
+            SettingID::GHOSTING_SECOND_GHOST => {
+                $settings.ghosting.settings.second_ghost.$borrow_op()
+            }
+
+            SettingID::COMPOSITE_NOISE_SEED => $settings.composite_noise_seed.$borrow_op(),
+            SettingID::SNOW_SEED => $settings.snow_seed.$borrow_op(),
+            SettingID::CHROMA_NOISE_SEED => $settings.chroma_noise.settings.seed.$borrow_op(),
+            SettingID::TRACKING_NOISE_SEED => $settings.tracking_noise.settings.seed.$borrow_op(),
+
+            SettingID::ANIMATE_NOISE => $settings.animate_noise.$borrow_op(),
+            SettingID::TIMESTAMP_NOISE => $settings.timestamp_noise.$borrow_op(),
         }
     };
 }
@@ -741,6 +1244,12 @@ impl SettingID {
             SettingID::FILTER_TYPE => {
                 settings.filter_type = FilterType::from_u32(value).ok_or_else(err)?;
             }
+            SettingID::PRECISION => {
+                settings.precision = Precision::from_u32(value).ok_or_else(err)?;
+            }
+            SettingID::SCANLINES_MASK => {
+                settings.scanlines.settings.mask = SubpixelMask::from_u32(value).ok_or_else(err)?;
+            }
             _ => {
                 return Err(SetFieldEnumError::not_an_enum(*self));
             }
@@ -764,6 +1273,8 @@ impl SettingID {
             SettingID::USE_FIELD => Some(settings.use_field.to_u32().unwrap()),
             SettingID::CHROMA_DEMODULATION => Some(settings.chroma_demodulation.to_u32().unwrap()),
             SettingID::FILTER_TYPE => Some(settings.filter_type.to_u32().unwrap()),
+            SettingID::PRECISION => Some(settings.precision.to_u32().unwrap()),
+            SettingID::SCANLINES_MASK => Some(settings.scanlines.settings.mask.to_u32().unwrap()),
             _ => None,
         }
     }
@@ -800,6 +1311,8 @@ impl SettingID {
             SettingID::CHROMA_DELAY_HORIZONTAL => "chroma_delay_horizontal",
             SettingID::CHROMA_DELAY_VERTICAL => "chroma_delay_vertical",
             SettingID::CHROMA_LOWPASS_OUT => "chroma_lowpass_out",
+            SettingID::BLACK_LEVEL => "black_level",
+            SettingID::WHITE_LEVEL => "white_level",
             SettingID::HEAD_SWITCHING => "head_switching",
             SettingID::HEAD_SWITCHING_HEIGHT => "head_switching_height",
             SettingID::HEAD_SWITCHING_OFFSET => "head_switching_offset",
@@ -812,6 +1325,7 @@ impl SettingID {
             SettingID::RINGING_FREQUENCY => "ringing_frequency",
             SettingID::RINGING_POWER => "ringing_power",
             SettingID::RINGING_SCALE => "ringing_scale",
+            SettingID::RINGING_HARMONICS => "ringing_harmonics",
             SettingID::VHS_SETTINGS => "vhs_settings",
             SettingID::VHS_TAPE_SPEED => "vhs_tape_speed",
             SettingID::CHROMA_VERT_BLEND => "vhs_chroma_vert_blend",
@@ -825,6 +1339,9 @@ impl SettingID {
             SettingID::VHS_EDGE_WAVE_FREQUENCY => "vhs_edge_wave_frequency",
             SettingID::VHS_EDGE_WAVE_DETAIL => "vhs_edge_wave_detail",
             SettingID::USE_FIELD => "use_field",
+            SettingID::FIELD_ASYMMETRY => "field_asymmetry",
+            SettingID::INPUT_GAMMA => "input_gamma",
+            SettingID::OUTPUT_GAMMA => "output_gamma",
             SettingID::TRACKING_NOISE_NOISE_INTENSITY => "tracking_noise_noise_intensity",
             SettingID::BANDWIDTH_SCALE => "bandwidth_scale",
             SettingID::CHROMA_DEMODULATION => "chroma_demodulation",
@@ -837,7 +1354,54 @@ impl SettingID {
             SettingID::CHROMA_NOISE_FREQUENCY => "chroma_noise_frequency",
             SettingID::CHROMA_NOISE_DETAIL => "chroma_noise_detail",
             SettingID::LUMA_SMEAR => "luma_smear",
+            SettingID::LUMA_OVERSHOOT => "luma_overshoot",
+            SettingID::LUMA_UNDERSHOOT => "luma_undershoot",
             SettingID::FILTER_TYPE => "filter_type",
+            SettingID::PRECISION => "precision",
+            SettingID::VIGNETTE => "vignette",
+            SettingID::VIGNETTE_INTENSITY => "vignette_intensity",
+            SettingID::VIGNETTE_RADIUS => "vignette_radius",
+            SettingID::VIGNETTE_SOFTNESS => "vignette_softness",
+            SettingID::SCANLINES => "scanlines",
+            SettingID::SCANLINES_DARKNESS => "scanlines_darkness",
+            SettingID::SCANLINES_THICKNESS => "scanlines_thickness",
+            SettingID::SCANLINES_MASK => "scanlines_mask",
+            SettingID::HUM_BARS => "hum_bars",
+            SettingID::HUM_BARS_INTENSITY => "hum_bars_intensity",
+            SettingID::HUM_BARS_FREQUENCY => "hum_bars_frequency",
+            SettingID::HUM_BARS_SPEED => "hum_bars_speed",
+            SettingID::TBC_JITTER => "tbc_jitter",
+            SettingID::DROPOUT => "dropout",
+            SettingID::DROPOUT_DENSITY => "dropout_density",
+            SettingID::DROPOUT_MIN_LENGTH => "dropout_min_length",
+            SettingID::DROPOUT_MAX_LENGTH => "dropout_max_length",
+            SettingID::DROPOUT_BRIGHTNESS => "dropout_brightness",
+            SettingID::DROPOUT_CLUSTERING => "dropout_clustering",
+            SettingID::NO_SIGNAL => "no_signal",
+            SettingID::NO_SIGNAL_GHOST => "no_signal_ghost",
+            SettingID::NOISE_BEFORE_GHOSTING => "noise_before_ghosting",
+            SettingID::CHROMA_DELAY_BEFORE_MODULATION => "chroma_delay_before_modulation",
+            SettingID::FILM_GRAIN => "film_grain",
+            SettingID::FILM_GRAIN_SIZE => "film_grain_size",
+            SettingID::FILM_GRAIN_INTENSITY => "film_grain_intensity",
+            SettingID::FILM_GRAIN_COLOR => "film_grain_color",
+            SettingID::FILM_GRAIN_MONOCHROME => "film_grain_monochrome",
+            SettingID::BLOOM => "bloom",
+            SettingID::BLOOM_THRESHOLD => "bloom_threshold",
+            SettingID::BLOOM_RADIUS => "bloom_radius",
+            SettingID::BLOOM_INTENSITY => "bloom_intensity",
+            SettingID::ADVANCED => "advanced",
+            SettingID::SUBCARRIER_FREQUENCY => "subcarrier_frequency",
+            SettingID::GHOSTING => "ghosting",
+            SettingID::GHOSTING_DELAY => "ghosting_delay",
+            SettingID::GHOSTING_INTENSITY => "ghosting_intensity",
+            SettingID::GHOSTING_SECOND_GHOST => "ghosting_second_ghost",
+            SettingID::COMPOSITE_NOISE_SEED => "composite_noise_seed",
+            SettingID::SNOW_SEED => "snow_seed",
+            SettingID::CHROMA_NOISE_SEED => "chroma_noise_seed",
+            SettingID::TRACKING_NOISE_SEED => "tracking_noise_seed",
+            SettingID::ANIMATE_NOISE => "animate_noise",
+            SettingID::TIMESTAMP_NOISE => "timestamp_noise",
         }
     }
 }
@@ -894,6 +1458,60 @@ impl GetAndExpect for HashMap<String, JsonValue> {
     }
 }
 
+/// Information about a settings preset that isn't part of the settings themselves, for presets that are shared around.
+/// It's stored alongside the settings in the preset's JSON, and doesn't affect the effect.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PresetMetadata {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub description: Option<String>,
+}
+
+impl PresetMetadata {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.author.is_none() && self.description.is_none()
+    }
+
+    /// Read the metadata from a preset's JSON. Fields that are missing, empty, or not strings are left unset, since
+    /// they're only informational.
+    pub fn from_json(json: &str) -> Result<Self, ParseSettingsError> {
+        let parsed = json.parse::<JsonValue>()?;
+        let parsed_map = parsed.get::<HashMap<_, _>>().ok_or_else(|| {
+            ParseSettingsError::InvalidSettingType {
+                key: "<root>".to_string(),
+                expected: "object",
+            }
+        })?;
+
+        let read = |key: &str| {
+            parsed_map
+                .get(key)
+                .and_then(|value| value.get::<String>())
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+                .map(str::to_owned)
+        };
+
+        Ok(Self {
+            name: read("name"),
+            author: read("author"),
+            description: read("description"),
+        })
+    }
+
+    fn write_json(&self, dst: &mut HashMap<String, JsonValue>) {
+        for (key, value) in [
+            ("name", &self.name),
+            ("author", &self.author),
+            ("description", &self.description),
+        ] {
+            if let Some(value) = value {
+                dst.insert(key.to_string(), JsonValue::String(value.clone()));
+            }
+        }
+    }
+}
+
 pub struct SettingsList {
     pub settings: Box<[SettingDescriptor]>,
     pub by_id: Box<[Option<Box<[usize]>>]>,
@@ -931,6 +1549,18 @@ impl SettingsList {
                 kind: SettingKind::IntRange { range: i32::MIN..=i32::MAX, default_value: default_settings.random_seed },
                 id: SettingID::RANDOM_SEED,
             },
+            SettingDescriptor {
+                label: "Animate noise",
+                description: Some("Generate new noise every frame. If disabled, the noise is frozen in place."),
+                kind: SettingKind::Boolean { default_value: default_settings.animate_noise },
+                id: SettingID::ANIMATE_NOISE,
+            },
+            SettingDescriptor {
+                label: "Noise follows timestamps",
+                description: Some("Generate each frame's noise from its timestamp instead of its frame number, so re-timing the video or adding or removing frames earlier on doesn't change the noise on the frames after. Only used where timestamps are available."),
+                kind: SettingKind::Boolean { default_value: default_settings.timestamp_noise },
+                id: SettingID::TIMESTAMP_NOISE,
+            },
             SettingDescriptor {
                 label: "Bandwidth scale",
                 description: Some("Horizontally scale the effect by this amount."),
@@ -977,6 +1607,18 @@ impl SettingsList {
                 },
                 id: SettingID::USE_FIELD,
             },
+            SettingDescriptor {
+                label: "Field asymmetry",
+                description: Some("Make the noise, waves, ghosting, hum bars and dropouts stronger on upper fields and weaker on lower ones, so alternating fields look different."),
+                kind: SettingKind::Percentage { logarithmic: false, default_value: default_settings.field_asymmetry },
+                id: SettingID::FIELD_ASYMMETRY,
+            },
+            SettingDescriptor {
+                label: "Input gamma",
+                description: Some("Raise the input to this power before applying the effect. Set it to your source's gamma (e.g. 2.2) to process it in linear light. 1 leaves the input as-is."),
+                kind: SettingKind::FloatRange { range: 0.5..=3.0, logarithmic: false, default_value: default_settings.input_gamma },
+                id: SettingID::INPUT_GAMMA,
+            },
             SettingDescriptor {
                 label: "Lowpass filter type",
                 description: Some("The low-pass filter to use throughout the effect."),
@@ -997,6 +1639,26 @@ impl SettingsList {
                 },
                 id: SettingID::FILTER_TYPE,
             },
+            SettingDescriptor {
+                label: "Filter precision",
+                description: Some("Precision of the arithmetic inside the filters used throughout the effect."),
+                kind: SettingKind::Enumeration {
+                    options: vec![
+                        MenuItem {
+                            label: "32-bit (faster)",
+                            description: Some("Single-precision floating point. Accurate enough for almost everything."),
+                            index: Precision::Single.to_u32().unwrap(),
+                        },
+                        MenuItem {
+                            label: "64-bit (archival)",
+                            description: Some("Double-precision floating point. Slower, but rounding errors build up less in the filters, for archival-quality renders."),
+                            index: Precision::Double.to_u32().unwrap(),
+                        },
+                    ],
+                    default_value: default_settings.precision.to_u32().unwrap(),
+                },
+                id: SettingID::PRECISION,
+            },
             SettingDescriptor {
                 label: "Input luma filter",
                 description: Some("Filter the input luminance to decrease rainbow artifacts."),
@@ -1066,6 +1728,12 @@ impl SettingsList {
                 },
                 id: SettingID::COMPOSITE_NOISE_INTENSITY,
             },
+            SettingDescriptor {
+                label: "Composite noise seed",
+                description: Some("Offset added to the random seed for the composite noise, so it can be changed independently."),
+                kind: SettingKind::IntRange { range: i32::MIN..=i32::MAX, default_value: default_settings.composite_noise_seed },
+                id: SettingID::COMPOSITE_NOISE_SEED,
+            },
             SettingDescriptor {
                 label: "Snow",
                 description: Some("Frequency of random speckles in the image."),
@@ -1085,6 +1753,12 @@ impl SettingsList {
                 },
                 id: SettingID::SNOW_ANISOTROPY,
             },
+            SettingDescriptor {
+                label: "Snow seed",
+                description: Some("Offset added to the random seed for the snow, so it can be changed independently."),
+                kind: SettingKind::IntRange { range: i32::MIN..=i32::MAX, default_value: default_settings.snow_seed },
+                id: SettingID::SNOW_SEED,
+            },
             SettingDescriptor {
                 label: "Scanline phase shift",
                 description: Some("Phase shift of the chrominance signal each scanline. Usually 180 degrees."),
@@ -1148,6 +1822,11 @@ impl SettingsList {
                             label: "2-line comb",
                             description: Some("Average the current row with the previous and next ones, phase-cancelling the chrominance signals. Only works if the scanline phase shift is 180 degrees."),
                             index: ChromaDemodulationFilter::TwoLineComb.to_u32().unwrap()
+                        },
+                        MenuItem {
+                            label: "3-line comb",
+                            description: Some("Average the current row with whichever of the previous and next rows is more similar to it, phase-cancelling the chrominance signals without blurring luma across vertical edges. Only works if the scanline phase shift is 180 degrees."),
+                            index: ChromaDemodulationFilter::ThreeLineComb.to_u32().unwrap()
                         }
                     ],
                     default_value: default_settings.chroma_demodulation.to_u32().unwrap(),
@@ -1160,6 +1839,18 @@ impl SettingsList {
                 kind: SettingKind::FloatRange { range: 0.0..=1.0, logarithmic: false, default_value: default_settings.luma_smear },
                 id: SettingID::LUMA_SMEAR
             },
+            SettingDescriptor {
+                label: "Luma overshoot",
+                description: Some("Brighten the bright side of sharp edges, producing a light halo."),
+                kind: SettingKind::FloatRange { range: 0.0..=2.0, logarithmic: false, default_value: default_settings.luma_overshoot },
+                id: SettingID::LUMA_OVERSHOOT
+            },
+            SettingDescriptor {
+                label: "Luma undershoot",
+                description: Some("Darken the dark side of sharp edges, producing a dark halo."),
+                kind: SettingKind::FloatRange { range: 0.0..=2.0, logarithmic: false, default_value: default_settings.luma_undershoot },
+                id: SettingID::LUMA_UNDERSHOOT
+            },
             SettingDescriptor {
                 label: "Head switching",
                 description: Some("Emulate VHS head-switching artifacts at the bottom of the image."),
@@ -1214,7 +1905,7 @@ impl SettingsList {
                         SettingDescriptor {
                             label: "Snow anisotropy",
                             description: Some("How much the speckles are clustered by scanline."),
-                            kind: SettingKind::Percentage { logarithmic: false, default_value: default_settings.tracking_noise.settings.snow_intensity },
+                            kind: SettingKind::Percentage { logarithmic: false, default_value: default_settings.tracking_noise.settings.snow_anisotropy },
                             id: SettingID::TRACKING_NOISE_SNOW_ANISOTROPY
                         },
                         SettingDescriptor {
@@ -1223,11 +1914,141 @@ impl SettingsList {
                             kind: SettingKind::Percentage { logarithmic: true, default_value: default_settings.tracking_noise.settings.noise_intensity },
                             id: SettingID::TRACKING_NOISE_NOISE_INTENSITY
                         },
+                        SettingDescriptor {
+                            label: "Seed",
+                            description: Some("Offset added to the random seed for the tracking noise, so it can be changed independently."),
+                            kind: SettingKind::IntRange { range: i32::MIN..=i32::MAX, default_value: default_settings.tracking_noise.settings.seed },
+                            id: SettingID::TRACKING_NOISE_SEED
+                        },
                     ],
                     default_value: true,
                 },
                 id: SettingID::TRACKING_NOISE,
             },
+            SettingDescriptor {
+                label: "TBC jitter",
+                description: Some("Shift the whole image horizontally by a random amount each frame, up to this many pixels, emulating an unstable time-base corrector."),
+                kind: SettingKind::FloatRange { range: 0.0..=20.0, logarithmic: false, default_value: default_settings.tbc_jitter },
+                id: SettingID::TBC_JITTER
+            },
+            SettingDescriptor {
+                label: "Hum bars",
+                description: Some("Emulate horizontal bars that roll up or down the image, caused by AC ground-loop hum."),
+                kind: SettingKind::Group {
+                    children: vec![
+                        SettingDescriptor {
+                            label: "Intensity",
+                            description: Some("How much the bars brighten and darken the image."),
+                            kind: SettingKind::Percentage { logarithmic: false, default_value: default_settings.hum_bars.settings.intensity },
+                            id: SettingID::HUM_BARS_INTENSITY
+                        },
+                        SettingDescriptor {
+                            label: "Frequency",
+                            description: Some("Number of bars visible on-screen at once."),
+                            kind: SettingKind::FloatRange { range: 0.0..=10.0, logarithmic: false, default_value: default_settings.hum_bars.settings.frequency },
+                            id: SettingID::HUM_BARS_FREQUENCY
+                        },
+                        SettingDescriptor {
+                            label: "Speed",
+                            description: Some("How far the bars scroll each frame, as a fraction of the distance between bars. Negative values scroll upwards."),
+                            kind: SettingKind::FloatRange { range: -0.5..=0.5, logarithmic: false, default_value: default_settings.hum_bars.settings.speed },
+                            id: SettingID::HUM_BARS_SPEED
+                        },
+                    ],
+                    default_value: false,
+                },
+                id: SettingID::HUM_BARS,
+            },
+            SettingDescriptor {
+                label: "Dropout",
+                description: Some("Emulate short horizontal streaks where the signal drops out, caused by damaged or dirty tape."),
+                kind: SettingKind::Group {
+                    children: vec![
+                        SettingDescriptor {
+                            label: "Density",
+                            description: Some("Chance of a dropout starting on any given scanline."),
+                            kind: SettingKind::Percentage { logarithmic: true, default_value: default_settings.dropout.settings.density },
+                            id: SettingID::DROPOUT_DENSITY
+                        },
+                        SettingDescriptor {
+                            label: "Minimum length",
+                            description: Some("Length of the shortest dropouts, as a fraction of the image width."),
+                            kind: SettingKind::Percentage { logarithmic: false, default_value: default_settings.dropout.settings.min_length },
+                            id: SettingID::DROPOUT_MIN_LENGTH
+                        },
+                        SettingDescriptor {
+                            label: "Maximum length",
+                            description: Some("Length of the longest dropouts, as a fraction of the image width."),
+                            kind: SettingKind::Percentage { logarithmic: false, default_value: default_settings.dropout.settings.max_length },
+                            id: SettingID::DROPOUT_MAX_LENGTH
+                        },
+                        SettingDescriptor {
+                            label: "Brightness",
+                            description: Some("Brightness of the dropouts. 0% gives black streaks and 100% gives white ones."),
+                            kind: SettingKind::Percentage { logarithmic: false, default_value: default_settings.dropout.settings.brightness },
+                            id: SettingID::DROPOUT_BRIGHTNESS
+                        },
+                        SettingDescriptor {
+                            label: "Clustering",
+                            description: Some("Chance of a dropout continuing onto the next scanline, making taller clumps of streaks."),
+                            kind: SettingKind::Percentage { logarithmic: false, default_value: default_settings.dropout.settings.clustering },
+                            id: SettingID::DROPOUT_CLUSTERING
+                        },
+                    ],
+                    default_value: false,
+                },
+                id: SettingID::DROPOUT,
+            },
+            SettingDescriptor {
+                label: "No signal",
+                description: Some("Fill the frame with static, like a TV tuned to a dead channel. The composite noise and snow are turned all the way up."),
+                kind: SettingKind::Group {
+                    children: vec![
+                        SettingDescriptor {
+                            label: "Ghost",
+                            description: Some("How much of the input image shows through the static."),
+                            kind: SettingKind::Percentage { logarithmic: false, default_value: default_settings.no_signal.settings.ghost },
+                            id: SettingID::NO_SIGNAL_GHOST
+                        },
+                    ],
+                    default_value: false,
+                },
+                id: SettingID::NO_SIGNAL,
+            },
+            SettingDescriptor {
+                label: "Ghosting",
+                description: Some("Emulate \"ghost\" images caused by over-the-air signal reflections (multipath reception)."),
+                kind: SettingKind::Group {
+                    children: vec![
+                        SettingDescriptor {
+                            label: "Delay",
+                            description: Some("Horizontal offset of the ghost, in pixels. Negative values produce \"pre-ghosts\" to the left of the image."),
+                            kind: SettingKind::FloatRange { range: -100.0..=100.0, logarithmic: false, default_value: default_settings.ghosting.settings.delay },
+                            id: SettingID::GHOSTING_DELAY
+                        },
+                        SettingDescriptor {
+                            label: "Intensity",
+                            description: Some("Strength of the ghost relative to the main image."),
+                            kind: SettingKind::Percentage { logarithmic: false, default_value: default_settings.ghosting.settings.intensity },
+                            id: SettingID::GHOSTING_INTENSITY
+                        },
+                        SettingDescriptor {
+                            label: "Second ghost",
+                            description: Some("Add a second, fainter ghost twice as far away."),
+                            kind: SettingKind::Boolean { default_value: default_settings.ghosting.settings.second_ghost },
+                            id: SettingID::GHOSTING_SECOND_GHOST
+                        },
+                    ],
+                    default_value: false,
+                },
+                id: SettingID::GHOSTING,
+            },
+            SettingDescriptor {
+                label: "Ghost the noise",
+                description: Some("Add the composite noise and snow before the ghosting instead of after it, so the noise gets ghost images too."),
+                kind: SettingKind::Boolean { default_value: default_settings.noise_before_ghosting },
+                id: SettingID::NOISE_BEFORE_GHOSTING,
+            },
             SettingDescriptor {
                 label: "Ringing",
                 description: Some("Additional ringing artifacts, simulated with a notch filter."),
@@ -1251,6 +2072,12 @@ impl SettingsList {
                             kind: SettingKind::FloatRange { range: 0.0..=10.0, logarithmic: false, default_value: default_settings.ringing.settings.intensity },
                             id: SettingID::RINGING_SCALE
                         },
+                        SettingDescriptor {
+                            label: "Harmonics",
+                            description: Some("Number of ringing frequencies. Each one past the first adds a weaker ring at the next multiple of the base frequency."),
+                            kind: SettingKind::IntRange { range: 1..=5, default_value: default_settings.ringing.settings.harmonics as i32 },
+                            id: SettingID::RINGING_HARMONICS
+                        },
                     ],
                     default_value: true,
                 },
@@ -1279,6 +2106,12 @@ impl SettingsList {
                             kind: SettingKind::IntRange { range: 1..=5, default_value: default_settings.chroma_noise.settings.detail as i32 },
                             id: SettingID::CHROMA_NOISE_DETAIL
                         },
+                        SettingDescriptor {
+                            label: "Seed",
+                            description: Some("Offset added to the random seed for the chroma noise, so it can be changed independently."),
+                            kind: SettingKind::IntRange { range: i32::MIN..=i32::MAX, default_value: default_settings.chroma_noise.settings.seed },
+                            id: SettingID::CHROMA_NOISE_SEED
+                        },
                     ],
                     default_value: true,
                 },
@@ -1321,6 +2154,12 @@ impl SettingsList {
                 },
                 id: SettingID::CHROMA_DELAY_VERTICAL,
             },
+            SettingDescriptor {
+                label: "Delay chroma before modulation",
+                description: Some("Apply the chroma delay before the chrominance is modulated into the composite signal instead of after it's demodulated. The color fringing from demodulation then moves along with the color instead of staying put."),
+                kind: SettingKind::Boolean { default_value: default_settings.chroma_delay_before_modulation },
+                id: SettingID::CHROMA_DELAY_BEFORE_MODULATION,
+            },
             SettingDescriptor {
                 label: "VHS emulation",
                 description: None,
@@ -1451,6 +2290,177 @@ impl SettingsList {
                 },
                 id: SettingID::CHROMA_LOWPASS_OUT,
             },
+            SettingDescriptor {
+                label: "Black level",
+                description: Some("Luma level that's shown as black. Anything darker is crushed to black, emulating miscalibrated equipment."),
+                kind: SettingKind::FloatRange { range: 0.0..=0.5, logarithmic: false, default_value: default_settings.black_level },
+                id: SettingID::BLACK_LEVEL,
+            },
+            SettingDescriptor {
+                label: "White level",
+                description: Some("Luma level that's shown as white. Anything brighter is blown out to white."),
+                kind: SettingKind::FloatRange { range: 0.5..=1.0, logarithmic: false, default_value: default_settings.white_level },
+                id: SettingID::WHITE_LEVEL,
+            },
+            SettingDescriptor {
+                label: "Output gamma",
+                description: Some("Gamma to re-encode the output with after applying the effect. Set it to the same value as the input gamma to undo it. 1 leaves the output as-is."),
+                kind: SettingKind::FloatRange { range: 0.5..=3.0, logarithmic: false, default_value: default_settings.output_gamma },
+                id: SettingID::OUTPUT_GAMMA,
+            },
+            SettingDescriptor {
+                label: "Vignette",
+                description: Some("Darken the image towards its edges, like a CRT or camcorder lens."),
+                kind: SettingKind::Group {
+                    children: vec![
+                        SettingDescriptor {
+                            label: "Intensity",
+                            description: Some("How much the edges of the image are darkened."),
+                            kind: SettingKind::Percentage { logarithmic: false, default_value: default_settings.vignette.settings.intensity },
+                            id: SettingID::VIGNETTE_INTENSITY
+                        },
+                        SettingDescriptor {
+                            label: "Radius",
+                            description: Some("Distance from the center of the image at which the darkening begins."),
+                            kind: SettingKind::FloatRange { range: 0.0..=1.5, logarithmic: false, default_value: default_settings.vignette.settings.radius },
+                            id: SettingID::VIGNETTE_RADIUS
+                        },
+                        SettingDescriptor {
+                            label: "Softness",
+                            description: Some("How gradually the darkening fades in."),
+                            kind: SettingKind::FloatRange { range: 0.0..=1.5, logarithmic: false, default_value: default_settings.vignette.settings.softness },
+                            id: SettingID::VIGNETTE_SOFTNESS
+                        },
+                    ],
+                    default_value: false,
+                },
+                id: SettingID::VIGNETTE,
+            },
+            SettingDescriptor {
+                label: "Scanlines",
+                description: Some("Overlay dark gaps between scanlines, like those visible on a CRT screen."),
+                kind: SettingKind::Group {
+                    children: vec![
+                        SettingDescriptor {
+                            label: "Darkness",
+                            description: Some("How much the gaps between scanlines are darkened."),
+                            kind: SettingKind::Percentage { logarithmic: false, default_value: default_settings.scanlines.settings.darkness },
+                            id: SettingID::SCANLINES_DARKNESS
+                        },
+                        SettingDescriptor {
+                            label: "Thickness",
+                            description: Some("Fraction of each scanline taken up by the dark gap."),
+                            kind: SettingKind::Percentage { logarithmic: false, default_value: default_settings.scanlines.settings.thickness },
+                            id: SettingID::SCANLINES_THICKNESS
+                        },
+                        SettingDescriptor {
+                            label: "Subpixel mask",
+                            description: Some("Pattern of red, green, and blue phosphors to overlay on top of the image."),
+                            kind: SettingKind::Enumeration {
+                                options: vec![
+                                    MenuItem {
+                                        label: "None",
+                                        description: Some("No phosphor pattern."),
+                                        index: SubpixelMask::None.to_u32().unwrap(),
+                                    },
+                                    MenuItem {
+                                        label: "Aperture grille",
+                                        description: Some("Vertical red, green, and blue stripes, as used in Trinitron CRTs."),
+                                        index: SubpixelMask::ApertureGrille.to_u32().unwrap(),
+                                    },
+                                    MenuItem {
+                                        label: "Shadow mask",
+                                        description: Some("Red, green, and blue phosphor triads, offset every other scanline."),
+                                        index: SubpixelMask::ShadowMask.to_u32().unwrap(),
+                                    },
+                                ],
+                                default_value: default_settings.scanlines.settings.mask.to_u32().unwrap(),
+                            },
+                            id: SettingID::SCANLINES_MASK
+                        },
+                    ],
+                    default_value: false,
+                },
+                id: SettingID::SCANLINES,
+            },
+            SettingDescriptor {
+                label: "Film grain",
+                description: Some("Add grain like that of photographic film, for the look of video that's been transferred to film. It's most visible in the midtones."),
+                kind: SettingKind::Group {
+                    children: vec![
+                        SettingDescriptor {
+                            label: "Size",
+                            description: Some("Size of each grain, in pixels."),
+                            kind: SettingKind::FloatRange { range: 0.5..=8.0, logarithmic: true, default_value: default_settings.film_grain.settings.size },
+                            id: SettingID::FILM_GRAIN_SIZE,
+                        },
+                        SettingDescriptor {
+                            label: "Intensity",
+                            description: Some("How much the grain varies the brightness of the image."),
+                            kind: SettingKind::Percentage { logarithmic: true, default_value: default_settings.film_grain.settings.intensity },
+                            id: SettingID::FILM_GRAIN_INTENSITY,
+                        },
+                        SettingDescriptor {
+                            label: "Color",
+                            description: Some("How much the grain varies in color, relative to its intensity."),
+                            kind: SettingKind::Percentage { logarithmic: false, default_value: default_settings.film_grain.settings.color },
+                            id: SettingID::FILM_GRAIN_COLOR,
+                        },
+                        SettingDescriptor {
+                            label: "Monochrome",
+                            description: Some("Only vary the brightness of the image, like black-and-white film grain."),
+                            kind: SettingKind::Boolean { default_value: default_settings.film_grain.settings.monochrome },
+                            id: SettingID::FILM_GRAIN_MONOCHROME,
+                        },
+                    ],
+                    default_value: false,
+                },
+                id: SettingID::FILM_GRAIN,
+            },
+            SettingDescriptor {
+                label: "Bloom",
+                description: Some("Make bright areas of the image glow into their surroundings, like a CRT's phosphors do."),
+                kind: SettingKind::Group {
+                    children: vec![
+                        SettingDescriptor {
+                            label: "Threshold",
+                            description: Some("Brightness above which the image starts to glow."),
+                            kind: SettingKind::Percentage { logarithmic: false, default_value: default_settings.bloom.settings.threshold },
+                            id: SettingID::BLOOM_THRESHOLD,
+                        },
+                        SettingDescriptor {
+                            label: "Radius",
+                            description: Some("How far the glow spreads, in scanlines of a 480-line image. It's scaled to match the image's actual height."),
+                            kind: SettingKind::FloatRange { range: 0.0..=32.0, logarithmic: false, default_value: default_settings.bloom.settings.radius },
+                            id: SettingID::BLOOM_RADIUS,
+                        },
+                        SettingDescriptor {
+                            label: "Intensity",
+                            description: Some("Brightness of the glow."),
+                            kind: SettingKind::Percentage { logarithmic: false, default_value: default_settings.bloom.settings.intensity },
+                            id: SettingID::BLOOM_INTENSITY,
+                        },
+                    ],
+                    default_value: false,
+                },
+                id: SettingID::BLOOM,
+            },
+            SettingDescriptor {
+                label: "Advanced",
+                description: Some("Change how the signal itself works. These make the effect less physically accurate, and are mostly useful for glitchy looks or matching non-standard signals."),
+                kind: SettingKind::Group {
+                    children: vec![
+                        SettingDescriptor {
+                            label: "Subcarrier frequency",
+                            description: Some("Frequency of the color carrier, in MHz. The standard NTSC frequency is about 3.58 MHz. The receiver's filters stay tuned to the standard frequency, so detuning the carrier lets color bleed into the brightness and vice versa."),
+                            kind: SettingKind::FloatRange { range: 2.0..=6.0, logarithmic: false, default_value: default_settings.advanced.settings.subcarrier_frequency },
+                            id: SettingID::SUBCARRIER_FREQUENCY,
+                        },
+                    ],
+                    default_value: false,
+                },
+                id: SettingID::ADVANCED,
+            },
         ];
 
         let mut by_id = Vec::new();
@@ -1497,6 +2507,49 @@ impl SettingsList {
         }
     }
 
+    fn changed_settings_in<'a>(
+        dst: &mut Vec<&'a SettingDescriptor>,
+        descriptors: &'a [SettingDescriptor],
+        a: &NtscEffectFullSettings,
+        b: &NtscEffectFullSettings,
+    ) {
+        for descriptor in descriptors {
+            let id = &descriptor.id;
+            let changed = match &descriptor.kind {
+                SettingKind::Enumeration { .. } => id.get_field_enum(a) != id.get_field_enum(b),
+                SettingKind::Percentage { .. } | SettingKind::FloatRange { .. } => {
+                    id.get_field_ref::<f32>(a) != id.get_field_ref::<f32>(b)
+                }
+                SettingKind::IntRange { .. } => {
+                    id.get_field_ref::<u32>(a) != id.get_field_ref::<u32>(b)
+                        || id.get_field_ref::<i32>(a) != id.get_field_ref::<i32>(b)
+                }
+                SettingKind::Boolean { .. } => {
+                    id.get_field_ref::<bool>(a) != id.get_field_ref::<bool>(b)
+                }
+                SettingKind::Group { children, .. } => {
+                    Self::changed_settings_in(dst, children, a, b);
+                    id.get_field_ref::<bool>(a) != id.get_field_ref::<bool>(b)
+                }
+            };
+
+            if changed {
+                dst.push(descriptor);
+            }
+        }
+    }
+
+    /// Returns the descriptors of all settings whose values differ between `a` and `b`.
+    pub fn changed_settings<'a>(
+        &'a self,
+        a: &NtscEffectFullSettings,
+        b: &NtscEffectFullSettings,
+    ) -> Vec<&'a SettingDescriptor> {
+        let mut changed = Vec::new();
+        Self::changed_settings_in(&mut changed, &self.settings, a, b);
+        changed
+    }
+
     pub fn to_json(&self, settings: &NtscEffectFullSettings) -> JsonValue {
         let mut dst_map = HashMap::<String, JsonValue>::new();
         Self::settings_to_json(&mut dst_map, &self.settings, settings);
@@ -1506,26 +2559,236 @@ impl SettingsList {
         JsonValue::Object(dst_map)
     }
 
-    fn settings_from_json(
-        json: &HashMap<String, JsonValue>,
+    /// Like [`SettingsList::to_json`], but also stores a preset's name, author, and description alongside the
+    /// settings. [`SettingsList::from_json`] ignores them; use [`PresetMetadata::from_json`] to read them back.
+    pub fn to_json_with_metadata(
+        &self,
+        settings: &NtscEffectFullSettings,
+        metadata: &PresetMetadata,
+    ) -> JsonValue {
+        let mut json = self.to_json(settings);
+        if let JsonValue::Object(dst_map) = &mut json {
+            metadata.write_json(dst_map);
+        }
+
+        json
+    }
+
+    fn settings_to_json_schema(
+        dst: &mut HashMap<String, JsonValue>,
         descriptors: &[SettingDescriptor],
+    ) {
+        // Go through the f32's shortest decimal representation, so that e.g. 0.1 doesn't become 0.10000000149011612
+        // and reject hand-written values that are right at the edge of the range
+        let f32_to_json = |n: f32| JsonValue::Number(n.to_string().parse().unwrap());
+
+        for descriptor in descriptors {
+            let mut schema = HashMap::<String, JsonValue>::new();
+            schema.insert(
+                "title".to_string(),
+                JsonValue::String(descriptor.label.to_string()),
+            );
+            if let Some(description) = descriptor.description {
+                schema.insert(
+                    "description".to_string(),
+                    JsonValue::String(description.to_string()),
+                );
+            }
+
+            let (json_type, default_value) = match &descriptor.kind {
+                SettingKind::Enumeration {
+                    options,
+                    default_value,
+                } => {
+                    let options = options
+                        .iter()
+                        .map(|item| {
+                            let mut option = HashMap::<String, JsonValue>::new();
+                            option
+                                .insert("const".to_string(), JsonValue::Number(item.index as f64));
+                            option.insert(
+                                "title".to_string(),
+                                JsonValue::String(item.label.to_string()),
+                            );
+                            if let Some(description) = item.description {
+                                option.insert(
+                                    "description".to_string(),
+                                    JsonValue::String(description.to_string()),
+                                );
+                            }
+                            JsonValue::Object(option)
+                        })
+                        .collect();
+                    schema.insert("oneOf".to_string(), JsonValue::Array(options));
+                    ("integer", JsonValue::Number(*default_value as f64))
+                }
+                SettingKind::Percentage { default_value, .. } => {
+                    schema.insert("minimum".to_string(), JsonValue::Number(0.0));
+                    schema.insert("maximum".to_string(), JsonValue::Number(1.0));
+                    ("number", f32_to_json(*default_value))
+                }
+                SettingKind::IntRange {
+                    range,
+                    default_value,
+                } => {
+                    schema.insert(
+                        "minimum".to_string(),
+                        JsonValue::Number(*range.start() as f64),
+                    );
+                    schema.insert(
+                        "maximum".to_string(),
+                        JsonValue::Number(*range.end() as f64),
+                    );
+                    ("integer", JsonValue::Number(*default_value as f64))
+                }
+                SettingKind::FloatRange {
+                    range,
+                    default_value,
+                    ..
+                } => {
+                    schema.insert("minimum".to_string(), f32_to_json(*range.start()));
+                    schema.insert("maximum".to_string(), f32_to_json(*range.end()));
+                    ("number", f32_to_json(*default_value))
+                }
+                SettingKind::Boolean { default_value } => {
+                    ("boolean", JsonValue::Boolean(*default_value))
+                }
+                SettingKind::Group {
+                    children,
+                    default_value,
+                } => {
+                    // Groups' settings are stored alongside the group's own on/off switch, not nested inside it
+                    Self::settings_to_json_schema(dst, children);
+                    ("boolean", JsonValue::Boolean(*default_value))
+                }
+            };
+            schema.insert("type".to_string(), JsonValue::String(json_type.to_string()));
+            schema.insert("default".to_string(), default_value);
+
+            dst.insert(descriptor.id.name().to_string(), JsonValue::Object(schema));
+        }
+    }
+
+    /// Generate a JSON Schema describing the settings format that [`SettingsList::to_json`] writes, including each
+    /// setting's range and the options for each menu. Editors can use it to validate hand-written presets.
+    pub fn json_schema(&self) -> JsonValue {
+        let mut properties = HashMap::<String, JsonValue>::new();
+        Self::settings_to_json_schema(&mut properties, &self.settings);
+
+        let mut version = HashMap::<String, JsonValue>::new();
+        version.insert("const".to_string(), JsonValue::Number(1.0));
+        properties.insert("version".to_string(), JsonValue::Object(version));
+
+        for (key, description) in [
+            ("name", "The preset's name"),
+            ("author", "Who made the preset"),
+            (
+                "description",
+                "What the preset is for, or how it should be used",
+            ),
+        ] {
+            let mut field = HashMap::<String, JsonValue>::new();
+            field.insert("type".to_string(), JsonValue::String("string".to_string()));
+            field.insert(
+                "description".to_string(),
+                JsonValue::String(description.to_string()),
+            );
+            properties.insert(key.to_string(), JsonValue::Object(field));
+        }
+
+        let mut schema = HashMap::<String, JsonValue>::new();
+        schema.insert(
+            "$schema".to_string(),
+            JsonValue::String("http://json-schema.org/draft-07/schema#".to_string()),
+        );
+        schema.insert(
+            "title".to_string(),
+            JsonValue::String("ntsc-rs settings".to_string()),
+        );
+        schema.insert("type".to_string(), JsonValue::String("object".to_string()));
+        schema.insert("properties".to_string(), JsonValue::Object(properties));
+        // Any settings that are left out keep their default values
+        schema.insert(
+            "required".to_string(),
+            JsonValue::Array(vec![JsonValue::String("version".to_string())]),
+        );
+        schema.insert(
+            "additionalProperties".to_string(),
+            JsonValue::Boolean(false),
+        );
+
+        JsonValue::Object(schema)
+    }
+
+    /// Compute a hash of the given settings, for checking at a glance whether two renders used the same settings.
+    /// It's the same across runs and platforms, and doesn't depend on the order of keys in a settings JSON file.
+    ///
+    /// Only settings that affect the output are hashed: the values inside a disabled settings group don't change the
+    /// hash, since they don't change the effect. Running on the GPU isn't saved with the settings, but it does change
+    /// the output slightly, so it's hashed too.
+    pub fn settings_hash(&self, settings: &NtscEffectFullSettings) -> String {
+        let effective_settings = NtscEffectFullSettings::from(NtscEffect::from(settings));
+        let JsonValue::Object(json) = self.to_json(&effective_settings) else {
+            unreachable!("settings JSON is always an object");
+        };
+
+        let mut entries = json.into_iter().collect::<Vec<_>>();
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut hasher = SipHasher13::new_with_keys(0, 0);
+        for (key, value) in entries {
+            hasher.write(key.as_bytes());
+            hasher.write_u8(b'=');
+            hasher.write(value.stringify().unwrap().as_bytes());
+            hasher.write_u8(b';');
+        }
+        // Only hashed when it's on, so CPU renders keep the hashes they had before the GPU path existed
+        if effective_settings.use_gpu {
+            hasher.write(b"use_gpu;");
+        }
+
+        format!("{:032x}", hasher.finish128().as_u128())
+    }
+
+    fn settings_from_json<'a>(
+        json: &HashMap<String, JsonValue>,
+        descriptors: &'a [SettingDescriptor],
         settings: &mut NtscEffectFullSettings,
+        clamped: &mut Vec<&'a SettingDescriptor>,
     ) -> Result<(), ParseSettingsError> {
         for descriptor in descriptors {
             let key = descriptor.id.name();
+            // Clamp the value into the setting's range, and note down if it was out of range. This is done after
+            // converting floats to f32, so that values right at the edge of a range aren't reported due to rounding.
+            let mut clamp = |n: f64, range: RangeInclusive<f64>| {
+                let clamped_n = n.clamp(*range.start(), *range.end());
+                if clamped_n != n {
+                    clamped.push(descriptor);
+                }
+                clamped_n
+            };
             match &descriptor.kind {
                 SettingKind::Enumeration { .. } => {
                     json.get_and_expect_number(key)?
                         .map(|n| descriptor.id.set_field_enum(settings, n as u32))
                         .transpose()?;
                 }
-                SettingKind::Percentage { .. } | SettingKind::FloatRange { .. } => {
+                SettingKind::Percentage { .. } => {
                     json.get_and_expect_number(key)?.map(|n| {
-                        *descriptor.id.get_field_mut::<f32>(settings).unwrap() = n as f32;
+                        *descriptor.id.get_field_mut::<f32>(settings).unwrap() =
+                            clamp(n as f32 as f64, 0.0..=1.0) as f32;
                     });
                 }
-                SettingKind::IntRange { .. } => {
+                SettingKind::FloatRange { range, .. } => {
                     json.get_and_expect_number(key)?.map(|n| {
+                        *descriptor.id.get_field_mut::<f32>(settings).unwrap() =
+                            clamp(n as f32 as f64, *range.start() as f64..=*range.end() as f64)
+                                as f32;
+                    });
+                }
+                SettingKind::IntRange { range, .. } => {
+                    json.get_and_expect_number(key)?.map(|n| {
+                        let n = clamp(n.round(), *range.start() as f64..=*range.end() as f64);
                         if let Some(field) = descriptor.id.get_field_mut::<u32>(settings) {
                             *field = n as u32;
                         } else if let Some(field) = descriptor.id.get_field_mut::<i32>(settings) {
@@ -1542,7 +2805,7 @@ impl SettingsList {
                     json.get_and_expect_bool(key)?.map(|b| {
                         *descriptor.id.get_field_mut::<bool>(settings).unwrap() = b;
                     });
-                    Self::settings_from_json(json, children, settings)?;
+                    Self::settings_from_json(json, children, settings, clamped)?;
                 }
             }
         }
@@ -1550,7 +2813,18 @@ impl SettingsList {
         Ok(())
     }
 
+    /// Parse settings from JSON. Values outside of a setting's range are clamped into it.
     pub fn from_json(&self, json: &str) -> Result<NtscEffectFullSettings, ParseSettingsError> {
+        self.from_json_reporting_clamped(json)
+            .map(|(settings, _)| settings)
+    }
+
+    /// Like [`SettingsList::from_json`], but also returns the settings whose values were out of range and had to be
+    /// clamped, so they can be pointed out.
+    pub fn from_json_reporting_clamped(
+        &self,
+        json: &str,
+    ) -> Result<(NtscEffectFullSettings, Vec<&SettingDescriptor>), ParseSettingsError> {
         let parsed = json.parse::<JsonValue>()?;
 
         let parsed_map = parsed.get::<HashMap<_, _>>().ok_or_else(|| {
@@ -1568,8 +2842,139 @@ impl SettingsList {
         }
 
         let mut dst_settings = NtscEffectFullSettings::default();
-        Self::settings_from_json(parsed_map, &self.settings, &mut dst_settings)?;
+        let mut clamped = Vec::new();
+        Self::settings_from_json(parsed_map, &self.settings, &mut dst_settings, &mut clamped)?;
 
-        Ok(dst_settings)
+        Ok((dst_settings, clamped))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_hash_ignores_key_order() {
+        let settings_list = SettingsList::new();
+        let settings = NtscEffectFullSettings::default();
+        let JsonValue::Object(json) = settings_list.to_json(&settings) else {
+            unreachable!();
+        };
+
+        // Write the keys out in both orders, since HashMap iteration order is arbitrary
+        let mut entries = json.into_iter().collect::<Vec<_>>();
+        let json_with_order = |entries: &[(String, JsonValue)]| {
+            let fields = entries
+                .iter()
+                .map(|(key, value)| format!("\"{key}\":{}", value.stringify().unwrap()))
+                .collect::<Vec<_>>();
+            format!("{{{}}}", fields.join(","))
+        };
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let forward = settings_list.from_json(&json_with_order(&entries)).unwrap();
+        entries.reverse();
+        let backward = settings_list.from_json(&json_with_order(&entries)).unwrap();
+
+        assert_eq!(
+            settings_list.settings_hash(&forward),
+            settings_list.settings_hash(&backward)
+        );
+        assert_eq!(
+            settings_list.settings_hash(&forward),
+            settings_list.settings_hash(&settings)
+        );
+    }
+
+    #[test]
+    fn settings_hash_changes_with_settings() {
+        let settings_list = SettingsList::new();
+        let settings = NtscEffectFullSettings::default();
+        let mut changed_settings = settings.clone();
+        changed_settings.snow_intensity += 0.01;
+
+        assert_ne!(
+            settings_list.settings_hash(&settings),
+            settings_list.settings_hash(&changed_settings)
+        );
+    }
+
+    #[test]
+    fn settings_hash_changes_with_gpu() {
+        let settings_list = SettingsList::new();
+        let settings = NtscEffectFullSettings::default();
+        let mut gpu_settings = settings.clone();
+        gpu_settings.use_gpu = true;
+
+        assert_ne!(
+            settings_list.settings_hash(&settings),
+            settings_list.settings_hash(&gpu_settings)
+        );
+    }
+
+    #[test]
+    fn preset_metadata_round_trips_without_affecting_settings() {
+        let settings_list = SettingsList::new();
+        let mut settings = NtscEffectFullSettings::default();
+        settings.snow_intensity += 0.01;
+        let metadata = PresetMetadata {
+            name: Some("Worn tape".to_string()),
+            author: None,
+            description: Some(String::from("Lots of snow")),
+        };
+
+        let json = settings_list
+            .to_json_with_metadata(&settings, &metadata)
+            .stringify()
+            .unwrap();
+        assert_eq!(PresetMetadata::from_json(&json).unwrap(), metadata);
+        assert_eq!(
+            settings_list.settings_hash(&settings_list.from_json(&json).unwrap()),
+            settings_list.settings_hash(&settings)
+        );
+
+        // Metadata of the wrong type is ignored rather than rejected
+        let json = r#"{"version": 1, "name": 5, "author": ""}"#;
+        assert!(PresetMetadata::from_json(json).unwrap().is_empty());
+        assert!(settings_list.from_json(json).is_ok());
+    }
+
+    #[test]
+    fn out_of_range_json_values_are_clamped() {
+        let settings_list = SettingsList::new();
+        let json = r#"{"version": 1, "composite_preemphasis": 50, "snow_intensity": -1, "bandwidth_scale": 1}"#;
+        let (settings, clamped) = settings_list.from_json_reporting_clamped(json).unwrap();
+
+        let clamped_ids = clamped
+            .iter()
+            .map(|descriptor| descriptor.id)
+            .collect::<Vec<_>>();
+        assert_eq!(clamped_ids.len(), 2);
+        assert!(clamped_ids.contains(&SettingID::COMPOSITE_PREEMPHASIS));
+        assert!(clamped_ids.contains(&SettingID::SNOW_INTENSITY));
+
+        assert_eq!(settings.snow_intensity, 0.0);
+        assert_eq!(settings.bandwidth_scale, 1.0);
+    }
+
+    #[test]
+    fn tracking_noise_descriptor_defaults_match_settings() {
+        let settings_list = SettingsList::new();
+        let settings = NtscEffectFullSettings::default();
+        let tracking_noise = settings_list
+            .settings
+            .iter()
+            .find(|descriptor| descriptor.id == SettingID::TRACKING_NOISE)
+            .unwrap();
+        let SettingKind::Group { children, .. } = &tracking_noise.kind else {
+            unreachable!();
+        };
+
+        for descriptor in children {
+            assert!(
+                descriptor.is_default(&settings),
+                "default value of \"{}\" doesn't match the default settings",
+                descriptor.label
+            );
+        }
     }
 }
@@ -0,0 +1,53 @@
+// Apply an IIR filter to each row of a plane, one row per invocation. This mirrors
+// `TransferFunction::filter_signal_in_place`; see `gpu.rs` for how the coefficients are laid out.
+
+struct Params {
+    width: u32,
+    height: u32,
+    filter_len: u32,
+    delay: u32,
+    scale: f32,
+    // 0 to start the filter at `initial_value`, 1 to start it at each row's first sample
+    initial_from_first_sample: u32,
+    initial_value: f32,
+    _padding: u32,
+}
+
+const MAX_FILTER_LEN: u32 = 16u;
+
+@group(0) @binding(0) var<uniform> params: Params;
+// The numerator, then the denominator, then the filter's initial condition for a steady-state value of 1
+@group(0) @binding(1) var<storage, read> coeffs: array<f32>;
+@group(0) @binding(2) var<storage, read_write> plane: array<f32>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let row = id.x;
+    if row >= params.height {
+        return;
+    }
+
+    let n = params.filter_len;
+    let start = row * params.width;
+
+    var initial = params.initial_value;
+    if params.initial_from_first_sample != 0u {
+        initial = plane[start];
+    }
+
+    var z: array<f32, MAX_FILTER_LEN>;
+    for (var i = 0u; i < n; i++) {
+        z[i] = coeffs[2u * n + i] * initial;
+    }
+
+    for (var i = 0u; i < params.width + params.delay; i++) {
+        let sample = plane[start + min(i, params.width - 1u)];
+        let filt_sample = z[0] + coeffs[0] * sample;
+        for (var j = 0u; j + 1u < n; j++) {
+            z[j] = z[j + 1u] + coeffs[j + 1u] * sample - coeffs[n + j + 1u] * filt_sample;
+        }
+        if i >= params.delay {
+            plane[start + i - params.delay] = (filt_sample - sample) * params.scale + sample;
+        }
+    }
+}
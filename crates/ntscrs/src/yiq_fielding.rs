@@ -175,6 +175,37 @@ impl_pix_fmt!(Xbgr32f, SwizzleOrder::Xbgr, f32);
 impl_pix_fmt!(Rgb32f, SwizzleOrder::Rgb, f32);
 impl_pix_fmt!(Bgr32f, SwizzleOrder::Bgr, f32);
 
+/// Convert a row of pixels in the given format to YIQ. Any alpha channel is ignored.
+pub fn pixel_row_to_yiq<S: PixelFormat>(
+    src: &[S::DataFormat],
+    y: &mut [f32],
+    i: &mut [f32],
+    q: &mut [f32],
+) {
+    let (r_idx, g_idx, b_idx, ..) = S::ORDER.rgba_indices();
+    let pixels = src.chunks_exact(S::ORDER.num_components());
+    for (pixel_idx, pixel) in pixels.take(y.len()).enumerate() {
+        let yiq_pixel = rgb_to_yiq([
+            pixel[r_idx].to_norm(),
+            pixel[g_idx].to_norm(),
+            pixel[b_idx].to_norm(),
+        ]);
+        y[pixel_idx] = yiq_pixel[0];
+        i[pixel_idx] = yiq_pixel[1];
+        q[pixel_idx] = yiq_pixel[2];
+    }
+}
+
+/// Convert a YIQ pixel to the given format, leaving its alpha channel (if any) as it was.
+#[inline(always)]
+pub fn yiq_to_pixel<S: PixelFormat>(yiq: [f32; 3], pixel: &mut [S::DataFormat]) {
+    let (r_idx, g_idx, b_idx, ..) = S::ORDER.rgba_indices();
+    let rgb = yiq_to_rgb(yiq);
+    pixel[r_idx] = S::DataFormat::from_norm(rgb[0]);
+    pixel[g_idx] = S::DataFormat::from_norm(rgb[1]);
+    pixel[b_idx] = S::DataFormat::from_norm(rgb[2]);
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DeinterlaceMode {
     /// Interpolate between the given fields.
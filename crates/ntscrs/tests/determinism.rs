@@ -0,0 +1,294 @@
+//! The effect must produce bit-identical output for the same input, settings, and seed, no matter how many times it's
+//! run or how the work is split up between threads. Renders are archived and compared against each other, so any
+//! randomness has to come from the seed and frame number alone.
+
+use std::time::Duration;
+
+use image::{Rgb, RgbImage};
+use ntscrs::{
+    ntsc::{FrameTime, NtscEffect, StageTimings},
+    settings::{
+        AdvancedSettings, BloomSettings, ChromaDemodulationFilter, DropoutSettings,
+        FilmGrainSettings, GhostingSettings, HeadSwitchingSettings, HumBarSettings, Precision,
+        ScanlineSettings, TrackingNoiseSettings, UseField, VHSSettings, VignetteSettings,
+    },
+    yiq_fielding::{DeinterlaceMode, Rgb8, YiqOwned, YiqView},
+};
+
+const WIDTH: u32 = 160;
+const HEIGHT: u32 = 120;
+const NUM_FRAMES: usize = 4;
+
+/// Color bars on the top half and a luma ramp on the bottom, so that both the luma and chroma paths get exercised.
+fn test_pattern() -> RgbImage {
+    const BARS: [[u8; 3]; 7] = [
+        [191, 191, 191],
+        [191, 191, 0],
+        [0, 191, 191],
+        [0, 191, 0],
+        [191, 0, 191],
+        [191, 0, 0],
+        [0, 0, 191],
+    ];
+
+    RgbImage::from_fn(WIDTH, HEIGHT, |x, y| {
+        if y < HEIGHT / 2 {
+            Rgb(BARS[(x * BARS.len() as u32 / WIDTH) as usize])
+        } else {
+            let level = (x * 255 / (WIDTH - 1)) as u8;
+            Rgb([level, level, level])
+        }
+    })
+}
+
+/// The default settings, plus the noise sources that are off by default.
+fn noisy_settings(seed: i32) -> NtscEffect {
+    let mut settings = NtscEffect::default();
+    settings.random_seed = seed;
+    settings.hum_bars = Some(HumBarSettings::default());
+    settings.ghosting = Some(GhostingSettings::default());
+    settings.snow_intensity = 0.1;
+    settings
+}
+
+/// Render the test pattern inside a pool of `num_threads` threads. The effect runs in the pool it's called from, if
+/// any, so this is how many threads it's split up between.
+fn render(settings: &NtscEffect, num_threads: usize) -> Vec<RgbImage> {
+    let pattern = test_pattern();
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .unwrap()
+        .install(|| {
+            assert_eq!(rayon::current_num_threads(), num_threads);
+            (0..NUM_FRAMES)
+                .map(|frame_num| settings.apply_effect(&pattern, frame_num))
+                .collect()
+        })
+}
+
+fn assert_frames_identical(a: &[RgbImage], b: &[RgbImage]) {
+    for (frame_num, (a, b)) in a.iter().zip(b).enumerate() {
+        assert!(
+            a.as_raw() == b.as_raw(),
+            "frame {frame_num} differs between renders"
+        );
+    }
+}
+
+#[test]
+fn same_seed_gives_identical_output() {
+    let settings = noisy_settings(12345);
+    assert_frames_identical(&render(&settings, 4), &render(&settings, 4));
+}
+
+#[test]
+fn output_does_not_depend_on_thread_count() {
+    let settings = noisy_settings(12345);
+    assert_frames_identical(&render(&settings, 1), &render(&settings, 8));
+}
+
+#[test]
+fn different_seeds_give_different_output() {
+    // Make sure the tests above aren't passing because the noise isn't doing anything
+    let a = render(&noisy_settings(1), 4);
+    let b = render(&noisy_settings(2), 4);
+    assert!(a.iter().zip(&b).any(|(a, b)| a.as_raw() != b.as_raw()));
+}
+
+/// Settings that turn on every effect that depends on a row's position or pulls in neighboring rows.
+fn row_dependent_settings(use_field: UseField) -> NtscEffect {
+    let mut settings = noisy_settings(12345);
+    settings.use_field = use_field;
+    settings.chroma_demodulation = ChromaDemodulationFilter::ThreeLineComb;
+    settings.chroma_delay = (1.5, -3);
+    settings.chroma_vert_blend = true;
+    settings.head_switching = Some(HeadSwitchingSettings::default());
+    settings.tracking_noise = Some(TrackingNoiseSettings::default());
+    settings.dropout = Some(DropoutSettings::default());
+    settings.vhs_settings = Some(VHSSettings::default());
+    settings.vignette = Some(VignetteSettings::default());
+    settings.scanlines = Some(ScanlineSettings::default());
+    settings
+}
+
+fn render_in_strips(settings: &NtscEffect, frame_num: usize, strip_rows: usize) -> RgbImage {
+    let pattern = test_pattern();
+    let mut output = RgbImage::new(WIDTH, HEIGHT);
+    settings.apply_effect_in_strips::<Rgb8, Rgb8>(
+        pattern.as_raw(),
+        WIDTH as usize * 3,
+        &mut output,
+        WIDTH as usize * 3,
+        (WIDTH as usize, HEIGHT as usize),
+        settings.use_field.to_yiq_field(frame_num),
+        frame_num,
+        None,
+        strip_rows,
+        DeinterlaceMode::Bob,
+    );
+    output
+}
+
+fn assert_strips_match_whole_frame(settings: &NtscEffect) {
+    let whole = render(settings, 4);
+    // Include a strip height that doesn't evenly divide the frame, and one that covers all of it
+    for strip_rows in [8, 24, 1000] {
+        let strips = (0..NUM_FRAMES)
+            .map(|frame_num| render_in_strips(settings, frame_num, strip_rows))
+            .collect::<Vec<_>>();
+        for (frame_num, (a, b)) in whole.iter().zip(&strips).enumerate() {
+            assert!(
+                a.as_raw() == b.as_raw(),
+                "frame {frame_num} differs when rendered in {strip_rows}-row strips with {:?}",
+                settings.use_field
+            );
+        }
+    }
+}
+
+#[test]
+fn strips_match_whole_frame() {
+    for use_field in [
+        UseField::Alternating,
+        UseField::Both,
+        UseField::InterleavedUpper,
+        UseField::InterleavedLower,
+    ] {
+        assert_strips_match_whole_frame(&row_dependent_settings(use_field));
+    }
+}
+
+#[test]
+fn bloom_strips_match_whole_frame() {
+    // Bloom blurs across many more rows than the other effects, so its strips need the most overlap
+    for use_field in [UseField::Alternating, UseField::Both] {
+        let mut settings = noisy_settings(12345);
+        settings.use_field = use_field;
+        settings.bloom = Some(BloomSettings::default());
+        assert_strips_match_whole_frame(&settings);
+    }
+}
+
+#[test]
+fn double_precision_is_deterministic_and_close_to_single() {
+    let mut double = NtscEffect::default();
+    double.random_seed = 777;
+    double.precision = Precision::Double;
+    let mut single = double.clone();
+    single.precision = Precision::Single;
+
+    let double_frames = render(&double, 4);
+    assert_frames_identical(&double_frames, &render(&double, 1));
+
+    // The noise is the same either way, so only the filters' rounding should differ
+    for (frame_num, (a, b)) in double_frames.iter().zip(&render(&single, 4)).enumerate() {
+        let max_diff = a
+            .as_raw()
+            .iter()
+            .zip(b.as_raw())
+            .map(|(a, b)| a.abs_diff(*b))
+            .max()
+            .unwrap();
+        assert!(
+            max_diff <= 3,
+            "frame {frame_num} differs by up to {max_diff} levels between 32- and 64-bit filtering"
+        );
+    }
+}
+
+fn render_at(settings: &NtscEffect, frame_num: usize, timestamp_ms: u64) -> RgbImage {
+    let mut yiq = YiqOwned::from_image(&test_pattern(), settings.use_field.to_yiq_field(frame_num));
+    let mut view = YiqView::from(&mut yiq);
+    let time = FrameTime {
+        timestamp: Duration::from_millis(timestamp_ms),
+        duration: Duration::from_millis(40),
+    };
+    settings.apply_effect_to_yiq_at(&mut view, frame_num, time);
+    RgbImage::from(&view)
+}
+
+#[test]
+fn timestamp_noise_ignores_frame_number() {
+    let mut settings = noisy_settings(4321);
+    settings.use_field = UseField::InterleavedUpper;
+    settings.timestamp_noise = true;
+
+    // The same moment, reached at a different frame number (e.g. after two frames were dropped earlier on). The
+    // frame numbers are kept 2 apart so that the chroma subcarrier phase, which follows the frame number, matches.
+    assert_eq!(
+        render_at(&settings, 25, 1000),
+        render_at(&settings, 27, 1000)
+    );
+    assert_ne!(
+        render_at(&settings, 25, 1000),
+        render_at(&settings, 25, 1040)
+    );
+
+    // Without timestamp noise, the frame number decides the noise like before
+    settings.timestamp_noise = false;
+    assert_ne!(
+        render_at(&settings, 25, 1000),
+        render_at(&settings, 27, 1000)
+    );
+    assert_eq!(
+        render_at(&settings, 25, 1000),
+        settings.apply_effect(&test_pattern(), 25)
+    );
+}
+
+#[test]
+fn profiling_does_not_change_output() {
+    let mut settings = row_dependent_settings(UseField::InterleavedUpper);
+    settings.field_asymmetry = 0.5;
+    let frame_num = 3;
+
+    let mut yiq = YiqOwned::from_image(&test_pattern(), settings.use_field.to_yiq_field(frame_num));
+    let mut view = YiqView::from(&mut yiq);
+    let mut timings = StageTimings::default();
+    settings.apply_effect_to_yiq_profiled(&mut view, frame_num, None, &mut timings);
+
+    assert_eq!(
+        RgbImage::from(&view),
+        settings.apply_effect(&test_pattern(), frame_num)
+    );
+    // Both fields' stages are added together rather than listed twice
+    let stages = timings.stages();
+    assert!(!stages.is_empty());
+    for (index, (name, _)) in stages.iter().enumerate() {
+        assert!(!stages[index + 1..].iter().any(|(other, _)| other == name));
+    }
+}
+
+#[test]
+fn film_grain_is_deterministic() {
+    let without_grain = noisy_settings(12345);
+    let mut settings = without_grain.clone();
+    settings.film_grain = Some(FilmGrainSettings::default());
+    let grainy = render(&settings, 1);
+    assert_frames_identical(&grainy, &render(&settings, 8));
+
+    // Make sure the grain is actually there
+    assert!(render(&without_grain, 4)
+        .iter()
+        .zip(&grainy)
+        .any(|(a, b)| a.as_raw() != b.as_raw()));
+}
+
+#[test]
+fn standard_subcarrier_frequency_matches_default() {
+    let settings = noisy_settings(12345);
+    let mut with_advanced = settings.clone();
+    with_advanced.advanced = Some(AdvancedSettings::default());
+    assert_frames_identical(&render(&settings, 4), &render(&with_advanced, 4));
+
+    // Make sure the frequency is actually used
+    with_advanced.advanced = Some(AdvancedSettings {
+        subcarrier_frequency: 4.0,
+    });
+    let detuned = render(&with_advanced, 4);
+    assert!(render(&settings, 4)
+        .iter()
+        .zip(&detuned)
+        .any(|(a, b)| a.as_raw() != b.as_raw()));
+}
@@ -0,0 +1,123 @@
+//! Checks that individual effects do what they're meant to, as opposed to the determinism and in-place tests, which
+//! only check that the output is consistent.
+
+use image::{Rgb, RgbImage};
+use ntscrs::{
+    ntsc::NtscEffect,
+    settings::{ChromaDemodulationFilter, NoSignalSettings, UseField},
+};
+
+const WIDTH: u32 = 64;
+const HEIGHT: u32 = 48;
+
+/// The default settings with everything that adds noise or blurs vertically turned off, so what's left is just the
+/// signal being modulated and demodulated.
+fn clean_settings() -> NtscEffect {
+    let mut settings = NtscEffect::default();
+    settings.use_field = UseField::Both;
+    settings.head_switching = None;
+    settings.tracking_noise = None;
+    settings.ringing = None;
+    settings.chroma_noise = None;
+    settings.vhs_settings = None;
+    settings.composite_noise_intensity = 0.0;
+    settings.snow_intensity = 0.0;
+    settings.chroma_phase_noise_intensity = 0.0;
+    settings.chroma_vert_blend = false;
+    settings
+}
+
+#[test]
+fn three_line_comb_keeps_luma_edges_in_place() {
+    const DARK: u8 = 40;
+    const BRIGHT: u8 = 200;
+    let edge = HEIGHT / 2;
+    let image = RgbImage::from_fn(WIDTH, HEIGHT, |_, y| {
+        let level = if y < edge { DARK } else { BRIGHT };
+        Rgb([level, level, level])
+    });
+
+    let mut settings = clean_settings();
+    settings.chroma_demodulation = ChromaDemodulationFilter::ThreeLineComb;
+    let output = settings.apply_effect(&image, 0);
+
+    // The filters run horizontally from a zero initial state, so leave out the columns near the left and right edges
+    for y in 0..HEIGHT {
+        for x in WIDTH / 4..WIDTH * 3 / 4 {
+            let expected = image.get_pixel(x, y);
+            let actual = output.get_pixel(x, y);
+            for channel in 0..3 {
+                assert!(
+                    expected[channel].abs_diff(actual[channel]) <= 8,
+                    "pixel ({x}, {y}) is {actual:?}, expected {expected:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn luma_undershoot_is_symmetric() {
+    const WIDE: u32 = 128;
+    const DARK: u8 = 100;
+    const BRIGHT: u8 = 200;
+    let stripe = WIDE * 3 / 8..WIDE * 5 / 8;
+    let image = RgbImage::from_fn(WIDE, HEIGHT, |x, _| {
+        let level = if stripe.contains(&x) { BRIGHT } else { DARK };
+        Rgb([level, level, level])
+    });
+
+    let settings = clean_settings();
+    let without = settings.apply_effect(&image, 0);
+    let mut settings = clean_settings();
+    settings.luma_undershoot = 1.0;
+    let with = settings.apply_effect(&image, 0);
+
+    // How much darker the undershoot makes the darkest pixel just outside each side of the stripe
+    let y = HEIGHT / 2;
+    let darkening = |columns: std::ops::Range<u32>| {
+        columns
+            .map(|x| {
+                let without = without.get_pixel(x, y)[1] as i32;
+                let with = with.get_pixel(x, y)[1] as i32;
+                without - with
+            })
+            .max()
+            .unwrap()
+    };
+    let left = darkening(stripe.start - 8..stripe.start);
+    let right = darkening(stripe.end..stripe.end + 8);
+
+    assert!(
+        left > 2 && right > 2,
+        "left side darkened by {left}, right by {right}"
+    );
+    assert!(
+        (left - right).abs() <= left.max(right) / 2,
+        "left side darkened by {left}, right by {right}"
+    );
+}
+
+#[test]
+fn no_signal_without_ghost_ignores_input() {
+    // Leave the noise on, since that's what the "no signal" static is made of
+    let mut settings = NtscEffect::default();
+    settings.no_signal = Some(NoSignalSettings { ghost: 0.0 });
+    let pattern = RgbImage::from_fn(WIDTH, HEIGHT, |x, y| {
+        let level = (x * 255 / (WIDTH - 1)) as u8;
+        if y < HEIGHT / 2 {
+            Rgb([level, 255 - level, 128])
+        } else {
+            Rgb([level, level, level])
+        }
+    });
+    let blank = RgbImage::from_pixel(WIDTH, HEIGHT, Rgb([0, 0, 0]));
+    for frame_num in 0..4 {
+        let from_pattern = settings.apply_effect(&pattern, frame_num);
+        let from_blank = settings.apply_effect(&blank, frame_num);
+        assert!(
+            from_pattern.as_raw() == from_blank.as_raw(),
+            "frame {frame_num} shows the input through the static"
+        );
+    }
+}
@@ -0,0 +1,86 @@
+//! The in-place RGB and RGBA functions are what other Rust programs use to apply the effect without going through
+//! GStreamer, so they need to match the output of the rest of the pipeline exactly.
+
+use image::{Rgb, RgbImage};
+use ntscrs::{ntsc::NtscEffect, settings::UseField};
+
+const WIDTH: u32 = 64;
+const HEIGHT: u32 = 48;
+
+/// A small gradient with something different in each channel.
+fn test_pattern() -> RgbImage {
+    RgbImage::from_fn(WIDTH, HEIGHT, |x, y| {
+        Rgb([
+            (x * 255 / (WIDTH - 1)) as u8,
+            (y * 255 / (HEIGHT - 1)) as u8,
+            ((x + y) % 2 * 255) as u8,
+        ])
+    })
+}
+
+fn to_rgba(rgb: &[u8], alpha: u8) -> Vec<u8> {
+    rgb.chunks_exact(3)
+        .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], alpha])
+        .collect()
+}
+
+#[test]
+fn rgb_matches_apply_effect() {
+    for use_field in [
+        UseField::Alternating,
+        UseField::Both,
+        UseField::InterleavedUpper,
+    ] {
+        let mut settings = NtscEffect::default();
+        settings.use_field = use_field;
+        for frame_num in 0..2 {
+            let expected = settings.apply_effect(&test_pattern(), frame_num);
+            let mut buffer = test_pattern().into_raw();
+            settings.apply_to_rgb(&mut buffer, WIDTH as usize, HEIGHT as usize, frame_num);
+            assert!(
+                buffer == expected.into_raw(),
+                "frame {frame_num} differs with {use_field:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn rgba_matches_rgb_and_keeps_alpha() {
+    let settings = NtscEffect::default();
+    let mut rgb = test_pattern().into_raw();
+    settings.apply_to_rgb(&mut rgb, WIDTH as usize, HEIGHT as usize, 0);
+
+    let mut rgba = to_rgba(test_pattern().as_raw(), 77);
+    settings.apply_to_rgba(&mut rgba, WIDTH as usize, HEIGHT as usize, 0);
+
+    assert!(rgba == to_rgba(&rgb, 77));
+}
+
+#[test]
+fn frames_are_numbered_and_reported() {
+    let settings = NtscEffect::default();
+    let mut frames = vec![test_pattern().into_raw(); 3];
+    let mut reported = Vec::new();
+    settings.apply_to_rgb_frames(
+        frames.iter_mut().map(Vec::as_mut_slice),
+        WIDTH as usize,
+        HEIGHT as usize,
+        5,
+        |done| reported.push(done),
+    );
+    assert_eq!(reported, [1, 2, 3]);
+
+    for (i, frame) in frames.iter().enumerate() {
+        let mut expected = test_pattern().into_raw();
+        settings.apply_to_rgb(&mut expected, WIDTH as usize, HEIGHT as usize, 5 + i);
+        assert!(*frame == expected, "frame {i} wasn't numbered {}", 5 + i);
+    }
+}
+
+#[test]
+#[should_panic]
+fn wrong_buffer_length_panics() {
+    let mut buffer = vec![0u8; 10];
+    NtscEffect::default().apply_to_rgb(&mut buffer, WIDTH as usize, HEIGHT as usize, 0);
+}
@@ -257,6 +257,13 @@ unsafe fn action_describe(descriptor: OfxImageEffectHandle) -> OfxResult<()> {
         0,
         0,
     );
+    // Fields are handled by the "use field" setting, so ask for whole frames even when the footage is interlaced
+    propSetInt(
+        effectProps,
+        ofx_str!(kOfxImageEffectPluginPropFieldRenderTwiceAlways),
+        0,
+        0,
+    );
 
     Ok(())
 }
@@ -1282,6 +1289,15 @@ impl From<(SupportedImageComponents, SupportedPixelDepth)> for SupportedPixelFor
     }
 }
 
+/// Turn the host's render time into the frame number that the effect's noise and field choice are based on. OFX times
+/// are measured in frames from the start of the effect, but aren't always whole numbers: hosts that render fields
+/// separately ask for the second field half a frame later, retimed clips can land anywhere in between, and some hosts
+/// accumulate floating-point error (asking for 9.9999 instead of 10). Both fields of a frame should get the same frame
+/// number, so this rounds down after allowing for that error. Negative times (before the effect starts) are clamped to 0.
+fn time_to_frame_num(time: f64) -> usize {
+    (time + 1e-3).floor().max(0.0) as usize
+}
+
 unsafe fn action_render(
     descriptor: OfxImageEffectHandle,
     inArgs: OfxPropertySetHandle,
@@ -1479,7 +1495,7 @@ unsafe fn action_render(
     let apply_srgb_gamma = srgb_bool_value != 0;
 
     let effect: NtscEffect = out_settings.into();
-    let frame_num = time as usize;
+    let frame_num = time_to_frame_num(time);
 
     let application_params = EffectApplicationParams {
         src_ptr: srcPtr,
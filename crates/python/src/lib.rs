@@ -0,0 +1,109 @@
+use ntscrs::{
+    ntsc::NtscEffect,
+    settings::{NtscEffectFullSettings, SettingsList},
+};
+use numpy::{PyArray1, PyArray3, PyArrayMethods, PyReadonlyArray3, PyUntypedArrayMethods};
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyDict};
+
+fn parse_settings(json: &str) -> PyResult<NtscEffectFullSettings> {
+    SettingsList::new()
+        .from_json(json)
+        .map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+/// Effect settings, stored in the same format as the standalone app's presets.
+#[pyclass(name = "Settings", module = "ntscrs")]
+#[derive(Clone, Default)]
+struct Settings(NtscEffectFullSettings);
+
+#[pymethods]
+impl Settings {
+    /// Create settings from a dict with the same keys as a JSON preset. Any settings not in the dict keep their default
+    /// values, and the "version" key can be left out.
+    #[new]
+    #[pyo3(signature = (values = None))]
+    fn new(py: Python<'_>, values: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let Some(values) = values else {
+            return Ok(Self::default());
+        };
+        let values = values.copy()?;
+        if !values.contains("version")? {
+            values.set_item("version", 1)?;
+        }
+        let json: String = py
+            .import_bound("json")?
+            .call_method1("dumps", (values,))?
+            .extract()?;
+        Ok(Self(parse_settings(&json)?))
+    }
+
+    /// Load settings from a JSON preset, as saved by the standalone app.
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        Ok(Self(parse_settings(json)?))
+    }
+
+    /// Save these settings as a JSON preset.
+    fn to_json(&self) -> PyResult<String> {
+        SettingsList::new()
+            .to_json(&self.0)
+            .stringify()
+            .map_err(|err| PyValueError::new_err(err.to_string()))
+    }
+
+    /// Convert these settings into a dict with the same keys as a JSON preset.
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        py.import_bound("json")?
+            .call_method1("loads", (self.to_json()?,))
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!("Settings.from_json({:?})", self.to_json()?))
+    }
+}
+
+/// Apply the effect to an image, returning a new image. The image must be an array of 8-bit values with shape
+/// (height, width, 3) for RGB or (height, width, 4) for RGBA; the alpha channel is passed through unchanged. The frame
+/// number animates the noise and decides which field is processed, just like in a video.
+#[pyfunction]
+#[pyo3(signature = (image, settings = None, frame_num = 0))]
+fn apply<'py>(
+    py: Python<'py>,
+    image: PyReadonlyArray3<'py, u8>,
+    settings: Option<&Settings>,
+    frame_num: usize,
+) -> PyResult<Bound<'py, PyArray3<u8>>> {
+    let &[height, width, channels] = image.shape() else {
+        unreachable!()
+    };
+    if channels != 3 && channels != 4 {
+        return Err(PyValueError::new_err(format!(
+            "expected an array of shape (height, width, 3) or (height, width, 4), got {:?}",
+            image.shape()
+        )));
+    }
+
+    // Copy the image in row-major order so that non-contiguous arrays (e.g. slices) work too
+    let mut buffer = image.as_array().iter().copied().collect::<Vec<u8>>();
+    let effect = settings
+        .map(|settings| NtscEffect::from(&settings.0))
+        .unwrap_or_default();
+
+    py.allow_threads(|| {
+        if channels == 4 {
+            effect.apply_to_rgba(&mut buffer, width, height, frame_num);
+        } else {
+            effect.apply_to_rgb(&mut buffer, width, height, frame_num);
+        }
+    });
+
+    PyArray1::from_vec_bound(py, buffer).reshape([height, width, channels])
+}
+
+#[pymodule]
+#[pyo3(name = "ntscrs")]
+fn ntscrs_python(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Settings>()?;
+    m.add_function(wrap_pyfunction!(apply, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use ntscrs::{ntsc::NtscEffect, settings::SettingsList};
+use wasm_bindgen::{prelude::*, Clamped};
+
+/// The effect, configured from a set of settings. Create one whenever the settings change, and reuse it for every
+/// frame rendered with them.
+#[wasm_bindgen]
+pub struct Effect(NtscEffect);
+
+#[wasm_bindgen]
+impl Effect {
+    /// Create the effect from settings in the same JSON format as the standalone app's presets. With no settings, the
+    /// defaults are used.
+    #[wasm_bindgen(constructor)]
+    pub fn new(settings_json: Option<String>) -> Result<Effect, JsError> {
+        let Some(settings_json) = settings_json else {
+            return Ok(Self(NtscEffect::default()));
+        };
+        let settings = SettingsList::new().from_json(&settings_json)?;
+        Ok(Self(NtscEffect::from(&settings)))
+    }
+
+    /// Apply the effect to an RGBA frame in place, e.g. the `data` of an `ImageData`. The alpha channel is left as it
+    /// is. The frame number animates the noise and decides which field is processed, just like in a video.
+    pub fn apply(
+        &self,
+        mut data: Clamped<&mut [u8]>,
+        width: usize,
+        height: usize,
+        frame_num: usize,
+    ) -> Result<(), JsError> {
+        if data.len() != width * height * 4 {
+            return Err(JsError::new(&format!(
+                "expected {} bytes of RGBA data for a {width}x{height} frame, got {}",
+                width * height * 4,
+                data.len()
+            )));
+        }
+        self.0.apply_to_rgba(&mut data.0, width, height, frame_num);
+        Ok(())
+    }
+}
+
+/// The default settings, as JSON.
+#[wasm_bindgen(js_name = defaultSettingsJson)]
+pub fn default_settings_json() -> String {
+    let settings_list = SettingsList::new();
+    settings_list
+        .to_json(&Default::default())
+        .stringify()
+        .unwrap()
+}